@@ -0,0 +1,507 @@
+# German UI text. Anything missing here is shown in English.
+
+## Main menu and screens
+
+tutorial = Tutorial
+tutorial-hint = Lerne, ein Schiff zu starten und auf einem Mond zu landen
+campaign = Kampagne
+level-locked = Schließe zuerst das vorige Level ab
+sandbox = Sandkasten
+paused = Pausiert
+resume-hint = Drücke { $key }, um fortzufahren.
+resume = Fortsetzen
+main-menu = Hauptmenü
+time = Zeit
+delta-v-used = Verbrauchtes Delta-v
+gravity-assists = Swing-bys
+crafts-lost = Verlorene Schiffe
+next-level = Nächstes Level
+keep-playing = Weiterspielen
+restart = Neu starten
+
+## Menu bar
+
+menu-game = Spiel
+scenarios = Szenarien…
+mods = Mods…
+open-log-folder = Log-Ordner öffnen
+export-replay = Wiederholung exportieren
+export-replay-hint = Alle bisherigen Befehle speichern, um dieses Spiel zu teilen
+replay-saved = Wiederholung gespeichert unter { $path }
+replay-not-saved = Wiederholung nicht gespeichert: { $error }
+export-scenario = Szenario exportieren
+export-scenario-hint = Das System in seinem jetzigen Zustand als neues Startszenario speichern
+with-crafts = Mit Schiffen
+share-link = Link teilen
+share-link-hint = Einen Link erstellen, der das System in seinem jetzigen Zustand im Browser öffnet
+screenshot = Bildschirmfoto
+record-gif = GIF aufnehmen
+stop-recording-gif = GIF-Aufnahme beenden
+pause-when-idle = Bei Untätigkeit pausieren
+idle-timeout = Wartezeit (s)
+combat = Kampf
+combat-attrition = Abnutzung
+combat-duels = Duelle
+defender-advantage = Verteidigervorteil
+hot-seat = Zwei Spieler (Hot Seat)
+hot-seat-hint = Ein zweiter Spieler übernimmt den Gegner, abwechselnd
+menu-edit = Bearbeiten
+undo = { $edit } rückgängig
+redo = { $edit } wiederholen
+edit-add = { $body } hinzufügen
+edit-delete = { $body } löschen
+edit-density = Dichte von { $body }
+edit-move = { $body } verschieben
+edit-velocity = Geschwindigkeit von { $body }
+edit-on-rails = { $body } auf Schienen setzen
+edit-off-rails = { $body } von den Schienen nehmen
+add-body = Körper hinzufügen
+menu-view = Ansicht
+spheres-of-influence = Einflusssphären
+lagrange-points = Lagrange-Punkte
+zero-velocity-curves = Nullgeschwindigkeitskurven
+hill-spheres = Hill-Sphären
+roche-limits = Roche-Grenzen
+scoreboard = Punktestand
+event-log = Ereignisprotokoll
+statistics = Statistik
+go-to-body = Zu Körper springen…
+patched-conics = Kegelschnitt-Näherung
+patched-conics-hint = Schnellere, weiterreichende, aber ungenaue Vorhersagen
+menu-debug = Debug
+physics-constants = Physikalische Konstanten
+record-telemetry = Telemetrie aufzeichnen
+record-telemetry-hint = Bewegung und Energie jedes Körpers für den Export aufzeichnen
+sample-interval = Messintervall (s)
+samples = { $count ->
+    [one] { $count } Messwert
+   *[other] { $count } Messwerte
+}
+export-format = { $format } exportieren
+telemetry-saved = Telemetrie gespeichert unter { $path }
+replay-orders-left = Wiederholung: noch { $count } Befehle
+replay-orders-left-hint = Befehle, die du jetzt gibst, werden der Wiederholung hinzugefügt
+turn = Am Zug: { $player }
+seconds-left = noch { $seconds } s
+end-turn = Zug beenden
+help-hint = Hilfe ({ $key })
+settings = Einstellungen
+
+## Plot overlays
+
+grid = Gitter
+axes = Achsen
+body-names = Körpernamen
+craft-counts = Schiffsanzahl
+center-of-mass = Schwerpunkt
+trails = Spuren
+craft-legend = Schiffslegende
+starfield = Sternenfeld
+night-sides = Nachtseiten
+gravity-wells = Gravitationstrichter
+
+## Statistics
+
+crafts-launched = Gestartete Schiffe
+crafts-landed = Gelandete Schiffe
+crafts-crashed = Abgestürzte Schiffe
+delta-v-spent = Verbrauchtes Δv
+biggest-assist = Größter Swing-by
+closest-flyby = Nächster Vorbeiflug
+collisions = Kollisionen
+simulated-time = Simulierte Zeit
+past-crafts = Frühere Schiffe ({ $count })
+
+## Settings
+
+settings-general = Allgemein
+settings-keys = Tasten
+language = Sprache
+theme = Design
+theme-system = System
+theme-light = Hell
+theme-dark = Dunkel
+trail-length = Spurlänge
+trail-length-hint = 0 blendet Spuren aus
+vectors = Vektoren
+velocity = Geschwindigkeit
+particles = Partikel
+particles-low = Wenig
+particles-high = Viel
+sim-speed = Simulationstempo
+sim-speed-hint = Gilt auch beim nächsten Spielstart
+ui-scale = UI-Skalierung
+colors = Farben
+colors-standard = Standard
+colors-red-green = Rot-Grün-sicher
+colors-blue-yellow = Blau-Gelb-sicher
+faction-patterns = Fraktionen an Formen erkennen
+text-scale = Textgröße
+music = Musik
+effects = Effekte
+reset-defaults = Auf Standard zurücksetzen
+key = Taste
+gamepad = Gamepad
+press-key = Drücken…
+also-bound = Auch { $actions }
+
+## Actions
+
+action-pause = Pause
+action-speed-up = Schneller
+action-slow-down = Langsamer
+action-follow = Auswahl folgen
+action-fit-all = Alles zeigen
+action-frame-selection = Auf Auswahl zoomen
+action-deselect = Abbrechen / abwählen
+action-launch = Start zielen
+action-fire = Starten
+action-aim-left = Ziel nach links drehen
+action-aim-right = Ziel nach rechts drehen
+action-aim-faster = Start schneller
+action-aim-slower = Start langsamer
+action-throttle-up = Mehr Schub
+action-throttle-down = Weniger Schub
+action-next-body = Nächster Körper
+action-previous-body = Voriger Körper
+action-palette = Zu Körper springen
+action-undo = Rückgängig
+action-redo = Wiederholen
+action-screenshot = Bildschirmfoto
+action-record-gif = GIF aufnehmen
+action-help = Hilfe
+action-console = Entwicklerkonsole
+action-profiler = Leistungsanzeige
+
+## Help
+
+help = Hilfe
+help-keys = Tasten
+help-rebind = Belegen lassen sie sich in den Einstellungen.
+help-mouse = Maus
+help-plot = Karte
+help-click = Klicken
+help-click-does = Einen Körper oder ein Schiff auswählen
+help-shift-click = Umschalt+Klicken
+help-shift-click-does = Einen Körper zur Auswahl hinzufügen oder daraus entfernen
+help-drag = Ziehen
+help-drag-does = Die Karte verschieben
+help-scroll = Scrollen oder Zwei-Finger-Zoom
+help-scroll-does = Zum Zeiger hin zoomen
+help-long-press = Lange auf einen Körper drücken
+help-long-press-does = Auf einem Touchscreen, dann ziehen, um einen Start zu zielen, und loslassen, um zu starten
+help-aim-mode = Modus Start zielen
+help-aim-mode-does = Vom ausgewählten Körper weg zeigen, klicken zum Starten
+help-burn-mode = Modus Zündung planen
+help-burn-mode-does = Auf die Bahn des ausgewählten Schiffs klicken, um eine Zündung zu setzen, und an ihren Griffen ziehen
+help-measure-mode = Modus Messen
+help-measure-mode-does = Zwei Punkte oder Körper anklicken, um dazwischen zu messen
+help-move-mode = Modus Verschieben
+help-move-mode-does = Körper verschieben, mit Umschalt ihre Geschwindigkeit setzen. Geht auch pausiert
+help-box-mode = Modus Rahmenauswahl
+help-box-mode-does = Einen Rahmen um Körper ziehen, um sie auszuwählen
+help-lagrange = × L1–L5
+help-lagrange-means = Lagrange-Punkte
+help-soi = Blasser Kreis
+help-soi-means = Einflusssphäre
+help-hill = Gepunkteter Kreis
+help-hill-means = Hill-Sphäre
+help-roche = Gestrichelter Kreis
+help-roche-means = Roche-Grenze, in der Farbe des gefährdeten Körpers
+
+## Tutorial
+
+tutorial-step = Schritt { $step } von { $count }
+tutorial-select-body = Gliblot ist dein Heimatplanet, auf dem Schiffe angedockt sind. Klicke ihn an, um ihn auszuwählen.
+tutorial-adjust-launch = Seine Karte zeigt, wie Schiffe starten. Ziehe den Regler Tempo, um zu wählen, wie schnell sie abheben.
+tutorial-launch = Stelle den Winkel Richtung Mond ein und drücke Starten, oder ziele im Modus Start zielen und klicke.
+tutorial-land = Die Schwerkraft krümmt jede Bahn. Lande ein Schiff auf dem Mond: Zu schnell, und es zerschellt, also starte ein weiteres, falls eins danebengeht.
+tutorial-done = Du bist auf dem Mond gelandet. Mehr braucht es für den Anfang nicht!
+tutorial-finish = Fertig
+tutorial-skip = Tutorial überspringen
+
+## Plot
+
+menu-bar-region = Menüleiste
+status-region = Status
+cursor-hint = Auf die Karte zeigen für Koordinaten
+energy = PE: { $potential }, KE: { $kinetic }, Gesamt: { $total }
+zero-velocity-curves-frame = Nullgeschwindigkeitskurven, System { $pair }
+target-named = Ziel: { $name }
+predicted-orbit = Vorhergesagte Bahn
+launch-aim = Startrichtung
+apsis-periapsis = Pe { $value }
+apsis-apoapsis = Ap { $value }
+closest-approach = Größte Annäherung
+closest-approach-marker = GA { $value }
+trajectory = Flugbahn
+planned-trajectory = Geplante Flugbahn
+maneuver-node = Manöverpunkt
+measurement = Messung
+measure-apart = { $value } voneinander entfernt
+measure-closing = { $relative }/s relativ, nähern sich mit { $speed }/s
+measure-separating = { $relative }/s relativ, entfernen sich mit { $speed }/s
+measure-travel-time = { $value } s bei Schiffstempo
+measure-no-crafts = keine Schiffe im Flug zum Messen
+unowned = Herrenlos
+
+## Interaction modes
+
+mode-inspect = Untersuchen
+mode-aim-launch = Start zielen
+mode-plan-burn = Zündung planen
+mode-measure = Messen
+mode-move = Verschieben
+mode-box-select = Rahmenauswahl
+
+## Bodies
+
+faction-player = Du
+faction-opponent = Gegner
+neutral = Neutral
+owner = Besitzer: { $owner }
+bodies = Körper
+readout-mass = Masse: { $value }
+readout-radius = Radius: { $value }
+readout-speed = Tempo: { $value }
+readout-crafts = Schiffe: { $value }
+selected-body = Ausgewählter Körper: { $name }
+production = Produktion: { $value } Schiffe/min
+production-unowned = Produktion: { $value } Schiffe/min, sobald in deinem Besitz
+density = Dichte:
+on-rails = Auf Schienen
+on-rails-hint = Einer festen Bahn folgen, die nichts anderes stört
+kinetic-energy = Kinetische Energie: { $value }
+not-orbiting = Umkreist nichts Schwereres
+orbiting = Umkreist { $body }
+semi-major-axis = Große Halbachse: { $value }
+eccentricity = Exzentrizität: { $value }
+periapsis = Periapsis: { $value }
+apoapsis = Apoapsis: { $value }
+period = Umlaufzeit: { $value } s
+escaping = Entkommt (ungebunden)
+set-target = Als Ziel setzen
+clear-target = Ziel aufheben
+delete-body = Körper löschen
+docked-crafts = Angedockte Schiffe: { $value }
+only-owned-launch = Nur Körper in deinem Besitz können Schiffe starten
+bodies-selected = { $count } Körper
+total-mass = Gesamtmasse: { $value }
+total-crafts = Schiffe insgesamt: { $value }
+barycenter = Schwerpunkt: ({ $x }; { $y })
+launch-from-each = Von jedem 1 Schiff starten ({ $count })
+launch-from-each-hint = Richtung Ziel, mit dem für einzelne Körper eingestellten Starttempo
+launch-from-each-disabled = Braucht ein Ziel und einen ausgewählten eigenen Körper mit Schiffen
+
+## Body and craft lists
+
+crafts-tab = Schiffe ({ $count })
+filter-by-name = Nach Namen filtern
+click-to-sort = Zum Sortieren klicken
+distance-from = Von { $body }
+column-name = Name
+column-mass = Masse
+column-radius = Radius
+column-crafts = Schiffe
+column-owner = Besitzer
+column-distance = Entfernung
+column-origin = Herkunft
+column-destination = Ziel
+column-fuel = Treibstoff
+column-eta = Ankunft
+eta-hint = Bis der antriebslose Flug einen Körper erreicht
+body-summary = { $name }, Masse { $mass }, Radius { $radius }, { $crafts ->
+    [one] { $crafts } Schiff
+   *[other] { $crafts } Schiffe
+}, { $owner }
+no-crafts-in-flight = Keine Schiffe im Flug
+seconds = { $value } s
+
+## Crafts
+
+craft-coasting = Antriebslos
+craft-burning = Zündet
+craft-out-of-fuel = Kein Treibstoff
+craft-class = Klasse: { $class }
+craft-destination = Ziel: { $body }
+closest-approach-readout = Größte Annäherung: { $approach }
+after-maneuver = Nach dem Manöver: { $approach }
+impact-in = Aufschlag in { $time } s
+approach-in = { $distance } in { $time } s
+landing-on = Landung auf { $body }: { $speed } von { $max }
+approach-angle = Anflug: { $angle }° von { $max }°
+target-distance = Ziel: { $target }, { $distance } entfernt
+relative-velocity = Relativgeschwindigkeit: { $value }
+delta-v-remaining = Verbleibendes Δv: { $value }
+hull-heat = Hüllenhitze: { $value } %
+craft-assists = Swing-bys: { $count } (+{ $delta_v })
+craft-log = Logbuch
+loading-craft-classes = Schiffsklassen werden geladen…
+destination = Ziel
+target = Ziel
+speed = Tempo
+delta-v-after-launch = Δv nach dem Start: { $value }
+angle = Winkel°
+launch-class = { $class } starten
+direction = Richtung
+burn = Zünden
+burn-prograde = Prograd
+burn-retrograde = Retrograd
+burn-left = Links
+burn-right = Rechts
+radial = Radial
+maneuver-hint = Mit { $mode } einen Manöverpunkt setzen
+maneuver-in = Manöver in { $value } s
+maneuver-delta-v = Δv { $value }
+maneuver-not-enough-fuel = Δv { $value }: nicht genug Treibstoff
+remove-node = Punkt entfernen
+autopilot = Autopilot
+off = Aus
+autopilot-hold-orbit = Umlaufbahn halten
+autopilot-intercept = Ziel abfangen
+autopilot-return-home = Heimkehren
+autopilot-station-keep = Position bei L{ $point } halten
+thrust = Schub
+throttle = Schubregler
+acceleration = Beschleunigung: { $value }
+solar-sail = Sonnensegel
+solar-sail-hint = Vom Licht des Sterns weggedrückt, ohne Treibstoff zu verbrauchen
+sail-push = Segelschub: { $value }
+not-enough-fuel = Nicht genug Treibstoff
+insertion = Bahneintritt
+insertion-auto-execute = Zündungen automatisch ausführen
+insertion-orbit = { $body } umkreisen in { $time } s
+insertion-landing = Auf { $body } landen in { $time } s
+insertion-delta-v = Δv { $delta_v } von { $fuel } Treibstoff
+insertion-cancel = Zündung abbrechen
+insertion-commit = Zündung festlegen
+
+## Physics constants
+
+constant-density = Standarddichte
+constant-softening = Glättung
+constant-breakup-energy = Bruchenergie
+constant-kepler-threshold = Kepler-Schwelle
+constant-kepler-threshold-hint = So wenig gestörte Körper folgen exakten Bahnen. 0 schaltet es aus.
+constant-step-tolerance = Schritttoleranz
+constant-step-tolerance-hint = Teilt Ticks bei nahen Begegnungen in Teilschritte. 0 schaltet es aus.
+substeps = { $count ->
+    [one] { $count } Teilschritt
+   *[other] { $count } Teilschritte
+}
+constant-frame = Bezugssystem
+constant-frame-hint = Verhindert, dass das System über lange Zeit davondriftet
+frame-zero-momentum = Gesamtimpuls null
+frame-recenter = Auf Schwerpunkt zentrieren
+gravity-solver = Gravitationslöser
+solver-cpu = Direkt (CPU)
+solver-gpu = Direkt (GPU)
+
+## Scoreboard
+
+docked = Angedockt
+in-flight = Im Flug
+
+## Events
+
+event-log-empty = Noch ist nichts passiert
+event-launched = { $craft } gestartet
+event-landed = Ein Schiff ist auf { $body } gelandet
+event-crashed = Ein Schiff ist mit { $speed } auf { $body } zerschellt
+event-captured = { $body } erobert von: { $faction }
+event-torn-apart = { $lost } wurde von den Gezeiten von { $other } zerrissen
+event-shattered = { $lost } ist an { $other } zerborsten
+event-merged = { $lost } ist mit { $other } verschmolzen
+event-assist = { $craft } hat { $delta_v } durch einen Swing-by an { $body } gewonnen
+no-matching-bodies = Keine passenden Körper
+
+## Game over
+
+victory = Sieg
+victory-eliminated = Der Gegner hat nichts mehr.
+victory-every-body = Dir gehört jeder Körper im System.
+victory-fleet = Du hast die angestrebte Flotte aufgebaut.
+defeat = Niederlage
+defeat-every-body = Der Gegner hat jeden deiner Körper erobert.
+defeat-opponent-won = Der Gegner hat sein Ziel erreicht.
+out-of-crafts = Keine Schiffe mehr
+out-of-crafts-details = Jedes Schiff ist verloren oder verbraucht.
+level-complete = Level geschafft
+level-reached = { $level }: { $goal } erreicht.
+
+## Scenarios and mods
+
+picker-scenarios = Szenarien
+picker-scenario = Szenario
+picker-built-in = Eingebaut
+picker-imported = Importiert: { $file }
+picker-chaos = Chaos
+picker-session = Spieldauer
+picker-divergence = Störungen wachsen um { $rate }/s
+picker-minutes = ~{ $minutes } min
+picker-invalid = Ungültig
+picker-refresh = Aktualisieren
+picker-import = Importieren…
+picker-import-hint = Eine Szenariodatei von anderswo laden
+chaos-calm = Ruhig
+chaos-lively = Lebhaft
+chaos-chaotic = Chaotisch
+loading = Wird geladen…
+load = Laden
+cancel = Abbrechen
+copy = Kopieren
+link-too-long = Zu viel für einen Link; exportiere es stattdessen als Datei
+link-not-made = Kein Link erstellt: { $error }
+scenario-saved = Szenario gespeichert unter { $path }
+scenario-not-saved = Szenario nicht gespeichert: { $error }
+share-link-explanation = Wer diesen Link öffnet, beginnt mit dem System in seinem jetzigen Zustand.
+drop-title = Abgelegte Datei laden?
+drop-warning = { $file } zu laden beendet das laufende Spiel.
+mods-title = Mods
+mods-none = Keine Pakete in { $path }. Lege dort einen Ordner pro Paket an und suche erneut.
+mods-by = von { $author }
+mods-version-by = { $version } von { $author }
+mods-invalid = Ungültige mod.toml
+mods-rescan = Erneut suchen
+
+## Online
+
+net-online-match = Online-Partie
+net-address-hint = Adresse, auf der gehostet wird, oder des Hosts zum Beitreten
+net-host = Hosten
+net-join = Beitreten
+net-waiting-for-player = Warte auf einen Spieler unter { $address }
+net-waiting-for-host = Warte darauf, dass der Host startet
+net-host-failed = Hosten fehlgeschlagen: { $error }
+net-join-failed = Beitreten fehlgeschlagen: { $error }
+net-stopped-hosting = Hosten beendet: { $error }
+net-disconnected = Getrennt: { $error }
+net-connection-lost = Verbindung verloren
+
+## While away
+
+idle-title = Während du weg warst
+idle-paused = Die Simulation wurde wegen Untätigkeit pausiert.
+idle-simulated = { $seconds } s simuliert
+idle-crafts-changed = { $name }: Schiffe { $before } → { $after }
+idle-appeared = { $name } ist aufgetaucht
+idle-lost = { $name } ging verloren
+idle-energy-drift = Gesamtenergie hat sich um { $drift } geändert
+
+## Performance overlay
+
+profiler = Leistung
+profiler-frames = { $fps } FPS, { $average } ms pro Frame, schlechtester { $worst } ms
+profiler-gravity = Gravitation
+profiler-motion = Bewegung
+profiler-ui = Karte und Fenster
+profiler-crafts = Schiffe
+profiler-test-particles = Testteilchen
+profiler-entities = Entitäten
+profiler-frame-time = Framezeit
+
+## Watchdog
+
+watchdog-unnamed = Etwas
+watchdog-rewound = { $name } ist aus dem Ruder gelaufen; { $seconds } s zurückgespult
+watchdog-removed = { $name } ist aus dem Ruder gelaufen und wurde entfernt
@@ -0,0 +1,508 @@
+# English UI text, which other languages fall back to for anything they
+# haven't translated. Ids are grouped by where they're shown.
+
+## Main menu and screens
+
+tutorial = Tutorial
+tutorial-hint = Learn to launch a craft and land it on a moon
+campaign = Campaign
+level-locked = Finish the previous level first
+sandbox = Sandbox
+paused = Paused
+resume-hint = Press { $key } to resume.
+resume = Resume
+main-menu = Main menu
+time = Time
+delta-v-used = Delta-v used
+gravity-assists = Gravity assists
+crafts-lost = Crafts lost
+next-level = Next level
+keep-playing = Keep playing
+restart = Restart
+
+## Menu bar
+
+menu-game = Game
+scenarios = Scenarios…
+mods = Mods…
+open-log-folder = Open log folder
+export-replay = Export replay
+export-replay-hint = Save every order so far to share this game
+replay-saved = Replay saved to { $path }
+replay-not-saved = Replay not saved: { $error }
+export-scenario = Export scenario
+export-scenario-hint = Save the system as it is now as a new starting scenario
+with-crafts = With crafts
+share-link = Share link
+share-link-hint = Make a link that opens the system as it is now in a browser
+screenshot = Screenshot
+record-gif = Record GIF
+stop-recording-gif = Stop recording GIF
+pause-when-idle = Pause when idle
+idle-timeout = Idle timeout (s)
+combat = Combat
+combat-attrition = Attrition
+combat-duels = Duels
+defender-advantage = Defender advantage
+hot-seat = Two players (hot seat)
+hot-seat-hint = A second player takes over the opponent, taking turns
+menu-edit = Edit
+undo = Undo { $edit }
+redo = Redo { $edit }
+edit-add = add { $body }
+edit-delete = delete { $body }
+edit-density = { $body }'s density
+edit-move = move { $body }
+edit-velocity = { $body }'s velocity
+edit-on-rails = put { $body } on rails
+edit-off-rails = take { $body } off rails
+add-body = Add body
+menu-view = View
+spheres-of-influence = Spheres of influence
+lagrange-points = Lagrange points
+zero-velocity-curves = Zero-velocity curves
+hill-spheres = Hill spheres
+roche-limits = Roche limits
+scoreboard = Scoreboard
+event-log = Event log
+statistics = Statistics
+go-to-body = Go to body…
+patched-conics = Patched-conic trajectories
+patched-conics-hint = Faster, longer-range but approximate predictions
+menu-debug = Debug
+physics-constants = Physics constants
+record-telemetry = Record telemetry
+record-telemetry-hint = Sample every body's motion and energy for export
+sample-interval = Sample interval (s)
+samples = { $count ->
+    [one] { $count } sample
+   *[other] { $count } samples
+}
+export-format = Export { $format }
+telemetry-saved = Telemetry saved to { $path }
+replay-orders-left = Replay: { $count } orders left
+replay-orders-left-hint = Any orders you give now are added to the replay
+turn = Turn: { $player }
+seconds-left = { $seconds } s left
+end-turn = End turn
+help-hint = Help ({ $key })
+settings = Settings
+
+## Plot overlays
+
+grid = Grid
+axes = Axes
+body-names = Body names
+craft-counts = Craft counts
+center-of-mass = Center of mass
+trails = Trails
+craft-legend = Craft legend
+starfield = Starfield
+night-sides = Night sides
+gravity-wells = Gravity wells
+
+## Statistics
+
+crafts-launched = Crafts launched
+crafts-landed = Crafts landed
+crafts-crashed = Crafts crashed
+delta-v-spent = Δv spent
+biggest-assist = Biggest assist
+closest-flyby = Closest flyby
+collisions = Collisions
+simulated-time = Simulated time
+past-crafts = Past crafts ({ $count })
+
+## Settings
+
+settings-general = General
+settings-keys = Keys
+language = Language
+theme = Theme
+theme-system = System
+theme-light = Light
+theme-dark = Dark
+trail-length = Trail length
+trail-length-hint = 0 hides trails
+vectors = Vectors
+velocity = Velocity
+particles = Particles
+particles-low = Low
+particles-high = High
+sim-speed = Sim speed
+sim-speed-hint = Also used the next time the game starts
+ui-scale = UI scale
+colors = Colors
+colors-standard = Standard
+colors-red-green = Red-green safe
+colors-blue-yellow = Blue-yellow safe
+faction-patterns = Mark factions by shape
+text-scale = Text size
+music = Music
+effects = Effects
+reset-defaults = Reset to defaults
+key = Key
+gamepad = Gamepad
+press-key = Press…
+also-bound = Also { $actions }
+
+## Actions
+
+action-pause = Pause
+action-speed-up = Speed up
+action-slow-down = Slow down
+action-follow = Follow selection
+action-fit-all = Zoom to fit
+action-frame-selection = Zoom to selection
+action-deselect = Cancel / deselect
+action-launch = Aim launch
+action-fire = Launch
+action-aim-left = Turn aim left
+action-aim-right = Turn aim right
+action-aim-faster = Aim faster
+action-aim-slower = Aim slower
+action-throttle-up = Throttle up
+action-throttle-down = Throttle down
+action-next-body = Next body
+action-previous-body = Previous body
+action-palette = Go to body
+action-undo = Undo
+action-redo = Redo
+action-screenshot = Screenshot
+action-record-gif = Record GIF
+action-help = Help
+action-console = Developer console
+action-profiler = Performance overlay
+
+## Help
+
+help = Help
+help-keys = Keys
+help-rebind = Rebind them in the settings.
+help-mouse = Mouse
+help-plot = Plot
+help-click = Click
+help-click-does = Select a body or craft
+help-shift-click = Shift+click
+help-shift-click-does = Add a body to the selection, or take it out
+help-drag = Drag
+help-drag-does = Pan the plot
+help-scroll = Scroll or pinch
+help-scroll-does = Zoom toward the pointer
+help-long-press = Long press a body
+help-long-press-does = On a touchscreen, then drag to aim a launch from it and lift to launch
+help-aim-mode = Aim launch mode
+help-aim-mode-does = Point away from the selected body, click to launch
+help-burn-mode = Plan burn mode
+help-burn-mode-does = Click the selected craft's path to place a burn, drag its handles to set it
+help-measure-mode = Measure mode
+help-measure-mode-does = Click two points or bodies to measure between
+help-move-mode = Move mode
+help-move-mode-does = Drag bodies around, with Shift to set their velocity. Works while paused too
+help-box-mode = Box select mode
+help-box-mode-does = Drag a box around bodies to select them
+help-lagrange = × L1–L5
+help-lagrange-means = Lagrange points
+help-soi = Faint circle
+help-soi-means = Sphere of influence
+help-hill = Dotted circle
+help-hill-means = Hill sphere
+help-roche = Dashed circle
+help-roche-means = Roche limit, in the color of the body at risk
+
+## Tutorial
+
+tutorial-step = Step { $step } of { $count }
+tutorial-select-body = Gliblot is your home planet, with crafts docked on it. Click it to select it.
+tutorial-adjust-launch = Its card shows how crafts launch. Drag the Speed slider to choose how fast they leave.
+tutorial-launch = Set the Angle toward the Moon and press Launch, or use the Aim launch mode to point and click.
+tutorial-land = Gravity bends every path. Land a craft on the Moon: arrive too fast and it crashes, so launch another if one misses.
+tutorial-done = You landed on the Moon. That's all there is to get started!
+tutorial-finish = Finish
+tutorial-skip = Skip tutorial
+
+## Plot
+
+menu-bar-region = Menu bar
+status-region = Status
+cursor-hint = Point at the plot for coordinates
+energy = PE: { $potential }, KE: { $kinetic }, Total: { $total }
+zero-velocity-curves-frame = Zero-velocity curves, { $pair } frame
+target-named = Target: { $name }
+predicted-orbit = Predicted orbit
+launch-aim = Launch aim
+apsis-periapsis = Pe { $value }
+apsis-apoapsis = Ap { $value }
+closest-approach = Closest approach
+closest-approach-marker = CA { $value }
+trajectory = Trajectory
+planned-trajectory = Planned trajectory
+maneuver-node = Maneuver node
+measurement = Measurement
+measure-apart = { $value } apart
+measure-closing = { $relative }/s relative, closing at { $speed }/s
+measure-separating = { $relative }/s relative, separating at { $speed }/s
+measure-travel-time = { $value }s at craft speed
+measure-no-crafts = no crafts in flight to time
+unowned = Unowned
+
+## Interaction modes
+
+mode-inspect = Inspect
+mode-aim-launch = Aim launch
+mode-plan-burn = Plan burn
+mode-measure = Measure
+mode-move = Move
+mode-box-select = Box select
+
+## Bodies
+
+faction-player = You
+faction-opponent = Opponent
+neutral = Neutral
+owner = Owner: { $owner }
+bodies = Bodies
+readout-mass = Mass: { $value }
+readout-radius = Radius: { $value }
+readout-speed = Speed: { $value }
+readout-crafts = Crafts: { $value }
+selected-body = Selected body: { $name }
+production = Production: { $value } crafts/min
+production-unowned = Production: { $value } crafts/min once owned
+density = Density:
+on-rails = On rails
+on-rails-hint = Follow a fixed orbit that nothing else disturbs
+kinetic-energy = Kinetic Energy: { $value }
+not-orbiting = Not orbiting anything heavier
+orbiting = Orbiting { $body }
+semi-major-axis = Semi-major axis: { $value }
+eccentricity = Eccentricity: { $value }
+periapsis = Periapsis: { $value }
+apoapsis = Apoapsis: { $value }
+period = Period: { $value }s
+escaping = Escaping (unbound)
+set-target = Set as target
+clear-target = Clear target
+delete-body = Delete body
+docked-crafts = Docked crafts: { $value }
+only-owned-launch = Only bodies you own can launch crafts
+bodies-selected = { $count } bodies
+total-mass = Total mass: { $value }
+total-crafts = Total crafts: { $value }
+barycenter = Barycenter: ({ $x }, { $y })
+launch-from-each = Launch 1 craft from each ({ $count })
+launch-from-each-hint = Toward the target, at the launch speed set for single bodies
+launch-from-each-disabled = Needs a target, and a selected body of yours with crafts
+
+## Body and craft lists
+
+crafts-tab = Crafts ({ $count })
+filter-by-name = Filter by name
+click-to-sort = Click to sort
+distance-from = From { $body }
+column-name = Name
+column-mass = Mass
+column-radius = Radius
+column-crafts = Crafts
+column-owner = Owner
+column-distance = Distance
+column-origin = Origin
+column-destination = Destination
+column-fuel = Fuel
+column-eta = ETA
+eta-hint = Until the coast reaches a body
+body-summary = { $name }, mass { $mass }, radius { $radius }, { $crafts ->
+    [one] { $crafts } craft
+   *[other] { $crafts } crafts
+}, { $owner }
+no-crafts-in-flight = No crafts in flight
+seconds = { $value }s
+
+## Crafts
+
+craft-coasting = Coasting
+craft-burning = Burning
+craft-out-of-fuel = Out of fuel
+craft-class = Class: { $class }
+craft-destination = Destination: { $body }
+closest-approach-readout = Closest approach: { $approach }
+after-maneuver = After maneuver: { $approach }
+impact-in = impact in { $time }s
+approach-in = { $distance } in { $time }s
+landing-on = Landing on { $body }: { $speed } of { $max }
+approach-angle = Approach: { $angle }° of { $max }°
+target-distance = Target: { $target }, { $distance } away
+relative-velocity = Relative velocity: { $value }
+delta-v-remaining = Δv remaining: { $value }
+hull-heat = Hull heat: { $value }%
+craft-assists = Gravity assists: { $count } (+{ $delta_v })
+craft-log = Log
+loading-craft-classes = Loading craft classes…
+destination = Destination
+target = Target
+speed = Speed
+delta-v-after-launch = Δv after launch: { $value }
+angle = Angle°
+launch-class = Launch { $class }
+direction = Direction
+burn = Burn
+burn-prograde = Prograde
+burn-retrograde = Retrograde
+burn-left = Left
+burn-right = Right
+radial = Radial
+maneuver-hint = Use { $mode } to place a maneuver node
+maneuver-in = Maneuver in { $value }s
+maneuver-delta-v = Δv { $value }
+maneuver-not-enough-fuel = Δv { $value }: not enough fuel
+remove-node = Remove node
+autopilot = Autopilot
+off = Off
+autopilot-hold-orbit = Hold orbit
+autopilot-intercept = Intercept target
+autopilot-return-home = Return home
+autopilot-station-keep = Station-keep at L{ $point }
+thrust = Thrust
+throttle = Throttle
+acceleration = Acceleration: { $value }
+solar-sail = Solar sail
+solar-sail-hint = Pushed away from the star by its light, without spending fuel
+sail-push = Sail push: { $value }
+not-enough-fuel = Not enough fuel
+insertion = Orbit insertion
+insertion-auto-execute = Auto-execute burns
+insertion-orbit = Orbit { $body } in { $time }s
+insertion-landing = Land on { $body } in { $time }s
+insertion-delta-v = Δv { $delta_v } of { $fuel } fuel
+insertion-cancel = Cancel burn
+insertion-commit = Commit burn
+
+## Physics constants
+
+constant-density = Default density
+constant-softening = Softening
+constant-breakup-energy = Breakup energy
+constant-kepler-threshold = Kepler threshold
+constant-kepler-threshold-hint = Bodies this little perturbed follow exact orbits. 0 is off.
+constant-step-tolerance = Step tolerance
+constant-step-tolerance-hint = Splits ticks into substeps during close encounters. 0 is off.
+substeps = { $count ->
+    [one] { $count } substep
+   *[other] { $count } substeps
+}
+constant-frame = Frame
+constant-frame-hint = Keeps the system from drifting off over long runs
+frame-zero-momentum = Zero momentum
+frame-recenter = Recenter on barycenter
+gravity-solver = Gravity solver
+solver-cpu = Direct (CPU)
+solver-gpu = Direct (GPU)
+
+## Scoreboard
+
+docked = Docked
+in-flight = In flight
+
+## Events
+
+event-log-empty = Nothing has happened yet
+event-launched = { $craft } launched
+event-landed = A craft landed on { $body }
+event-crashed = A craft crashed into { $body } at { $speed }
+event-captured = { $faction } captured { $body }
+event-torn-apart = { $lost } was torn apart by { $other }'s tides
+event-shattered = { $lost } shattered against { $other }
+event-merged = { $lost } merged into { $other }
+event-assist = { $craft } gained { $delta_v } from a gravity assist at { $body }
+no-matching-bodies = No matching bodies
+
+## Game over
+
+victory = Victory
+victory-eliminated = The opponent has nothing left.
+victory-every-body = You own every body in the system.
+victory-fleet = You built up the fleet you were after.
+defeat = Defeat
+defeat-every-body = The opponent took every body you held.
+defeat-opponent-won = The opponent met their goal.
+out-of-crafts = Out of crafts
+out-of-crafts-details = Every craft has been lost or used up.
+level-complete = Level complete
+level-reached = { $level } reached { $goal }.
+
+## Scenarios and mods
+
+picker-scenarios = Scenarios
+picker-scenario = Scenario
+picker-built-in = Built-in
+picker-imported = Imported: { $file }
+picker-chaos = Chaos
+picker-session = Session
+picker-divergence = Perturbations grow by { $rate }/s
+picker-minutes = ~{ $minutes } min
+picker-invalid = Invalid
+picker-refresh = Refresh
+picker-import = Import…
+picker-import-hint = Load a scenario file from elsewhere
+chaos-calm = Calm
+chaos-lively = Lively
+chaos-chaotic = Chaotic
+loading = Loading…
+load = Load
+cancel = Cancel
+copy = Copy
+link-too-long = Too much to fit in a link; export it as a file instead
+link-not-made = No link made: { $error }
+scenario-saved = Scenario saved to { $path }
+scenario-not-saved = Scenario not saved: { $error }
+share-link-explanation = Anyone opening this link starts from the system as it is now.
+drop-title = Load dropped file?
+drop-warning = Loading { $file } ends the game in progress.
+mods-title = Mods
+mods-none = No packs in { $path }. Add a folder per pack there and rescan.
+mods-by = by { $author }
+mods-version-by = { $version } by { $author }
+mods-invalid = Invalid mod.toml
+mods-rescan = Rescan
+
+## Online
+
+net-online-match = Online match
+net-address-hint = Address to host on, or of the host to join
+net-host = Host
+net-join = Join
+net-waiting-for-player = Waiting for a player on { $address }
+net-waiting-for-host = Waiting for the host to start
+net-host-failed = Couldn't host: { $error }
+net-join-failed = Couldn't join: { $error }
+net-stopped-hosting = Stopped hosting: { $error }
+net-disconnected = Disconnected: { $error }
+net-connection-lost = Connection lost
+
+## While away
+
+idle-title = While you were away
+idle-paused = The simulation was paused for inactivity.
+idle-simulated = Simulated { $seconds }s
+idle-crafts-changed = { $name }: crafts { $before } → { $after }
+idle-appeared = { $name } appeared
+idle-lost = { $name } was lost
+idle-energy-drift = Total energy changed by { $drift }
+
+## Performance overlay
+
+profiler = Performance
+profiler-frames = { $fps } FPS, { $average } ms per frame, worst { $worst } ms
+profiler-gravity = Gravity
+profiler-motion = Motion
+profiler-ui = Plot and panels
+profiler-crafts = Crafts
+profiler-test-particles = Test particles
+profiler-entities = Entities
+profiler-frame-time = Frame time
+
+## Watchdog
+
+watchdog-unnamed = Something
+watchdog-rewound = { $name } went off the charts; rewound { $seconds }s
+watchdog-removed = { $name } went off the charts and was removed
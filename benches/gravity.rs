@@ -0,0 +1,61 @@
+use bevy::math::DVec3;
+use bevy::tasks::{ComputeTaskPool, TaskPool};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use slingcraft::physics::{PhysicsConstants, ScratchBody, pairwise_gravity, step_scratch};
+use std::hint::black_box;
+
+const SIZES: [usize; 3] = [100, 500, 2000];
+
+/// `count` bodies scattered over a disc the size of a busy system.
+fn bodies(count: usize) -> Vec<ScratchBody> {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    (0..count)
+        .map(|_| ScratchBody {
+            position: DVec3::new(
+                rng.random_range(-500.0..500.),
+                rng.random_range(-500.0..500.),
+                0.,
+            ),
+            velocity: DVec3::new(rng.random_range(-1.0..1.), rng.random_range(-1.0..1.), 0.),
+            mass: rng.random_range(1.0..100.),
+        })
+        .collect()
+}
+
+fn gravity(c: &mut Criterion) {
+    let constants = PhysicsConstants::default();
+    let softening = constants.softening as f64;
+    let g = constants.g as f64;
+    let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+
+    let mut group = c.benchmark_group("pairwise_gravity");
+    for count in SIZES {
+        let masses: Vec<_> = bodies(count)
+            .iter()
+            .map(|body| (body.position, body.mass))
+            .collect();
+        group.bench_with_input(
+            BenchmarkId::new("sequential", count),
+            &masses,
+            |b, masses| b.iter(|| pairwise_gravity(black_box(masses), softening, g, None)),
+        );
+        group.bench_with_input(BenchmarkId::new("parallel", count), &masses, |b, masses| {
+            b.iter(|| pairwise_gravity(black_box(masses), softening, g, Some(pool)))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("step_scratch");
+    for count in SIZES {
+        group.bench_function(BenchmarkId::from_parameter(count), |b| {
+            let mut scratch = bodies(count);
+            b.iter(|| step_scratch(black_box(&mut scratch), &constants, 1. / 64.))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, gravity);
+criterion_main!(benches);
@@ -0,0 +1,125 @@
+use crate::i18n::tr;
+use bevy_egui::egui::{Color32, ecolor::Hsva};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Colors for bodies and factions, with schemes that stay apart for players
+/// who can't tell some hues apart.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum ColorScheme {
+    /// Scenario colors as written.
+    #[default]
+    Standard,
+    /// For protanopia and deuteranopia, from the Okabe-Ito palette.
+    RedGreen,
+    /// For tritanopia.
+    BlueYellow,
+}
+
+impl ColorScheme {
+    pub const ALL: [Self; 3] = [Self::Standard, Self::RedGreen, Self::BlueYellow];
+
+    pub fn label(self) -> String {
+        tr(match self {
+            Self::Standard => "colors-standard",
+            Self::RedGreen => "colors-red-green",
+            Self::BlueYellow => "colors-blue-yellow",
+        })
+    }
+
+    /// What saturated body colors snap to, `None` to keep them as they are.
+    fn palette(self) -> Option<&'static [Color32]> {
+        match self {
+            Self::Standard => None,
+            Self::RedGreen => Some(&[
+                Color32::from_rgb(230, 159, 0),
+                Color32::from_rgb(86, 180, 233),
+                Color32::from_rgb(0, 158, 115),
+                Color32::from_rgb(240, 228, 66),
+                Color32::from_rgb(0, 114, 178),
+                Color32::from_rgb(213, 94, 0),
+                Color32::from_rgb(204, 121, 167),
+            ]),
+            Self::BlueYellow => Some(&[
+                Color32::from_rgb(220, 50, 32),
+                Color32::from_rgb(0, 160, 160),
+                Color32::from_rgb(255, 140, 170),
+                Color32::from_rgb(150, 225, 225),
+                Color32::from_rgb(140, 30, 70),
+            ]),
+        }
+    }
+
+    /// The player's and the opponent's colors.
+    fn factions(self) -> [Color32; 2] {
+        match self {
+            Self::Standard => [
+                Color32::from_rgb(90, 200, 255),
+                Color32::from_rgb(255, 110, 70),
+            ],
+            Self::RedGreen => [
+                Color32::from_rgb(86, 180, 233),
+                Color32::from_rgb(230, 159, 0),
+            ],
+            Self::BlueYellow => [
+                Color32::from_rgb(0, 170, 170),
+                Color32::from_rgb(230, 75, 60),
+            ],
+        }
+    }
+}
+
+/// The scheme colors are drawn in, as an index into [`ColorScheme::ALL`], and
+/// whether factions are told apart by pattern too. Global like the language,
+/// so colors can be looked up wherever they're drawn.
+static SCHEME: AtomicUsize = AtomicUsize::new(0);
+static PATTERNS: AtomicBool = AtomicBool::new(false);
+
+pub fn color_scheme() -> ColorScheme {
+    ColorScheme::ALL[SCHEME.load(Ordering::Relaxed)]
+}
+
+pub fn set_color_scheme(scheme: ColorScheme) {
+    let index = ColorScheme::ALL
+        .iter()
+        .position(|&other| other == scheme)
+        .unwrap_or_default();
+    SCHEME.store(index, Ordering::Relaxed);
+}
+
+/// Whether factions are marked with line patterns and symbols as well as
+/// colors.
+pub fn faction_patterns() -> bool {
+    PATTERNS.load(Ordering::Relaxed)
+}
+
+pub fn set_faction_patterns(enabled: bool) {
+    PATTERNS.store(enabled, Ordering::Relaxed);
+}
+
+/// `fill` as the current scheme draws it: grays stay, and other colors snap
+/// to the palette color nearest in hue, keeping their transparency.
+pub fn body_color(fill: Color32) -> Color32 {
+    let Some(palette) = color_scheme().palette() else {
+        return fill;
+    };
+    let hsva = Hsva::from(fill);
+    if hsva.s < 0.2 || hsva.v < 0.15 {
+        return fill;
+    }
+    let hue_distance = |color: &&Color32| {
+        let difference = (Hsva::from(**color).h - hsva.h).abs();
+        difference.min(1. - difference)
+    };
+    let nearest = palette
+        .iter()
+        .min_by(|a, b| hue_distance(a).total_cmp(&hue_distance(b)))
+        .copied()
+        .unwrap_or(fill);
+    Color32::from_rgba_unmultiplied(nearest.r(), nearest.g(), nearest.b(), fill.a())
+}
+
+/// The player's color, then the opponent's, in the current scheme.
+pub fn faction_colors() -> [Color32; 2] {
+    color_scheme().factions()
+}
@@ -0,0 +1,101 @@
+use crate::bodies::Body;
+use crate::crafts::{Craft, Crafts};
+use crate::factions::{Owner, authoritative};
+use crate::i18n::tr;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+
+/// The app's top-level state machine. The simulation only advances while
+/// [`AppState::Playing`], and stops once there's nothing left to play with.
+pub struct AppStatePlugin {
+    pub initial: AppState,
+}
+
+impl Default for AppStatePlugin {
+    fn default() -> Self {
+        Self {
+            initial: AppState::Menu,
+        }
+    }
+}
+
+impl Plugin for AppStatePlugin {
+    fn build(&self, app: &mut App) {
+        // Headless apps built on MinimalPlugins have no state machinery yet
+        if !app.is_plugin_added::<StatesPlugin>() {
+            app.add_plugins(StatesPlugin);
+        }
+        app.insert_state(self.initial)
+            .init_resource::<GameOutcome>()
+            .add_systems(OnEnter(AppState::Playing), resume_time)
+            // The app can start in the menu without ever having played
+            .add_systems(OnEnter(AppState::Menu), pause_time)
+            .add_systems(OnExit(AppState::Playing), pause_time)
+            .add_systems(
+                FixedPostUpdate,
+                detect_out_of_crafts
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(authoritative),
+            );
+    }
+}
+
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppState {
+    /// The main menu, with the simulation frozen behind it.
+    #[default]
+    Menu,
+    Playing,
+    /// Frozen, with the plot still visible for planning.
+    Paused,
+    /// A level was won or lost; shows the [`GameOutcome`].
+    GameOver,
+}
+
+impl AppState {
+    /// Whether the plot and its tools are shown.
+    pub fn in_game(state: Option<Res<State<Self>>>) -> bool {
+        state.is_some_and(|state| matches!(state.get(), Self::Playing | Self::Paused))
+    }
+}
+
+/// Why the game ended, for the summary screen.
+#[derive(Resource, Default)]
+pub struct GameOutcome {
+    pub won: bool,
+    pub title: String,
+    pub details: Vec<String>,
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+/// Ends the game once every craft is gone, none docked, none in flight, and
+/// no owned body is left to build more.
+fn detect_out_of_crafts(
+    bodies: Query<(&Crafts, Option<&Owner>), With<Body>>,
+    crafts: Query<(), With<Craft>>,
+    mut outcome: ResMut<GameOutcome>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    // Nothing is docked before a scenario finishes loading
+    if bodies.is_empty()
+        || !crafts.is_empty()
+        || bodies
+            .iter()
+            .any(|(crafts, owner)| crafts.0 > 0 || owner.is_some())
+    {
+        return;
+    }
+    *outcome = GameOutcome {
+        won: false,
+        title: tr("out-of-crafts"),
+        details: vec![tr("out-of-crafts-details")],
+    };
+    next_state.set(AppState::GameOver);
+}
@@ -0,0 +1,115 @@
+use crate::app_state::AppState;
+use crate::bodies::Body;
+use crate::crafts::{Craft, Fuel};
+use crate::influence::{SphereOfInfluence, governing_body};
+use crate::maneuver::scratch_bodies;
+use crate::physics::{Mass, SimPosition, SimVelocity};
+use bevy::prelude::*;
+
+/// Spots crafts gaining speed from close flybys and tallies the gains.
+pub struct AssistsPlugin;
+
+impl Plugin for AssistsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GravityAssist>().add_systems(
+            FixedPostUpdate,
+            track_flybys.run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+/// Smallest speed gain, relative to the heaviest body, that counts as an
+/// assist.
+const MIN_ASSIST: f64 = 1.;
+
+/// A craft sped up by passing through a body's sphere of influence without
+/// burning.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct GravityAssist {
+    pub craft: Entity,
+    pub body: Entity,
+    /// Speed gained relative to the heaviest body.
+    pub delta_v: f32,
+}
+
+/// Gravity assists a craft has received.
+#[derive(Component, Default)]
+pub struct AssistTally {
+    pub count: u32,
+    pub delta_v: f32,
+}
+
+/// A craft's passage through a body's sphere of influence, as it entered.
+#[derive(Component)]
+struct Flyby {
+    body: Entity,
+    /// Relative to the heaviest body.
+    speed: f64,
+    fuel: f32,
+}
+
+type CraftState = (
+    Entity,
+    &'static Name,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Fuel,
+    &'static mut AssistTally,
+    Option<&'static Flyby>,
+);
+
+fn track_flybys(
+    mut commands: Commands,
+    bodies: Query<
+        (
+            Entity,
+            &SimPosition,
+            &SimVelocity,
+            &Mass,
+            &SphereOfInfluence,
+        ),
+        With<Body>,
+    >,
+    mut crafts: Query<CraftState, With<Craft>>,
+    mut assists: EventWriter<GravityAssist>,
+) {
+    let entities: Vec<_> = bodies.iter().map(|(entity, ..)| entity).collect();
+    let scratch = scratch_bodies(bodies.iter().map(|(_, p, v, m, _)| (p, v, m)));
+    let radii: Vec<_> = bodies.iter().map(|(.., sphere)| sphere.0).collect();
+    // The heaviest body has the only infinite sphere and stands still for flybys
+    let Some(primary) = radii.iter().position(|radius| radius.is_infinite()) else {
+        return;
+    };
+
+    for (craft, name, position, velocity, fuel, mut tally, flyby) in &mut crafts {
+        let governing = governing_body(position.0, &scratch, &radii)
+            .filter(|&index| index != primary)
+            .map(|index| entities[index]);
+        if flyby.map(|flyby| flyby.body) == governing {
+            continue;
+        }
+        let speed = (velocity.0 - scratch[primary].velocity).length();
+
+        if let Some(flyby) = flyby {
+            commands.entity(craft).remove::<Flyby>();
+            let gain = speed - flyby.speed;
+            if gain >= MIN_ASSIST && fuel.0 >= flyby.fuel {
+                tally.count += 1;
+                tally.delta_v += gain as f32;
+                assists.write(GravityAssist {
+                    craft,
+                    body: flyby.body,
+                    delta_v: gain as f32,
+                });
+                log::info!("{name} gained {gain:.2} from a gravity assist");
+            }
+        }
+        if let Some(body) = governing {
+            commands.entity(craft).insert(Flyby {
+                body,
+                speed,
+                fuel: fuel.0,
+            });
+        }
+    }
+}
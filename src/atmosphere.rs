@@ -0,0 +1,108 @@
+use crate::app_state::AppState;
+use crate::bodies::{Body, Radius};
+use crate::crafts::{Craft, CraftsLost};
+use crate::factions::Owner;
+use crate::physics::{SimPosition, SimVelocity};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Air around bodies that have an [`Atmosphere`]: crafts passing through
+/// are slowed by drag, which lets them aerobrake into orbit, and heated,
+/// burning up if they dive too deep or too fast.
+pub struct AtmospherePlugin;
+
+impl Plugin for AtmospherePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedPostUpdate,
+            (atmospheric_drag, burn_up)
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+/// Heat gained per unit of drag power, that is deceleration times speed.
+const HEATING: f32 = 0.002;
+/// Heat shed per second, inside an atmosphere or out of it.
+const COOLING: f32 = 0.2;
+
+/// A layer of air around a body, thinning linearly from the surface out to
+/// its edge.
+#[derive(Component, Reflect, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct Atmosphere {
+    /// Where the air ends, in body radii from the center.
+    pub radius: f32,
+    /// Drag per unit of speed squared at the surface.
+    pub density: f32,
+}
+
+impl Atmosphere {
+    /// How thick the air is `distance` from the center of a body of
+    /// `body_radius`.
+    pub fn density_at(&self, distance: f64, body_radius: f32) -> f64 {
+        let height = (distance / body_radius as f64 - 1.).max(0.);
+        let depth = (self.radius as f64 - 1.).max(f64::EPSILON);
+        self.density as f64 * (1. - height / depth).max(0.)
+    }
+}
+
+/// How hot a craft's hull is, from cold at 0 to burning up at 1.
+#[derive(Component, Reflect, Default, Clone, Copy, Debug)]
+#[reflect(Component)]
+pub struct Heat(pub f32);
+
+type BodyState = (
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Radius,
+    &'static Atmosphere,
+);
+
+/// Slows crafts against the air of every body they're inside the
+/// atmosphere of, and heats them with the energy lost.
+fn atmospheric_drag(
+    mut crafts: Query<(&SimPosition, &mut SimVelocity, &mut Heat), With<Craft>>,
+    bodies: Query<BodyState, (With<Body>, Without<Craft>)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs_f64();
+    for (position, mut velocity, mut heat) in &mut crafts {
+        let mut power = 0.;
+        for (body_position, body_velocity, radius, atmosphere) in &bodies {
+            let density = atmosphere.density_at(position.0.distance(body_position.0), radius.0);
+            if density <= 0. {
+                continue;
+            }
+            // The air moves along with its body
+            let airspeed = velocity.0 - body_velocity.0;
+            let speed = airspeed.length();
+            // Never enough to turn the craft around within a tick
+            let slowing = (density * speed * speed).min(speed / dt);
+            velocity.0 -= airspeed.normalize_or_zero() * slowing * dt;
+            power += slowing * speed;
+        }
+        heat.0 = (heat.0 + (HEATING * power as f32 - COOLING) * dt as f32).max(0.);
+    }
+}
+
+fn burn_up(
+    mut commands: Commands,
+    crafts: Query<(Entity, &Name, &Heat, Option<&Owner>), With<Craft>>,
+    mut losses: EventWriter<CraftsLost>,
+) {
+    for (craft, name, heat, owner) in &crafts {
+        if heat.0 < 1. {
+            continue;
+        }
+        log::info!("{name} burned up");
+        commands.entity(craft).despawn();
+        if let Some(owner) = owner {
+            losses.write(CraftsLost {
+                faction: owner.0,
+                count: 1,
+            });
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::bodies::{Body, Radius};
+use crate::collisions::{BodyDestroyed, Destruction};
+use crate::crafts::{Craft, CraftDocked, CraftLaunched, Destination, Home};
+use crate::physics::{SimPosition, SimVelocity};
+use crate::settings::Settings;
+use bevy::audio::Volume;
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use bevy_persistent::prelude::*;
+
+/// Background music and sound effects for launches, landings, collisions
+/// and close flybys, at the volumes chosen in the settings.
+///
+/// Only built with the `audio` feature. Sounds are read from
+/// `assets/audio/`; any that are missing are skipped with a logged error.
+pub struct GameAudioPlugin;
+
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, (load_sounds, start_music).chain())
+            .add_systems(Update, (apply_music_volume, play_event_sounds, play_flybys));
+    }
+}
+
+/// Flybys are heard within this many body radii of the surface.
+const FLYBY_RANGE: f64 = 2.;
+/// Closing speed that doubles a flyby's pitch, and opening speed that halves
+/// it. Roughly a fast launch.
+const DOPPLER_SPEED: f64 = 20.;
+
+#[derive(Resource)]
+struct Sounds {
+    launch: Handle<AudioSource>,
+    landing: Handle<AudioSource>,
+    collision: Handle<AudioSource>,
+    flyby: Handle<AudioSource>,
+}
+
+/// The looping background track.
+#[derive(Component)]
+struct Music;
+
+fn load_sounds(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Sounds {
+        launch: asset_server.load("audio/launch.ogg"),
+        landing: asset_server.load("audio/landing.ogg"),
+        collision: asset_server.load("audio/collision.ogg"),
+        flyby: asset_server.load("audio/flyby.ogg"),
+    });
+}
+
+fn start_music(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Persistent<Settings>>,
+) {
+    commands.spawn((
+        Music,
+        AudioPlayer::new(asset_server.load("audio/music.ogg")),
+        PlaybackSettings::LOOP.with_volume(Volume::Linear(settings.music_volume)),
+    ));
+}
+
+fn apply_music_volume(
+    settings: Res<Persistent<Settings>>,
+    mut music: Query<&mut AudioSink, With<Music>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for mut sink in &mut music {
+        sink.set_volume(Volume::Linear(settings.music_volume));
+    }
+}
+
+fn play_event_sounds(
+    mut commands: Commands,
+    mut launches: EventReader<CraftLaunched>,
+    mut landings: EventReader<CraftDocked>,
+    mut destroyed: EventReader<BodyDestroyed>,
+    sounds: Res<Sounds>,
+    settings: Res<Persistent<Settings>>,
+) {
+    let collisions = destroyed
+        .read()
+        .filter(|destroyed| !matches!(destroyed.cause, Destruction::TornApart { .. }));
+    // One of each per frame, however many happened at once
+    let played = [
+        (launches.read().count() > 0).then_some(&sounds.launch),
+        (landings.read().count() > 0).then_some(&sounds.landing),
+        (collisions.count() > 0).then_some(&sounds.collision),
+    ];
+    for sound in played.into_iter().flatten() {
+        commands.spawn((
+            AudioPlayer::new(sound.clone()),
+            PlaybackSettings::DESPAWN.with_volume(Volume::Linear(settings.effects_volume)),
+        ));
+    }
+}
+
+type CraftState = (
+    Entity,
+    &'static SimPosition,
+    &'static SimVelocity,
+    Option<&'static Home>,
+    Option<&'static Destination>,
+);
+
+/// Plays a whoosh as a craft comes close to a body it isn't launched from or
+/// headed for, pitched up when closing fast and down when pulling away.
+fn play_flybys(
+    mut commands: Commands,
+    crafts: Query<CraftState, With<Craft>>,
+    bodies: Query<(Entity, &SimPosition, &SimVelocity, &Radius), With<Body>>,
+    sounds: Res<Sounds>,
+    settings: Res<Persistent<Settings>>,
+    mut passing: Local<HashSet<(Entity, Entity)>>,
+) {
+    let mut still_passing = HashSet::default();
+    for (craft, position, velocity, home, destination) in &crafts {
+        for (body, body_position, body_velocity, radius) in &bodies {
+            if home.is_some_and(|home| home.0 == body)
+                || destination.is_some_and(|destination| destination.0 == body)
+            {
+                continue;
+            }
+            let offset = position.0 - body_position.0;
+            let radius = radius.0 as f64;
+            if offset.length() - radius > FLYBY_RANGE * radius {
+                continue;
+            }
+            still_passing.insert((craft, body));
+            if passing.contains(&(craft, body)) {
+                continue;
+            }
+            let closing = -(velocity.0 - body_velocity.0).dot(offset.normalize_or_zero());
+            let pitch = 2f64.powf(closing / DOPPLER_SPEED).clamp(0.5, 2.);
+            commands.spawn((
+                AudioPlayer::new(sounds.flyby.clone()),
+                PlaybackSettings::DESPAWN
+                    .with_volume(Volume::Linear(settings.effects_volume))
+                    .with_speed(pitch as f32),
+            ));
+        }
+    }
+    *passing = still_passing;
+}
@@ -0,0 +1,181 @@
+use crate::app_state::AppState;
+use crate::bodies::Body;
+use crate::crafts::{Craft, Fuel, Home};
+use crate::i18n::{tr, tr_args};
+use crate::influence::LagrangePoints;
+use crate::maneuver::scratch_bodies;
+use crate::orbital_mechanics::strongest_attractor;
+use crate::physics::{Mass, PhysicsConstants, SimPosition, SimVelocity, gravity_acceleration};
+use crate::trajectory::PredictedTrajectory;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+/// Flies crafts that have an [`Autopilot`], with small burns every tick paid
+/// for out of their fuel.
+pub struct AutopilotPlugin;
+
+impl Plugin for AutopilotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedPostUpdate,
+            fly_autopilots.run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+/// Delta-v an autopilot may spend per second.
+const THRUST: f64 = 2.;
+/// Velocity errors smaller than this are left alone rather than wasting fuel.
+const DEADBAND: f64 = 0.05;
+/// Fastest an autopilot closes on a Lagrange point.
+const MAX_APPROACH_SPEED: f64 = 8.;
+
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum Autopilot {
+    /// Circularize around whichever body pulls hardest, and stay there.
+    HoldOrbit,
+    /// Fly to a body until the craft docks there.
+    Intercept(Entity),
+    /// Fly back to the craft's [`Home`].
+    ReturnHome,
+    /// Hold position at one of a body's Lagrange points, 0 being L1.
+    StationKeep { body: Entity, point: usize },
+}
+
+impl Autopilot {
+    /// The body this autopilot is flying the craft to, if any.
+    pub fn goal(&self, home: Option<&Home>) -> Option<Entity> {
+        match *self {
+            Self::Intercept(body) => Some(body),
+            Self::ReturnHome => home.map(|home| home.0),
+            Self::HoldOrbit | Self::StationKeep { .. } => None,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::HoldOrbit => tr("autopilot-hold-orbit"),
+            Self::Intercept(_) => tr("autopilot-intercept"),
+            Self::ReturnHome => tr("autopilot-return-home"),
+            Self::StationKeep { point, .. } => {
+                tr_args("autopilot-station-keep", &[("point", (point + 1).into())])
+            }
+        }
+    }
+}
+
+type BodyState = (
+    Entity,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Mass,
+    &'static LagrangePoints,
+);
+
+type CraftState = (
+    Entity,
+    &'static Name,
+    &'static SimPosition,
+    &'static mut SimVelocity,
+    &'static mut Fuel,
+    &'static Autopilot,
+    Option<&'static Home>,
+    &'static PredictedTrajectory,
+);
+
+fn fly_autopilots(
+    mut commands: Commands,
+    time: Res<Time>,
+    constants: Res<PhysicsConstants>,
+    bodies: Query<BodyState, (With<Body>, Without<Craft>)>,
+    mut crafts: Query<CraftState, With<Craft>>,
+) {
+    let scratch = scratch_bodies(bodies.iter().map(|(_, p, v, m, _)| (p, v, m)));
+    let primary = scratch
+        .iter()
+        .max_by(|a, b| a.mass.total_cmp(&b.mass))
+        .copied();
+    let max_burn = THRUST * time.delta_secs_f64();
+
+    for (craft, name, position, mut velocity, mut fuel, autopilot, home, trajectory) in &mut crafts
+    {
+        let body = |entity: Entity| bodies.get(entity).ok();
+        let desired = match *autopilot {
+            Autopilot::HoldOrbit => strongest_attractor(position.0, &scratch).and_then(|index| {
+                let attractor = &scratch[index];
+                let offset = position.0 - attractor.position;
+                let pull = gravity_acceleration(
+                    -offset,
+                    attractor.mass,
+                    constants.softening as f64,
+                    constants.g as f64,
+                );
+                // Keep going around the same way
+                let relative = velocity.0 - attractor.velocity;
+                let sense = offset.cross(relative).z.signum();
+                let tangent = DVec3::new(-offset.y, offset.x, 0.).try_normalize()? * sense;
+                Some(attractor.velocity + tangent * (pull.length() * offset.length()).sqrt())
+            }),
+            // Predictions aim at the goal, so nudge the coast until it hits
+            Autopilot::Intercept(_) | Autopilot::ReturnHome => autopilot
+                .goal(home)
+                .and_then(body)
+                .map(|_| match trajectory.closest_approach {
+                    Some(approach) if approach.distance > 0. => {
+                        // Aim for the center, so small misses still close
+                        let miss = approach.body_position - approach.craft_position;
+                        velocity.0 + miss / approach.time.max(1.)
+                    }
+                    // Already on course, or not yet predicted
+                    _ => velocity.0,
+                }),
+            Autopilot::StationKeep {
+                body: entity,
+                point,
+            } => body(entity).zip(primary).and_then(
+                |((_, secondary, secondary_velocity, _, points), primary)| {
+                    let goal = points.0?[point];
+                    // Lagrange points turn with the secondary around the primary
+                    let offset = secondary.0 - primary.position;
+                    let relative = secondary_velocity.0 - primary.velocity;
+                    let angular_velocity = offset.cross(relative).z / offset.length_squared();
+                    let arm = goal - primary.position;
+                    let goal_velocity =
+                        primary.velocity + DVec3::new(-arm.y, arm.x, 0.) * angular_velocity;
+                    Some(rendezvous(position.0, goal, goal_velocity))
+                },
+            ),
+        };
+
+        let Some(desired) = desired else {
+            log::info!("{name}'s autopilot has nowhere to go");
+            commands.entity(craft).remove::<Autopilot>();
+            continue;
+        };
+        let error = desired - velocity.0;
+        if error.length() < DEADBAND {
+            continue;
+        }
+        if fuel.0 <= 0. {
+            log::info!("{name}'s autopilot is out of fuel");
+            commands.entity(craft).remove::<Autopilot>();
+            continue;
+        }
+        let delta_v = error
+            .clamp_length_max(max_burn)
+            .clamp_length_max(fuel.0 as f64);
+        fuel.0 = (fuel.0 - delta_v.length() as f32).max(0.);
+        velocity.0 += delta_v;
+    }
+}
+
+/// Velocity that closes on a point at `goal` moving at `goal_velocity`,
+/// approaching slowly enough to match its motion on arrival.
+fn rendezvous(position: DVec3, goal: DVec3, goal_velocity: DVec3) -> DVec3 {
+    let offset = goal - position;
+    let speed = (2. * THRUST * offset.length())
+        .sqrt()
+        .min(MAX_APPROACH_SPEED);
+    goal_velocity + offset.normalize_or_zero() * speed
+}
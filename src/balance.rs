@@ -0,0 +1,141 @@
+use crate::engines::Engine;
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Loads the craft balance table from `assets/crafts.balance.toml`.
+///
+/// With the `file_watcher` feature enabled, edits to the file are picked up
+/// while the game is running.
+pub struct BalancePlugin;
+
+impl Plugin for BalancePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<BalanceTable>()
+            .init_asset_loader::<BalanceTableLoader>()
+            .add_systems(PreStartup, load_balance_table)
+            .add_systems(Update, log_balance_changes);
+    }
+}
+
+pub const BALANCE_TABLE_PATH: &str = "crafts.balance.toml";
+
+/// Stats for every craft class, keyed by class name.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct BalanceTable {
+    /// Class given to crafts when nothing else is specified.
+    pub default_class: String,
+    pub classes: BTreeMap<String, CraftClass>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CraftClass {
+    pub mass: f32,
+    pub max_launch_speed: f32,
+    pub combat_strength: f32,
+    /// Room for goods, reserved for trade and not used by anything yet.
+    #[serde(default)]
+    pub cargo: f32,
+    /// Total delta-v, spent by the launch and every later burn.
+    pub fuel: f32,
+    /// Fastest a craft can touch down relative to a body; any faster and it
+    /// crashes. Any speed goes if unset.
+    #[serde(default = "unlimited")]
+    pub max_landing_speed: f32,
+    /// Widest angle from straight down, in degrees, a craft can touch down
+    /// at. Any angle goes if unset.
+    #[serde(default)]
+    pub max_landing_angle: Option<f32>,
+    /// For thrusting continuously rather than only in instant burns.
+    #[serde(default)]
+    pub engine: Option<Engine>,
+}
+
+fn unlimited() -> f32 {
+    f32::INFINITY
+}
+
+impl CraftClass {
+    /// Fastest launch the class can afford.
+    pub fn launch_speed_limit(&self) -> f32 {
+        self.max_launch_speed.min(self.fuel)
+    }
+}
+
+#[derive(Resource)]
+pub struct BalanceTableHandle(pub Handle<BalanceTable>);
+
+/// Read access to the currently loaded balance table.
+///
+/// Everything returns `None` until the asset has finished loading.
+#[derive(SystemParam)]
+pub struct Balance<'w> {
+    handle: Res<'w, BalanceTableHandle>,
+    tables: Res<'w, Assets<BalanceTable>>,
+}
+
+impl Balance<'_> {
+    pub fn table(&self) -> Option<&BalanceTable> {
+        self.tables.get(&self.handle.0)
+    }
+
+    pub fn class(&self, name: &str) -> Option<&CraftClass> {
+        self.table()?.classes.get(name)
+    }
+
+    pub fn default_class(&self) -> Option<(&str, &CraftClass)> {
+        let table = self.table()?;
+        let class = table.classes.get(&table.default_class)?;
+        Some((&table.default_class, class))
+    }
+}
+
+fn load_balance_table(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(BalanceTableHandle(asset_server.load(BALANCE_TABLE_PATH)));
+}
+
+fn log_balance_changes(mut events: EventReader<AssetEvent<BalanceTable>>) {
+    for event in events.read() {
+        match event {
+            AssetEvent::LoadedWithDependencies { .. } => log::info!("Loaded craft balance table"),
+            AssetEvent::Modified { .. } => log::info!("Reloaded craft balance table"),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Default)]
+struct BalanceTableLoader;
+
+#[derive(Debug, thiserror::Error)]
+enum BalanceTableLoaderError {
+    #[error("could not read balance table: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("balance table is not valid UTF-8: {0}")]
+    Utf8(#[from] std::str::Utf8Error),
+    #[error("could not parse balance table: {0}")]
+    Toml(#[from] toml::de::Error),
+}
+
+impl AssetLoader for BalanceTableLoader {
+    type Asset = BalanceTable;
+    type Settings = ();
+    type Error = BalanceTableLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<BalanceTable, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(toml::from_str(std::str::from_utf8(&bytes)?)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["balance.toml"]
+    }
+}
@@ -0,0 +1,92 @@
+use crate::SlingcraftPlugins;
+use crate::app_state::AppState;
+use crate::physics::{SimRng, TotalEnergy};
+use crate::replay::ReplayLog;
+use crate::scenario::Scenario;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// How fast a scenario simulates headless, and how well it holds on to its
+/// energy, as reported by `--bench-scenario`.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchReport {
+    pub ticks: u64,
+    /// Wall-clock time the ticks took.
+    pub elapsed: Duration,
+    pub initial_energy: f64,
+    pub final_energy: f64,
+}
+
+impl BenchReport {
+    pub fn ticks_per_second(&self) -> f64 {
+        self.ticks as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// Change in total energy relative to where it started.
+    pub fn energy_drift(&self) -> f64 {
+        (self.final_energy - self.initial_energy) / self.initial_energy.abs()
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} ticks in {:.2}s: {:.0} ticks/s",
+            self.ticks,
+            self.elapsed.as_secs_f64(),
+            self.ticks_per_second()
+        )?;
+        write!(
+            f,
+            "Energy {:.6} -> {:.6}: {:+.3e} drift",
+            self.initial_energy,
+            self.final_energy,
+            self.energy_drift()
+        )
+    }
+}
+
+/// An app simulating `scenario` headless, stepping time by exactly one
+/// `timestep`, and so one physics tick, per update. It has already run the
+/// first tick, which spawns the scenario and settles its orbits.
+pub fn headless_app(scenario: Scenario, timestep: f64, seed: u64) -> App {
+    let timestep = Duration::from_secs_f64(timestep);
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        SlingcraftPlugins::headless(),
+    ))
+    .insert_resource(scenario)
+    .insert_resource(SimRng::seeded(seed))
+    .insert_resource(Time::<Fixed>::from_duration(timestep))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(timestep))
+    .insert_state(AppState::Playing);
+    app.finish();
+    app.cleanup();
+    while app.world().resource::<ReplayLog>().tick() == 0 {
+        app.update();
+    }
+    app
+}
+
+/// Simulates `ticks` physics ticks of `scenario` headless, as fast as they
+/// run.
+pub fn run_scenario(scenario: Scenario, ticks: u64, timestep: f64, seed: u64) -> BenchReport {
+    let mut app = headless_app(scenario, timestep, seed);
+    let start_tick = app.world().resource::<ReplayLog>().tick();
+    let initial_energy = app.world().resource::<TotalEnergy>().0;
+    let start = Instant::now();
+    while app.world().resource::<ReplayLog>().tick() - start_tick < ticks {
+        app.update();
+    }
+    BenchReport {
+        ticks: app.world().resource::<ReplayLog>().tick() - start_tick,
+        elapsed: start.elapsed(),
+        initial_energy,
+        final_energy: app.world().resource::<TotalEnergy>().0,
+    }
+}
@@ -0,0 +1,452 @@
+use crate::i18n::{tr, tr_args};
+use crate::persistence::state_directory;
+use bevy::ecs::system::SystemParam;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+use bevy_persistent::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Maps keys and gamepad buttons to [`Action`]s, so every hotkey can be
+/// rebound from the settings window. Bindings persist alongside the other
+/// settings.
+pub struct BindingsPlugin;
+
+impl Plugin for BindingsPlugin {
+    fn build(&self, app: &mut App) {
+        let bindings = Persistent::<KeyBindings>::builder()
+            .name("key_bindings")
+            .format(StorageFormat::Toml)
+            .path(state_directory().join("key-bindings.toml"))
+            .default(KeyBindings::default())
+            .revertible(true)
+            .revert_to_default_on_deserialization_errors(true)
+            .build()
+            .expect("failed to initialize key bindings");
+
+        app.insert_resource(bindings)
+            .init_resource::<Rebinding>()
+            .add_systems(PreUpdate, capture_rebinding.after(InputSystem));
+    }
+}
+
+/// Something the player can do from the keyboard or a gamepad.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Action {
+    Pause,
+    SpeedUp,
+    SlowDown,
+    /// Keep the selected body or craft in the middle of the plot.
+    Follow,
+    /// Zoom the plot to fit every body.
+    FitAll,
+    /// Zoom the plot in on the selected body or craft.
+    FrameSelection,
+    /// Leaves any interaction mode, then clears the selection.
+    Deselect,
+    /// Aim a launch from the selected body.
+    Launch,
+    /// Launch where the pointer or right stick is aiming.
+    Fire,
+    /// Turn the launch aim a step counterclockwise, for aiming without a
+    /// pointer.
+    AimLeft,
+    AimRight,
+    /// Launch a step faster.
+    AimFaster,
+    AimSlower,
+    /// Open the selected craft's throttle a notch.
+    ThrottleUp,
+    ThrottleDown,
+    NextBody,
+    PreviousBody,
+    /// Search for a body to jump to.
+    Palette,
+    Undo,
+    Redo,
+    Screenshot,
+    RecordGif,
+    /// Show or hide the keys, mouse controls and plot markers.
+    Help,
+    /// Open the developer console.
+    Console,
+    /// Show or hide frame times, system timings and entity counts.
+    Profiler,
+}
+
+impl Action {
+    pub const ALL: [Self; 25] = [
+        Self::Pause,
+        Self::SpeedUp,
+        Self::SlowDown,
+        Self::Follow,
+        Self::FitAll,
+        Self::FrameSelection,
+        Self::Deselect,
+        Self::Launch,
+        Self::Fire,
+        Self::AimLeft,
+        Self::AimRight,
+        Self::AimFaster,
+        Self::AimSlower,
+        Self::ThrottleUp,
+        Self::ThrottleDown,
+        Self::NextBody,
+        Self::PreviousBody,
+        Self::Palette,
+        Self::Undo,
+        Self::Redo,
+        Self::Screenshot,
+        Self::RecordGif,
+        Self::Help,
+        Self::Console,
+        Self::Profiler,
+    ];
+
+    pub fn label(self) -> String {
+        tr(match self {
+            Self::Pause => "action-pause",
+            Self::SpeedUp => "action-speed-up",
+            Self::SlowDown => "action-slow-down",
+            Self::Follow => "action-follow",
+            Self::FitAll => "action-fit-all",
+            Self::FrameSelection => "action-frame-selection",
+            Self::Deselect => "action-deselect",
+            Self::Launch => "action-launch",
+            Self::Fire => "action-fire",
+            Self::AimLeft => "action-aim-left",
+            Self::AimRight => "action-aim-right",
+            Self::AimFaster => "action-aim-faster",
+            Self::AimSlower => "action-aim-slower",
+            Self::ThrottleUp => "action-throttle-up",
+            Self::ThrottleDown => "action-throttle-down",
+            Self::NextBody => "action-next-body",
+            Self::PreviousBody => "action-previous-body",
+            Self::Palette => "action-palette",
+            Self::Undo => "action-undo",
+            Self::Redo => "action-redo",
+            Self::Screenshot => "action-screenshot",
+            Self::RecordGif => "action-record-gif",
+            Self::Help => "action-help",
+            Self::Console => "action-console",
+            Self::Profiler => "action-profiler",
+        })
+    }
+
+    fn default_binding(self) -> KeyBinding {
+        match self {
+            Self::Pause => KeyBinding::key(KeyCode::Space),
+            Self::SpeedUp => KeyBinding::key(KeyCode::BracketRight),
+            Self::SlowDown => KeyBinding::key(KeyCode::BracketLeft),
+            Self::Follow => KeyBinding {
+                shift: true,
+                ..KeyBinding::key(KeyCode::KeyF)
+            },
+            Self::FitAll => KeyBinding::key(KeyCode::Home),
+            Self::FrameSelection => KeyBinding::key(KeyCode::KeyF),
+            Self::Deselect => KeyBinding::key(KeyCode::Escape),
+            Self::Launch => KeyBinding::key(KeyCode::KeyL),
+            Self::Fire => KeyBinding::key(KeyCode::Enter),
+            Self::AimLeft => KeyBinding::key(KeyCode::ArrowLeft),
+            Self::AimRight => KeyBinding::key(KeyCode::ArrowRight),
+            Self::AimFaster => KeyBinding::key(KeyCode::ArrowUp),
+            Self::AimSlower => KeyBinding::key(KeyCode::ArrowDown),
+            Self::ThrottleUp => KeyBinding::key(KeyCode::KeyW),
+            Self::ThrottleDown => KeyBinding::key(KeyCode::KeyS),
+            Self::NextBody => KeyBinding::key(KeyCode::Period),
+            Self::PreviousBody => KeyBinding::key(KeyCode::Comma),
+            Self::Palette => KeyBinding::ctrl(KeyCode::KeyK),
+            Self::Undo => KeyBinding::ctrl(KeyCode::KeyZ),
+            Self::Redo => KeyBinding::ctrl(KeyCode::KeyY),
+            Self::Screenshot => KeyBinding::key(KeyCode::F12),
+            Self::RecordGif => KeyBinding {
+                shift: true,
+                ..KeyBinding::key(KeyCode::F12)
+            },
+            Self::Help => KeyBinding::key(KeyCode::F1),
+            Self::Console => KeyBinding::key(KeyCode::Backquote),
+            Self::Profiler => KeyBinding::key(KeyCode::F3),
+        }
+    }
+
+    fn default_button(self) -> Option<GamepadButton> {
+        Some(match self {
+            Self::Pause => GamepadButton::Start,
+            Self::SpeedUp => GamepadButton::DPadUp,
+            Self::SlowDown => GamepadButton::DPadDown,
+            Self::Follow => GamepadButton::North,
+            Self::FitAll => GamepadButton::Select,
+            Self::FrameSelection => GamepadButton::RightThumb,
+            Self::Deselect => GamepadButton::East,
+            Self::Launch => GamepadButton::West,
+            Self::Fire => GamepadButton::South,
+            Self::ThrottleUp => GamepadButton::DPadRight,
+            Self::ThrottleDown => GamepadButton::DPadLeft,
+            Self::NextBody => GamepadButton::RightTrigger,
+            Self::PreviousBody => GamepadButton::LeftTrigger,
+            // The sticks and triggers aim
+            Self::AimLeft
+            | Self::AimRight
+            | Self::AimFaster
+            | Self::AimSlower
+            | Self::Palette
+            | Self::Undo
+            | Self::Redo
+            | Self::Screenshot
+            | Self::RecordGif
+            | Self::Help
+            | Self::Console
+            | Self::Profiler => {
+                return None;
+            }
+        })
+    }
+}
+
+/// What a binding is for, while rebinding.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Device {
+    Keyboard,
+    Gamepad,
+}
+
+/// A key, and the modifiers that must be held with it.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub shift: bool,
+}
+
+impl KeyBinding {
+    fn key(key: KeyCode) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            shift: false,
+        }
+    }
+
+    fn ctrl(key: KeyCode) -> Self {
+        Self {
+            ctrl: true,
+            ..Self::key(key)
+        }
+    }
+
+    fn just_pressed(self, input: &ButtonInput<KeyCode>) -> bool {
+        input.just_pressed(self.key)
+            && self.ctrl == input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+            && self.shift == input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        let name = format!("{:?}", self.key);
+        let name = name
+            .strip_prefix("Key")
+            .or_else(|| name.strip_prefix("Digit"))
+            .unwrap_or(&name);
+        write!(f, "{name}")
+    }
+}
+
+/// Bindings the player has changed. Actions missing here use their defaults,
+/// so new actions get keys without resetting old files.
+#[derive(Resource, Serialize, Deserialize, Default, Debug)]
+pub struct KeyBindings {
+    #[serde(default)]
+    bindings: BTreeMap<Action, KeyBinding>,
+    #[serde(default)]
+    buttons: BTreeMap<Action, GamepadButton>,
+}
+
+impl KeyBindings {
+    pub fn binding(&self, action: Action) -> KeyBinding {
+        self.bindings
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| action.default_binding())
+    }
+
+    pub fn button(&self, action: Action) -> Option<GamepadButton> {
+        self.buttons
+            .get(&action)
+            .copied()
+            .or_else(|| action.default_button())
+    }
+
+    /// Other actions bound to the same keys or button as `action`.
+    fn conflicts(&self, action: Action) -> impl Iterator<Item = Action> + '_ {
+        let binding = self.binding(action);
+        let button = self.button(action);
+        Action::ALL.into_iter().filter(move |&other| {
+            other != action
+                && (self.binding(other) == binding
+                    || button.is_some() && self.button(other) == button)
+        })
+    }
+}
+
+/// The action and device waiting for a new binding, while rebinding.
+#[derive(Resource, Default)]
+pub struct Rebinding(pub Option<(Action, Device)>);
+
+/// Whether each [`Action`]'s keys or button were just pressed. Nothing is
+/// pressed while a binding is being changed, and keys aren't while egui is
+/// taking keyboard input.
+#[derive(SystemParam)]
+pub struct ActionInput<'w, 's> {
+    input: Res<'w, ButtonInput<KeyCode>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+    bindings: Res<'w, Persistent<KeyBindings>>,
+    rebinding: Res<'w, Rebinding>,
+    contexts: EguiContexts<'w, 's>,
+}
+
+impl ActionInput<'_, '_> {
+    pub fn just_pressed(&mut self, action: Action) -> bool {
+        let typing = self
+            .contexts
+            .ctx_mut()
+            .is_ok_and(|ctx| ctx.wants_keyboard_input());
+        self.pressed(action, !typing)
+    }
+
+    /// Like [`Self::just_pressed`], but counts keys even while egui is
+    /// taking keyboard input, for closing text-entry windows such as the
+    /// console with the key that opened them.
+    pub fn just_pressed_while_typing(&mut self, action: Action) -> bool {
+        self.pressed(action, true)
+    }
+
+    fn pressed(&self, action: Action, keys: bool) -> bool {
+        if self.rebinding.0.is_some() {
+            return false;
+        }
+        if let Some(button) = self.bindings.button(action)
+            && self
+                .gamepads
+                .iter()
+                .any(|gamepad| gamepad.just_pressed(button))
+        {
+            return true;
+        }
+        keys && self.bindings.binding(action).just_pressed(&self.input)
+    }
+}
+
+/// Binds the first key or button pressed while rebinding, and swallows it so
+/// it doesn't also trigger whatever it's now bound to.
+fn capture_rebinding(
+    mut input: ResMut<ButtonInput<KeyCode>>,
+    mut gamepads: Query<&mut Gamepad>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<Persistent<KeyBindings>>,
+) {
+    let Some((action, device)) = rebinding.0 else {
+        return;
+    };
+    if device == Device::Gamepad {
+        for mut gamepad in &mut gamepads {
+            let Some(button) = gamepad.get_just_pressed().next().copied() else {
+                continue;
+            };
+            gamepad.digital_mut().clear_just_pressed(button);
+            rebinding.0 = None;
+            if let Err(err) = bindings.update(|bindings| {
+                bindings.buttons.insert(action, button);
+            }) {
+                log::error!("failed to save key bindings: {err}");
+            }
+            return;
+        }
+        return;
+    }
+    let modifiers = [
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::ShiftLeft,
+        KeyCode::ShiftRight,
+    ];
+    let Some(key) = input
+        .get_just_pressed()
+        .copied()
+        .find(|key| !modifiers.contains(key))
+    else {
+        return;
+    };
+    let binding = KeyBinding {
+        key,
+        ctrl: input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]),
+        shift: input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+    };
+    input.clear_just_pressed(key);
+    rebinding.0 = None;
+    if let Err(err) = bindings.update(|bindings| {
+        bindings.bindings.insert(action, binding);
+    }) {
+        log::error!("failed to save key bindings: {err}");
+    }
+}
+
+/// The rebinding page of the settings window: each action's key and button,
+/// which to click to rebind.
+pub fn bindings_page(
+    ui: &mut egui::Ui,
+    bindings: &mut Persistent<KeyBindings>,
+    rebinding: &mut Rebinding,
+) {
+    egui::Grid::new("key_bindings")
+        .num_columns(4)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("");
+            ui.strong(tr("key"));
+            ui.strong(tr("gamepad"));
+            ui.end_row();
+            for action in Action::ALL {
+                ui.label(action.label());
+                for device in [Device::Keyboard, Device::Gamepad] {
+                    let waiting = rebinding.0 == Some((action, device));
+                    let text = match device {
+                        _ if waiting => tr("press-key"),
+                        Device::Keyboard => bindings.binding(action).to_string(),
+                        Device::Gamepad => bindings
+                            .button(action)
+                            .map_or("—".to_string(), |button| format!("{button:?}")),
+                    };
+                    if ui.selectable_label(waiting, text).clicked() {
+                        rebinding.0 = (!waiting).then_some((action, device));
+                    }
+                }
+                let conflicts: Vec<_> = bindings.conflicts(action).map(Action::label).collect();
+                if conflicts.is_empty() {
+                    ui.label("");
+                } else {
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        tr_args("also-bound", &[("actions", conflicts.join(", ").into())]),
+                    );
+                }
+                ui.end_row();
+            }
+        });
+    if ui.button(tr("reset-defaults")).clicked() {
+        rebinding.0 = None;
+        if let Err(err) = bindings.set(KeyBindings::default()) {
+            log::error!("failed to save key bindings: {err}");
+        }
+    }
+}
@@ -0,0 +1,421 @@
+use crate::accessibility::body_color;
+use crate::app_state::AppState;
+use crate::atmosphere::Atmosphere;
+use crate::comets::Comet;
+use crate::crafts::{Craft, Crafts, Destination, Fuel, Home};
+use crate::factions::{Faction, Owner};
+use crate::influence::{HillSphere, LagrangePoints, RocheLimit, SphereOfInfluence};
+use crate::kepler::Rails;
+use crate::physics::{Mass, PhysicsConstants, SimPosition, SimVelocity};
+use crate::production::Production;
+use crate::scenario::{BodySpec, Scenario};
+use crate::trajectory::PredictedTrajectory;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy_egui::egui::Color32;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+/// Spawns the celestial bodies and derives their masses and initial orbits.
+///
+/// The system is (re)spawned whenever the [`Scenario`] resource changes, so
+/// inserting a new scenario loads it.
+pub struct BodiesPlugin;
+
+impl Plugin for BodiesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Scenario>()
+            .configure_sets(
+                Update,
+                (ScenarioSystems::Spawn, ScenarioSystems::Initialize)
+                    .chain()
+                    .run_if(resource_changed::<Scenario>),
+            )
+            .add_systems(Update, spawn_scenario.in_set(ScenarioSystems::Spawn))
+            .add_systems(
+                Update,
+                (assign_masses, recalculate_orbital_velocities)
+                    .chain()
+                    .in_set(ScenarioSystems::Initialize),
+            )
+            .add_systems(Update, assign_masses.after(ScenarioSystems::Initialize))
+            .add_systems(
+                FixedUpdate,
+                rotate_bodies.run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(Update, face_star.run_if(AppState::in_game));
+    }
+}
+
+/// Loading a scenario: spawning its entities, then deriving their state.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ScenarioSystems {
+    Spawn,
+    Initialize,
+}
+
+#[derive(Component)]
+pub struct Fill(pub Color32);
+
+impl Fill {
+    /// The color to draw the body in, in the player's color scheme.
+    pub fn color(&self) -> Color32 {
+        body_color(self.0)
+    }
+}
+
+/// How a body looks beyond its flat [`Fill`], as set in the scenario file.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Appearance {
+    /// RGB color the fill shades into toward the rim.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gradient: Option<[u8; 3]>,
+    /// How far a halo reaches beyond the surface, in body radii, as around a
+    /// star.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub glow: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rings: Option<Rings>,
+    /// Image wrapped over the body, relative to the assets directory. Only
+    /// the `mesh_render` renderer draws it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub texture: Option<String>,
+}
+
+/// Crafts leave a spinning body's launch site at most this far, in radians,
+/// from straight up.
+const LAUNCH_ARC: f32 = PI / 3.;
+
+/// A body's rotation, which carries its launch site around with it. Crafts
+/// can only launch from the site, so the time to launch is when it faces
+/// the right way.
+#[derive(Component, Reflect, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct Spin {
+    /// Radians per second, counterclockwise.
+    pub rate: f32,
+    /// Which way the launch site faces, in radians counterclockwise from +x.
+    #[serde(default)]
+    pub angle: f32,
+}
+
+impl Spin {
+    /// Straight up from the launch site.
+    pub fn site_normal(&self) -> Vec2 {
+        Vec2::from_angle(self.angle)
+    }
+
+    /// The closest direction to `direction` a craft can leave the site in.
+    pub fn launch_direction(&self, direction: Vec2) -> Vec2 {
+        let off_normal = self.site_normal().angle_to(direction);
+        Vec2::from_angle(self.angle + off_normal.clamp(-LAUNCH_ARC, LAUNCH_ARC))
+    }
+
+    /// How fast the launch site is moving relative to the body's center.
+    pub fn site_velocity(&self, radius: f32) -> Vec2 {
+        self.site_normal().perp() * self.rate * radius
+    }
+}
+
+/// A flat band around a body, like Saturn's.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Rings {
+    /// Inner edge, in body radii.
+    pub inner: f32,
+    /// Outer edge, in body radii.
+    pub outer: f32,
+    /// RGB.
+    pub color: [u8; 3],
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Radius(pub f32);
+
+/// Mass per unit volume, so a small dense body can outweigh a large light one.
+#[derive(Component, Reflect, Clone, Copy)]
+#[reflect(Component)]
+pub struct Density(pub f32);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+#[require(
+    Mass,
+    Crafts,
+    Production,
+    SphereOfInfluence,
+    LagrangePoints,
+    HillSphere,
+    RocheLimit,
+    PredictedTrajectory,
+    Sunward
+)]
+pub struct Body;
+
+/// Which way the heaviest body, the star lighting the rest, lies from this
+/// one. `None` for the star itself, which has no night side.
+#[derive(Component, Reflect, Default, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct Sunward(pub Option<Vec2>);
+
+/// Replaces all bodies and crafts with those of the current scenario.
+type Spawned = Or<(With<Body>, With<Craft>)>;
+
+fn spawn_scenario(
+    mut commands: Commands,
+    scenario: Res<Scenario>,
+    existing: Query<Entity, Spawned>,
+) {
+    for entity in &existing {
+        commands.entity(entity).despawn();
+    }
+
+    commands.insert_resource(scenario.physics);
+    commands.insert_resource(scenario.combat);
+    commands.insert_resource(scenario.victory);
+    let unowned = scenario.bodies.iter().all(|spec| spec.owner.is_none());
+    let mut spawned = Vec::new();
+    for spec in &scenario.bodies {
+        let [x, y] = spec.position;
+        let mut body = commands.spawn((
+            body_bundle(
+                spec.name.clone(),
+                spec.radius,
+                spec.density(&scenario.physics),
+                spec.color,
+            ),
+            SimPosition::new(x as f64, y as f64),
+            // Set in recalculate_orbital_velocities once masses are known
+            SimVelocity::default(),
+        ));
+        let owner = if unowned {
+            Some(Faction::Player)
+        } else {
+            spec.owner
+        };
+        if let Some(owner) = owner {
+            body.insert(Owner(owner));
+        }
+        if let Some(appearance) = &spec.appearance {
+            body.insert(appearance.clone());
+        }
+        if let Some(spin) = spec.spin {
+            body.insert(spin);
+        }
+        if let Some(atmosphere) = spec.atmosphere {
+            body.insert(atmosphere);
+        }
+        if spec.on_rails {
+            body.insert(Rails);
+        }
+        if let Some(comet) = spec.comet {
+            body.insert(comet);
+        }
+        spawned.push((spec.name.as_str(), body.id()));
+    }
+
+    let body_named = |name: &Option<String>| {
+        spawned
+            .iter()
+            .find(|(spawned, _)| Some(*spawned) == name.as_deref())
+            .map(|&(_, entity)| entity)
+    };
+    for spec in &scenario.crafts {
+        let [x, y] = spec.position;
+        let [vx, vy] = spec.velocity;
+        let home = body_named(&spec.home);
+        let name = match &spec.home {
+            Some(home) => format!("{} from {home}", spec.class),
+            None => spec.class.clone(),
+        };
+        let mut craft = commands.spawn((
+            Craft {
+                class: spec.class.clone(),
+            },
+            Name::new(name),
+            SimPosition::new(x as f64, y as f64),
+            SimVelocity(DVec3::new(vx as f64, vy as f64, 0.)),
+            Fuel(spec.fuel),
+        ));
+        if let Some(owner) = spec.owner {
+            craft.insert(Owner(owner));
+        }
+        if let Some(home) = home {
+            craft.insert(Home(home));
+        }
+        if let Some(destination) = body_named(&spec.destination) {
+            craft.insert(Destination(destination));
+        }
+    }
+}
+
+/// Everything about a body except where it is and how it moves.
+pub fn body_bundle(name: String, radius: f32, density: f32, color: [u8; 3]) -> impl Bundle {
+    let [r, g, b] = color;
+    (
+        Body,
+        Radius(radius),
+        Density(density),
+        Name::new(name),
+        Fill(Color32::from_rgb(r, g, b)),
+    )
+}
+
+fn rotate_bodies(mut bodies: Query<&mut Spin>, time: Res<Time>) {
+    for mut spin in &mut bodies {
+        spin.angle = (spin.angle + spin.rate * time.delta_secs()).rem_euclid(2. * PI);
+    }
+}
+
+fn face_star(mut bodies: Query<(&Transform, &Mass, &mut Sunward)>) {
+    let Some(star) = bodies
+        .iter()
+        .max_by(|(_, a, _), (_, b, _)| a.0.total_cmp(&b.0))
+        .map(|(transform, ..)| transform.translation.truncate())
+    else {
+        return;
+    };
+    for (transform, _, mut sunward) in &mut bodies {
+        let direction = (star - transform.translation.truncate()).try_normalize();
+        sunward.set_if_neq(Sunward(direction));
+    }
+}
+
+/// Mass = density * volume, where for a sphere volume = (4/3) * π * r³
+pub fn body_mass(radius: f32, density: f32) -> f32 {
+    density * (4.0 / 3.0) * PI * radius.powi(3)
+}
+
+type Resized = Or<(Changed<Radius>, Changed<Density>)>;
+
+fn assign_masses(mut bodies: Query<(&Radius, &Density, &mut Mass), Resized>) {
+    for (radius, density, mut mass) in bodies.iter_mut() {
+        mass.0 = body_mass(radius.0, density.0);
+    }
+}
+
+/// Velocities that put every body on a circular orbit around its local
+/// attractor, given each body's position and mass.
+///
+/// Bodies are placed heaviest first, the heaviest staying still. Each later
+/// one orbits the innermost placed body whose Hill sphere it's inside, along
+/// with whatever already orbits that body closer in: a moon its planet, a
+/// planet its star, and a planet around a binary both stars at once.
+pub fn circular_orbit_velocities(
+    bodies: &[(DVec3, f64)],
+    constants: &PhysicsConstants,
+) -> Vec<DVec3> {
+    let bodies: Vec<_> = bodies
+        .iter()
+        .map(|&(position, mass)| (position, mass, 1.))
+        .collect();
+    orbit_velocities(&bodies, constants)
+}
+
+/// Like [`circular_orbit_velocities`], given each body's position, mass and
+/// speed relative to its attractor as a fraction of circular, such as
+/// [`Comet::apoapsis_speed`].
+pub fn orbit_velocities(bodies: &[(DVec3, f64, f64)], constants: &PhysicsConstants) -> Vec<DVec3> {
+    let g = constants.g as f64;
+    let softening = constants.softening as f64;
+    let mut order: Vec<usize> = (0..bodies.len()).collect();
+    order.sort_by(|&a, &b| bodies[b].1.total_cmp(&bodies[a].1));
+
+    let mut velocities = vec![DVec3::ZERO; bodies.len()];
+    let mut primaries: Vec<Option<usize>> = vec![None; bodies.len()];
+    let mut hill_radii = vec![f64::INFINITY; bodies.len()];
+    let orbits = |primaries: &[Option<usize>], mut body: usize, around: usize| {
+        while let Some(primary) = primaries[body] {
+            if primary == around {
+                return true;
+            }
+            body = primary;
+        }
+        false
+    };
+
+    for (placed, &body) in order.iter().enumerate() {
+        let (position, mass, speed) = bodies[body];
+        // The attractor each placed body anchors: itself and what orbits it
+        // closer in than this body is
+        let attractor = order[..placed]
+            .iter()
+            .filter_map(|&anchor| {
+                let reach = position.distance(bodies[anchor].0);
+                let members = order[..placed].iter().filter(|&&member| {
+                    member == anchor
+                        || (orbits(&primaries, member, anchor)
+                            && bodies[member].0.distance(bodies[anchor].0) < reach)
+                });
+                let (mut total, mut center, mut momentum) = (0., DVec3::ZERO, DVec3::ZERO);
+                for &member in members {
+                    let (member_position, member_mass, _) = bodies[member];
+                    total += member_mass;
+                    center += member_position * member_mass;
+                    momentum += velocities[member] * member_mass;
+                }
+                let center = center / total;
+                let distance = position.distance(center);
+                (distance < hill_radii[anchor]).then_some((
+                    anchor,
+                    distance,
+                    total,
+                    center,
+                    momentum / total,
+                ))
+            })
+            .min_by(|(_, a, ..), (_, b, ..)| a.total_cmp(b));
+        let Some((anchor, distance, total, center, drift)) = attractor else {
+            // The heaviest body stays still
+            continue;
+        };
+
+        primaries[body] = Some(anchor);
+        hill_radii[body] = distance * (mass / (3. * total)).cbrt();
+        velocities[body] = drift;
+        if distance <= 0. {
+            continue;
+        }
+        // Balance the softened pull: v² / r = G * (M + m) * r / (r² + ε²)^(3/2)
+        let softened = (distance * distance + softening * softening).sqrt();
+        let orbital_speed = (g * (total + mass) / softened).sqrt() * distance / softened;
+        let direction = position - center;
+        // Velocity perpendicular to the radius vector
+        let tangent = DVec3::new(-direction.y, direction.x, 0.0).normalize();
+        velocities[body] += tangent * orbital_speed * speed;
+    }
+    velocities
+}
+
+type BodyState = (
+    &'static Name,
+    &'static SimPosition,
+    &'static Mass,
+    Option<&'static Comet>,
+    &'static mut SimVelocity,
+);
+
+fn recalculate_orbital_velocities(
+    mut bodies: Query<BodyState, With<Body>>,
+    constants: Res<PhysicsConstants>,
+    scenario: Res<Scenario>,
+) {
+    let states: Vec<_> = bodies
+        .iter()
+        .map(|(_, position, mass, comet, _)| {
+            // Comets start at the far end of their orbits
+            let speed = comet.map_or(1., Comet::apoapsis_speed);
+            (position.0, mass.0 as f64, speed)
+        })
+        .collect();
+    let velocities = orbit_velocities(&states, &constants);
+    for ((name, _, _, _, mut velocity), orbital_velocity) in bodies.iter_mut().zip(velocities) {
+        let given = scenario
+            .bodies
+            .iter()
+            .find(|spec| spec.name == name.as_str())
+            .and_then(BodySpec::initial_velocity);
+        velocity.0 = given.unwrap_or(orbital_velocity);
+    }
+}
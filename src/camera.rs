@@ -0,0 +1,127 @@
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+/// Where the space plot looks: panned by dragging, zoomed toward the pointer
+/// by scrolling or pinching, and eased smoothly between zoom levels.
+///
+/// The plot takes its bounds from here every frame rather than navigating
+/// itself, as does the Bevy camera under it with the `mesh_render` feature.
+pub struct PlotCameraPlugin;
+
+impl Plugin for PlotCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlotCamera>()
+            .add_systems(Update, ease_zoom);
+    }
+}
+
+/// World units per screen point when zoomed all the way in.
+const MIN_SCALE: f64 = 1e-4;
+/// World units per screen point when zoomed all the way out.
+const MAX_SCALE: f64 = 50.;
+/// How quickly zooming catches up, per second. Higher is snappier.
+const ZOOM_RATE: f64 = 12.;
+/// Zoom factor per screen point scrolled.
+const SCROLL_ZOOM: f64 = 0.002;
+
+#[derive(Resource, Default, Debug)]
+pub struct PlotCamera {
+    /// The world point in the middle of the plot.
+    pub center: DVec2,
+    /// World units per screen point. Zero until the plot has first been shown.
+    pub scale: f64,
+    /// The scale being eased toward.
+    target_scale: f64,
+    /// A world point held still on screen while zooming, and its offset from
+    /// the middle of the plot in screen points, y up.
+    anchor: Option<(DVec2, DVec2)>,
+}
+
+impl PlotCamera {
+    pub fn is_placed(&self) -> bool {
+        self.scale > 0.
+    }
+
+    /// Moves the view along with a pointer or fingers dragged across it.
+    pub fn drag(&mut self, delta: egui::Vec2) {
+        self.shift(DVec2::new(-delta.x as f64, delta.y as f64) * self.scale);
+    }
+
+    /// Pans by fractions of the plot's width and height.
+    pub fn pan(&mut self, fraction: Vec2, size: egui::Vec2) {
+        let points = DVec2::new((fraction.x * size.x) as f64, (fraction.y * size.y) as f64);
+        self.shift(points * self.scale);
+    }
+
+    fn shift(&mut self, world: DVec2) {
+        self.center += world;
+        if let Some((anchor, _)) = &mut self.anchor {
+            *anchor += world;
+        }
+    }
+
+    pub fn look_at(&mut self, center: DVec2) {
+        self.center = center;
+        self.anchor = None;
+    }
+
+    /// Starts easing in by `factor`, or out for factors under one, keeping
+    /// `anchor` in world coordinates where it is on screen.
+    pub fn zoom_toward(&mut self, factor: f64, anchor: Option<DVec2>) {
+        if !self.is_placed() {
+            return;
+        }
+        self.target_scale = (self.target_scale / factor).clamp(MIN_SCALE, MAX_SCALE);
+        self.anchor = Some(match anchor {
+            Some(world) => (world, (world - self.center) / self.scale),
+            None => (self.center, DVec2::ZERO),
+        });
+    }
+
+    /// Zooms in by scrolling up or pinching out, and out the other way.
+    pub fn zoom_from_input(&mut self, input: &egui::InputState, anchor: Option<DVec2>) {
+        let factor =
+            input.zoom_delta() as f64 * (input.smooth_scroll_delta.y as f64 * SCROLL_ZOOM).exp();
+        if factor != 1. {
+            self.zoom_toward(factor, anchor);
+        }
+    }
+
+    /// Jumps straight to showing at least `bounds` on a plot of `size`.
+    pub fn frame(&mut self, bounds: &egui_plot::PlotBounds, size: egui::Vec2) {
+        let center = bounds.center();
+        self.center = DVec2::new(center.x, center.y);
+        self.scale = (bounds.width() / size.x as f64)
+            .max(bounds.height() / size.y as f64)
+            .clamp(MIN_SCALE, MAX_SCALE);
+        self.target_scale = self.scale;
+        self.anchor = None;
+    }
+
+    /// What a plot of `size` screen points shows.
+    pub fn bounds(&self, size: egui::Vec2) -> egui_plot::PlotBounds {
+        let half = DVec2::new(size.x as f64, size.y as f64) * self.scale / 2.;
+        let (min, max) = (self.center - half, self.center + half);
+        egui_plot::PlotBounds::from_min_max([min.x, min.y], [max.x, max.y])
+    }
+}
+
+fn ease_zoom(mut camera: ResMut<PlotCamera>, time: Res<Time<Real>>) {
+    if camera.scale == camera.target_scale {
+        return;
+    }
+    // Eased in log space so zooming in and out feel the same
+    let progress = 1. - (-ZOOM_RATE * time.delta_secs_f64()).exp();
+    let ratio = camera.target_scale / camera.scale;
+    camera.scale *= ratio.powf(progress);
+    if (ratio - 1.).abs() < 1e-3 {
+        camera.scale = camera.target_scale;
+    }
+    if let Some((world, offset)) = camera.anchor {
+        camera.center = world - offset * camera.scale;
+    }
+    if camera.scale == camera.target_scale {
+        camera.anchor = None;
+    }
+}
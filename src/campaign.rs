@@ -0,0 +1,203 @@
+use crate::app_state::{AppState, GameOutcome};
+use crate::crafts::CraftDocked;
+use crate::i18n::{tr, tr_args};
+use crate::persistence::state_directory;
+use crate::scenario::Scenario;
+use crate::tutorial::{Tutorial, TutorialProgress};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use bevy_persistent::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The main menu and the campaign of bundled levels, each unlocked by
+/// finishing the one before.
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut App) {
+        let progress = Persistent::<CampaignProgress>::builder()
+            .name("campaign")
+            .format(StorageFormat::Toml)
+            .path(state_directory().join("campaign.toml"))
+            .default(CampaignProgress::default())
+            .revertible(true)
+            .revert_to_default_on_deserialization_errors(true)
+            .build()
+            .expect("failed to initialize campaign progress");
+
+        app.insert_resource(progress)
+            .insert_resource(Campaign::bundled())
+            .init_resource::<CurrentLevel>()
+            .add_systems(
+                EguiPrimaryContextPass,
+                main_menu.run_if(in_state(AppState::Menu)),
+            )
+            .add_systems(Update, complete_levels.run_if(in_state(AppState::Playing)));
+    }
+}
+
+/// How far the player has got through the campaign.
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct CampaignProgress {
+    /// Levels before this index are finished.
+    pub completed: usize,
+}
+
+/// The level being played, if it's from the campaign rather than a sandbox.
+#[derive(Resource, Default)]
+pub struct CurrentLevel(pub Option<usize>);
+
+/// Metadata a scenario needs to be a campaign level.
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelInfo {
+    pub name: String,
+    pub briefing: String,
+    /// Body a craft must dock at to finish the level.
+    pub goal: String,
+}
+
+#[derive(Deserialize)]
+struct LevelFile {
+    level: LevelInfo,
+    #[serde(flatten)]
+    scenario: Scenario,
+}
+
+/// Campaign levels, in order.
+#[derive(Resource)]
+pub struct Campaign(pub Vec<(LevelInfo, Scenario)>);
+
+/// Embedded so the campaign works on the web and from any directory.
+const LEVEL_FILES: [&str; 3] = [
+    include_str!("../assets/campaign/01-first-contact.toml"),
+    include_str!("../assets/campaign/02-outer-moon.toml"),
+    include_str!("../assets/campaign/03-slingshot.toml"),
+];
+
+impl Campaign {
+    pub fn bundled() -> Self {
+        Self(
+            LEVEL_FILES
+                .iter()
+                .map(|text| {
+                    let mut file: LevelFile =
+                        toml::from_str(text).expect("bundled campaign level is invalid");
+                    file.scenario
+                        .place_orbits()
+                        .expect("bundled campaign level has an invalid orbit");
+                    (file.level, file.scenario)
+                })
+                .collect(),
+        )
+    }
+}
+
+/// The tutorial, and whether it's been finished before.
+#[derive(SystemParam)]
+struct TutorialEntry<'w> {
+    tutorial: ResMut<'w, Tutorial>,
+    progress: Res<'w, Persistent<TutorialProgress>>,
+}
+
+fn main_menu(
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+    campaign: Res<Campaign>,
+    progress: Res<Persistent<CampaignProgress>>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut tutorial: TutorialEntry,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            ui.add_space(48.);
+            ui.heading(egui::RichText::new("SlingCraft").size(40.));
+            ui.add_space(24.);
+
+            let label = if tutorial.progress.completed {
+                format!("✔ {}", tr("tutorial"))
+            } else {
+                tr("tutorial")
+            };
+            let button = ui.button(label).on_hover_text(tr("tutorial-hint"));
+            if button.clicked() {
+                tutorial.tutorial.start(&mut commands);
+                current_level.0 = None;
+                next_state.set(AppState::Playing);
+            }
+            ui.add_space(16.);
+
+            ui.strong(tr("campaign"));
+            for (index, (level, scenario)) in campaign.0.iter().enumerate() {
+                let unlocked = index <= progress.completed;
+                let label = if index < progress.completed {
+                    format!("✔ {}", level.name)
+                } else {
+                    level.name.clone()
+                };
+                let button = ui
+                    .add_enabled(unlocked, egui::Button::new(label))
+                    .on_hover_text(&level.briefing)
+                    .on_disabled_hover_text(tr("level-locked"));
+                if button.clicked() {
+                    commands.insert_resource(scenario.clone());
+                    current_level.0 = Some(index);
+                    next_state.set(AppState::Playing);
+                }
+            }
+
+            ui.add_space(16.);
+            if ui.button(tr("sandbox")).clicked() {
+                commands.insert_resource(Scenario::default());
+                current_level.0 = None;
+                next_state.set(AppState::Playing);
+            }
+        });
+    });
+}
+
+fn complete_levels(
+    mut docked: EventReader<CraftDocked>,
+    names: Query<&Name>,
+    campaign: Res<Campaign>,
+    current_level: Res<CurrentLevel>,
+    mut progress: ResMut<Persistent<CampaignProgress>>,
+    mut outcome: ResMut<GameOutcome>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Some(index) = current_level.0 else {
+        docked.clear();
+        return;
+    };
+    let (level, _) = &campaign.0[index];
+    for event in docked.read() {
+        if names
+            .get(event.body)
+            .is_ok_and(|name| name.as_str() == level.goal)
+        {
+            *outcome = GameOutcome {
+                won: true,
+                title: tr("level-complete"),
+                details: vec![tr_args(
+                    "level-reached",
+                    &[
+                        ("level", level.name.as_str().into()),
+                        ("goal", level.goal.as_str().into()),
+                    ],
+                )],
+            };
+            next_state.set(AppState::GameOver);
+            if progress.completed <= index {
+                let completed = index + 1;
+                if let Err(err) = progress.update(|progress| progress.completed = completed) {
+                    log::error!("failed to save campaign progress: {err}");
+                }
+            }
+        }
+    }
+}
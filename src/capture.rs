@@ -0,0 +1,242 @@
+use crate::bindings::{Action, ActionInput, KeyBindings};
+use crate::events::Toasts;
+use crate::persistence::{next_free_path, state_directory};
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{Screenshot, ScreenshotCaptured};
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on, futures_lite::future};
+use bevy_persistent::prelude::*;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+/// Saves screenshots and short animated GIFs to the captures folder next to
+/// the saved state, with a toast saying where. Web builds have nowhere to
+/// save them, so the hotkeys do nothing there.
+pub struct CapturePlugin;
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GifRecording>()
+            .add_event::<Capture>()
+            .add_systems(
+                Update,
+                (start_captures, record_gif_frames, finish_gifs).chain(),
+            );
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(Update, capture_keys.before(start_captures));
+    }
+}
+
+/// Seconds between GIF frames, in real time.
+const GIF_FRAME_INTERVAL: f32 = 0.1;
+/// Recording stops by itself after this many frames.
+const GIF_MAX_FRAMES: usize = 100;
+/// Frames are scaled down to at most this wide to keep files shareable.
+const GIF_MAX_WIDTH: u32 = 640;
+
+/// Request from the menus or hotkeys.
+#[derive(Event, Clone, Copy, Debug)]
+pub enum Capture {
+    Screenshot,
+    /// Starts recording a GIF, or stops and saves the one being recorded.
+    ToggleGif,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CaptureError {
+    #[error("could not save capture: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not convert frame: {0}")]
+    Image(String),
+    #[error("could not encode GIF: {0}")]
+    Gif(#[from] gif::EncodingError),
+}
+
+/// The GIF being recorded, if any, and ones still being encoded.
+#[derive(Resource, Default)]
+pub struct GifRecording {
+    /// Frames so far, while recording.
+    frames: Option<Vec<GifFrame>>,
+    timer: Timer,
+    encoding: Vec<Task<Result<PathBuf, CaptureError>>>,
+}
+
+impl GifRecording {
+    pub fn is_recording(&self) -> bool {
+        self.frames.is_some()
+    }
+}
+
+struct GifFrame {
+    width: u16,
+    height: u16,
+    rgba: Vec<u8>,
+}
+
+impl GifFrame {
+    /// Samples every nth pixel so the frame fits [`GIF_MAX_WIDTH`].
+    fn from_image(image: Image) -> Result<Self, CaptureError> {
+        let image = image
+            .try_into_dynamic()
+            .map_err(|err| CaptureError::Image(err.to_string()))?
+            .to_rgba8();
+        let (width, height) = image.dimensions();
+        let step = width.div_ceil(GIF_MAX_WIDTH).max(1) as usize;
+        let pixels = image.into_raw();
+        let mut rgba = Vec::new();
+        for y in (0..height as usize).step_by(step) {
+            for x in (0..width as usize).step_by(step) {
+                let index = (y * width as usize + x) * 4;
+                rgba.extend_from_slice(&pixels[index..index + 4]);
+            }
+        }
+        Ok(Self {
+            width: width.div_ceil(step as u32) as u16,
+            height: height.div_ceil(step as u32) as u16,
+            rgba,
+        })
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+fn capture_keys(mut input: ActionInput, mut captures: EventWriter<Capture>) {
+    if input.just_pressed(Action::Screenshot) {
+        captures.write(Capture::Screenshot);
+    } else if input.just_pressed(Action::RecordGif) {
+        captures.write(Capture::ToggleGif);
+    }
+}
+
+fn captures_directory() -> PathBuf {
+    state_directory().with_file_name("captures")
+}
+
+fn save_png(image: Image, path: &Path) -> Result<(), CaptureError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    image
+        .try_into_dynamic()
+        .map_err(|err| CaptureError::Image(err.to_string()))?
+        .to_rgb8()
+        .save(path)
+        .map_err(|err| CaptureError::Image(err.to_string()))
+}
+
+fn start_captures(
+    mut commands: Commands,
+    mut captures: EventReader<Capture>,
+    mut recording: ResMut<GifRecording>,
+    mut toasts: ResMut<Toasts>,
+    time: Res<Time<Real>>,
+    bindings: Res<Persistent<KeyBindings>>,
+) {
+    let now = time.elapsed_secs_f64();
+    for capture in captures.read() {
+        match capture {
+            Capture::Screenshot => {
+                let path = next_free_path(&captures_directory(), "screenshot", "png");
+                commands.spawn(Screenshot::primary_window()).observe(
+                    move |captured: Trigger<ScreenshotCaptured>,
+                          mut toasts: ResMut<Toasts>,
+                          time: Res<Time<Real>>| {
+                        let message = match save_png(captured.event().0.clone(), &path) {
+                            Ok(()) => format!("Screenshot saved to {}", path.display()),
+                            Err(err) => err.to_string(),
+                        };
+                        toasts.push(message, time.elapsed_secs_f64());
+                    },
+                );
+            }
+            Capture::ToggleGif => match recording.frames.take() {
+                Some(frames) => stop_gif(&mut recording, frames),
+                None => {
+                    recording.frames = Some(Vec::new());
+                    recording.timer = Timer::from_seconds(GIF_FRAME_INTERVAL, TimerMode::Repeating);
+                    let stop = bindings.binding(Action::RecordGif);
+                    toasts.push(format!("Recording GIF… ({stop} to stop)"), now);
+                }
+            },
+        }
+    }
+}
+
+/// Hands the frames to a background task to encode and save.
+fn stop_gif(recording: &mut GifRecording, frames: Vec<GifFrame>) {
+    let path = next_free_path(&captures_directory(), "capture", "gif");
+    recording
+        .encoding
+        .push(AsyncComputeTaskPool::get().spawn(async move { encode_gif(frames, path) }));
+}
+
+fn encode_gif(frames: Vec<GifFrame>, path: PathBuf) -> Result<PathBuf, CaptureError> {
+    let Some(first) = frames.first() else {
+        return Err(CaptureError::Image("no frames were captured".to_string()));
+    };
+    let (width, height) = (first.width, first.height);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut encoder = gif::Encoder::new(BufWriter::new(File::create(&path)?), width, height, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    // Frames from before a window resize no longer fit
+    for mut frame in frames
+        .into_iter()
+        .filter(|frame| (frame.width, frame.height) == (width, height))
+    {
+        let mut gif_frame = gif::Frame::from_rgba_speed(width, height, &mut frame.rgba, 10);
+        gif_frame.delay = (GIF_FRAME_INTERVAL * 100.) as u16;
+        encoder.write_frame(&gif_frame)?;
+    }
+    Ok(path)
+}
+
+fn record_gif_frames(
+    mut commands: Commands,
+    mut recording: ResMut<GifRecording>,
+    time: Res<Time<Real>>,
+) {
+    let Some(count) = recording.frames.as_ref().map(Vec::len) else {
+        return;
+    };
+    if count >= GIF_MAX_FRAMES {
+        if let Some(frames) = recording.frames.take() {
+            stop_gif(&mut recording, frames);
+        }
+        return;
+    }
+    if !recording.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    commands.spawn(Screenshot::primary_window()).observe(
+        |captured: Trigger<ScreenshotCaptured>, mut recording: ResMut<GifRecording>| {
+            // Screenshots still in flight when recording stops are dropped
+            let Some(frames) = &mut recording.frames else {
+                return;
+            };
+            match GifFrame::from_image(captured.event().0.clone()) {
+                Ok(frame) => frames.push(frame),
+                Err(err) => log::warn!("Dropped a GIF frame: {err}"),
+            }
+        },
+    );
+}
+
+fn finish_gifs(
+    mut recording: ResMut<GifRecording>,
+    mut toasts: ResMut<Toasts>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed_secs_f64();
+    recording.encoding.retain_mut(|task| {
+        let Some(result) = block_on(future::poll_once(task)) else {
+            return true;
+        };
+        let message = match result {
+            Ok(path) => format!("GIF saved to {}", path.display()),
+            Err(err) => err.to_string(),
+        };
+        toasts.push(message, now);
+        false
+    });
+}
@@ -0,0 +1,150 @@
+use crate::app_state::AppState;
+use crate::physics::SimRng;
+use crate::recorder::{Recorder, Telemetry};
+use crate::replay::{Playback, Replay};
+use crate::scenario::Scenario;
+use bevy::prelude::*;
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Startup options.
+///
+/// Added as a plugin after the Slingcraft plugins, it overrides their defaults
+/// before any `Startup` system runs.
+#[derive(Parser, Debug, Clone)]
+#[command(version, about = "An orbital slingshot game")]
+pub struct Cli {
+    /// Scenario file (TOML) to load instead of the built-in system.
+    #[arg(long)]
+    pub scenario: Option<PathBuf>,
+
+    /// Seed for all randomness in the simulation.
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+
+    /// Physics timestep in seconds.
+    #[arg(long, default_value_t = 1. / 64., value_parser = positive_seconds)]
+    pub timestep: f64,
+
+    /// Simulation speed relative to real time. Defaults to the one in the
+    /// settings.
+    #[arg(long)]
+    pub speed: Option<f32>,
+
+    /// Run the simulation without a window or UI.
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Record body states after every physics tick to this CSV file.
+    #[arg(long)]
+    pub record: Option<PathBuf>,
+
+    /// Sample body motion and energy, and write it to this CSV or JSON file on
+    /// exit.
+    #[arg(long, value_name = "PATH")]
+    pub record_telemetry: Option<PathBuf>,
+
+    /// Simulated seconds between telemetry samples.
+    #[arg(long, default_value_t = 0.1, value_parser = positive_seconds)]
+    pub telemetry_interval: f64,
+
+    /// Replay file to play back, overriding the scenario and timestep.
+    #[arg(long, conflicts_with = "scenario")]
+    pub replay: Option<PathBuf>,
+
+    /// Simulate this scenario file headless as fast as possible, then print
+    /// ticks per second and energy drift and exit.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["scenario", "replay"])]
+    pub bench_scenario: Option<PathBuf>,
+
+    /// Physics ticks to run with `--bench-scenario`.
+    #[arg(long, default_value_t = 10_000)]
+    pub bench_ticks: u64,
+}
+
+impl Cli {
+    /// Parses the process arguments, exiting with usage on error.
+    ///
+    /// Web builds have no arguments and always get the defaults.
+    pub fn from_env() -> Self {
+        if cfg!(target_arch = "wasm32") {
+            Self::parse_from(["slingcraft"])
+        } else {
+            Self::parse()
+        }
+    }
+}
+
+/// Parses a duration that can actually be stepped by: finite and above zero.
+fn positive_seconds(text: &str) -> Result<f64, String> {
+    let seconds: f64 = text.parse().map_err(|err| format!("{err}"))?;
+    if seconds.is_finite() && seconds > 0. {
+        Ok(seconds)
+    } else {
+        Err(format!("{seconds} isn't a positive number of seconds"))
+    }
+}
+
+impl Default for Cli {
+    fn default() -> Self {
+        Self::parse_from(["slingcraft"])
+    }
+}
+
+impl Plugin for Cli {
+    fn build(&self, app: &mut App) {
+        if let Some(path) = &self.scenario {
+            let scenario = Scenario::load(path)
+                .unwrap_or_else(|err| panic!("failed to load scenario {}: {err}", path.display()));
+            app.insert_resource(scenario);
+            // A scenario from the command line skips the main menu
+            app.insert_state(AppState::Playing);
+        }
+        // Likewise a scenario shared in the page's link
+        #[cfg(target_arch = "wasm32")]
+        if let Some(scenario) = shared_scenario() {
+            app.insert_resource(scenario);
+            app.insert_state(AppState::Playing);
+        }
+
+        if let Some(path) = &self.record {
+            let recorder = Recorder::create(path).unwrap_or_else(|err| {
+                panic!("failed to create recording {}: {err}", path.display())
+            });
+            app.insert_resource(recorder);
+        }
+
+        let mut telemetry = Telemetry::default();
+        telemetry.recording = self.record_telemetry.is_some();
+        telemetry.interval = self.telemetry_interval;
+        telemetry.output = self.record_telemetry.clone();
+        app.insert_resource(telemetry);
+
+        app.insert_resource(SimRng::seeded(self.seed))
+            .insert_resource(Time::<Fixed>::from_seconds(self.timestep));
+        if let Some(speed) = self.speed {
+            app.world_mut()
+                .resource_mut::<Time<Virtual>>()
+                .set_relative_speed(speed);
+        }
+
+        if let Some(path) = &self.replay {
+            let replay = Replay::load(path)
+                .unwrap_or_else(|err| panic!("failed to load replay {}: {err}", path.display()));
+            Playback::start(replay, app.world_mut());
+            app.insert_state(AppState::Playing);
+        }
+    }
+}
+
+/// The scenario in the page's URL fragment, if it has one and it decodes.
+#[cfg(target_arch = "wasm32")]
+fn shared_scenario() -> Option<Scenario> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    let fragment = hash
+        .strip_prefix('#')?
+        .strip_prefix(crate::scenario::LINK_PREFIX)?;
+    Scenario::from_link_fragment(fragment)
+        .inspect_err(|err| log::error!("failed to load shared scenario: {err}"))
+        .ok()
+}
@@ -0,0 +1,282 @@
+use crate::app_state::AppState;
+use crate::bodies::{Body, Density, Fill, Radius, body_mass};
+use crate::crafts::Crafts;
+use crate::factions::Owner;
+use crate::influence::RocheLimit;
+use crate::physics::{Mass, PhysicsConstants, SimPosition, SimRng, SimVelocity};
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy_egui::egui::Color32;
+use rand::Rng;
+use std::collections::HashSet;
+use std::f32::consts::PI;
+use std::f64::consts::TAU;
+
+/// Resolves bodies that touch or stray inside a Roche limit: gentle impacts
+/// merge, while energetic impacts and tidal stress break bodies apart.
+pub struct CollisionsPlugin;
+
+impl Plugin for CollisionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BodyDestroyed>().add_systems(
+            FixedPostUpdate,
+            (tidal_disruption, collide_bodies)
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+/// Bodies no smaller than this can break apart.
+const MIN_FRAGMENT_RADIUS: f32 = 0.25;
+/// Top speed of the random spread given to fragments.
+const FRAGMENT_DISPERSION: f64 = 0.5;
+
+/// A body gone, broken apart or absorbed into another.
+#[derive(Event, Clone, Debug)]
+pub struct BodyDestroyed {
+    pub name: String,
+    pub cause: Destruction,
+    /// Where the body was, and how it was moving, when it went.
+    pub position: DVec3,
+    pub velocity: DVec3,
+    pub fill: Color32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Destruction {
+    /// Torn apart by the tides of this primary.
+    TornApart { primary: Entity },
+    /// Broken apart hitting this body.
+    Shattered { against: Entity },
+    /// Absorbed into this body.
+    Merged { into: Entity },
+}
+
+/// Left over from a body that broke apart. Fragments aren't torn apart by
+/// tides again, which would otherwise shred them down to the smallest size.
+#[derive(Component)]
+pub struct Fragment;
+
+/// A body's state, detached from the ECS so pairs can be resolved.
+struct Snapshot {
+    entity: Entity,
+    name: String,
+    radius: f32,
+    density: f32,
+    fill: Color32,
+    position: DVec3,
+    velocity: DVec3,
+    mass: f64,
+    crafts: u32,
+    owner: Option<Owner>,
+}
+
+type BodyState = (
+    Entity,
+    &'static Name,
+    &'static Radius,
+    &'static Density,
+    &'static Fill,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Mass,
+    &'static Crafts,
+    Option<&'static Owner>,
+);
+
+fn snapshots(bodies: &Query<BodyState, With<Body>>) -> Vec<Snapshot> {
+    bodies
+        .iter()
+        .map(
+            |(entity, name, radius, density, fill, position, velocity, mass, crafts, owner)| {
+                Snapshot {
+                    entity,
+                    name: name.to_string(),
+                    radius: radius.0,
+                    density: density.0,
+                    fill: fill.0,
+                    position: position.0,
+                    velocity: velocity.0,
+                    mass: mass.0 as f64,
+                    crafts: crafts.0,
+                    owner: owner.copied(),
+                }
+            },
+        )
+        .collect()
+}
+
+fn tidal_disruption(
+    mut commands: Commands,
+    bodies: Query<BodyState, With<Body>>,
+    limits: Query<&RocheLimit, Without<Fragment>>,
+    mut rng: ResMut<SimRng>,
+    mut destroyed: EventWriter<BodyDestroyed>,
+) {
+    for body in snapshots(&bodies) {
+        let Ok(RocheLimit(Some((primary, limit)))) = limits.get(body.entity) else {
+            continue;
+        };
+        let Ok((.., primary_position, _, _, _, _)) = bodies.get(*primary) else {
+            continue;
+        };
+        if body.position.distance(primary_position.0) < *limit
+            && spawn_fragments(&mut commands, &mut rng, &body, body.position, body.velocity)
+        {
+            log::info!("{} was torn apart by tides", body.name);
+            commands.entity(body.entity).despawn();
+            destroyed.write(BodyDestroyed {
+                name: body.name,
+                cause: Destruction::TornApart { primary: *primary },
+                position: body.position,
+                velocity: body.velocity,
+                fill: body.fill,
+            });
+        }
+    }
+}
+
+fn collide_bodies(
+    mut commands: Commands,
+    bodies: Query<BodyState, With<Body>>,
+    constants: Res<PhysicsConstants>,
+    mut rng: ResMut<SimRng>,
+    mut destroyed: EventWriter<BodyDestroyed>,
+) {
+    let snapshots = snapshots(&bodies);
+    // Each body takes part in at most one collision per step
+    let mut resolved = HashSet::new();
+
+    for (i, a) in snapshots.iter().enumerate() {
+        for b in &snapshots[i + 1..] {
+            if resolved.contains(&a.entity) || resolved.contains(&b.entity) {
+                continue;
+            }
+            if a.position.distance(b.position) > (a.radius + b.radius) as f64 {
+                continue;
+            }
+            resolved.extend([a.entity, b.entity]);
+
+            let (large, small) = if a.mass >= b.mass { (a, b) } else { (b, a) };
+            let total_mass = large.mass + small.mass;
+            let reduced_mass = large.mass * small.mass / total_mass;
+            // Impact energy per unit mass, compared against the breakup threshold
+            let impact_energy =
+                0.5 * reduced_mass * large.velocity.distance_squared(small.velocity) / total_mass;
+
+            let cause = if impact_energy > constants.breakup_energy as f64
+                && shatter(&mut commands, &mut rng, large, small)
+            {
+                log::info!("{} shattered against {}", small.name, large.name);
+                Destruction::Shattered {
+                    against: large.entity,
+                }
+            } else {
+                merge(&mut commands, large, small);
+                log::info!("{} merged into {}", small.name, large.name);
+                Destruction::Merged { into: large.entity }
+            };
+            destroyed.write(BodyDestroyed {
+                name: small.name.clone(),
+                cause,
+                position: small.position,
+                velocity: small.velocity,
+                fill: small.fill,
+            });
+        }
+    }
+}
+
+/// Combines `small` into `large`, conserving mass and momentum.
+fn merge(commands: &mut Commands, large: &Snapshot, small: &Snapshot) {
+    let total_mass = large.mass + small.mass;
+    // Volumes add, and the density is whatever fits the combined mass
+    let radius = (large.radius.powi(3) + small.radius.powi(3)).cbrt();
+    let density = total_mass as f32 / ((4. / 3.) * PI * radius.powi(3));
+    commands.entity(small.entity).despawn();
+    commands.entity(large.entity).insert((
+        Radius(radius),
+        Density(density),
+        Mass(total_mass as f32),
+        SimPosition((large.position * large.mass + small.position * small.mass) / total_mass),
+        SimVelocity((large.velocity * large.mass + small.velocity * small.mass) / total_mass),
+        Crafts(large.crafts + small.crafts),
+    ));
+}
+
+/// Breaks `small` apart on `large`, after a perfectly inelastic impact along
+/// the line between them so the fragments don't sink into `large`.
+///
+/// Returns `false`, changing nothing, if `small` is too small to break.
+fn shatter(commands: &mut Commands, rng: &mut SimRng, large: &Snapshot, small: &Snapshot) -> bool {
+    let normal = (small.position - large.position)
+        .try_normalize()
+        .unwrap_or(DVec3::X);
+    let normal_velocity = (large.velocity.dot(normal) * large.mass
+        + small.velocity.dot(normal) * small.mass)
+        / (large.mass + small.mass);
+    let large_velocity = large.velocity + normal * (normal_velocity - large.velocity.dot(normal));
+    let small_velocity = small.velocity + normal * (normal_velocity - small.velocity.dot(normal));
+
+    // Clear of the surface, leaving room for the fragments to spread out
+    let center = large.position + normal * (large.radius + 2.2 * small.radius) as f64;
+    if !spawn_fragments(commands, rng, small, center, small_velocity) {
+        return false;
+    }
+    commands.entity(small.entity).despawn();
+    commands
+        .entity(large.entity)
+        .insert(SimVelocity(large_velocity));
+    true
+}
+
+/// Spawns several equal fragments of `body` around `center`, with the same
+/// total mass and, moving at `velocity`, momentum. Returns `false` if they'd
+/// be too small.
+fn spawn_fragments(
+    commands: &mut Commands,
+    rng: &mut SimRng,
+    body: &Snapshot,
+    center: DVec3,
+    velocity: DVec3,
+) -> bool {
+    let count = rng.0.random_range(3..=5_u32);
+    let radius = body.radius / (count as f32).cbrt();
+    if radius < MIN_FRAGMENT_RADIUS {
+        return false;
+    }
+
+    let dispersions: Vec<_> = (0..count)
+        .map(|_| {
+            let angle = rng.0.random::<f64>() * TAU;
+            DVec3::new(angle.cos(), angle.sin(), 0.) * rng.0.random::<f64>() * FRAGMENT_DISPERSION
+        })
+        .collect();
+    // The masses are equal, so removing the mean spread conserves momentum
+    let mean_dispersion = dispersions.iter().sum::<DVec3>() / count as f64;
+    let spin = rng.0.random::<f64>() * TAU;
+
+    for (i, dispersion) in dispersions.into_iter().enumerate() {
+        let angle = spin + TAU * i as f64 / count as f64;
+        let offset = DVec3::new(angle.cos(), angle.sin(), 0.) * body.radius as f64 * 1.1;
+        // Docked crafts are shared out, any remainder going to the first fragments
+        let crafts = body.crafts / count + u32::from((i as u32) < body.crafts % count);
+        let mut fragment = commands.spawn((
+            Body,
+            Fragment,
+            Radius(radius),
+            Density(body.density),
+            Name::new(format!("{} fragment {}", body.name, i + 1)),
+            Fill(body.fill),
+            SimPosition(center + offset),
+            SimVelocity(velocity + dispersion - mean_dispersion),
+            Mass(body_mass(radius, body.density)),
+            Crafts(crafts),
+        ));
+        if let Some(owner) = body.owner {
+            fragment.insert(owner);
+        }
+    }
+    true
+}
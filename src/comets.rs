@@ -0,0 +1,128 @@
+use crate::app_state::AppState;
+use crate::bodies::{Body, Radius};
+use crate::physics::{Mass, SimPosition, SimVelocity};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Bodies with a [`Comet`]: they start at the far end of a long, thin orbit
+/// around the star, grow a tail pointing away from it that lengthens as they
+/// swing in close, and can lose some of their mass each time they do.
+pub struct CometsPlugin;
+
+impl Plugin for CometsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedPostUpdate,
+            lose_mass.run_if(in_state(AppState::Playing)),
+        )
+        .add_systems(Update, grow_tails.run_if(AppState::in_game));
+    }
+}
+
+/// Distance from the star, in star radii, at which a tail is as long as
+/// [`Comet::tail`] says.
+const TAIL_REFERENCE: f32 = 4.;
+/// Tails stop growing at this many times [`Comet::tail`].
+const MAX_TAIL: f32 = 4.;
+
+/// Makes a body a comet.
+#[derive(Component, Reflect, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component)]
+#[require(CometTail, Approaching)]
+pub struct Comet {
+    /// Of the orbit around the heaviest body it starts on, from its farthest
+    /// point, unless it's given a velocity of its own.
+    #[serde(default = "default_eccentricity")]
+    pub eccentricity: f32,
+    /// Tail length in the comet's own radii at [`TAIL_REFERENCE`] star radii
+    /// out, growing with the inverse square of the distance closer in.
+    #[serde(default = "default_tail")]
+    pub tail: f32,
+    /// Fraction of its mass boiled off each time it passes closest to the
+    /// star.
+    #[serde(default)]
+    pub mass_loss: f32,
+}
+
+fn default_eccentricity() -> f32 {
+    0.9
+}
+
+fn default_tail() -> f32 {
+    3.
+}
+
+impl Default for Comet {
+    fn default() -> Self {
+        Self {
+            eccentricity: default_eccentricity(),
+            tail: default_tail(),
+            mass_loss: 0.,
+        }
+    }
+}
+
+impl Comet {
+    /// Speed at the far end of its orbit, relative to that of a circular
+    /// orbit at the same distance.
+    pub fn apoapsis_speed(&self) -> f64 {
+        (1. - self.eccentricity.clamp(0., 1.) as f64).sqrt()
+    }
+}
+
+/// From the comet to the end of its tail, in plot units.
+#[derive(Component, Default, Clone, Copy, PartialEq)]
+pub struct CometTail(pub Vec2);
+
+/// Whether the comet was last heading in towards the star, to tell when it
+/// passes closest.
+#[derive(Component, Default)]
+struct Approaching(bool);
+
+fn grow_tails(
+    bodies: Query<(&Mass, &Transform, &Radius), With<Body>>,
+    mut comets: Query<(&Comet, &Transform, &Radius, &mut CometTail)>,
+) {
+    let Some((_, star, star_radius)) = bodies.iter().max_by(|(a, ..), (b, ..)| a.0.total_cmp(&b.0))
+    else {
+        return;
+    };
+    let star = star.translation.truncate();
+    for (comet, transform, radius, mut tail) in &mut comets {
+        let away = transform.translation.truncate() - star;
+        let Some(direction) = away.try_normalize() else {
+            continue;
+        };
+        let closeness = (TAIL_REFERENCE * star_radius.0 / away.length()).powi(2);
+        let length = comet.tail * radius.0 * closeness.min(MAX_TAIL);
+        tail.set_if_neq(CometTail(direction * length));
+    }
+}
+
+/// Shrinks comets by [`Comet::mass_loss`] as they pass closest to the star,
+/// when they turn from heading in to heading out.
+fn lose_mass(
+    bodies: Query<(&Mass, &SimPosition, &SimVelocity), With<Body>>,
+    mut comets: Query<(
+        &Comet,
+        &SimPosition,
+        &SimVelocity,
+        &mut Radius,
+        &mut Approaching,
+    )>,
+) {
+    let Some((_, star_position, star_velocity)) =
+        bodies.iter().max_by(|(a, ..), (b, ..)| a.0.total_cmp(&b.0))
+    else {
+        return;
+    };
+    for (comet, position, velocity, mut radius, mut approaching) in &mut comets {
+        let outward = position.0 - star_position.0;
+        let heading_in = (velocity.0 - star_velocity.0).dot(outward) < 0.;
+        if approaching.0 && !heading_in && comet.mass_loss > 0. {
+            // Mass goes with the cube of the radius
+            radius.0 *= (1. - comet.mass_loss.clamp(0., 1.)).cbrt();
+        }
+        approaching.0 = heading_in;
+    }
+}
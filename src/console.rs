@@ -0,0 +1,561 @@
+use crate::app_state::AppState;
+use crate::bindings::{Action, ActionInput};
+use crate::bodies::{Body, body_mass, circular_orbit_velocities};
+use crate::comets::Comet;
+use crate::editor::{BodyState, Edit, EditorAction};
+use crate::physics::{Mass, PhysicsConstants, SimPosition, SimVelocity};
+use bevy::ecs::system::SystemParam;
+use bevy::math::{DVec2, DVec3};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use std::str::FromStr;
+
+/// A developer console ([`Action::Console`]) that takes typed commands to
+/// spawn and move bodies, change physics constants and set the speed. Edits
+/// to bodies go through the editor, so they can be undone.
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Console>()
+            .add_systems(Update, toggle_console.run_if(AppState::in_game))
+            .add_systems(
+                EguiPrimaryContextPass,
+                console_window.run_if(AppState::in_game),
+            );
+    }
+}
+
+/// Most lines of output kept.
+const SCROLLBACK: usize = 200;
+
+const HELP: &str = "\
+spawn body r=<radius> at <x>,<y> [d=<density>] [v=<vx>,<vy>] [name=<name>]
+spawn comet r=<radius> at <x>,<y> [e=<eccentricity>] [tail=<radii>] [loss=<fraction>]
+set <G|density|softening|breakup|kepler> <value>
+tp <body> <x> <y>
+speed <multiplier>[x]
+clear";
+
+#[derive(Resource, Default)]
+pub struct Console {
+    pub open: bool,
+    /// Set for the frame the console opens in, so the key that opened it
+    /// doesn't type into it.
+    just_opened: bool,
+    input: String,
+    output: Vec<ConsoleLine>,
+    /// Commands entered, oldest first, and how far back Up has gone.
+    history: Vec<String>,
+    recalled: Option<usize>,
+}
+
+enum ConsoleLine {
+    Command(String),
+    Reply(String),
+    Error(String),
+}
+
+impl Console {
+    fn print(&mut self, line: ConsoleLine) {
+        self.output.push(line);
+        let excess = self.output.len().saturating_sub(SCROLLBACK);
+        self.output.drain(..excess);
+    }
+
+    /// Steps through earlier commands, `back` towards the oldest.
+    fn recall(&mut self, back: bool) {
+        let index = match (self.recalled, back) {
+            (None, true) => self.history.len().checked_sub(1),
+            (None, false) => None,
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) => Some(index + 1).filter(|&next| next < self.history.len()),
+        };
+        self.recalled = index;
+        self.input = index.map_or(String::new(), |index| self.history[index].clone());
+    }
+}
+
+/// A physics constant `set` can change.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Constant {
+    G,
+    Density,
+    Softening,
+    BreakupEnergy,
+    KeplerThreshold,
+}
+
+impl FromStr for Constant {
+    type Err = CommandError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(match name.to_lowercase().as_str() {
+            "g" => Self::G,
+            "density" => Self::Density,
+            "softening" => Self::Softening,
+            "breakup" => Self::BreakupEnergy,
+            "kepler" => Self::KeplerThreshold,
+            _ => return Err(CommandError::UnknownConstant(name.to_string())),
+        })
+    }
+}
+
+/// A parsed console command.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Command {
+    /// Adds a body, on a circular orbit around the heaviest one unless given
+    /// a velocity, or a comet at the far end of an eccentric one.
+    Spawn {
+        name: Option<String>,
+        radius: f32,
+        density: Option<f32>,
+        position: DVec2,
+        velocity: Option<DVec2>,
+        comet: Option<Comet>,
+    },
+    Set {
+        constant: Constant,
+        value: f32,
+    },
+    Teleport {
+        body: String,
+        position: DVec2,
+    },
+    Speed(f32),
+    Help,
+    Clear,
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum CommandError {
+    #[error("unknown command {0:?}, try help")]
+    UnknownCommand(String),
+    #[error("unknown constant {0:?}, expected G, density, softening, breakup or kepler")]
+    UnknownConstant(String),
+    #[error("{0:?} isn't a number")]
+    NotANumber(String),
+    #[error("expected x,y but got {0:?}")]
+    NotAPoint(String),
+    #[error("missing {0}")]
+    Missing(&'static str),
+    #[error("unexpected {0:?}")]
+    Unexpected(String),
+    #[error("no body named {0:?}")]
+    NoSuchBody(String),
+    #[error("a body named {0:?} already exists")]
+    NameTaken(String),
+}
+
+/// A finite number, so nan or inf can't get into the physics.
+fn number(text: &str) -> Result<f32, CommandError> {
+    text.parse()
+        .ok()
+        .filter(|value: &f32| value.is_finite())
+        .ok_or_else(|| CommandError::NotANumber(text.to_string()))
+}
+
+fn point(text: &str) -> Result<DVec2, CommandError> {
+    let (x, y) = text
+        .split_once(',')
+        .ok_or_else(|| CommandError::NotAPoint(text.to_string()))?;
+    Ok(DVec2::new(number(x)? as f64, number(y)? as f64))
+}
+
+impl FromStr for Command {
+    type Err = CommandError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            return Err(CommandError::Missing("a command"));
+        };
+        match command.to_lowercase().as_str() {
+            "spawn" => {
+                let mut comet = match words.next().map(str::to_lowercase).as_deref() {
+                    Some("body") => None,
+                    Some("comet") => Some(Comet::default()),
+                    _ => return Err(CommandError::Missing("\"body\" or \"comet\" after spawn")),
+                };
+                let (mut name, mut radius, mut density, mut position, mut velocity) =
+                    (None, None, None, None, None);
+                while let Some(word) = words.next() {
+                    match word.split_once('=') {
+                        Some(("r", value)) => radius = Some(number(value)?),
+                        Some(("d", value)) => density = Some(number(value)?),
+                        Some(("v", value)) => velocity = Some(point(value)?),
+                        Some(("name", value)) => name = Some(value.to_string()),
+                        Some((key @ ("e" | "loss" | "tail"), value)) => {
+                            let Some(comet) = &mut comet else {
+                                return Err(CommandError::Unexpected(word.to_string()));
+                            };
+                            let value = number(value)?;
+                            match key {
+                                "e" => comet.eccentricity = value,
+                                "loss" => comet.mass_loss = value,
+                                _ => comet.tail = value,
+                            }
+                        }
+                        None if word == "at" => {
+                            let at = words.next().ok_or(CommandError::Missing("x,y after at"))?;
+                            position = Some(point(at)?);
+                        }
+                        _ => return Err(CommandError::Unexpected(word.to_string())),
+                    }
+                }
+                Ok(Self::Spawn {
+                    name,
+                    radius: radius.ok_or(CommandError::Missing("r=<radius>"))?,
+                    density,
+                    position: position.ok_or(CommandError::Missing("at <x>,<y>"))?,
+                    velocity,
+                    comet,
+                })
+            }
+            "set" => {
+                let constant = words.next().ok_or(CommandError::Missing("a constant"))?;
+                let value = words.next().ok_or(CommandError::Missing("a value"))?;
+                if let Some(extra) = words.next() {
+                    return Err(CommandError::Unexpected(extra.to_string()));
+                }
+                Ok(Self::Set {
+                    constant: constant.parse()?,
+                    value: number(value)?,
+                })
+            }
+            "tp" => {
+                // Body names can have spaces, so the coordinates come off the end
+                let rest: Vec<_> = words.collect();
+                let [body @ .., x, y] = &rest[..] else {
+                    return Err(CommandError::Missing("<body> <x> <y>"));
+                };
+                if body.is_empty() {
+                    return Err(CommandError::Missing("a body"));
+                }
+                Ok(Self::Teleport {
+                    body: body.join(" "),
+                    position: DVec2::new(number(x)? as f64, number(y)? as f64),
+                })
+            }
+            "speed" => {
+                let speed = words.next().ok_or(CommandError::Missing("a multiplier"))?;
+                if let Some(extra) = words.next() {
+                    return Err(CommandError::Unexpected(extra.to_string()));
+                }
+                Ok(Self::Speed(number(speed.trim_end_matches(['x', '×']))?))
+            }
+            "help" => Ok(Self::Help),
+            "clear" => Ok(Self::Clear),
+            _ => Err(CommandError::UnknownCommand(command.to_string())),
+        }
+    }
+}
+
+fn toggle_console(mut actions: ActionInput, mut console: ResMut<Console>) {
+    // Typing into the console has egui's keyboard focus
+    let pressed = if console.open {
+        actions.just_pressed_while_typing(Action::Console)
+    } else {
+        actions.just_pressed(Action::Console)
+    };
+    if pressed {
+        console.open = !console.open;
+        console.just_opened = console.open;
+    }
+}
+
+/// What commands get to change.
+#[derive(SystemParam)]
+struct Sandbox<'w, 's> {
+    edits: EventWriter<'w, EditorAction>,
+    constants: ResMut<'w, PhysicsConstants>,
+    time: ResMut<'w, Time<Virtual>>,
+    bodies: Query<
+        'w,
+        's,
+        (
+            &'static Name,
+            &'static SimPosition,
+            &'static SimVelocity,
+            &'static Mass,
+        ),
+        With<Body>,
+    >,
+}
+
+impl Sandbox<'_, '_> {
+    /// Carries out `command`, returning what to print.
+    fn run(&mut self, command: Command) -> Result<String, CommandError> {
+        match command {
+            Command::Spawn {
+                name,
+                radius,
+                density,
+                position,
+                velocity,
+                comet,
+            } => {
+                let taken = |name: &str| self.bodies.iter().any(|(body, ..)| body.as_str() == name);
+                let name = match name {
+                    Some(name) if taken(&name) => return Err(CommandError::NameTaken(name)),
+                    Some(name) => name,
+                    None => (1..)
+                        .map(|number| match comet {
+                            Some(_) => format!("Comet {number}"),
+                            None => format!("Body {number}"),
+                        })
+                        .find(|name| !taken(name))
+                        .unwrap_or_default(),
+                };
+                let density = density.unwrap_or(self.constants.density);
+                let position = position.extend(0.);
+                let velocity = match velocity {
+                    Some(velocity) => velocity.extend(0.),
+                    None => self.orbital_velocity(
+                        position,
+                        body_mass(radius, density) as f64,
+                        comet.map_or(1., |comet| comet.apoapsis_speed()),
+                    ),
+                };
+                self.edits.write(EditorAction::Apply(Edit::Spawn(BodyState {
+                    name: name.clone(),
+                    radius,
+                    density,
+                    color: [170, 170, 180],
+                    position,
+                    velocity,
+                    owner: None,
+                    crafts: 0,
+                    appearance: None,
+                    spin: None,
+                    atmosphere: None,
+                    on_rails: false,
+                    comet,
+                })));
+                Ok(format!("spawned {name}"))
+            }
+            Command::Set { constant, value } => {
+                let field = match constant {
+                    Constant::G => &mut self.constants.g,
+                    Constant::Density => &mut self.constants.density,
+                    Constant::Softening => &mut self.constants.softening,
+                    Constant::BreakupEnergy => &mut self.constants.breakup_energy,
+                    Constant::KeplerThreshold => &mut self.constants.kepler_threshold,
+                };
+                let previous = std::mem::replace(field, value);
+                Ok(format!("{constant:?} {previous} → {value}"))
+            }
+            Command::Teleport { body, position } => {
+                if !self.bodies.iter().any(|(name, ..)| name.as_str() == body) {
+                    return Err(CommandError::NoSuchBody(body));
+                }
+                let reply = format!("moved {body} to {}, {}", position.x, position.y);
+                self.edits.write(EditorAction::Apply(Edit::SetPosition {
+                    body,
+                    position: position.extend(0.),
+                }));
+                Ok(reply)
+            }
+            Command::Speed(speed) => {
+                self.time.set_relative_speed(speed.max(0.));
+                Ok(format!("speed {}×", speed.max(0.)))
+            }
+            Command::Help => Ok(HELP.to_string()),
+            Command::Clear => Ok(String::new()),
+        }
+    }
+
+    /// The velocity for a circular orbit around the heaviest body, scaled
+    /// by `speed` relative to it, or rest if there are none.
+    fn orbital_velocity(&self, position: DVec3, mass: f64, speed: f64) -> DVec3 {
+        let heaviest = self
+            .bodies
+            .iter()
+            .max_by(|(.., a), (.., b)| a.0.total_cmp(&b.0));
+        let Some((_, center, center_velocity, center_mass)) = heaviest else {
+            return DVec3::ZERO;
+        };
+        let bodies = [(center.0, center_mass.0 as f64), (position, mass)];
+        circular_orbit_velocities(&bodies, &self.constants)[1] * speed + center_velocity.0
+    }
+}
+
+fn console_window(mut contexts: EguiContexts, mut console: ResMut<Console>, mut sandbox: Sandbox) {
+    if !console.open {
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    let (enter, up, down, escape) = ctx.input(|input| {
+        (
+            input.key_pressed(egui::Key::Enter),
+            input.key_pressed(egui::Key::ArrowUp),
+            input.key_pressed(egui::Key::ArrowDown),
+            input.key_pressed(egui::Key::Escape),
+        )
+    });
+    let just_opened = std::mem::take(&mut console.just_opened);
+    if escape {
+        console.open = false;
+        console.input.clear();
+        return;
+    }
+    if up || down {
+        console.recall(up);
+    }
+
+    let mut submitted = None;
+    egui::Window::new("Console")
+        .anchor(egui::Align2::CENTER_TOP, [0., 40.])
+        .collapsible(false)
+        .default_width(520.)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(240.)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    ui.set_min_width(ui.available_width());
+                    for line in &console.output {
+                        match line {
+                            ConsoleLine::Command(text) => ui.monospace(format!("> {text}")),
+                            ConsoleLine::Reply(text) => ui.monospace(text),
+                            ConsoleLine::Error(text) => ui.colored_label(
+                                ui.visuals().error_fg_color,
+                                egui::RichText::new(text).monospace(),
+                            ),
+                        };
+                    }
+                });
+            let field = ui.add(
+                egui::TextEdit::singleline(&mut console.input)
+                    .font(egui::TextStyle::Monospace)
+                    .hint_text("help")
+                    .desired_width(f32::INFINITY),
+            );
+            field.request_focus();
+            // The key that opened the console types nothing
+            if just_opened {
+                console.input.clear();
+            }
+            if enter && !console.input.trim().is_empty() {
+                submitted = Some(std::mem::take(&mut console.input));
+            }
+        });
+
+    let Some(line) = submitted else {
+        return;
+    };
+    let line = line.trim().to_string();
+    console.recalled = None;
+    console.history.push(line.clone());
+    console.print(ConsoleLine::Command(line.clone()));
+    match line.parse::<Command>() {
+        Ok(Command::Clear) => console.output.clear(),
+        Ok(command) => match sandbox.run(command) {
+            Ok(reply) => console.print(ConsoleLine::Reply(reply)),
+            Err(err) => console.print(ConsoleLine::Error(err.to_string())),
+        },
+        Err(err) => console.print(ConsoleLine::Error(err.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(
+            "spawn body r=2 d=1.5 at 10,-4 v=0,3 name=Rock".parse(),
+            Ok(Command::Spawn {
+                name: Some("Rock".into()),
+                radius: 2.,
+                density: Some(1.5),
+                position: DVec2::new(10., -4.),
+                velocity: Some(DVec2::new(0., 3.)),
+                comet: None,
+            })
+        );
+        assert_eq!(
+            "set kepler 0.5".parse(),
+            Ok(Command::Set {
+                constant: Constant::KeplerThreshold,
+                value: 0.5,
+            })
+        );
+        assert_eq!(
+            "tp Red Giant 1 2".parse(),
+            Ok(Command::Teleport {
+                body: "Red Giant".into(),
+                position: DVec2::new(1., 2.),
+            })
+        );
+        assert_eq!("speed 4x".parse(), Ok(Command::Speed(4.)));
+        assert_eq!("help".parse(), Ok(Command::Help));
+        assert_eq!("clear".parse(), Ok(Command::Clear));
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert_eq!(
+            "".parse::<Command>(),
+            Err(CommandError::Missing("a command"))
+        );
+        assert_eq!(
+            "fly".parse::<Command>(),
+            Err(CommandError::UnknownCommand("fly".into()))
+        );
+        assert_eq!(
+            "spawn body at 0,0".parse::<Command>(),
+            Err(CommandError::Missing("r=<radius>"))
+        );
+        assert_eq!(
+            "spawn body r=1 e=0.5 at 0,0".parse::<Command>(),
+            Err(CommandError::Unexpected("e=0.5".into()))
+        );
+        assert_eq!(
+            "spawn body r=1 at 0".parse::<Command>(),
+            Err(CommandError::NotAPoint("0".into()))
+        );
+        assert_eq!(
+            "set mass 1".parse::<Command>(),
+            Err(CommandError::UnknownConstant("mass".into()))
+        );
+        assert_eq!(
+            "set g 1 2".parse::<Command>(),
+            Err(CommandError::Unexpected("2".into()))
+        );
+        assert_eq!(
+            "tp 1 2".parse::<Command>(),
+            Err(CommandError::Missing("a body"))
+        );
+        for value in ["nan", "inf", "-inf", "many"] {
+            assert_eq!(
+                format!("set g {value}").parse::<Command>(),
+                Err(CommandError::NotANumber(value.into()))
+            );
+        }
+    }
+
+    #[test]
+    fn ignores_case_of_keywords() {
+        assert_eq!("HELP".parse(), Ok(Command::Help));
+        assert_eq!(
+            "Set Softening 2".parse(),
+            Ok(Command::Set {
+                constant: Constant::Softening,
+                value: 2.,
+            })
+        );
+        assert!(matches!(
+            "SPAWN Comet r=1 at 5,5".parse::<Command>(),
+            Ok(Command::Spawn { comet: Some(_), .. })
+        ));
+        assert!(matches!(
+            "spawn BODY r=1 at 5,5".parse::<Command>(),
+            Ok(Command::Spawn { comet: None, .. })
+        ));
+    }
+}
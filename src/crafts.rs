@@ -0,0 +1,427 @@
+use crate::app_state::AppState;
+use crate::assists::AssistTally;
+use crate::atmosphere::Heat;
+use crate::balance::{Balance, CraftClass};
+use crate::bodies::{Body, Density, Radius, Spin, body_mass};
+use crate::factions::{CombatRules, Faction, Owner, authoritative};
+use crate::logbook::CraftLog;
+use crate::physics::{SimPosition, SimRng, SimVelocity, TestParticle};
+use crate::spatial::{SpatialGrid, SpatialIndex};
+use crate::target::Target;
+use crate::trajectory::PredictedTrajectory;
+use bevy::ecs::system::SystemParam;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+/// Launches bodies' docked crafts and docks them on arrival.
+pub struct CraftsPlugin;
+
+impl Plugin for CraftsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LaunchCraft>()
+            .add_event::<BurnCraft>()
+            .add_event::<CraftLaunched>()
+            .add_event::<CraftBurned>()
+            .add_event::<CraftDocked>()
+            .add_event::<BodyCaptured>()
+            .add_event::<CraftsLost>()
+            .add_event::<CraftCrashed>()
+            // Orders land on a physics tick so replays can reproduce them
+            .add_systems(
+                FixedPreUpdate,
+                (launch_crafts, burn_crafts)
+                    .in_set(CraftOrders)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(
+                FixedPostUpdate,
+                dock_crafts
+                    .after(SpatialIndex)
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(authoritative),
+            );
+    }
+}
+
+/// Carries out [`LaunchCraft`] and [`BurnCraft`] requests at the start of a
+/// physics tick.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CraftOrders;
+
+/// Number of crafts docked at a body.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Crafts(pub u32);
+
+/// A craft in flight.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+#[require(TestParticle, PredictedTrajectory, AssistTally, Heat, CraftLog)]
+pub struct Craft {
+    /// Key into the balance table.
+    pub class: String,
+}
+
+/// The body a craft was launched toward.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Destination(pub Entity);
+
+/// The body a craft was launched from.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Home(pub Entity);
+
+/// Delta-v the craft has left for burns. Every burn spends its magnitude.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Fuel(pub f32);
+
+/// How a craft is coming in to a body, to tell whether it lands or crashes.
+#[derive(Clone, Copy, Debug)]
+pub struct Approach {
+    /// Relative to the body.
+    pub speed: f32,
+    /// Between the craft's heading, relative to the body, and straight down,
+    /// in degrees. Over 90 is heading away.
+    pub angle: f32,
+}
+
+impl Approach {
+    pub fn new(
+        position: DVec3,
+        velocity: DVec3,
+        body_position: DVec3,
+        body_velocity: DVec3,
+    ) -> Self {
+        let relative = velocity - body_velocity;
+        let down = (body_position - position).normalize_or_zero();
+        let angle = relative.try_normalize().map_or(0., |heading| {
+            heading.dot(down).clamp(-1., 1.).acos().to_degrees()
+        });
+        Self {
+            speed: relative.length() as f32,
+            angle: angle as f32,
+        }
+    }
+
+    /// Whether a craft of `class` touches down safely.
+    pub fn lands(&self, class: &CraftClass) -> bool {
+        self.speed <= class.max_landing_speed
+            && class.max_landing_angle.is_none_or(|max| self.angle <= max)
+    }
+}
+
+/// Request to launch one of a body's docked crafts.
+#[derive(Event, Clone)]
+pub struct LaunchCraft {
+    pub from: Entity,
+    /// Defaults to the [`Target`], if any.
+    pub destination: Option<Entity>,
+    /// Launch direction in the plane; normalized by the launcher.
+    pub direction: Vec2,
+    /// Launch speed relative to the body, capped at the class maximum and
+    /// paid for out of the class fuel.
+    pub speed: f32,
+    /// Sent by the scenario's script, which plays back by itself rather than
+    /// from logged orders.
+    pub scripted: bool,
+}
+
+/// Request for a mid-course correction, refused if the craft lacks the fuel.
+#[derive(Event)]
+pub struct BurnCraft {
+    pub craft: Entity,
+    pub delta_v: Vec2,
+}
+
+/// A craft left a body.
+#[derive(Event)]
+pub struct CraftLaunched {
+    pub craft: Entity,
+    pub from: Entity,
+}
+
+/// A craft spent fuel on a [`BurnCraft`].
+#[derive(Event)]
+pub struct CraftBurned {
+    pub craft: Entity,
+    pub delta_v: f32,
+}
+
+/// A craft landed on a body and joined its docked crafts.
+#[derive(Event)]
+pub struct CraftDocked {
+    pub craft: Entity,
+    pub body: Entity,
+}
+
+/// A craft hit a body faster than it could land, and was destroyed.
+#[derive(Event)]
+pub struct CraftCrashed {
+    pub craft: Entity,
+    pub body: Entity,
+    /// Relative to the body.
+    pub speed: f32,
+    /// Where it hit, and how the body there was moving.
+    pub position: DVec3,
+    pub velocity: DVec3,
+}
+
+/// A body changed hands, taken by a craft landing on it.
+#[derive(Event)]
+pub struct BodyCaptured {
+    pub body: Entity,
+    pub by: Faction,
+}
+
+/// Crafts of one side destroyed in combat, or burned up in an atmosphere.
+#[derive(Event)]
+pub struct CraftsLost {
+    pub faction: Faction,
+    pub count: u32,
+}
+
+type LaunchData = (
+    &'static Name,
+    &'static Radius,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static mut Crafts,
+    Option<&'static Owner>,
+    Option<&'static Spin>,
+);
+
+fn launch_crafts(
+    mut commands: Commands,
+    mut events: EventReader<LaunchCraft>,
+    mut bodies: Query<LaunchData, With<Body>>,
+    balance: Balance,
+    target: Res<Target>,
+    mut launched: EventWriter<CraftLaunched>,
+) {
+    for launch in events.read() {
+        let Some((class_name, class)) = balance.default_class() else {
+            log::warn!("Cannot launch before the balance table has loaded");
+            continue;
+        };
+        let Ok((name, radius, position, velocity, mut crafts, owner, spin)) =
+            bodies.get_mut(launch.from)
+        else {
+            continue;
+        };
+        if crafts.0 == 0 {
+            continue;
+        }
+        let Some(direction) = launch.direction.try_normalize() else {
+            continue;
+        };
+        // A spinning body launches from its site, carrying the site's speed
+        let (site, direction, surface_velocity) = match spin {
+            Some(spin) => (
+                spin.site_normal(),
+                spin.launch_direction(direction),
+                spin.site_velocity(radius.0),
+            ),
+            None => (direction, direction, Vec2::ZERO),
+        };
+        let site = site.extend(0.).as_dvec3();
+        let direction = direction.extend(0.).as_dvec3();
+        crafts.0 -= 1;
+
+        // Start just above the surface so the craft doesn't immediately dock again
+        let start = position.0 + site * (radius.0 as f64 + 0.1);
+        let speed = launch.speed.clamp(0., class.launch_speed_limit());
+        let mut craft = commands.spawn((
+            Craft {
+                class: class_name.to_string(),
+            },
+            Name::new(format!("{class_name} from {name}")),
+            SimPosition(start),
+            SimVelocity(
+                velocity.0 + direction * speed as f64 + surface_velocity.extend(0.).as_dvec3(),
+            ),
+            Fuel(class.fuel - speed),
+            Home(launch.from),
+        ));
+        if let Some(owner) = owner {
+            craft.insert(*owner);
+        }
+        launched.write(CraftLaunched {
+            craft: craft.id(),
+            from: launch.from,
+        });
+        let destination = launch
+            .destination
+            .or(target.0)
+            .filter(|&d| d != launch.from);
+        if let Some(destination) = destination {
+            craft.insert(Destination(destination));
+        }
+    }
+}
+
+fn burn_crafts(
+    mut events: EventReader<BurnCraft>,
+    mut crafts: Query<(&Name, &mut SimVelocity, &mut Fuel), With<Craft>>,
+    mut burned: EventWriter<CraftBurned>,
+) {
+    for burn in events.read() {
+        let Ok((name, mut velocity, mut fuel)) = crafts.get_mut(burn.craft) else {
+            continue;
+        };
+        let delta_v = burn.delta_v.length();
+        if delta_v > fuel.0 {
+            log::info!("{name} lacks the fuel for a {delta_v:.2} burn");
+            continue;
+        }
+        fuel.0 -= delta_v;
+        velocity.0 += burn.delta_v.extend(0.).as_dvec3();
+        burned.write(CraftBurned {
+            craft: burn.craft,
+            delta_v,
+        });
+    }
+}
+
+type DockingCraftData = (
+    Entity,
+    &'static Name,
+    &'static Craft,
+    &'static SimPosition,
+    &'static SimVelocity,
+    Option<&'static Owner>,
+);
+
+type DockingBodyData = (
+    Entity,
+    &'static Name,
+    &'static Radius,
+    &'static mut Density,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static mut Crafts,
+    Option<&'static Owner>,
+);
+
+/// How crafts touching down came out: landed, crashed, lost or capturing.
+#[derive(SystemParam)]
+struct DockingOutcomes<'w> {
+    docked: EventWriter<'w, CraftDocked>,
+    crashes: EventWriter<'w, CraftCrashed>,
+    losses: EventWriter<'w, CraftsLost>,
+    captures: EventWriter<'w, BodyCaptured>,
+}
+
+/// What decides fights between landing crafts and a body's defenders.
+#[derive(SystemParam)]
+struct Combat<'w> {
+    rules: Res<'w, CombatRules>,
+    rng: ResMut<'w, SimRng>,
+}
+
+/// Crafts touching a body land there, unless they come in too fast or too
+/// steeply for their class and crash into it instead. Landing on a neutral
+/// body claims it, while landing on an enemy's fights its defenders under the
+/// [`CombatRules`] and takes the body if the craft survives.
+fn dock_crafts(
+    mut commands: Commands,
+    crafts: Query<DockingCraftData>,
+    mut bodies: Query<DockingBodyData, With<Body>>,
+    mut outcomes: DockingOutcomes,
+    mut combat: Combat,
+    balance: Balance,
+    grid: Res<SpatialGrid>,
+) {
+    // Docked crafts have no class of their own, so defend as the default
+    let defense = balance
+        .default_class()
+        .map_or(1., |(_, class)| class.combat_strength);
+    for (craft, craft_name, craft_class, craft_position, craft_velocity, craft_owner) in &crafts {
+        for body in grid.bodies_near(craft_position.0.truncate(), 0.) {
+            let Ok((
+                body,
+                body_name,
+                radius,
+                mut density,
+                body_position,
+                body_velocity,
+                mut docked,
+                body_owner,
+            )) = bodies.get_mut(body)
+            else {
+                continue;
+            };
+            if craft_position.0.distance(body_position.0) > radius.0 as f64 {
+                continue;
+            }
+            commands.entity(craft).despawn();
+            let class = balance.class(&craft_class.class);
+            let approach = Approach::new(
+                craft_position.0,
+                craft_velocity.0,
+                body_position.0,
+                body_velocity.0,
+            );
+            if let Some(class) = class.filter(|class| !approach.lands(class)) {
+                let Approach { speed, angle } = approach;
+                log::info!("{craft_name} crashed into {body_name} at {speed:.2}, {angle:.0}° off");
+                // The wreck stays behind as part of the body
+                density.0 += class.mass / body_mass(radius.0, 1.);
+                outcomes.crashes.write(CraftCrashed {
+                    craft,
+                    body,
+                    speed,
+                    position: craft_position.0,
+                    velocity: body_velocity.0,
+                });
+                if let Some(owner) = craft_owner {
+                    outcomes.losses.write(CraftsLost {
+                        faction: owner.0,
+                        count: 1,
+                    });
+                }
+                break;
+            }
+            let hostile = craft_owner.zip(body_owner).is_some_and(|(a, b)| a != b);
+            if hostile {
+                let attack = class.map_or(defense, |class| class.combat_strength);
+                let defenders = docked.0;
+                let survived =
+                    combat
+                        .rules
+                        .resolve(&mut combat.rng, attack, defense, &mut docked.0);
+                log::info!(
+                    "{craft_name} destroyed {} of {defenders} defenders of {body_name}",
+                    defenders - docked.0
+                );
+                if let Some(owner) = body_owner {
+                    outcomes.losses.write(CraftsLost {
+                        faction: owner.0,
+                        count: defenders - docked.0,
+                    });
+                }
+                if !survived {
+                    if let Some(owner) = craft_owner {
+                        outcomes.losses.write(CraftsLost {
+                            faction: owner.0,
+                            count: 1,
+                        });
+                    }
+                    break;
+                }
+            }
+            if let Some(owner) = craft_owner
+                && body_owner != Some(owner)
+            {
+                log::info!("{} captured {body_name}", owner.0.name());
+                commands.entity(body).insert(*owner);
+                outcomes.captures.write(BodyCaptured { body, by: owner.0 });
+            }
+            log::info!("{craft_name} docked at {body_name}");
+            docked.0 += 1;
+            outcomes.docked.write(CraftDocked { craft, body });
+            break;
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use crate::bench::headless_app;
+use crate::bodies::Body;
+use crate::crafts::Craft;
+use crate::physics::{SimPosition, SimVelocity};
+use crate::replay::{LaunchNumber, ReplayLog};
+use crate::scenario::Scenario;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use std::fmt;
+
+/// Where everything in a simulation is at one tick, for checking that two
+/// runs of the same scenario agree, as lockstep play and replays rely on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimState {
+    pub tick: u64,
+    /// By name.
+    pub bodies: Vec<Snapshot>,
+    /// By launch number, which doesn't depend on spawn order.
+    pub crafts: Vec<Snapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub key: String,
+    pub position: DVec3,
+    pub velocity: DVec3,
+}
+
+/// The first difference found between two [`SimState`]s.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum Divergence {
+    #[error("states are from ticks {0} and {1}")]
+    Tick(u64, u64),
+    #[error("{0} bodies against {1}")]
+    BodyCount(usize, usize),
+    #[error("{0} crafts against {1}")]
+    CraftCount(usize, usize),
+    #[error("{key} is {distance:e} apart in {what}")]
+    Moved {
+        key: String,
+        what: &'static str,
+        distance: f64,
+    },
+    #[error("{0} against {1}")]
+    Missing(String, String),
+}
+
+impl SimState {
+    pub fn capture(world: &mut World) -> Self {
+        let tick = world.resource::<ReplayLog>().tick();
+        let mut bodies: Vec<_> = world
+            .query_filtered::<(&Name, &SimPosition, &SimVelocity), With<Body>>()
+            .iter(world)
+            .map(|(name, position, velocity)| Snapshot {
+                key: name.to_string(),
+                position: position.0,
+                velocity: velocity.0,
+            })
+            .collect();
+        bodies.sort_by(|a, b| a.key.cmp(&b.key));
+        let mut crafts: Vec<_> = world
+            .query_filtered::<(&LaunchNumber, &SimPosition, &SimVelocity), With<Craft>>()
+            .iter(world)
+            .map(|(number, position, velocity)| (number.0, position.0, velocity.0))
+            .collect();
+        crafts.sort_by_key(|(number, ..)| *number);
+        let crafts = crafts
+            .into_iter()
+            .map(|(number, position, velocity)| Snapshot {
+                key: format!("craft #{number}"),
+                position,
+                velocity,
+            })
+            .collect();
+        Self {
+            tick,
+            bodies,
+            crafts,
+        }
+    }
+
+    /// Checks every position and velocity is within `tolerance` of the
+    /// other state's. A tolerance of zero asks for bit-identical states.
+    pub fn compare(&self, other: &Self, tolerance: f64) -> Result<(), Divergence> {
+        if self.tick != other.tick {
+            return Err(Divergence::Tick(self.tick, other.tick));
+        }
+        if self.bodies.len() != other.bodies.len() {
+            return Err(Divergence::BodyCount(self.bodies.len(), other.bodies.len()));
+        }
+        if self.crafts.len() != other.crafts.len() {
+            return Err(Divergence::CraftCount(
+                self.crafts.len(),
+                other.crafts.len(),
+            ));
+        }
+        let pairs = self.bodies.iter().zip(&other.bodies);
+        for (a, b) in pairs.chain(self.crafts.iter().zip(&other.crafts)) {
+            if a.key != b.key {
+                return Err(Divergence::Missing(a.key.clone(), b.key.clone()));
+            }
+            for (what, a_value, b_value) in [
+                ("position", a.position, b.position),
+                ("velocity", a.velocity, b.velocity),
+            ] {
+                let distance = a_value.distance(b_value);
+                let identical =
+                    a_value.to_array().map(f64::to_bits) == b_value.to_array().map(f64::to_bits);
+                let close = if tolerance > 0. {
+                    distance <= tolerance
+                } else {
+                    identical
+                };
+                if !close {
+                    return Err(Divergence::Moved {
+                        key: a.key.clone(),
+                        what,
+                        distance,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// A hash of the exact bits of every position and velocity, the same on
+    /// every build, for comparing states across machines cheaply.
+    pub fn checksum(&self) -> u64 {
+        self.bodies
+            .iter()
+            .chain(&self.crafts)
+            .flat_map(|snapshot| {
+                [snapshot.position, snapshot.velocity]
+                    .into_iter()
+                    .flat_map(|vector| vector.to_array())
+            })
+            .flat_map(|value| value.to_bits().to_le_bytes())
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+}
+
+impl fmt::Display for SimState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "tick {}: {} bodies, {} crafts, checksum {:016x}",
+            self.tick,
+            self.bodies.len(),
+            self.crafts.len(),
+            self.checksum()
+        )
+    }
+}
+
+/// Simulates `ticks` physics ticks of `scenario` headless and captures
+/// where it ends up.
+pub fn simulate(scenario: Scenario, ticks: u64, timestep: f64, seed: u64) -> SimState {
+    let mut app = headless_app(scenario, timestep, seed);
+    for _ in 0..ticks {
+        app.update();
+    }
+    SimState::capture(app.world_mut())
+}
+
+/// Simulates `scenario` twice from scratch and checks both runs end up
+/// within `tolerance` of each other, returning the first run's state.
+pub fn assert_deterministic(
+    scenario: &Scenario,
+    ticks: u64,
+    timestep: f64,
+    seed: u64,
+    tolerance: f64,
+) -> SimState {
+    let first = simulate(scenario.clone(), ticks, timestep, seed);
+    let second = simulate(scenario.clone(), ticks, timestep, seed);
+    if let Err(divergence) = first.compare(&second, tolerance) {
+        panic!("runs diverged after {ticks} ticks: {divergence}");
+    }
+    first
+}
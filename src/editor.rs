@@ -0,0 +1,358 @@
+use crate::app_state::AppState;
+use crate::atmosphere::Atmosphere;
+use crate::bindings::{Action, ActionInput};
+use crate::bodies::{
+    Appearance, Body, Density, Fill, Radius, ScenarioSystems, Spin, body_bundle, body_mass,
+    circular_orbit_velocities,
+};
+use crate::comets::Comet;
+use crate::crafts::Crafts;
+use crate::factions::{Faction, Owner};
+use crate::i18n::tr_args;
+use crate::kepler::{OnRails, Rails};
+use crate::physics::{Mass, PhysicsConstants, SimPosition, SimVelocity};
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+/// Adds, deletes and edits bodies with undo and redo ([`Action::Undo`] and
+/// [`Action::Redo`]).
+///
+/// Every change goes through an [`EditorAction`], so the history sees all of
+/// them. Loading a scenario starts a fresh history.
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditHistory>()
+            .add_event::<EditorAction>()
+            .add_systems(
+                Update,
+                (undo_redo_keys.run_if(AppState::in_game), run_edits).chain(),
+            )
+            .add_systems(Update, clear_history.in_set(ScenarioSystems::Initialize));
+    }
+}
+
+/// Request to change the bodies, or to step through the history.
+#[derive(Event, Clone, Debug)]
+pub enum EditorAction {
+    Apply(Edit),
+    /// Adds a small neutral body on a circular orbit outside all the others.
+    AddBody,
+    Undo,
+    Redo,
+}
+
+/// A reversible change to the bodies in play. Bodies are referred to by name,
+/// since undoing a delete brings a body back as a new entity.
+#[derive(Clone, Debug)]
+pub enum Edit {
+    Spawn(BodyState),
+    Delete(String),
+    SetDensity { body: String, density: f32 },
+    SetPosition { body: String, position: DVec3 },
+    SetVelocity { body: String, velocity: DVec3 },
+    SetRails { body: String, on_rails: bool },
+}
+
+/// All there is to a body, enough to bring a deleted one back as it was.
+#[derive(Clone, Debug)]
+pub struct BodyState {
+    pub name: String,
+    pub radius: f32,
+    pub density: f32,
+    pub color: [u8; 3],
+    pub position: DVec3,
+    pub velocity: DVec3,
+    pub owner: Option<Faction>,
+    pub crafts: u32,
+    pub appearance: Option<Appearance>,
+    pub spin: Option<Spin>,
+    pub atmosphere: Option<Atmosphere>,
+    pub on_rails: bool,
+    pub comet: Option<Comet>,
+}
+
+impl Edit {
+    /// Names the edit for the Undo and Redo menu items.
+    pub fn label(&self) -> String {
+        let (id, body) = match self {
+            Self::Spawn(state) => ("edit-add", &state.name),
+            Self::Delete(body) => ("edit-delete", body),
+            Self::SetDensity { body, .. } => ("edit-density", body),
+            Self::SetPosition { body, .. } => ("edit-move", body),
+            Self::SetVelocity { body, .. } => ("edit-velocity", body),
+            Self::SetRails {
+                body,
+                on_rails: true,
+            } => ("edit-on-rails", body),
+            Self::SetRails {
+                body,
+                on_rails: false,
+            } => ("edit-off-rails", body),
+        };
+        tr_args(id, &[("body", body.as_str().into())])
+    }
+
+    /// Makes the change, returning the edit that reverts it, or `None` if the
+    /// body it refers to is gone.
+    fn apply(self, world: &mut World) -> Option<Edit> {
+        match self {
+            Self::Spawn(state) => {
+                let mut body = world.spawn((
+                    body_bundle(state.name.clone(), state.radius, state.density, state.color),
+                    SimPosition(state.position),
+                    SimVelocity(state.velocity),
+                    Crafts(state.crafts),
+                ));
+                if let Some(owner) = state.owner {
+                    body.insert(Owner(owner));
+                }
+                if let Some(appearance) = state.appearance {
+                    body.insert(appearance);
+                }
+                if let Some(spin) = state.spin {
+                    body.insert(spin);
+                }
+                if let Some(atmosphere) = state.atmosphere {
+                    body.insert(atmosphere);
+                }
+                if state.on_rails {
+                    body.insert(Rails);
+                }
+                if let Some(comet) = state.comet {
+                    body.insert(comet);
+                }
+                Some(Self::Delete(state.name))
+            }
+            Self::Delete(name) => {
+                let entity = body_named(world, &name)?;
+                let body = world.entity(entity);
+                let state = BodyState {
+                    radius: body.get::<Radius>()?.0,
+                    density: body.get::<Density>()?.0,
+                    color: body
+                        .get::<Fill>()
+                        .map(|fill| [fill.0.r(), fill.0.g(), fill.0.b()])?,
+                    position: body.get::<SimPosition>()?.0,
+                    velocity: body.get::<SimVelocity>()?.0,
+                    owner: body.get::<Owner>().map(|owner| owner.0),
+                    crafts: body.get::<Crafts>()?.0,
+                    appearance: body.get::<Appearance>().cloned(),
+                    spin: body.get::<Spin>().copied(),
+                    atmosphere: body.get::<Atmosphere>().copied(),
+                    on_rails: body.contains::<Rails>(),
+                    comet: body.get::<Comet>().copied(),
+                    name,
+                };
+                world.despawn(entity);
+                Some(Self::Spawn(state))
+            }
+            Self::SetDensity { body, density } => {
+                let entity = body_named(world, &body)?;
+                let mut current = world.get_mut::<Density>(entity)?;
+                let previous = std::mem::replace(&mut current.0, density);
+                Some(Self::SetDensity {
+                    body,
+                    density: previous,
+                })
+            }
+            Self::SetPosition { body, position } => {
+                let entity = body_named(world, &body)?;
+                let mut current = world.get_mut::<SimPosition>(entity)?;
+                let previous = std::mem::replace(&mut current.0, position);
+                Some(Self::SetPosition {
+                    body,
+                    position: previous,
+                })
+            }
+            Self::SetVelocity { body, velocity } => {
+                let entity = body_named(world, &body)?;
+                let mut current = world.get_mut::<SimVelocity>(entity)?;
+                let previous = std::mem::replace(&mut current.0, velocity);
+                Some(Self::SetVelocity {
+                    body,
+                    velocity: previous,
+                })
+            }
+            Self::SetRails { body, on_rails } => {
+                let entity = body_named(world, &body)?;
+                let mut body_entity = world.entity_mut(entity);
+                let previous = body_entity.contains::<Rails>();
+                if on_rails {
+                    body_entity.insert(Rails);
+                } else {
+                    body_entity.remove::<(Rails, OnRails)>();
+                }
+                Some(Self::SetRails {
+                    body,
+                    on_rails: previous,
+                })
+            }
+        }
+    }
+
+    /// Whether this edit, made right after `earlier`, should undo along with
+    /// it, so a drag undoes in one step rather than one per frame.
+    fn merges_into(&self, earlier: &Edit) -> bool {
+        match (self, earlier) {
+            (Self::SetDensity { body, .. }, Self::SetDensity { body: earlier, .. })
+            | (Self::SetPosition { body, .. }, Self::SetPosition { body: earlier, .. })
+            | (Self::SetVelocity { body, .. }, Self::SetVelocity { body: earlier, .. }) => {
+                body == earlier
+            }
+            _ => false,
+        }
+    }
+}
+
+fn body_named(world: &mut World, name: &str) -> Option<Entity> {
+    world
+        .query_filtered::<(Entity, &Name), With<Body>>()
+        .iter(world)
+        .find_map(|(entity, body)| (body.as_str() == name).then_some(entity))
+}
+
+/// Edits that can be undone and redone, most recent last.
+#[derive(Resource, Default)]
+pub struct EditHistory {
+    /// Each edit's label, and the edit that reverts it.
+    undo: Vec<(String, Edit)>,
+    /// Each undone edit's label, and the edit that makes it again.
+    redo: Vec<(String, Edit)>,
+}
+
+impl EditHistory {
+    /// The label of the edit Undo would revert.
+    pub fn next_undo(&self) -> Option<&str> {
+        self.undo.last().map(|(label, _)| label.as_str())
+    }
+
+    /// The label of the edit Redo would make again.
+    pub fn next_redo(&self) -> Option<&str> {
+        self.redo.last().map(|(label, _)| label.as_str())
+    }
+}
+
+fn clear_history(mut history: ResMut<EditHistory>) {
+    *history = EditHistory::default();
+}
+
+/// Left alone while egui is taking keyboard input, so text fields keep their
+/// own undo.
+fn undo_redo_keys(mut input: ActionInput, mut actions: EventWriter<EditorAction>) {
+    if input.just_pressed(Action::Redo) {
+        actions.write(EditorAction::Redo);
+    } else if input.just_pressed(Action::Undo) {
+        actions.write(EditorAction::Undo);
+    }
+}
+
+fn run_edits(world: &mut World) {
+    let actions: Vec<_> = world
+        .resource_mut::<Events<EditorAction>>()
+        .drain()
+        .collect();
+    for action in actions {
+        match action {
+            EditorAction::Apply(edit) => record(world, edit),
+            EditorAction::AddBody => {
+                let state = new_body(world);
+                record(world, Edit::Spawn(state));
+            }
+            EditorAction::Undo => {
+                let Some((label, inverse)) = world.resource_mut::<EditHistory>().undo.pop() else {
+                    continue;
+                };
+                if let Some(redo) = inverse.apply(world) {
+                    world.resource_mut::<EditHistory>().redo.push((label, redo));
+                }
+            }
+            EditorAction::Redo => {
+                let Some((label, edit)) = world.resource_mut::<EditHistory>().redo.pop() else {
+                    continue;
+                };
+                if let Some(inverse) = edit.apply(world) {
+                    world
+                        .resource_mut::<EditHistory>()
+                        .undo
+                        .push((label, inverse));
+                }
+            }
+        }
+    }
+}
+
+/// Makes a new edit, which forgets anything undone.
+fn record(world: &mut World, edit: Edit) {
+    let label = edit.label();
+    let Some(inverse) = edit.apply(world) else {
+        return;
+    };
+    let mut history = world.resource_mut::<EditHistory>();
+    history.redo.clear();
+    if history
+        .undo
+        .last()
+        .is_some_and(|(_, earlier)| inverse.merges_into(earlier))
+    {
+        return;
+    }
+    history.undo.push((label, inverse));
+}
+
+/// A body for [`EditorAction::AddBody`], named and placed to stay clear of
+/// the ones already there.
+fn new_body(world: &mut World) -> BodyState {
+    let mut bodies =
+        world.query_filtered::<(&Name, &SimPosition, &SimVelocity, &Mass), With<Body>>();
+    let existing: Vec<_> = bodies
+        .iter(world)
+        .map(|(name, position, velocity, mass)| {
+            (name.to_string(), position.0, velocity.0, mass.0 as f64)
+        })
+        .collect();
+    let constants = *world.resource::<PhysicsConstants>();
+
+    let number = (1..)
+        .find(|number| {
+            let name = format!("Body {number}");
+            existing.iter().all(|(existing, ..)| *existing != name)
+        })
+        .unwrap_or_default();
+    let (center, center_velocity, center_mass) = existing
+        .iter()
+        .max_by(|(.., a), (.., b)| a.total_cmp(b))
+        .map_or(
+            (DVec3::ZERO, DVec3::ZERO, 0.),
+            |&(_, position, velocity, mass)| (position, velocity, mass),
+        );
+    let farthest = existing
+        .iter()
+        .map(|(_, position, ..)| position.distance(center))
+        .fold(0., f64::max);
+
+    let radius = 1.;
+    // Golden-angle steps keep successive additions from lining up
+    let angle = number as f64 * 2.4;
+    let position = center + DVec3::new(angle.cos(), angle.sin(), 0.) * (farthest * 1.3 + 5.);
+    let mass = body_mass(radius, constants.density) as f64;
+    let velocity =
+        circular_orbit_velocities(&[(center, center_mass), (position, mass)], &constants)[1]
+            + center_velocity;
+    BodyState {
+        name: format!("Body {number}"),
+        radius,
+        density: constants.density,
+        color: [170, 170, 180],
+        position,
+        velocity,
+        owner: None,
+        crafts: 0,
+        appearance: None,
+        spin: None,
+        atmosphere: None,
+        on_rails: false,
+        comet: None,
+    }
+}
@@ -0,0 +1,117 @@
+use crate::app_state::AppState;
+use crate::balance::Balance;
+use crate::crafts::{Craft, Fuel};
+use crate::i18n::tr;
+use crate::physics::SimVelocity;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Continuous thrust for crafts whose class has an [`Engine`], at the
+/// [`Throttle`] set from the craft card or keyboard.
+///
+/// A craft's [`Fuel`] is still the delta-v it has left; its propellant mass
+/// follows from that by the rocket equation, so a craft accelerates harder
+/// as it burns off fuel.
+pub struct EnginesPlugin;
+
+impl Plugin for EnginesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedPostUpdate,
+            fire_engines.run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+/// How much a keypress opens or closes the throttle.
+pub const THROTTLE_STEP: f32 = 0.1;
+
+/// A craft class's engine.
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct Engine {
+    /// Force at full throttle.
+    pub thrust: f32,
+    /// Exhaust speed, in simulation units per second. Higher gets more
+    /// delta-v out of the same propellant.
+    pub specific_impulse: f32,
+}
+
+impl Engine {
+    /// Acceleration at full throttle with `fuel` delta-v left, for a craft
+    /// weighing `dry_mass` once its propellant is gone.
+    pub fn acceleration(&self, dry_mass: f32, fuel: f32) -> f32 {
+        let wet_mass = dry_mass * (fuel / self.specific_impulse).exp();
+        self.thrust / wet_mass
+    }
+}
+
+/// Directions relative to a craft's velocity.
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BurnDirection {
+    #[default]
+    Prograde,
+    Retrograde,
+    Left,
+    Right,
+}
+
+impl BurnDirection {
+    pub const ALL: [Self; 4] = [Self::Prograde, Self::Retrograde, Self::Left, Self::Right];
+
+    pub fn label(self) -> String {
+        tr(match self {
+            Self::Prograde => "burn-prograde",
+            Self::Retrograde => "burn-retrograde",
+            Self::Left => "burn-left",
+            Self::Right => "burn-right",
+        })
+    }
+
+    pub fn relative_to(self, velocity: Vec2) -> Option<Vec2> {
+        let prograde = velocity.try_normalize()?;
+        Some(match self {
+            Self::Prograde => prograde,
+            Self::Retrograde => -prograde,
+            Self::Left => prograde.perp(),
+            Self::Right => -prograde.perp(),
+        })
+    }
+}
+
+/// How hard, and which way, a craft's engine is firing.
+#[derive(Component, Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[reflect(Component)]
+pub struct Throttle {
+    /// From 0 for off to 1 for full thrust.
+    pub level: f32,
+    pub direction: BurnDirection,
+}
+
+fn fire_engines(
+    mut crafts: Query<(&Craft, &Throttle, &mut SimVelocity, &mut Fuel)>,
+    balance: Balance,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    for (craft, throttle, mut velocity, mut fuel) in &mut crafts {
+        if throttle.level <= 0. || fuel.0 <= 0. {
+            continue;
+        }
+        let Some(class) = balance.class(&craft.class) else {
+            continue;
+        };
+        let Some(engine) = class.engine else {
+            continue;
+        };
+        let Some(direction) = throttle
+            .direction
+            .relative_to(velocity.0.truncate().as_vec2())
+        else {
+            continue;
+        };
+        let delta_v = (engine.acceleration(class.mass, fuel.0) * throttle.level * dt).min(fuel.0);
+        fuel.0 -= delta_v;
+        velocity.0 += (direction * delta_v).extend(0.).as_dvec3();
+    }
+}
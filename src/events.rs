@@ -0,0 +1,286 @@
+use crate::app_state::AppState;
+use crate::assists::GravityAssist;
+use crate::bodies::{Body, ScenarioSystems};
+use crate::collisions::{BodyDestroyed, Destruction};
+use crate::crafts::{BodyCaptured, Craft, CraftCrashed, CraftDocked, CraftLaunched};
+use crate::i18n::{format_number, tr, tr_args};
+use crate::selection::{SelectedBody, SelectedCraft};
+use crate::ui::{UiState, ViewChange, ViewRequest};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+/// Tells the player what happened: a scrolling log of launches, landings,
+/// captures, collisions and gravity assists, and toasts as they happen.
+/// Clicking either selects and centers on what it was about.
+pub struct EventsPlugin;
+
+impl Plugin for EventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EventLog>()
+            .init_resource::<Toasts>()
+            .add_systems(Update, record_events.run_if(AppState::in_game))
+            .add_systems(Update, clear_log.in_set(ScenarioSystems::Initialize))
+            .add_systems(
+                EguiPrimaryContextPass,
+                (event_log_window.run_if(AppState::in_game), show_toasts),
+            );
+    }
+}
+
+/// Oldest entries are dropped beyond this many.
+const MAX_LOG_ENTRIES: usize = 500;
+/// How long a toast stays up, in seconds.
+const TOAST_DURATION: f64 = 4.;
+
+/// Everything that has happened this scenario, oldest first.
+#[derive(Resource, Default)]
+pub struct EventLog(Vec<LogEntry>);
+
+struct LogEntry {
+    /// Simulated seconds since the scenario started.
+    time: f64,
+    text: String,
+    /// The body or craft it was about.
+    subject: Entity,
+}
+
+/// Short-lived messages shown along the bottom of the screen.
+#[derive(Resource, Default)]
+pub struct Toasts(Vec<Toast>);
+
+struct Toast {
+    text: String,
+    /// Real time at which the toast disappears.
+    expires: f64,
+    subject: Option<Entity>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, text: impl Into<String>, now: f64) {
+        self.push_about(text, now, None);
+    }
+
+    /// Pushes a toast that focuses `subject` when clicked.
+    pub fn push_about(&mut self, text: impl Into<String>, now: f64, subject: Option<Entity>) {
+        self.0.push(Toast {
+            text: text.into(),
+            expires: now + TOAST_DURATION,
+            subject,
+        });
+    }
+}
+
+/// Selects a body or craft and centers the plot on it.
+#[derive(SystemParam)]
+struct Focus<'w, 's> {
+    bodies: Query<'w, 's, &'static Name, With<Body>>,
+    crafts: Query<'w, 's, (), With<Craft>>,
+    selected_body: ResMut<'w, SelectedBody>,
+    selected_craft: ResMut<'w, SelectedCraft>,
+    view: ResMut<'w, ViewRequest>,
+}
+
+impl Focus<'_, '_> {
+    /// Whether `subject` is still around to focus on.
+    fn can_focus(&self, subject: Entity) -> bool {
+        self.bodies.contains(subject) || self.crafts.contains(subject)
+    }
+
+    fn focus(&mut self, subject: Entity) {
+        if let Ok(name) = self.bodies.get(subject) {
+            self.selected_body.0 = Some(name.to_string());
+            self.selected_craft.0 = None;
+        } else if self.crafts.contains(subject) {
+            self.selected_craft.0 = Some(subject);
+            self.selected_body.0 = None;
+        } else {
+            return;
+        }
+        self.view.0 = Some(ViewChange::CenterSelection);
+    }
+}
+
+/// What happened this tick that the log and toasts tell of.
+#[derive(SystemParam)]
+struct Happenings<'w, 's> {
+    launches: EventReader<'w, 's, CraftLaunched>,
+    landings: EventReader<'w, 's, CraftDocked>,
+    crashes: EventReader<'w, 's, CraftCrashed>,
+    captures: EventReader<'w, 's, BodyCaptured>,
+    collisions: EventReader<'w, 's, BodyDestroyed>,
+    assists: EventReader<'w, 's, GravityAssist>,
+}
+
+fn record_events(
+    mut events: Happenings,
+    names: Query<&Name>,
+    mut log: ResMut<EventLog>,
+    mut toasts: ResMut<Toasts>,
+    sim_time: Res<Time<Fixed>>,
+    real_time: Res<Time<Real>>,
+) {
+    let name = |entity: Entity| names.get(entity).map_or("?", |name| name.as_str());
+    let mut happened = Vec::new();
+
+    for launch in events.launches.read() {
+        happened.push((
+            tr_args("event-launched", &[("craft", name(launch.craft).into())]),
+            launch.craft,
+        ));
+    }
+    // The craft is gone by now, so landings are about the body
+    for landing in events.landings.read() {
+        happened.push((
+            tr_args("event-landed", &[("body", name(landing.body).into())]),
+            landing.body,
+        ));
+    }
+    for crash in events.crashes.read() {
+        happened.push((
+            tr_args(
+                "event-crashed",
+                &[
+                    ("body", name(crash.body).into()),
+                    ("speed", format_number(crash.speed as f64, 1).into()),
+                ],
+            ),
+            crash.body,
+        ));
+    }
+    for capture in events.captures.read() {
+        happened.push((
+            tr_args(
+                "event-captured",
+                &[
+                    ("faction", capture.by.name().into()),
+                    ("body", name(capture.body).into()),
+                ],
+            ),
+            capture.body,
+        ));
+    }
+    for destroyed in events.collisions.read() {
+        let lost = destroyed.name.as_str();
+        let (id, other) = match destroyed.cause {
+            Destruction::TornApart { primary } => ("event-torn-apart", primary),
+            Destruction::Shattered { against } => ("event-shattered", against),
+            Destruction::Merged { into } => ("event-merged", into),
+        };
+        happened.push((
+            tr_args(id, &[("lost", lost.into()), ("other", name(other).into())]),
+            other,
+        ));
+    }
+    for assist in events.assists.read() {
+        happened.push((
+            tr_args(
+                "event-assist",
+                &[
+                    ("craft", name(assist.craft).into()),
+                    ("delta_v", format_number(assist.delta_v as f64, 2).into()),
+                    ("body", name(assist.body).into()),
+                ],
+            ),
+            assist.craft,
+        ));
+    }
+
+    for (text, subject) in happened {
+        toasts.push_about(&text, real_time.elapsed_secs_f64(), Some(subject));
+        log.0.push(LogEntry {
+            time: sim_time.elapsed_secs_f64(),
+            text,
+            subject,
+        });
+    }
+    let excess = log.0.len().saturating_sub(MAX_LOG_ENTRIES);
+    log.0.drain(..excess);
+}
+
+fn clear_log(mut log: ResMut<EventLog>) {
+    log.0.clear();
+}
+
+fn event_log_window(
+    mut contexts: EguiContexts,
+    mut ui_state: ResMut<UiState>,
+    log: Res<EventLog>,
+    mut focus: Focus,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    let mut clicked = None;
+    egui::Window::new(tr("event-log"))
+        .open(&mut ui_state.show_event_log)
+        .default_size([320., 240.])
+        .show(ctx, |ui| {
+            if log.0.is_empty() {
+                ui.weak(tr("event-log-empty"));
+                return;
+            }
+            egui::ScrollArea::vertical()
+                .stick_to_bottom(true)
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    for entry in &log.0 {
+                        ui.horizontal(|ui| {
+                            ui.weak(format!("{:>7}s", format_number(entry.time, 1)));
+                            if !focus.can_focus(entry.subject) {
+                                ui.label(&entry.text);
+                            } else if ui.link(&entry.text).clicked() {
+                                clicked = Some(entry.subject);
+                            }
+                        });
+                    }
+                });
+        });
+    if let Some(subject) = clicked {
+        focus.focus(subject);
+    }
+}
+
+fn show_toasts(
+    mut contexts: EguiContexts,
+    mut toasts: ResMut<Toasts>,
+    time: Res<Time<Real>>,
+    mut focus: Focus,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    let now = time.elapsed_secs_f64();
+    toasts.0.retain(|toast| toast.expires > now);
+    if toasts.0.is_empty() {
+        return;
+    }
+    let mut clicked = None;
+    egui::Area::new(egui::Id::new("toasts"))
+        .anchor(egui::Align2::CENTER_BOTTOM, [0., -40.])
+        .show(ctx, |ui| {
+            for (index, toast) in toasts.0.iter().enumerate() {
+                let focusable = toast
+                    .subject
+                    .is_some_and(|subject| focus.can_focus(subject));
+                let response = egui::Frame::popup(ui.style())
+                    .show(ui, |ui| ui.label(&toast.text))
+                    .response;
+                if focusable
+                    && response
+                        .interact(egui::Sense::click())
+                        .on_hover_cursor(egui::CursorIcon::PointingHand)
+                        .clicked()
+                {
+                    clicked = Some(index);
+                }
+            }
+        });
+    if let Some(index) = clicked {
+        let toast = toasts.0.remove(index);
+        if let Some(subject) = toast.subject {
+            focus.focus(subject);
+        }
+    }
+}
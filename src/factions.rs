@@ -0,0 +1,412 @@
+use crate::accessibility::{faction_colors, faction_patterns};
+use crate::app_state::AppState;
+use crate::autopilot::Autopilot;
+use crate::balance::Balance;
+use crate::bodies::{Body, Radius};
+use crate::crafts::{Craft, Crafts, Destination, LaunchCraft};
+use crate::i18n::tr;
+use crate::maneuver::scratch_bodies;
+use crate::physics::{
+    Mass, PhysicsConstants, ScratchBody, SimPosition, SimRng, SimVelocity, gravity_acceleration,
+    step_scratch,
+};
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy_egui::egui::Color32;
+use egui_plot::LineStyle;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
+
+/// Sides that own bodies and crafts, and an opponent that launches its own
+/// transfers to capture bodies, unless a second player takes it over.
+pub struct FactionsPlugin;
+
+impl Plugin for FactionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OpponentAi>()
+            .init_resource::<CombatRules>()
+            .init_resource::<Players>()
+            .add_systems(
+                FixedPostUpdate,
+                (
+                    (opponent_launches, assign_opponent_autopilots)
+                        .run_if(|players: Res<Players>| players.ai_opponent()),
+                    take_turns,
+                )
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Faction {
+    Player,
+    Opponent,
+}
+
+impl Faction {
+    pub fn name(self) -> String {
+        tr(match self {
+            Self::Player => "faction-player",
+            Self::Opponent => "faction-opponent",
+        })
+    }
+
+    pub fn other(self) -> Self {
+        match self {
+            Self::Player => Self::Opponent,
+            Self::Opponent => Self::Player,
+        }
+    }
+
+    pub fn color(self) -> Color32 {
+        faction_colors()[self as usize]
+    }
+
+    /// A symbol to put before the faction's name, which differs by shape
+    /// when [`faction_patterns`] are on.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Opponent if faction_patterns() => "▲",
+            _ => "●",
+        }
+    }
+
+    /// How lines marking what the faction owns are drawn.
+    pub fn line_style(self) -> LineStyle {
+        match self {
+            Self::Opponent if faction_patterns() => LineStyle::dashed_dense(),
+            _ => LineStyle::Solid,
+        }
+    }
+}
+
+/// Which side a body or craft belongs to. Bodies without one are neutral.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct Owner(pub Faction);
+
+/// Seconds of simulation each side gets per turn in hot-seat games.
+pub const TURN_LENGTH: f32 = 20.;
+
+/// Who gives orders. Normally that's only ever the player, with the AI flying
+/// the opponent; in a hot-seat game two people share the machine and take
+/// turns, and only the side whose turn it is can launch or steer crafts.
+#[derive(Resource)]
+pub struct Players {
+    pub hot_seat: bool,
+    /// The other side is played from another machine.
+    pub online: bool,
+    /// Orders from this machine go to an online host, which only takes
+    /// launches and burns.
+    pub remote: bool,
+    pub active: Faction,
+    pub turn: Timer,
+}
+
+impl Default for Players {
+    fn default() -> Self {
+        Self {
+            hot_seat: false,
+            online: false,
+            remote: false,
+            active: Faction::Player,
+            turn: Timer::from_seconds(TURN_LENGTH, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Whether this machine decides what happens, rather than mirroring an
+/// online host that does.
+pub fn authoritative(players: Option<Res<Players>>) -> bool {
+    players.is_none_or(|players| !players.remote)
+}
+
+impl Players {
+    pub fn ai_opponent(&self) -> bool {
+        !self.hot_seat && !self.online
+    }
+
+    /// The side whose statistics and victory count: the one at this machine,
+    /// or the first player in hot-seat games.
+    pub fn perspective(&self) -> Faction {
+        if self.hot_seat {
+            Faction::Player
+        } else {
+            self.active
+        }
+    }
+
+    /// Whether the side giving orders may command something with `owner`.
+    /// Unowned crafts take orders from anyone.
+    pub fn controls(&self, owner: Option<&Owner>) -> bool {
+        owner.is_none_or(|owner| owner.0 == self.active)
+    }
+
+    /// Hands over to the other side, with a full turn ahead of them.
+    pub fn end_turn(&mut self) {
+        if self.hot_seat {
+            self.active = self.active.other();
+        }
+        self.turn.reset();
+    }
+
+    /// Switches between one and two players, starting over with the player.
+    pub fn set_hot_seat(&mut self, hot_seat: bool) {
+        *self = Self {
+            hot_seat,
+            ..default()
+        };
+    }
+}
+
+/// How a craft landing on an enemy body fights its docked defenders.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct CombatRules {
+    pub resolution: CombatResolution,
+    /// Multiplies defenders' combat strength in [`CombatResolution::Duels`].
+    pub defender_advantage: f32,
+}
+
+impl Default for CombatRules {
+    fn default() -> Self {
+        Self {
+            resolution: CombatResolution::Attrition,
+            defender_advantage: 1.,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CombatResolution {
+    /// Every attacker trades itself for one defender, so it takes more
+    /// attackers than defenders to capture a body.
+    Attrition,
+    /// Each attacker duels defenders one at a time, winning with odds set by
+    /// the two sides' combat strengths, until it falls or none are left.
+    Duels,
+}
+
+impl CombatResolution {
+    pub const ALL: [Self; 2] = [Self::Attrition, Self::Duels];
+
+    pub fn label(self) -> String {
+        tr(match self {
+            Self::Attrition => "combat-attrition",
+            Self::Duels => "combat-duels",
+        })
+    }
+}
+
+impl CombatRules {
+    /// Fights one attacker of `attack` strength against `defenders` docked
+    /// crafts of `defense` strength each, removing those it destroys. Returns
+    /// whether the attacker survived to capture the body.
+    pub fn resolve(
+        &self,
+        rng: &mut SimRng,
+        attack: f32,
+        defense: f32,
+        defenders: &mut u32,
+    ) -> bool {
+        match self.resolution {
+            CombatResolution::Attrition => {
+                if *defenders == 0 {
+                    return true;
+                }
+                *defenders -= 1;
+                false
+            }
+            CombatResolution::Duels => {
+                let defense = defense * self.defender_advantage;
+                while *defenders > 0 {
+                    let total = attack + defense;
+                    let odds = if total > 0. { attack / total } else { 0.5 };
+                    if !rng.0.random_bool(odds.clamp(0., 1.) as f64) {
+                        return false;
+                    }
+                    *defenders -= 1;
+                }
+                true
+            }
+        }
+    }
+}
+
+/// Seconds between the opponent's launch decisions.
+const DECISION_INTERVAL: f32 = 4.;
+/// Crafts the opponent leaves docked at each of its bodies.
+const GARRISON: u32 = 1;
+/// How far ahead launches are tried out, in seconds.
+const AIM_HORIZON: f64 = 30.;
+const AIM_STEP: f64 = 1. / 32.;
+const AIM_ANGLES: usize = 24;
+/// Fractions of the class speed limit tried for each angle.
+const AIM_SPEEDS: [f32; 3] = [0.5, 0.75, 1.];
+/// Misses wider than this aren't worth a craft, even with course corrections.
+const MAX_AIM_MISS: f64 = 3.;
+
+#[derive(Resource)]
+struct OpponentAi {
+    timer: Timer,
+}
+
+impl Default for OpponentAi {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(DECISION_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+type BodyState = (
+    Entity,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Mass,
+    &'static Radius,
+    &'static Crafts,
+    Option<&'static Owner>,
+);
+
+/// Scores every launch the opponent could make and makes the best one: big,
+/// close, lightly defended bodies first, neutral ones ahead of the player's.
+fn opponent_launches(
+    time: Res<Time>,
+    mut ai: ResMut<OpponentAi>,
+    bodies: Query<BodyState, With<Body>>,
+    constants: Res<PhysicsConstants>,
+    balance: Balance,
+    mut launches: EventWriter<LaunchCraft>,
+) {
+    if !ai.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Some((_, class)) = balance.default_class() else {
+        return;
+    };
+
+    let entities: Vec<_> = bodies.iter().map(|(entity, ..)| entity).collect();
+    let radii: Vec<_> = bodies
+        .iter()
+        .map(|(.., radius, _, _)| radius.0 as f64)
+        .collect();
+    let scratch = scratch_bodies(bodies.iter().map(|(_, p, v, m, ..)| (p, v, m)));
+    let owners: Vec<_> = bodies.iter().map(|(.., owner)| owner.copied()).collect();
+    let docked: Vec<_> = bodies.iter().map(|(.., crafts, _)| crafts.0).collect();
+
+    let best = (0..entities.len())
+        .filter(|&from| owners[from] == Some(Owner(Faction::Opponent)) && docked[from] > GARRISON)
+        .flat_map(|from| (0..entities.len()).map(move |to| (from, to)))
+        .filter(|&(_, to)| owners[to] != Some(Owner(Faction::Opponent)))
+        .map(|(from, to)| {
+            let distance = scratch[from].position.distance(scratch[to].position);
+            let bonus = if owners[to].is_none() { 2. } else { 1. };
+            let utility =
+                radii[to].powi(2) * bonus / (1. + distance / 20.) / (1. + docked[to] as f64);
+            (from, to, utility)
+        })
+        .max_by(|(.., a), (.., b)| a.total_cmp(b));
+    let Some((from, to, _)) = best else {
+        return;
+    };
+
+    let Some((direction, speed)) = aim_launch(
+        from,
+        to,
+        &scratch,
+        &radii,
+        &constants,
+        class.launch_speed_limit(),
+    ) else {
+        return;
+    };
+    launches.write(LaunchCraft {
+        from: entities[from],
+        destination: Some(entities[to]),
+        direction,
+        speed,
+        scripted: false,
+    });
+}
+
+/// Launch direction and speed from `bodies[from]` that comes closest to
+/// hitting `bodies[to]`, preferring the slowest launch that hits.
+fn aim_launch(
+    from: usize,
+    to: usize,
+    bodies: &[ScratchBody],
+    radii: &[f64],
+    constants: &PhysicsConstants,
+    speed_limit: f32,
+) -> Option<(Vec2, f32)> {
+    let mut futures = Vec::new();
+    let mut scratch = bodies.to_vec();
+    for _ in 0..(AIM_HORIZON / AIM_STEP) as usize {
+        futures.push(scratch.clone());
+        step_scratch(&mut scratch, constants, AIM_STEP);
+    }
+
+    let (g, softening) = (constants.g as f64, constants.softening as f64);
+    let miss = |direction: DVec3, speed: f64| {
+        let mut position = bodies[from].position + direction * (radii[from] + 0.1);
+        let mut velocity = bodies[from].velocity + direction * speed;
+        let mut closest = f64::INFINITY;
+        for future in &futures {
+            let distance = position.distance(future[to].position) - radii[to];
+            closest = closest.min(distance);
+            let crashed = future
+                .iter()
+                .zip(radii)
+                .any(|(body, radius)| body.position.distance(position) <= *radius);
+            if crashed {
+                break;
+            }
+            velocity += future
+                .iter()
+                .map(|body| gravity_acceleration(body.position - position, body.mass, softening, g))
+                .sum::<DVec3>()
+                * AIM_STEP;
+            position += velocity * AIM_STEP;
+        }
+        closest
+    };
+
+    (0..AIM_ANGLES)
+        .flat_map(|i| {
+            let angle = TAU * i as f64 / AIM_ANGLES as f64;
+            AIM_SPEEDS.map(|fraction| (angle, fraction * speed_limit))
+        })
+        .map(|(angle, speed)| {
+            let direction = DVec3::new(angle.cos(), angle.sin(), 0.);
+            let miss = miss(direction, speed as f64).max(0.);
+            (direction, speed, miss)
+        })
+        .filter(|&(.., miss)| miss <= MAX_AIM_MISS)
+        .min_by(|a, b| a.2.total_cmp(&b.2).then(a.1.total_cmp(&b.1)))
+        .map(|(direction, speed, _)| (direction.truncate().as_vec2(), speed))
+}
+
+fn take_turns(time: Res<Time>, mut players: ResMut<Players>) {
+    if players.hot_seat && players.turn.tick(time.delta()).just_finished() {
+        players.end_turn();
+    }
+}
+
+/// Opponent crafts correct their own course once launched.
+fn assign_opponent_autopilots(
+    mut commands: Commands,
+    crafts: Query<(Entity, &Owner, &Destination), Added<Craft>>,
+) {
+    for (craft, owner, destination) in &crafts {
+        if owner.0 == Faction::Opponent {
+            commands
+                .entity(craft)
+                .insert(Autopilot::Intercept(destination.0));
+        }
+    }
+}
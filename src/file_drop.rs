@@ -0,0 +1,236 @@
+use crate::app_state::AppState;
+use crate::campaign::CurrentLevel;
+use crate::events::Toasts;
+use crate::i18n::{tr, tr_args};
+use crate::replay::{Playback, Replay};
+use crate::scenario::Scenario;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+/// Loads scenario and replay files dropped onto the window, asking first if
+/// that would end a game in progress.
+pub struct FileDropPlugin;
+
+impl Plugin for FileDropPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingDrop>()
+            .add_systems(Update, receive_drops)
+            .add_systems(EguiPrimaryContextPass, confirm_drop);
+        #[cfg(target_arch = "wasm32")]
+        app.add_systems(Startup, browser::listen);
+    }
+}
+
+/// What a dropped file turned out to be.
+enum Dropped {
+    Scenario(Scenario),
+    Replay(Replay),
+}
+
+impl Dropped {
+    /// Replays are told apart by the hash of their scenario, which scenarios
+    /// don't have.
+    fn parse(text: &str) -> Result<Self, String> {
+        let table: toml::Table = toml::from_str(text).map_err(|err| err.to_string())?;
+        if table.contains_key("scenario_hash") {
+            Replay::parse(text)
+                .map(Self::Replay)
+                .map_err(|err| err.to_string())
+        } else {
+            Scenario::from_bytes(text.as_bytes())
+                .map(Self::Scenario)
+                .map_err(|err| err.to_string())
+        }
+    }
+
+    fn load(self, commands: &mut Commands) {
+        match self {
+            Self::Scenario(scenario) => commands.insert_resource(scenario),
+            Self::Replay(replay) => commands.queue(|world: &mut World| {
+                Playback::start(replay, world);
+            }),
+        }
+    }
+}
+
+/// A dropped file waiting on the player to confirm ending the current game.
+#[derive(Resource, Default)]
+struct PendingDrop(Option<(String, Dropped)>);
+
+/// Starts playing what was dropped, outside of the campaign.
+#[derive(SystemParam)]
+struct StartDropped<'w> {
+    current_level: ResMut<'w, CurrentLevel>,
+    next_state: ResMut<'w, NextState<AppState>>,
+}
+
+impl StartDropped<'_> {
+    fn start(&mut self, commands: &mut Commands, dropped: Dropped) {
+        dropped.load(commands);
+        self.current_level.0 = None;
+        self.next_state.set(AppState::Playing);
+    }
+}
+
+fn receive_drops(
+    mut commands: Commands,
+    #[cfg(not(target_arch = "wasm32"))] mut drops: EventReader<FileDragAndDrop>,
+    #[cfg(target_arch = "wasm32")] inbox: NonSend<browser::Inbox>,
+    mut pending: ResMut<PendingDrop>,
+    mut game: StartDropped,
+    state: Res<State<AppState>>,
+    mut toasts: ResMut<Toasts>,
+    time: Res<Time<Real>>,
+) {
+    #[cfg(not(target_arch = "wasm32"))]
+    let files = drops.read().filter_map(|drop| match drop {
+        FileDragAndDrop::DroppedFile { path_buf, .. } => {
+            let name = path_buf
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            Some((name, std::fs::read_to_string(path_buf)))
+        }
+        _ => None,
+    });
+    #[cfg(target_arch = "wasm32")]
+    let files = inbox.take().into_iter();
+
+    for (name, text) in files {
+        let dropped = text
+            .map_err(|err| err.to_string())
+            .and_then(|text| Dropped::parse(&text));
+        match dropped {
+            // Don't throw away a game without asking
+            Ok(dropped) if matches!(state.get(), AppState::Playing | AppState::Paused) => {
+                pending.0 = Some((name, dropped));
+            }
+            Ok(dropped) => game.start(&mut commands, dropped),
+            Err(err) => toasts.push(format!("{name}: {err}"), time.elapsed_secs_f64()),
+        }
+    }
+}
+
+fn confirm_drop(
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+    mut pending: ResMut<PendingDrop>,
+    mut game: StartDropped,
+) {
+    let Some((name, _)) = &pending.0 else {
+        return;
+    };
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    let mut answer = None;
+    egui::Window::new(tr("drop-title"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.label(tr_args("drop-warning", &[("file", name.as_str().into())]));
+            ui.horizontal(|ui| {
+                if ui.button(tr("load")).clicked() {
+                    answer = Some(true);
+                }
+                if ui.button(tr("cancel")).clicked() {
+                    answer = Some(false);
+                }
+            });
+        });
+
+    match answer {
+        Some(true) => {
+            if let Some((_, dropped)) = pending.0.take() {
+                game.start(&mut commands, dropped);
+            }
+        }
+        Some(false) => pending.0 = None,
+        None => {}
+    }
+}
+
+/// The browser's drop events, since winit doesn't pass dropped files on from
+/// a web page.
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use bevy::prelude::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen::closure::Closure;
+    use web_sys::{DragEvent, FileReader};
+
+    /// Names and contents of files read.
+    type Files = Rc<RefCell<Vec<(String, std::io::Result<String>)>>>;
+
+    /// Files read since last taken, by name. Browsers only ever run one
+    /// thread, so this is a non-send resource.
+    #[derive(Default)]
+    pub struct Inbox {
+        files: Files,
+        _on_drag_over: Option<Closure<dyn FnMut(DragEvent)>>,
+        _on_drop: Option<Closure<dyn FnMut(DragEvent)>>,
+    }
+
+    impl Inbox {
+        pub fn take(&self) -> Vec<(String, std::io::Result<String>)> {
+            std::mem::take(&mut self.files.borrow_mut())
+        }
+    }
+
+    pub fn listen(world: &mut World) {
+        let mut inbox = Inbox::default();
+        let Some(window) = web_sys::window() else {
+            world.insert_non_send_resource(inbox);
+            return;
+        };
+
+        // The page would otherwise open the file itself
+        let on_drag_over =
+            Closure::<dyn FnMut(DragEvent)>::new(|event: DragEvent| event.prevent_default());
+        let on_drop = Closure::<dyn FnMut(DragEvent)>::new({
+            let files = inbox.files.clone();
+            move |event: DragEvent| {
+                event.prevent_default();
+                let Some(list) = event.data_transfer().and_then(|data| data.files()) else {
+                    return;
+                };
+                for file in (0..list.length()).filter_map(|index| list.get(index)) {
+                    read(file, files.clone());
+                }
+            }
+        });
+        let _ = window
+            .add_event_listener_with_callback("dragover", on_drag_over.as_ref().unchecked_ref());
+        let _ = window.add_event_listener_with_callback("drop", on_drop.as_ref().unchecked_ref());
+        inbox._on_drag_over = Some(on_drag_over);
+        inbox._on_drop = Some(on_drop);
+        world.insert_non_send_resource(inbox);
+    }
+
+    /// Reads the file as text into `files` once the browser has it.
+    fn read(file: web_sys::File, files: Files) {
+        let name = file.name();
+        let Ok(reader) = FileReader::new() else {
+            return;
+        };
+        let on_load = Closure::once_into_js({
+            let reader = reader.clone();
+            move || {
+                let text = reader
+                    .result()
+                    .ok()
+                    .and_then(|result| result.as_string())
+                    .ok_or_else(|| std::io::Error::other("could not read the file"));
+                files.borrow_mut().push((name, text));
+            }
+        });
+        reader.set_onloadend(Some(on_load.unchecked_ref()));
+        if reader.read_as_text(&file).is_err() {
+            reader.set_onloadend(None);
+        }
+    }
+}
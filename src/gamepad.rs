@@ -0,0 +1,149 @@
+use crate::app_state::AppState;
+use crate::balance::Balance;
+use crate::bindings::{Action, ActionInput};
+use crate::interaction::InteractionMode;
+use crate::ui::PlotPan;
+use bevy::prelude::*;
+use std::f32::consts::PI;
+
+/// Analog gamepad controls: the left stick pans the plot, the right stick
+/// aims launches and the triggers set their power. Buttons go through
+/// [`crate::bindings::ActionInput`] like keys do, and aiming keys step the
+/// same aim, so launches work without a pointer too.
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StickAim>()
+            .add_systems(
+                Update,
+                (pan_plot, aim_launch, adjust_launch_power, aim_with_keys)
+                    .run_if(AppState::in_game),
+            )
+            .add_systems(OnExit(InteractionMode::LaunchAim), forget_aim);
+    }
+}
+
+/// Stick deflection below this is ignored.
+const DEADZONE: f32 = 0.2;
+/// Fractions of the plot's size a fully deflected left stick pans per second.
+const PAN_RATE: f32 = 0.8;
+/// Launch speed a fully pulled trigger adds or removes per second.
+const POWER_RATE: f32 = 10.;
+/// Radians each aiming key press turns the aim.
+const AIM_STEP: f32 = PI / 12.;
+/// Launch speed each aiming key press adds or removes.
+const POWER_STEP: f32 = 1.;
+
+/// The launch the right stick and triggers are setting up.
+#[derive(Resource)]
+pub struct StickAim {
+    /// Set while aiming with the stick or keys; overrides the pointer.
+    pub direction: Option<Vec2>,
+    pub speed: f32,
+}
+
+impl Default for StickAim {
+    fn default() -> Self {
+        Self {
+            direction: None,
+            speed: 10.,
+        }
+    }
+}
+
+fn deflection(stick: Vec2) -> Option<Vec2> {
+    (stick.length() > DEADZONE).then_some(stick)
+}
+
+fn pan_plot(gamepads: Query<&Gamepad>, mut pan: ResMut<PlotPan>, time: Res<Time<Real>>) {
+    for gamepad in &gamepads {
+        if let Some(stick) = deflection(gamepad.left_stick()) {
+            pan.0 += stick * PAN_RATE * time.delta_secs();
+        }
+    }
+}
+
+/// Pointing the right stick enters [`InteractionMode::LaunchAim`] and aims
+/// that way. The aim stays put when the stick is let go, ready to fire.
+fn aim_launch(
+    gamepads: Query<&Gamepad>,
+    mut aim: ResMut<StickAim>,
+    mode: Res<State<InteractionMode>>,
+    mut next_mode: ResMut<NextState<InteractionMode>>,
+) {
+    let Some(stick) = gamepads
+        .iter()
+        .find_map(|gamepad| deflection(gamepad.right_stick()))
+    else {
+        return;
+    };
+    aim.direction = Some(stick.normalize());
+    if *mode.get() != InteractionMode::LaunchAim {
+        next_mode.set(InteractionMode::LaunchAim);
+    }
+}
+
+/// The right trigger speeds the launch up, the left slows it down.
+fn adjust_launch_power(
+    gamepads: Query<&Gamepad>,
+    mut aim: ResMut<StickAim>,
+    balance: Balance,
+    time: Res<Time<Real>>,
+) {
+    let pull: f32 = gamepads
+        .iter()
+        .map(|gamepad| {
+            gamepad.get(GamepadButton::RightTrigger2).unwrap_or(0.)
+                - gamepad.get(GamepadButton::LeftTrigger2).unwrap_or(0.)
+        })
+        .sum();
+    if pull.abs() < DEADZONE {
+        return;
+    }
+    let limit = balance
+        .default_class()
+        .map_or(f32::INFINITY, |(_, class)| class.launch_speed_limit());
+    aim.speed = (aim.speed + pull * POWER_RATE * time.delta_secs()).clamp(0., limit);
+}
+
+/// Aiming keys enter [`InteractionMode::LaunchAim`], starting from aiming
+/// right, then turn the aim or change its speed a step per press.
+fn aim_with_keys(
+    mut actions: ActionInput,
+    mut aim: ResMut<StickAim>,
+    balance: Balance,
+    mode: Res<State<InteractionMode>>,
+    mut next_mode: ResMut<NextState<InteractionMode>>,
+) {
+    let turn = if actions.just_pressed(Action::AimLeft) {
+        AIM_STEP
+    } else if actions.just_pressed(Action::AimRight) {
+        -AIM_STEP
+    } else {
+        0.
+    };
+    let power = if actions.just_pressed(Action::AimFaster) {
+        POWER_STEP
+    } else if actions.just_pressed(Action::AimSlower) {
+        -POWER_STEP
+    } else {
+        0.
+    };
+    if turn == 0. && power == 0. {
+        return;
+    }
+    let direction = aim.direction.unwrap_or(Vec2::X);
+    aim.direction = Some(Vec2::from_angle(turn).rotate(direction));
+    let limit = balance
+        .default_class()
+        .map_or(f32::INFINITY, |(_, class)| class.launch_speed_limit());
+    aim.speed = (aim.speed + power).clamp(0., limit);
+    if *mode.get() != InteractionMode::LaunchAim {
+        next_mode.set(InteractionMode::LaunchAim);
+    }
+}
+
+fn forget_aim(mut aim: ResMut<StickAim>) {
+    aim.direction = None;
+}
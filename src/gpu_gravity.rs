@@ -0,0 +1,280 @@
+use crate::app_state::AppState;
+use crate::physics::{
+    GravitySolver, GravityStep, Mass, MasslessParticles, PhysicsConstants, PotentialEnergy,
+    SimPosition, SimVelocity,
+};
+use bevy::ecs::system::SystemParam;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::render::render_resource::{
+    BindGroupEntry, BindGroupLayout, BindGroupLayoutEntry, BindingType, BufferBindingType,
+    BufferDescriptor, BufferInitDescriptor, BufferUsages, CommandEncoderDescriptor,
+    ComputePassDescriptor, ComputePipeline, Maintain, MapMode, PipelineLayoutDescriptor,
+    RawComputePipelineDescriptor, ShaderModuleDescriptor, ShaderSource, ShaderStages,
+};
+use bevy::render::renderer::{RenderDevice, RenderQueue};
+use std::borrow::Cow;
+
+/// Sums gravity up on the GPU when the [`GravitySolver`] says to, for far
+/// more test particles, such as debris, than the CPU keeps up with.
+///
+/// Only built with the `gpu_gravity` feature. Each tick uploads positions,
+/// runs a compute shader over every body and particle, and waits to read
+/// the accelerations back into [`SimVelocity`]. Without a GPU, as when
+/// headless, it falls back to the direct solver.
+pub struct GpuGravityPlugin;
+
+impl Plugin for GpuGravityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            gpu_gravity
+                .in_set(GravityStep)
+                .run_if(resource_equals(GravitySolver::Gpu))
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+/// Invocations per workgroup, matching the shader.
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Each point is a position and a mass, zero for test particles, which come
+/// after the bodies and pull on nothing. Each result is the acceleration and
+/// the potential per unit mass, mirroring `gravity_acceleration` and
+/// `pair_potential_energy`.
+const SHADER: &str = r"
+struct Params {
+    count: u32,
+    bodies: u32,
+    softening_sq: f32,
+    g: f32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> points: array<vec4<f32>>;
+@group(0) @binding(2) var<storage, read_write> results: array<vec4<f32>>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if i >= params.count {
+        return;
+    }
+    let position = points[i].xyz;
+    var acceleration = vec3<f32>(0.0);
+    var potential = 0.0;
+    for (var j = 0u; j < params.bodies; j++) {
+        if j == i {
+            continue;
+        }
+        let offset = points[j].xyz - position;
+        let inverse = inverseSqrt(dot(offset, offset) + params.softening_sq);
+        let pull = params.g * points[j].w * inverse;
+        acceleration += offset * pull * inverse * inverse;
+        potential -= pull;
+    }
+    results[i] = vec4<f32>(acceleration, potential);
+}
+";
+
+/// The compiled shader, made on first use.
+struct Pipeline {
+    layout: BindGroupLayout,
+    pipeline: ComputePipeline,
+}
+
+impl Pipeline {
+    fn new(device: &RenderDevice) -> Self {
+        let buffer = |binding, ty| BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::COMPUTE,
+            ty: BindingType::Buffer {
+                ty,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(
+            "gravity_layout",
+            &[
+                buffer(0, BufferBindingType::Uniform),
+                buffer(1, BufferBindingType::Storage { read_only: true }),
+                buffer(2, BufferBindingType::Storage { read_only: false }),
+            ],
+        );
+        let module = device.create_and_validate_shader_module(ShaderModuleDescriptor {
+            label: Some("gravity_shader"),
+            source: ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("gravity_pipeline_layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&RawComputePipelineDescriptor {
+            label: Some("gravity_pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: default(),
+            cache: None,
+        });
+        Self { layout, pipeline }
+    }
+
+    /// Acceleration and potential per unit mass for every point, as
+    /// position and mass, the first `bodies` of which pull on the rest.
+    fn run(
+        &self,
+        device: &RenderDevice,
+        queue: &RenderQueue,
+        points: &[(DVec3, f64)],
+        bodies: usize,
+        constants: &PhysicsConstants,
+    ) -> Vec<(DVec3, f64)> {
+        let softening = constants.softening;
+        let params: Vec<u8> = [points.len() as u32, bodies as u32]
+            .into_iter()
+            .flat_map(u32::to_le_bytes)
+            .chain(
+                [softening * softening, constants.g]
+                    .into_iter()
+                    .flat_map(f32::to_le_bytes),
+            )
+            .collect();
+        let point_data: Vec<u8> = points
+            .iter()
+            .flat_map(|(position, mass)| [position.x, position.y, position.z, *mass])
+            .flat_map(|value| (value as f32).to_le_bytes())
+            .collect();
+        let size = (points.len() * 16) as u64;
+
+        let params = device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("gravity_params"),
+            contents: &params,
+            usage: BufferUsages::UNIFORM,
+        });
+        let point_buffer = device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("gravity_points"),
+            contents: &point_data,
+            usage: BufferUsages::STORAGE,
+        });
+        let results = device.create_buffer(&BufferDescriptor {
+            label: Some("gravity_results"),
+            size,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = device.create_buffer(&BufferDescriptor {
+            label: Some("gravity_readback"),
+            size,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(
+            "gravity_bind_group",
+            &self.layout,
+            &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: point_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: results.as_entire_binding(),
+                },
+            ],
+        );
+
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("gravity_encoder"),
+        });
+        {
+            let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("gravity_pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups((points.len() as u32).div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&results, 0, &readback, 0, size);
+        queue.submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        slice.map_async(MapMode::Read, |result| {
+            if let Err(error) = result {
+                log::error!("Could not read gravity back from the GPU: {error}");
+            }
+        });
+        device.poll(Maintain::Wait);
+        let results = slice
+            .get_mapped_range()
+            .chunks_exact(16)
+            .map(|result| {
+                let [x, y, z, potential] = [0, 4, 8, 12].map(|offset| {
+                    f32::from_le_bytes(result[offset..offset + 4].try_into().unwrap()) as f64
+                });
+                (DVec3::new(x, y, z), potential)
+            })
+            .collect();
+        readback.unmap();
+        results
+    }
+}
+
+/// The GPU to sum gravity on, which headless apps don't have.
+#[derive(SystemParam)]
+struct Gpu<'w> {
+    device: Option<Res<'w, RenderDevice>>,
+    queue: Option<Res<'w, RenderQueue>>,
+}
+
+fn gpu_gravity(
+    mut bodies: Query<(&SimPosition, &mut SimVelocity, &Mass)>,
+    mut particles: Query<(&SimPosition, &mut SimVelocity), MasslessParticles>,
+    mut potential_energy: ResMut<PotentialEnergy>,
+    mut solver: ResMut<GravitySolver>,
+    constants: Res<PhysicsConstants>,
+    gpu: Gpu,
+    mut pipeline: Local<Option<Pipeline>>,
+    time: Res<Time>,
+) {
+    let (Some(device), Some(queue)) = (gpu.device, gpu.queue) else {
+        log::warn!("No GPU to sum gravity on, falling back to the CPU");
+        *solver = GravitySolver::Direct;
+        return;
+    };
+    let pipeline = pipeline.get_or_insert_with(|| Pipeline::new(&device));
+
+    let points: Vec<_> = bodies
+        .iter()
+        .map(|(position, _, mass)| (position.0, mass.0 as f64))
+        .chain(particles.iter().map(|(position, _)| (position.0, 0.)))
+        .collect();
+    if points.is_empty() {
+        return;
+    }
+    let body_count = bodies.iter().count();
+    let results = pipeline.run(&device, &queue, &points, body_count, &constants);
+
+    let dt = time.delta_secs_f64();
+    // Each pair's potential energy is counted from both ends
+    let mut new_potential_energy = 0.;
+    let (body_results, particle_results) = results.split_at(body_count);
+    for ((_, mut velocity, mass), (acceleration, potential)) in bodies.iter_mut().zip(body_results)
+    {
+        velocity.0 += *acceleration * dt;
+        new_potential_energy += mass.0 as f64 * potential / 2.;
+    }
+    for ((_, mut velocity), (acceleration, _)) in particles.iter_mut().zip(particle_results) {
+        velocity.0 += *acceleration * dt;
+    }
+    potential_energy.0 = new_potential_energy;
+}
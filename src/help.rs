@@ -0,0 +1,116 @@
+use crate::bindings::{Action, ActionInput, KeyBindings};
+use crate::i18n::tr;
+use crate::ui::legend_entries;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use bevy_persistent::prelude::*;
+
+/// A help window listing the current key and gamepad bindings, what the
+/// mouse does, and what the markers on the plot mean. Toggled with
+/// [`Action::Help`].
+pub struct HelpPlugin;
+
+impl Plugin for HelpPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HelpWindow>()
+            .add_systems(Update, toggle_help)
+            .add_systems(EguiPrimaryContextPass, help_window);
+    }
+}
+
+/// Whether the help window is open.
+#[derive(Resource, Default)]
+pub struct HelpWindow(pub bool);
+
+/// What the mouse and touch do on the plot, by mode, as message ids of the
+/// input and what it does.
+const POINTER: [(&str, &str); 10] = [
+    ("help-click", "help-click-does"),
+    ("help-shift-click", "help-shift-click-does"),
+    ("help-drag", "help-drag-does"),
+    ("help-scroll", "help-scroll-does"),
+    ("help-long-press", "help-long-press-does"),
+    ("help-aim-mode", "help-aim-mode-does"),
+    ("help-burn-mode", "help-burn-mode-does"),
+    ("help-measure-mode", "help-measure-mode-does"),
+    ("help-move-mode", "help-move-mode-does"),
+    ("help-box-mode", "help-box-mode-does"),
+];
+
+/// Plot markers besides crafts, which [`legend_entries`] covers, as message
+/// ids of the marker and its meaning.
+const MARKERS: [(&str, &str); 4] = [
+    ("help-lagrange", "help-lagrange-means"),
+    ("help-soi", "help-soi-means"),
+    ("help-hill", "help-hill-means"),
+    ("help-roche", "help-roche-means"),
+];
+
+fn toggle_help(mut actions: ActionInput, mut open: ResMut<HelpWindow>) {
+    if actions.just_pressed(Action::Help) {
+        open.0 = !open.0;
+    }
+}
+
+fn help_window(
+    mut contexts: EguiContexts,
+    mut open: ResMut<HelpWindow>,
+    bindings: Res<Persistent<KeyBindings>>,
+) {
+    if !open.0 {
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    egui::Window::new(tr("help"))
+        .open(&mut open.0)
+        .collapsible(false)
+        .default_width(420.)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.strong(tr("help-keys"));
+                egui::Grid::new("help_keys").striped(true).show(ui, |ui| {
+                    ui.label("");
+                    ui.weak(tr("key"));
+                    ui.weak(tr("gamepad"));
+                    ui.end_row();
+                    for action in Action::ALL {
+                        ui.label(action.label());
+                        ui.monospace(bindings.binding(action).to_string());
+                        ui.label(
+                            bindings
+                                .button(action)
+                                .map_or("—".to_string(), |button| format!("{button:?}")),
+                        );
+                        ui.end_row();
+                    }
+                });
+                ui.weak(tr("help-rebind"));
+
+                ui.separator();
+                ui.strong(tr("help-mouse"));
+                egui::Grid::new("help_pointer")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (input, effect) in POINTER {
+                            ui.label(tr(input));
+                            ui.label(tr(effect));
+                            ui.end_row();
+                        }
+                    });
+
+                ui.separator();
+                ui.strong(tr("help-plot"));
+                legend_entries(ui);
+                egui::Grid::new("help_markers").show(ui, |ui| {
+                    for (marker, meaning) in MARKERS {
+                        ui.label(tr(marker));
+                        ui.label(tr(meaning));
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+}
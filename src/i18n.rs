@@ -0,0 +1,168 @@
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A language the UI is translated into, as picked in the settings.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Self; 2] = [Self::English, Self::German];
+
+    /// The language's own name for itself, so players can find theirs.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::German => "Deutsch",
+        }
+    }
+
+    fn identifier(self) -> &'static str {
+        match self {
+            Self::English => "en-US",
+            Self::German => "de",
+        }
+    }
+
+    /// Embedded so translations work on the web and from any directory.
+    fn messages(self) -> &'static str {
+        match self {
+            Self::English => include_str!("../assets/locales/en-US.ftl"),
+            Self::German => include_str!("../assets/locales/de.ftl"),
+        }
+    }
+
+    /// Separators between whole and fractional digits, and between
+    /// thousands.
+    fn separators(self) -> (char, char) {
+        match self {
+            Self::English => ('.', ','),
+            Self::German => (',', '.'),
+        }
+    }
+}
+
+/// The language UI text is shown in, as an index into [`Language::ALL`].
+/// Global, like egui's own style, so any bit of UI can translate without a
+/// resource threaded through to it.
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+fn bundles() -> &'static [FluentBundle<FluentResource>] {
+    static BUNDLES: OnceLock<Vec<FluentBundle<FluentResource>>> = OnceLock::new();
+    BUNDLES.get_or_init(|| {
+        Language::ALL
+            .iter()
+            .map(|language| {
+                let identifier = language
+                    .identifier()
+                    .parse()
+                    .expect("bundled language identifier is invalid");
+                let mut bundle = FluentBundle::new_concurrent(vec![identifier]);
+                // egui would draw the bidirectional isolation marks as boxes
+                bundle.set_use_isolating(false);
+                bundle.set_formatter(Some(format_value));
+                let resource = FluentResource::try_new(language.messages().to_string())
+                    .unwrap_or_else(|(resource, errors)| {
+                        log::error!("{} translations have errors: {errors:?}", language.label());
+                        resource
+                    });
+                if let Err(errors) = bundle.add_resource(resource) {
+                    log::error!("{} translations have errors: {errors:?}", language.label());
+                }
+                bundle
+            })
+            .collect()
+    })
+}
+
+/// Writes numeric arguments with [`format_number`], keeping as many decimals
+/// as Fluent would. They stay numbers until written, so plurals still select.
+fn format_value<M>(value: &FluentValue, _: &M) -> Option<String> {
+    let FluentValue::Number(number) = value else {
+        return None;
+    };
+    let decimals = number
+        .as_string()
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    Some(format_number(number.value, decimals))
+}
+
+pub fn language() -> Language {
+    Language::ALL[CURRENT.load(Ordering::Relaxed)]
+}
+
+pub fn set_language(language: Language) {
+    let index = Language::ALL
+        .iter()
+        .position(|&other| other == language)
+        .unwrap_or_default();
+    CURRENT.store(index, Ordering::Relaxed);
+}
+
+/// The text for message `id` in the current language, falling back to
+/// English for anything not yet translated, and to the id itself for
+/// anything missing altogether.
+pub fn tr(id: &str) -> String {
+    tr_args(id, &[])
+}
+
+/// Like [`tr`], filling in the message's `{ $name }` placeables.
+pub fn tr_args(id: &str, args: &[(&str, FluentValue)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    let bundles = bundles();
+    let current = &bundles[CURRENT.load(Ordering::Relaxed)];
+    let Some(pattern) = [current, &bundles[0]]
+        .into_iter()
+        .find_map(|bundle| bundle.get_message(id)?.value())
+    else {
+        return id.to_string();
+    };
+    let mut errors = Vec::new();
+    // Both bundles hold the same message ids, so either can format the other's pattern
+    let text = current.format_pattern(pattern, Some(&fluent_args), &mut errors);
+    if !errors.is_empty() {
+        log::warn!("message {id}: {errors:?}");
+    }
+    text.into_owned()
+}
+
+/// `value` with `decimals` digits after the separator, and thousands grouped,
+/// as the current language writes numbers.
+pub fn format_number(value: f64, decimals: usize) -> String {
+    let (decimal, group) = language().separators();
+    let formatted = format!("{:.decimals$}", value.abs());
+    let (whole, fraction) = formatted
+        .split_once('.')
+        .map_or((formatted.as_str(), None), |(whole, fraction)| {
+            (whole, Some(fraction))
+        });
+    let mut text = String::new();
+    if value.is_sign_negative()
+        && formatted
+            .chars()
+            .any(|digit| digit.is_ascii_digit() && digit != '0')
+    {
+        text.push('-');
+    }
+    for (index, digit) in whole.chars().enumerate() {
+        if index > 0 && (whole.len() - index) % 3 == 0 {
+            text.push(group);
+        }
+        text.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        text.push(decimal);
+        text.push_str(fraction);
+    }
+    text
+}
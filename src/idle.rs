@@ -0,0 +1,205 @@
+use crate::app_state::AppState;
+use crate::bodies::Body;
+use crate::crafts::Crafts;
+use crate::i18n::{format_number, tr, tr_args};
+use crate::physics::TotalEnergy;
+use bevy::ecs::system::SystemParam;
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use std::collections::BTreeMap;
+
+/// Pauses the simulation when the player stops giving input, and summarizes
+/// what happened while they were away once they return.
+pub struct IdlePlugin;
+
+impl Plugin for IdlePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<IdleSettings>()
+            .init_resource::<IdleState>()
+            .add_systems(PreUpdate, detect_activity)
+            .add_systems(Update, auto_pause.run_if(in_state(AppState::Playing)))
+            .add_systems(EguiPrimaryContextPass, away_summary_window);
+    }
+}
+
+#[derive(Resource)]
+pub struct IdleSettings {
+    pub auto_pause: bool,
+    /// Seconds without input before pausing.
+    pub timeout: f32,
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        Self {
+            auto_pause: true,
+            timeout: 120.,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct IdleState {
+    /// Real time of the most recent input.
+    last_input: f64,
+    /// State when input was last seen, to compare against on return.
+    snapshot: Option<Snapshot>,
+    auto_paused: bool,
+    /// Shown until dismissed after returning from an auto-pause.
+    pub summary: Option<Vec<String>>,
+}
+
+struct Snapshot {
+    sim_time: f64,
+    total_energy: f64,
+    crafts: BTreeMap<String, u32>,
+}
+
+impl Snapshot {
+    fn take(
+        sim_time: f64,
+        total_energy: f64,
+        bodies: &Query<(&Name, &Crafts), With<Body>>,
+    ) -> Self {
+        Self {
+            sim_time,
+            total_energy,
+            crafts: bodies
+                .iter()
+                .map(|(name, crafts)| (name.to_string(), crafts.0))
+                .collect(),
+        }
+    }
+
+    fn summarize(&self, now: &Snapshot) -> Vec<String> {
+        let mut lines = vec![tr_args(
+            "idle-simulated",
+            &[(
+                "seconds",
+                format_number(now.sim_time - self.sim_time, 1).into(),
+            )],
+        )];
+        for (name, &crafts) in &now.crafts {
+            match self.crafts.get(name) {
+                Some(&before) if before != crafts => {
+                    lines.push(tr_args(
+                        "idle-crafts-changed",
+                        &[
+                            ("name", name.as_str().into()),
+                            ("before", before.into()),
+                            ("after", crafts.into()),
+                        ],
+                    ));
+                }
+                Some(_) => {}
+                None => lines.push(tr_args("idle-appeared", &[("name", name.as_str().into())])),
+            }
+        }
+        for name in self.crafts.keys() {
+            if !now.crafts.contains_key(name) {
+                lines.push(tr_args("idle-lost", &[("name", name.as_str().into())]));
+            }
+        }
+        let drift = now.total_energy - self.total_energy;
+        if drift.abs() > 1e-3 {
+            let sign = if drift > 0. { "+" } else { "" };
+            let drift = format!("{sign}{}", format_number(drift, 3));
+            lines.push(tr_args("idle-energy-drift", &[("drift", drift.into())]));
+        }
+        lines
+    }
+}
+
+/// Everything that counts as the player doing something.
+#[derive(SystemParam)]
+struct PlayerInput<'w, 's> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse_buttons: Res<'w, ButtonInput<MouseButton>>,
+    touches: Res<'w, Touches>,
+    mouse_motion: EventReader<'w, 's, MouseMotion>,
+    mouse_wheel: EventReader<'w, 's, MouseWheel>,
+}
+
+impl PlayerInput<'_, '_> {
+    fn any(&mut self) -> bool {
+        self.keys.get_pressed().next().is_some()
+            || self.mouse_buttons.get_pressed().next().is_some()
+            || self.touches.iter().next().is_some()
+            || self.mouse_motion.read().count() > 0
+            || self.mouse_wheel.read().count() > 0
+    }
+}
+
+fn detect_activity(
+    mut input: PlayerInput,
+    real_time: Res<Time<Real>>,
+    time: Res<Time<Virtual>>,
+    total_energy: Res<TotalEnergy>,
+    bodies: Query<(&Name, &Crafts), With<Body>>,
+    mut state: ResMut<IdleState>,
+) {
+    if !input.any() {
+        return;
+    }
+
+    state.last_input = real_time.elapsed_secs_f64();
+    let now = Snapshot::take(time.elapsed_secs_f64(), total_energy.0, &bodies);
+    if !state.auto_paused {
+        state.snapshot = Some(now);
+    } else if state.summary.is_none() {
+        // Stay paused until the summary is dismissed
+        state.summary = Some(match &state.snapshot {
+            Some(before) => before.summarize(&now),
+            None => Vec::new(),
+        });
+    }
+}
+
+fn auto_pause(
+    settings: Res<IdleSettings>,
+    real_time: Res<Time<Real>>,
+    mut state: ResMut<IdleState>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if !settings.auto_pause || state.auto_paused {
+        return;
+    }
+    if real_time.elapsed_secs_f64() - state.last_input > settings.timeout as f64 {
+        log::info!("No input for {}s, pausing", settings.timeout);
+        next_state.set(AppState::Paused);
+        state.auto_paused = true;
+    }
+}
+
+fn away_summary_window(
+    mut contexts: EguiContexts,
+    mut state: ResMut<IdleState>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    let Some(summary) = &state.summary else {
+        return;
+    };
+
+    let mut resumed = false;
+    egui::Window::new(tr("idle-title"))
+        .anchor(egui::Align2::CENTER_CENTER, [0., 0.])
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            for line in summary {
+                ui.label(line);
+            }
+            ui.separator();
+            ui.label(tr("idle-paused"));
+            resumed = ui.button(tr("resume")).clicked();
+        });
+    if resumed {
+        state.summary = None;
+        state.auto_paused = false;
+        next_state.set(AppState::Playing);
+    }
+}
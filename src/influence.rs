@@ -0,0 +1,177 @@
+use crate::app_state::AppState;
+use crate::bodies::{Body, Radius};
+use crate::maneuver::scratch_bodies;
+use crate::physics::{Mass, ScratchBody, SimPosition, SimVelocity};
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+/// Keeps each body's sphere of influence, Lagrange points and tidal limits
+/// up to date.
+pub struct InfluencePlugin;
+
+impl Plugin for InfluencePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedPostUpdate,
+            (
+                update_spheres_of_influence,
+                update_lagrange_points,
+                update_tidal_limits,
+            )
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+/// Radius within which a body's gravity dominates that of the heaviest body,
+/// which itself has an infinite sphere.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct SphereOfInfluence(pub f64);
+
+impl Default for SphereOfInfluence {
+    fn default() -> Self {
+        Self(f64::INFINITY)
+    }
+}
+
+/// L1 to L5 of a body and the heaviest body, which has none of its own.
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub struct LagrangePoints(pub Option<[DVec3; 5]>);
+
+/// Radius around a body within which it can hold satellites against the
+/// heaviest body's tides. The heaviest body has none.
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub struct HillSphere(pub Option<f64>);
+
+/// How close to the heaviest body this body can orbit before tides tear it
+/// apart, as that body and the distance from its center.
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub struct RocheLimit(pub Option<(Entity, f64)>);
+
+/// Laplace's sphere-of-influence radius, r = a * (m / M)^(2/5), of every body
+/// relative to the heaviest one.
+pub fn sphere_of_influence_radii(bodies: &[ScratchBody]) -> Vec<f64> {
+    let Some(dominant) = bodies.iter().max_by(|a, b| a.mass.total_cmp(&b.mass)) else {
+        return Vec::new();
+    };
+    bodies
+        .iter()
+        .map(|body| {
+            let distance = body.position.distance(dominant.position);
+            if distance <= 0. {
+                return f64::INFINITY;
+            }
+            distance * (body.mass / dominant.mass).powf(0.4)
+        })
+        .collect()
+}
+
+/// Index of the body whose sphere of influence `position` lies in, preferring
+/// the innermost one.
+pub fn governing_body(position: DVec3, bodies: &[ScratchBody], radii: &[f64]) -> Option<usize> {
+    bodies
+        .iter()
+        .zip(radii)
+        .enumerate()
+        .filter(|(_, (body, radius))| body.position.distance(position) <= **radius)
+        .min_by(|(_, (_, a)), (_, (_, b))| a.total_cmp(b))
+        .map(|(index, _)| index)
+}
+
+/// L1 to L5 of `secondary` orbiting `primary`, using the usual approximations
+/// for a secondary much lighter than its primary.
+pub fn lagrange_points(primary: &ScratchBody, secondary: &ScratchBody) -> Option<[DVec3; 5]> {
+    let offset = secondary.position - primary.position;
+    let distance = offset.length();
+    let toward = offset.try_normalize()?;
+    let mass_ratio = secondary.mass / (primary.mass + secondary.mass);
+
+    // L4 leads the secondary along its orbit and L5 trails it
+    let orbit_sense = offset
+        .cross(secondary.velocity - primary.velocity)
+        .z
+        .signum();
+    let rotated = |angle: f64| {
+        let (sin, cos) = (angle * orbit_sense).sin_cos();
+        DVec3::new(
+            toward.x * cos - toward.y * sin,
+            toward.x * sin + toward.y * cos,
+            0.,
+        )
+    };
+
+    let hill = distance * (mass_ratio / 3.).cbrt();
+    Some([
+        secondary.position - toward * hill,
+        secondary.position + toward * hill,
+        primary.position - toward * distance * (1. + 5. * mass_ratio / 12.),
+        primary.position + rotated(std::f64::consts::FRAC_PI_3) * distance,
+        primary.position + rotated(-std::f64::consts::FRAC_PI_3) * distance,
+    ])
+}
+
+/// Hill sphere radius, r = a * (m / 3M)^(1/3), of `secondary` orbiting `primary`.
+pub fn hill_radius(primary: &ScratchBody, secondary: &ScratchBody) -> f64 {
+    secondary.position.distance(primary.position) * (secondary.mass / (3. * primary.mass)).cbrt()
+}
+
+/// Rigid-body Roche limit, d = R * (2M / m)^(1/3), of a secondary of `mass`
+/// and `radius` around a primary of `primary_mass`.
+pub fn roche_limit(primary_mass: f64, mass: f64, radius: f64) -> f64 {
+    radius * (2. * primary_mass / mass).cbrt()
+}
+
+fn update_spheres_of_influence(
+    mut bodies: Query<(&SimPosition, &SimVelocity, &Mass, &mut SphereOfInfluence), With<Body>>,
+) {
+    let scratch = scratch_bodies(bodies.iter().map(|(p, v, m, _)| (p, v, m)));
+    let radii = sphere_of_influence_radii(&scratch);
+    for ((.., mut sphere), radius) in bodies.iter_mut().zip(radii) {
+        sphere.0 = radius;
+    }
+}
+
+fn update_lagrange_points(
+    mut bodies: Query<(&SimPosition, &SimVelocity, &Mass, &mut LagrangePoints), With<Body>>,
+) {
+    let scratch = scratch_bodies(bodies.iter().map(|(p, v, m, _)| (p, v, m)));
+    let primary = scratch
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.mass.total_cmp(&b.mass))
+        .map(|(index, _)| index);
+    for (index, (.., mut points)) in bodies.iter_mut().enumerate() {
+        points.0 = primary
+            .filter(|&primary| primary != index)
+            .and_then(|primary| lagrange_points(&scratch[primary], &scratch[index]));
+    }
+}
+
+type BodyState = (
+    Entity,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Mass,
+    &'static Radius,
+    &'static mut HillSphere,
+    &'static mut RocheLimit,
+);
+
+fn update_tidal_limits(mut bodies: Query<BodyState, With<Body>>) {
+    let scratch = scratch_bodies(bodies.iter().map(|(_, p, v, m, ..)| (p, v, m)));
+    let primary = bodies
+        .iter()
+        .zip(&scratch)
+        .max_by(|(_, a), (_, b)| a.mass.total_cmp(&b.mass))
+        .map(|((entity, ..), body)| (entity, *body));
+    for ((entity, .., radius, mut hill, mut roche), body) in bodies.iter_mut().zip(&scratch) {
+        let primary = primary.filter(|&(primary, _)| primary != entity);
+        hill.0 = primary.map(|(_, primary)| hill_radius(&primary, body));
+        roche.0 = primary.map(|(primary_entity, primary)| {
+            (
+                primary_entity,
+                roche_limit(primary.mass, body.mass, radius.0 as f64),
+            )
+        });
+    }
+}
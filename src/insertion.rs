@@ -0,0 +1,258 @@
+use crate::app_state::AppState;
+use crate::bodies::{Body, Radius};
+use crate::crafts::{Craft, Destination, Fuel};
+use crate::i18n::{format_number, tr, tr_args};
+use crate::physics::{Mass, PhysicsConstants, SimPosition, SimVelocity};
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+/// Plans the retro-burn that captures an approaching craft into orbit around
+/// its destination, or sets it down softly, and executes it on command.
+pub struct InsertionPlugin;
+
+impl Plugin for InsertionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InsertionSettings>()
+            .add_systems(
+                FixedPostUpdate,
+                (add_insertion_assist, plan_insertions, execute_insertions)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(
+                EguiPrimaryContextPass,
+                insertion_window.run_if(AppState::in_game),
+            );
+    }
+}
+
+/// How far ahead approaches are planned, in seconds.
+const HORIZON: f64 = 60.;
+/// Integration step used when looking ahead.
+const STEP: f64 = 1. / 64.;
+/// Landing burns happen at this multiple of the destination's radius, leaving
+/// a short fall to the surface.
+const LANDING_ALTITUDE: f64 = 1.2;
+
+#[derive(Resource, Default)]
+pub struct InsertionSettings {
+    /// Commit every planned burn without waiting for the player.
+    pub auto_execute: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InsertionKind {
+    /// Circularize at periapsis.
+    Orbit,
+    /// Cancel all relative velocity just above the surface.
+    Landing,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct InsertionPlan {
+    pub kind: InsertionKind,
+    /// Seconds until the burn.
+    pub time_to_burn: f32,
+    pub delta_v: f32,
+}
+
+#[derive(Component, Default)]
+pub struct InsertionAssist {
+    pub plan: Option<InsertionPlan>,
+    /// Execute the burn when the countdown reaches zero.
+    pub committed: bool,
+}
+
+/// Looks ahead along the two-body trajectory relative to the destination for
+/// the periapsis or the landing altitude, whichever comes first.
+///
+/// Returns `None` when the craft is receding or won't arrive within [`HORIZON`].
+pub fn plan_insertion(
+    mut position: DVec3,
+    mut velocity: DVec3,
+    mu: f64,
+    radius: f64,
+) -> Option<InsertionPlan> {
+    if position.dot(velocity) >= 0. {
+        return None;
+    }
+
+    let mut time = 0.;
+    while time < HORIZON {
+        let distance = position.length();
+        if distance <= radius * LANDING_ALTITUDE {
+            return Some(InsertionPlan {
+                kind: InsertionKind::Landing,
+                time_to_burn: time as f32,
+                delta_v: velocity.length() as f32,
+            });
+        }
+        if position.dot(velocity) >= 0. {
+            return Some(InsertionPlan {
+                kind: InsertionKind::Orbit,
+                time_to_burn: time as f32,
+                delta_v: (velocity.length() - (mu / distance).sqrt()).abs() as f32,
+            });
+        }
+
+        velocity -= position * mu / distance.powi(3) * STEP;
+        position += velocity * STEP;
+        time += STEP;
+    }
+    None
+}
+
+fn add_insertion_assist(
+    mut commands: Commands,
+    crafts: Query<Entity, (With<Destination>, Without<InsertionAssist>)>,
+) {
+    for craft in &crafts {
+        commands.entity(craft).insert(InsertionAssist::default());
+    }
+}
+
+fn plan_insertions(
+    mut crafts: Query<(
+        &SimPosition,
+        &SimVelocity,
+        &Destination,
+        &mut InsertionAssist,
+    )>,
+    bodies: Query<(&SimPosition, &SimVelocity, &Mass, &Radius), With<Body>>,
+    constants: Res<PhysicsConstants>,
+    settings: Res<InsertionSettings>,
+) {
+    for (position, velocity, destination, mut assist) in &mut crafts {
+        let Ok((body_position, body_velocity, mass, radius)) = bodies.get(destination.0) else {
+            assist.plan = None;
+            continue;
+        };
+        assist.plan = plan_insertion(
+            position.0 - body_position.0,
+            velocity.0 - body_velocity.0,
+            constants.g as f64 * mass.0 as f64,
+            radius.0 as f64,
+        );
+        if assist.plan.is_none() {
+            assist.committed = false;
+        } else if settings.auto_execute {
+            assist.committed = true;
+        }
+    }
+}
+
+type CraftState = (
+    &'static Name,
+    &'static SimPosition,
+    &'static mut SimVelocity,
+    &'static mut Fuel,
+    &'static Destination,
+    &'static mut InsertionAssist,
+);
+
+type BodyState = (&'static SimPosition, &'static SimVelocity, &'static Mass);
+
+fn execute_insertions(
+    mut crafts: Query<CraftState, With<Craft>>,
+    bodies: Query<BodyState, (With<Body>, Without<Craft>)>,
+    constants: Res<PhysicsConstants>,
+    time: Res<Time>,
+) {
+    for (name, position, mut velocity, mut fuel, destination, mut assist) in &mut crafts {
+        let Some(plan) = assist.plan else {
+            continue;
+        };
+        if !assist.committed || plan.time_to_burn > time.delta_secs() {
+            continue;
+        }
+        let Ok((body_position, body_velocity, mass)) = bodies.get(destination.0) else {
+            continue;
+        };
+
+        let offset = position.0 - body_position.0;
+        let relative_velocity = velocity.0 - body_velocity.0;
+        let new_relative_velocity = match plan.kind {
+            InsertionKind::Orbit => {
+                relative_velocity.normalize_or_zero()
+                    * (constants.g as f64 * mass.0 as f64 / offset.length()).sqrt()
+            }
+            InsertionKind::Landing => DVec3::ZERO,
+        };
+        let delta_v = (new_relative_velocity - relative_velocity).length() as f32;
+        if delta_v > fuel.0 {
+            log::info!("{name} lacks the fuel for its insertion burn");
+            assist.committed = false;
+            continue;
+        }
+
+        fuel.0 -= delta_v;
+        velocity.0 = body_velocity.0 + new_relative_velocity;
+        assist.committed = false;
+        assist.plan = None;
+    }
+}
+
+fn insertion_window(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<InsertionSettings>,
+    mut crafts: Query<(&Name, &Fuel, &Destination, &mut InsertionAssist)>,
+    bodies: Query<&Name, With<Body>>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    if crafts.iter().all(|(_, _, _, assist)| assist.plan.is_none()) {
+        return;
+    }
+
+    egui::Window::new(tr("insertion"))
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-16., -40.])
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.checkbox(&mut settings.auto_execute, tr("insertion-auto-execute"));
+            for (name, fuel, destination, mut assist) in &mut crafts {
+                let Some(plan) = assist.plan else {
+                    continue;
+                };
+                let destination = bodies
+                    .get(destination.0)
+                    .map(|name| name.to_string())
+                    .unwrap_or_default();
+                let action = match plan.kind {
+                    InsertionKind::Orbit => "insertion-orbit",
+                    InsertionKind::Landing => "insertion-landing",
+                };
+
+                ui.separator();
+                ui.strong(name.to_string());
+                ui.label(tr_args(
+                    action,
+                    &[
+                        ("body", destination.into()),
+                        ("time", format_number(plan.time_to_burn as f64, 1).into()),
+                    ],
+                ));
+                ui.label(tr_args(
+                    "insertion-delta-v",
+                    &[
+                        ("delta_v", format_number(plan.delta_v as f64, 2).into()),
+                        ("fuel", format_number(fuel.0 as f64, 2).into()),
+                    ],
+                ));
+                if assist.committed {
+                    if ui.button(tr("insertion-cancel")).clicked() {
+                        assist.committed = false;
+                    }
+                } else {
+                    let affordable = plan.delta_v <= fuel.0;
+                    let commit = ui
+                        .add_enabled(affordable, egui::Button::new(tr("insertion-commit")))
+                        .on_disabled_hover_text(tr("not-enough-fuel"));
+                    if commit.clicked() {
+                        assist.committed = true;
+                    }
+                }
+            }
+        });
+}
@@ -0,0 +1,115 @@
+use crate::atmosphere::{Atmosphere, Heat};
+use crate::autopilot::Autopilot;
+use crate::bodies::{Body, Density, Radius, Spin, Sunward};
+use crate::comets::Comet;
+use crate::crafts::{Craft, Crafts, Destination, Fuel, Home};
+use crate::engines::Throttle;
+use crate::factions::Owner;
+use crate::kepler::Rails;
+use crate::physics::{Mass, PhysicsConstants, SimPosition, SimVelocity};
+use bevy::prelude::*;
+use bevy_egui::{EguiContext, EguiPrimaryContextPass, PrimaryEguiContext, egui};
+use bevy_inspector_egui::DefaultInspectorConfigPlugin;
+use bevy_inspector_egui::bevy_inspector::{ui_for_entity, ui_for_resource};
+
+/// A window, toggled with [`TOGGLE_KEY`], for reading and editing the
+/// components of every body and craft and the physics resources while the
+/// game runs. Only built with the `inspector` feature.
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<DefaultInspectorConfigPlugin>() {
+            app.add_plugins(DefaultInspectorConfigPlugin);
+        }
+        app.register_type::<Body>()
+            .register_type::<Radius>()
+            .register_type::<Density>()
+            .register_type::<Spin>()
+            .register_type::<Sunward>()
+            .register_type::<Atmosphere>()
+            .register_type::<Comet>()
+            .register_type::<SimPosition>()
+            .register_type::<SimVelocity>()
+            .register_type::<Mass>()
+            .register_type::<Rails>()
+            .register_type::<Owner>()
+            .register_type::<Crafts>()
+            .register_type::<Craft>()
+            .register_type::<Destination>()
+            .register_type::<Home>()
+            .register_type::<Fuel>()
+            .register_type::<Heat>()
+            .register_type::<Throttle>()
+            .register_type::<Autopilot>()
+            .register_type::<PhysicsConstants>()
+            .init_resource::<InspectorWindow>()
+            .add_systems(Update, toggle_inspector)
+            .add_systems(EguiPrimaryContextPass, inspector_window);
+    }
+}
+
+/// A fixed key rather than an [`crate::bindings::Action`], since the
+/// inspector is for developers and only in their builds.
+const TOGGLE_KEY: KeyCode = KeyCode::F9;
+
+/// Whether the inspector window is open.
+#[derive(Resource, Default)]
+pub struct InspectorWindow(pub bool);
+
+fn toggle_inspector(keys: Res<ButtonInput<KeyCode>>, mut open: ResMut<InspectorWindow>) {
+    if keys.just_pressed(TOGGLE_KEY) {
+        open.0 = !open.0;
+    }
+}
+
+/// Sorted by name, so the list doesn't shuffle between frames.
+fn named<F: bevy::ecs::query::QueryFilter>(world: &mut World) -> Vec<(Entity, String)> {
+    let mut entities: Vec<_> = world
+        .query_filtered::<(Entity, &Name), F>()
+        .iter(world)
+        .map(|(entity, name)| (entity, name.to_string()))
+        .collect();
+    entities.sort_by(|(_, a), (_, b)| a.cmp(b));
+    entities
+}
+
+fn inspector_window(world: &mut World) {
+    if !world.resource::<InspectorWindow>().0 {
+        return;
+    }
+    let Ok(context) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryEguiContext>>()
+        .single(world)
+    else {
+        return;
+    };
+    // Cloned so the world can be borrowed while the window is drawn
+    let mut context = context.clone();
+    let bodies = named::<With<Body>>(world);
+    let crafts = named::<With<Craft>>(world);
+
+    let mut open = true;
+    egui::Window::new("Inspector")
+        .open(&mut open)
+        .default_width(360.)
+        .show(context.get_mut(), |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.collapsing("Physics constants", |ui| {
+                    ui_for_resource::<PhysicsConstants>(world, ui);
+                });
+                for (heading, entities) in [("Bodies", &bodies), ("Crafts", &crafts)] {
+                    ui.collapsing(format!("{heading} ({})", entities.len()), |ui| {
+                        for (entity, name) in entities {
+                            egui::CollapsingHeader::new(name)
+                                .id_salt(entity)
+                                .show(ui, |ui| ui_for_entity(world, *entity, ui));
+                        }
+                    });
+                }
+            });
+        });
+    if !open {
+        world.resource_mut::<InspectorWindow>().0 = false;
+    }
+}
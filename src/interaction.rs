@@ -0,0 +1,111 @@
+use crate::app_state::AppState;
+use crate::bindings::{Action, ActionInput};
+use crate::i18n::tr;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+/// Decides what pointer gestures on the plot mean, so tools that all want
+/// clicks and drags don't fight over them.
+///
+/// [`Action::Deselect`] always returns to [`InteractionMode::Inspect`], and
+/// [`Action::Launch`] toggles [`InteractionMode::LaunchAim`].
+pub struct InteractionPlugin;
+
+impl Plugin for InteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<InteractionMode>()
+            .add_systems(Update, mode_keys)
+            .add_systems(
+                EguiPrimaryContextPass,
+                mode_toolbar.run_if(AppState::in_game),
+            );
+    }
+}
+
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InteractionMode {
+    /// Hover and click bodies to select them; drag pans the plot.
+    #[default]
+    Inspect,
+    /// Point away from the selected body to aim a launch; click to launch.
+    LaunchAim,
+    /// Click the selected craft's trajectory to place a maneuver node, and
+    /// drag its handles to set the burn.
+    Maneuver,
+    /// Clicks place the ends of a ruler instead of selecting.
+    Measure,
+    /// Drags move bodies instead of panning, or with Shift held, set their
+    /// velocity. While paused, [`InteractionMode::Inspect`] drags bodies too.
+    EditDrag,
+    /// Drags sweep out a selection box instead of panning.
+    BoxSelect,
+}
+
+impl InteractionMode {
+    pub const ALL: [Self; 6] = [
+        Self::Inspect,
+        Self::LaunchAim,
+        Self::Maneuver,
+        Self::Measure,
+        Self::EditDrag,
+        Self::BoxSelect,
+    ];
+
+    pub fn label(self) -> String {
+        tr(match self {
+            Self::Inspect => "mode-inspect",
+            Self::LaunchAim => "mode-aim-launch",
+            Self::Maneuver => "mode-plan-burn",
+            Self::Measure => "mode-measure",
+            Self::EditDrag => "mode-move",
+            Self::BoxSelect => "mode-box-select",
+        })
+    }
+
+    /// Whether dragging on the plot pans it, rather than belonging to the mode.
+    pub fn allows_pan(self) -> bool {
+        matches!(self, Self::Inspect | Self::LaunchAim | Self::Measure)
+    }
+}
+
+fn mode_keys(
+    mut actions: ActionInput,
+    mode: Res<State<InteractionMode>>,
+    mut next_mode: ResMut<NextState<InteractionMode>>,
+) {
+    let mode = *mode.get();
+    if actions.just_pressed(Action::Deselect) && mode != InteractionMode::Inspect {
+        next_mode.set(InteractionMode::Inspect);
+    } else if actions.just_pressed(Action::Launch) {
+        next_mode.set(if mode == InteractionMode::LaunchAim {
+            InteractionMode::Inspect
+        } else {
+            InteractionMode::LaunchAim
+        });
+    }
+}
+
+fn mode_toolbar(
+    mut contexts: EguiContexts,
+    mode: Res<State<InteractionMode>>,
+    mut next_mode: ResMut<NextState<InteractionMode>>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    egui::Window::new("Mode")
+        .anchor(egui::Align2::CENTER_TOP, [0., 32.])
+        .title_bar(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                for candidate in InteractionMode::ALL {
+                    let selected = *mode.get() == candidate;
+                    if ui.selectable_label(selected, candidate.label()).clicked() && !selected {
+                        next_mode.set(candidate);
+                    }
+                }
+            });
+        });
+}
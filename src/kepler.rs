@@ -0,0 +1,268 @@
+use crate::app_state::AppState;
+use crate::bodies::Body;
+use crate::orbital_mechanics::{dominant_attractor, propagate_kepler};
+use crate::physics::{
+    Mass, PhysicsConstants, Propagation, ScratchBody, SimPosition, SimVelocity, pairwise_gravity,
+};
+use crate::replay::ReplayLog;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+/// Moves bodies that are barely perturbed along exact conics around their
+/// primary, rather than integrating them, so they neither drift nor gain
+/// energy over long games.
+///
+/// Whether a body qualifies is checked every [`CHECK_INTERVAL`] ticks
+/// against [`PhysicsConstants::kepler_threshold`]; once its other pulls grow
+/// past twice that, it goes back to N-body integration. Primaries still
+/// move, and feel their satellites, as usual. Bodies with [`Rails`] stay on
+/// rails regardless.
+pub struct KeplerPlugin;
+
+impl Plugin for KeplerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (follow_conics, pin_bodies, check_perturbations)
+                .chain()
+                .in_set(Propagation)
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+/// Ticks between checks of how perturbed each body is.
+pub const CHECK_INTERVAL: u64 = 16;
+
+/// How far past the threshold a body on rails may be perturbed before it's
+/// integrated again, so bodies near it don't flip every check.
+const HYSTERESIS: f64 = 2.;
+
+/// Keeps a body on rails around whatever it orbits, however perturbed, so
+/// nothing but its primary's motion moves it. Set in the scenario or editor
+/// to keep a planetary system stable.
+#[derive(Component, Reflect, Clone, Copy, Debug, Default)]
+#[reflect(Component)]
+pub struct Rails;
+
+/// A body following a Kepler orbit around `primary` from where it was put on
+/// it.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct OnRails {
+    pub primary: Entity,
+    /// G * (M + m) of the pair.
+    mu: f64,
+    /// Position and velocity relative to the primary when put on rails.
+    position: DVec3,
+    velocity: DVec3,
+    /// Seconds since.
+    elapsed: f64,
+}
+
+impl OnRails {
+    fn new(primary: Entity, body: &ScratchBody, attractor: &ScratchBody, g: f64) -> Self {
+        Self {
+            primary,
+            mu: g * (body.mass + attractor.mass),
+            position: body.position - attractor.position,
+            velocity: body.velocity - attractor.velocity,
+            elapsed: 0.,
+        }
+    }
+}
+
+/// Bodies as [`ScratchBody`]s, for finding primaries and perturbations.
+fn scratch_bodies<'a>(
+    bodies: impl Iterator<Item = (&'a SimPosition, &'a SimVelocity, &'a Mass)>,
+) -> Vec<ScratchBody> {
+    bodies
+        .map(|(position, velocity, mass)| ScratchBody {
+            position: position.0,
+            velocity: velocity.0,
+            mass: mass.0 as f64,
+        })
+        .collect()
+}
+
+type ConicState = (
+    Entity,
+    &'static mut SimPosition,
+    &'static mut SimVelocity,
+    &'static Mass,
+    Option<&'static mut OnRails>,
+);
+
+fn follow_conics(
+    mut commands: Commands,
+    mut bodies: Query<ConicState, With<Body>>,
+    time: Res<Time>,
+) {
+    // Primaries are heavier than their satellites, so heavier bodies go first
+    // and any primary on rails is in place before its satellites follow it
+    let mut on_rails: Vec<_> = bodies
+        .iter()
+        .filter(|(.., rails)| rails.is_some())
+        .map(|(entity, _, _, mass, _)| (entity, mass.0))
+        .collect();
+    on_rails.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    let dt = time.delta_secs_f64();
+    for (entity, _) in on_rails {
+        let Ok((_, _, _, _, Some(&rails))) = bodies.get(entity) else {
+            continue;
+        };
+        let primary = bodies
+            .get(rails.primary)
+            .map(|(_, position, velocity, ..)| (position.0, velocity.0));
+        let elapsed = rails.elapsed + dt;
+        let propagated = propagate_kepler(rails.position, rails.velocity, rails.mu, elapsed);
+        let (Ok((primary_position, primary_velocity)), Some((offset, relative))) =
+            (primary, propagated)
+        else {
+            // The primary is gone, or the orbit couldn't be solved
+            commands.entity(entity).remove::<OnRails>();
+            continue;
+        };
+        let Ok((_, mut position, mut velocity, _, Some(mut rails))) = bodies.get_mut(entity) else {
+            continue;
+        };
+        rails.elapsed = elapsed;
+        position.0 = primary_position + offset;
+        velocity.0 = primary_velocity + relative;
+    }
+}
+
+type PinState = (
+    Entity,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Mass,
+    Has<Rails>,
+    Has<OnRails>,
+);
+
+/// Puts bodies with [`Rails`] on them as soon as they appear, or lose their
+/// primary.
+fn pin_bodies(
+    mut commands: Commands,
+    bodies: Query<PinState, With<Body>>,
+    constants: Res<PhysicsConstants>,
+) {
+    if !bodies
+        .iter()
+        .any(|(.., pinned, on_rails)| pinned && !on_rails)
+    {
+        return;
+    }
+    let entities: Vec<_> = bodies.iter().map(|(entity, ..)| entity).collect();
+    let scratch = scratch_bodies(
+        bodies
+            .iter()
+            .map(|(_, position, velocity, mass, ..)| (position, velocity, mass)),
+    );
+    for (index, (entity, .., pinned, on_rails)) in bodies.iter().enumerate() {
+        if !pinned || on_rails {
+            continue;
+        }
+        if let Some(primary) = dominant_attractor(index, &scratch) {
+            commands.entity(entity).insert(OnRails::new(
+                entities[primary],
+                &scratch[index],
+                &scratch[primary],
+                constants.g as f64,
+            ));
+        }
+    }
+}
+
+type FreeBodyState = (
+    Entity,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Mass,
+    Option<&'static OnRails>,
+);
+
+type BodyState = (
+    Entity,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Mass,
+);
+
+fn check_perturbations(
+    mut commands: Commands,
+    bodies: Query<FreeBodyState, (With<Body>, Without<Rails>)>,
+    pinned: Query<BodyState, (With<Body>, With<Rails>)>,
+    constants: Res<PhysicsConstants>,
+    log: Res<ReplayLog>,
+) {
+    let threshold = constants.kepler_threshold as f64;
+    if threshold <= 0. {
+        for (entity, .., rails) in &bodies {
+            if rails.is_some() {
+                commands.entity(entity).remove::<OnRails>();
+            }
+        }
+        return;
+    }
+    if log.tick() % CHECK_INTERVAL != 0 {
+        return;
+    }
+
+    let g = constants.g as f64;
+    // Pinned bodies come last, so they pull and may be primaries, but are
+    // never checked themselves
+    let entities: Vec<_> = bodies
+        .iter()
+        .map(|(entity, ..)| entity)
+        .chain(pinned.iter().map(|(entity, ..)| entity))
+        .collect();
+    let scratch = scratch_bodies(
+        bodies
+            .iter()
+            .map(|(_, position, velocity, mass, _)| (position, velocity, mass))
+            .chain(
+                pinned
+                    .iter()
+                    .map(|(_, position, velocity, mass)| (position, velocity, mass)),
+            ),
+    );
+    let masses: Vec<_> = scratch
+        .iter()
+        .map(|body| (body.position, body.mass))
+        .collect();
+    let (accelerations, _) = pairwise_gravity(&masses, constants.softening as f64, g, None);
+
+    for (index, (entity, .., rails)) in bodies.iter().enumerate() {
+        let Some(primary) = dominant_attractor(index, &scratch) else {
+            if rails.is_some() {
+                commands.entity(entity).remove::<OnRails>();
+            }
+            continue;
+        };
+        let (body, attractor) = (&scratch[index], &scratch[primary]);
+        let offset = body.position - attractor.position;
+        let mu = g * (body.mass + attractor.mass);
+        // What the pair alone would do to their separation, against what
+        // everything, softening included, actually does
+        let kepler = -offset * mu / offset.length().powi(3);
+        let actual = accelerations[index] - accelerations[primary];
+        let perturbation = (actual - kepler).length() / kepler.length();
+
+        let current = rails.filter(|rails| rails.primary == entities[primary] && rails.mu == mu);
+        let keep = perturbation < threshold
+            || (current.is_some() && perturbation <= threshold * HYSTERESIS);
+        if !keep {
+            if rails.is_some() {
+                commands.entity(entity).remove::<OnRails>();
+            }
+        } else if current.is_none() {
+            // Starting over from where it is also picks up a new primary or
+            // mass
+            commands
+                .entity(entity)
+                .insert(OnRails::new(entities[primary], body, attractor, g));
+        }
+    }
+}
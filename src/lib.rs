@@ -0,0 +1,217 @@
+use bevy::app::PluginGroupBuilder;
+use bevy::prelude::*;
+
+pub mod accessibility;
+pub mod app_state;
+pub mod assists;
+pub mod atmosphere;
+#[cfg(feature = "audio")]
+pub mod audio;
+pub mod autopilot;
+pub mod balance;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod bench;
+pub mod bindings;
+pub mod bodies;
+pub mod camera;
+pub mod campaign;
+pub mod capture;
+pub mod cli;
+pub mod collisions;
+pub mod comets;
+pub mod console;
+pub mod crafts;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod determinism;
+pub mod editor;
+pub mod engines;
+pub mod events;
+pub mod factions;
+pub mod file_drop;
+pub mod gamepad;
+#[cfg(feature = "gpu_gravity")]
+pub mod gpu_gravity;
+pub mod help;
+pub mod i18n;
+pub mod idle;
+pub mod influence;
+pub mod insertion;
+#[cfg(feature = "inspector")]
+pub mod inspector;
+pub mod interaction;
+pub mod kepler;
+pub mod logbook;
+pub mod logging;
+pub mod maneuver;
+pub mod measure;
+pub mod mods;
+#[cfg(feature = "net")]
+pub mod net;
+pub mod orbital_mechanics;
+pub mod palette;
+pub mod particles;
+pub mod persistence;
+pub mod physics;
+pub mod picker;
+pub mod production;
+pub mod profiler;
+pub mod recorder;
+#[cfg(feature = "mesh_render")]
+pub mod renderer;
+pub mod replay;
+pub mod sails;
+pub mod scenario;
+pub mod screens;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+pub mod selection;
+pub mod settings;
+pub mod spatial;
+pub mod starfield;
+pub mod stats;
+pub mod target;
+pub mod touch;
+pub mod trajectory;
+pub mod tutorial;
+pub mod ui;
+pub mod victory;
+pub mod watchdog;
+#[cfg(feature = "net")]
+mod websocket;
+pub mod wells;
+pub mod zero_velocity;
+
+/// Every Slingcraft plugin, in the order they should be added.
+///
+/// Individual plugins can be disabled to run a subset of the game. See
+/// [`SlingcraftPlugins::headless`] for running the simulation without a window:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use slingcraft::*;
+/// App::new()
+///     .add_plugins((MinimalPlugins, AssetPlugin::default()))
+///     .add_plugins(SlingcraftPlugins::headless())
+///     .run();
+/// ```
+pub struct SlingcraftPlugins;
+
+impl PluginGroup for SlingcraftPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        let group = PluginGroupBuilder::start::<Self>()
+            .add(app_state::AppStatePlugin::default())
+            .add(persistence::PersistencePlugin)
+            .add(settings::SettingsPlugin)
+            .add(bindings::BindingsPlugin)
+            .add(balance::BalancePlugin)
+            .add(physics::PhysicsPlugin)
+            .add(watchdog::WatchdogPlugin)
+            .add(kepler::KeplerPlugin)
+            .add(bodies::BodiesPlugin)
+            .add(influence::InfluencePlugin)
+            .add(collisions::CollisionsPlugin)
+            .add(atmosphere::AtmospherePlugin)
+            .add(comets::CometsPlugin)
+            .add(target::TargetPlugin)
+            .add(spatial::SpatialPlugin)
+            .add(crafts::CraftsPlugin)
+            .add(production::ProductionPlugin)
+            .add(insertion::InsertionPlugin)
+            .add(assists::AssistsPlugin)
+            .add(maneuver::ManeuverPlugin)
+            .add(autopilot::AutopilotPlugin)
+            .add(engines::EnginesPlugin)
+            .add(sails::SailsPlugin)
+            .add(factions::FactionsPlugin)
+            .add(victory::VictoryPlugin)
+            .add(stats::StatsPlugin)
+            .add(logbook::LogbookPlugin)
+            .add(logging::LoggingPlugin)
+            .add(trajectory::TrajectoryPlugin)
+            .add(particles::ParticlesPlugin)
+            .add(recorder::RecorderPlugin)
+            .add(replay::ReplayPlugin)
+            .add(interaction::InteractionPlugin)
+            .add(measure::MeasurePlugin)
+            .add(selection::SelectionPlugin)
+            .add(editor::EditorPlugin)
+            .add(idle::IdlePlugin)
+            .add(campaign::CampaignPlugin)
+            .add(tutorial::TutorialPlugin)
+            .add(screens::ScreensPlugin)
+            .add(picker::ScenarioPickerPlugin)
+            .add(mods::ModsPlugin)
+            .add(file_drop::FileDropPlugin)
+            .add(camera::PlotCameraPlugin)
+            .add(starfield::StarfieldPlugin)
+            .add(wells::GravityWellsPlugin)
+            .add(ui::UiPlugin)
+            .add(events::EventsPlugin)
+            .add(gamepad::GamepadPlugin)
+            .add(help::HelpPlugin)
+            .add(console::ConsolePlugin)
+            .add(profiler::ProfilerPlugin)
+            .add(palette::PalettePlugin)
+            .add(capture::CapturePlugin);
+        #[cfg(feature = "audio")]
+        let group = group.add(audio::GameAudioPlugin);
+        #[cfg(feature = "mesh_render")]
+        let group = group.add(renderer::RendererPlugin);
+        #[cfg(feature = "net")]
+        let group = group.add(net::NetPlugin);
+        #[cfg(feature = "gpu_gravity")]
+        let group = group.add(gpu_gravity::GpuGravityPlugin);
+        #[cfg(feature = "scripting")]
+        let group = group.add(scripting::ScriptingPlugin);
+        #[cfg(feature = "inspector")]
+        let group = group.add(inspector::InspectorPlugin);
+        group
+    }
+}
+
+impl SlingcraftPlugins {
+    /// Everything that works without a window, input or egui.
+    pub fn headless() -> PluginGroupBuilder {
+        let group = Self
+            .build()
+            .set(app_state::AppStatePlugin {
+                initial: app_state::AppState::Playing,
+            })
+            .disable::<persistence::PersistencePlugin>()
+            .disable::<settings::SettingsPlugin>()
+            .disable::<bindings::BindingsPlugin>()
+            .disable::<interaction::InteractionPlugin>()
+            .disable::<measure::MeasurePlugin>()
+            .disable::<particles::ParticlesPlugin>()
+            .disable::<selection::SelectionPlugin>()
+            .disable::<editor::EditorPlugin>()
+            .disable::<idle::IdlePlugin>()
+            .disable::<campaign::CampaignPlugin>()
+            .disable::<tutorial::TutorialPlugin>()
+            .disable::<screens::ScreensPlugin>()
+            .disable::<picker::ScenarioPickerPlugin>()
+            .disable::<mods::ModsPlugin>()
+            .disable::<file_drop::FileDropPlugin>()
+            .disable::<camera::PlotCameraPlugin>()
+            .disable::<starfield::StarfieldPlugin>()
+            .disable::<wells::GravityWellsPlugin>()
+            .disable::<ui::UiPlugin>()
+            .disable::<events::EventsPlugin>()
+            .disable::<gamepad::GamepadPlugin>()
+            .disable::<help::HelpPlugin>()
+            .disable::<console::ConsolePlugin>()
+            .disable::<profiler::ProfilerPlugin>()
+            .disable::<palette::PalettePlugin>()
+            .disable::<capture::CapturePlugin>();
+        #[cfg(feature = "audio")]
+        let group = group.disable::<audio::GameAudioPlugin>();
+        #[cfg(feature = "mesh_render")]
+        let group = group.disable::<renderer::RendererPlugin>();
+        // The lobby is part of the menu
+        #[cfg(feature = "net")]
+        let group = group.disable::<net::NetPlugin>();
+        #[cfg(feature = "inspector")]
+        let group = group.disable::<inspector::InspectorPlugin>();
+        group
+    }
+}
@@ -0,0 +1,230 @@
+use crate::app_state::AppState;
+use crate::assists::GravityAssist;
+use crate::bodies::ScenarioSystems;
+use crate::crafts::{Craft, CraftBurned, CraftCrashed, CraftDocked, CraftLaunched};
+use crate::replay::LaunchNumber;
+use crate::stats::RunStats;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use std::fmt;
+
+/// Names every craft and keeps a log of what happened to it, from launch to
+/// landing or crashing. Crafts that are gone leave their log in the [`Logbook`] for after
+/// the run.
+pub struct LogbookPlugin;
+
+impl Plugin for LogbookPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Logbook>()
+            .add_observer(archive_log)
+            .add_systems(Update, clear_logbook.in_set(ScenarioSystems::Initialize))
+            .add_systems(Update, name_crafts)
+            .add_systems(FixedLast, record_events.run_if(in_state(AppState::Playing)));
+    }
+}
+
+/// What happened to a craft, and when.
+#[derive(Component, Default, Clone, Debug)]
+pub struct CraftLog(pub Vec<LogEntry>);
+
+#[derive(Clone, Debug)]
+pub struct LogEntry {
+    /// Simulated seconds into the run, as in [`RunStats::sim_time`].
+    pub time: f64,
+    pub event: CraftEvent,
+}
+
+#[derive(Clone, Debug)]
+pub enum CraftEvent {
+    Launched { from: String },
+    Burned { delta_v: f32 },
+    Assisted { by: String, delta_v: f32 },
+    Landed { on: String },
+    Crashed { into: String, speed: f32 },
+}
+
+impl fmt::Display for CraftEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Launched { from } => write!(f, "Launched from {from}"),
+            Self::Burned { delta_v } => write!(f, "Burned {delta_v:.2}"),
+            Self::Assisted { by, delta_v } => write!(f, "Assist from {by} (+{delta_v:.2})"),
+            Self::Landed { on } => write!(f, "Landed on {on}"),
+            Self::Crashed { into, speed } => write!(f, "Crashed into {into} at {speed:.1}"),
+        }
+    }
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:>6.0}s  {}", self.time, self.event)
+    }
+}
+
+/// Logs of crafts no longer in flight, oldest first.
+#[derive(Resource, Default)]
+pub struct Logbook(pub Vec<PastCraft>);
+
+pub struct PastCraft {
+    pub name: String,
+    pub log: CraftLog,
+    /// What the craft was, for adding its landing.
+    entity: Entity,
+}
+
+const ADJECTIVES: [&str; 16] = [
+    "Bold",
+    "Swift",
+    "Quiet",
+    "Lucky",
+    "Brave",
+    "Bright",
+    "Distant",
+    "Eager",
+    "Fleet",
+    "Gentle",
+    "Hardy",
+    "Keen",
+    "Lone",
+    "Nimble",
+    "Restless",
+    "Wandering",
+];
+
+const NOUNS: [&str; 16] = [
+    "Heron", "Comet", "Pebble", "Falcon", "Lantern", "Otter", "Sparrow", "Arrow", "Ember",
+    "Kestrel", "Marble", "Pilgrim", "Drifter", "Moth", "Finch", "Voyager",
+];
+
+/// A name for the craft launched `number`th in a run. The first 256 are all
+/// different, and later ones get a number.
+pub fn craft_name(number: u32) -> String {
+    let combinations = (ADJECTIVES.len() * NOUNS.len()) as u32;
+    // Stepping by a number coprime to the count visits every combination
+    // before repeating, without neighbors sounding alike
+    let index = (number.wrapping_mul(167).wrapping_add(13) % combinations) as usize;
+    let name = format!(
+        "{} {}",
+        ADJECTIVES[index / NOUNS.len()],
+        NOUNS[index % NOUNS.len()]
+    );
+    match number / combinations {
+        0 => name,
+        round => format!("{name} {}", round + 1),
+    }
+}
+
+/// Names crafts once they're numbered, so replays name them alike.
+fn name_crafts(mut crafts: Query<(&LaunchNumber, &mut Name), Added<LaunchNumber>>) {
+    for (number, mut name) in &mut crafts {
+        *name = Name::new(craft_name(number.0));
+    }
+}
+
+fn clear_logbook(mut logbook: ResMut<Logbook>) {
+    logbook.0.clear();
+}
+
+/// Everything that happens to a craft that goes in its log.
+#[derive(SystemParam)]
+struct CraftEvents<'w, 's> {
+    launches: EventReader<'w, 's, CraftLaunched>,
+    burns: EventReader<'w, 's, CraftBurned>,
+    assists: EventReader<'w, 's, GravityAssist>,
+    landings: EventReader<'w, 's, CraftDocked>,
+    crashes: EventReader<'w, 's, CraftCrashed>,
+}
+
+fn record_events(
+    mut events: CraftEvents,
+    mut crafts: Query<&mut CraftLog>,
+    names: Query<&Name>,
+    mut logbook: ResMut<Logbook>,
+    stats: Res<RunStats>,
+) {
+    let name = |entity: Entity| {
+        names
+            .get(entity)
+            .map_or_else(|_| "somewhere".to_string(), ToString::to_string)
+    };
+    let mut record = |craft: Entity, event: CraftEvent| {
+        if let Ok(mut log) = crafts.get_mut(craft) {
+            log.0.push(LogEntry {
+                time: stats.sim_time,
+                event,
+            });
+        }
+    };
+    for launch in events.launches.read() {
+        record(
+            launch.craft,
+            CraftEvent::Launched {
+                from: name(launch.from),
+            },
+        );
+    }
+    for burn in events.burns.read() {
+        record(
+            burn.craft,
+            CraftEvent::Burned {
+                delta_v: burn.delta_v,
+            },
+        );
+    }
+    for assist in events.assists.read() {
+        record(
+            assist.craft,
+            CraftEvent::Assisted {
+                by: name(assist.body),
+                delta_v: assist.delta_v,
+            },
+        );
+    }
+    // Crafts that landed or crashed are gone, and their logs with them into
+    // the logbook
+    let endings = events
+        .landings
+        .read()
+        .map(|landing| {
+            (
+                landing.craft,
+                CraftEvent::Landed {
+                    on: name(landing.body),
+                },
+            )
+        })
+        .chain(events.crashes.read().map(|crash| {
+            (
+                crash.craft,
+                CraftEvent::Crashed {
+                    into: name(crash.body),
+                    speed: crash.speed,
+                },
+            )
+        }));
+    for (craft, event) in endings {
+        let past = logbook.0.iter_mut().rev().find(|past| past.entity == craft);
+        if let Some(past) = past {
+            past.log.0.push(LogEntry {
+                time: stats.sim_time,
+                event,
+            });
+        }
+    }
+}
+
+/// Keeps the log of a craft that landed, burned up or was destroyed.
+fn archive_log(
+    trigger: Trigger<OnRemove, CraftLog>,
+    crafts: Query<(&Name, &CraftLog), With<Craft>>,
+    mut logbook: ResMut<Logbook>,
+) {
+    let entity = trigger.target();
+    if let Ok((name, log)) = crafts.get(entity) {
+        logbook.0.push(PastCraft {
+            name: name.to_string(),
+            log: log.clone(),
+            entity,
+        });
+    }
+}
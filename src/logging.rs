@@ -0,0 +1,149 @@
+use crate::app_state::AppState;
+use crate::collisions::{BodyDestroyed, Destruction};
+use crate::crafts::{BodyCaptured, CraftCrashed, CraftLaunched, CraftsLost};
+use crate::persistence::state_directory;
+use crate::physics::{Mass, TotalEnergy};
+use bevy::log::{BoxedLayer, info, warn};
+use bevy::prelude::*;
+use std::path::PathBuf;
+
+/// Logs what happens in the simulation, and warns when energy jumps. The
+/// [`crate::watchdog`] logs anything that blows up. With [`file_layer`]
+/// installed, logs also go to a file in [`log_directory`] to attach to bug
+/// reports.
+pub struct LoggingPlugin;
+
+impl Plugin for LoggingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedPostUpdate,
+            watch_energy.run_if(in_state(AppState::Playing)),
+        )
+        .add_systems(Update, log_events);
+    }
+}
+
+/// Relative change in total energy over one tick that counts as a spike,
+/// while no bodies come or go.
+const ENERGY_SPIKE: f64 = 0.05;
+/// Days of logs kept.
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_LOG_FILES: usize = 7;
+
+/// Next to the persisted state, in the data directory.
+pub fn log_directory() -> PathBuf {
+    let state = state_directory();
+    state.parent().unwrap_or(&state).join("logs")
+}
+
+/// Keeps the log file writer running for as long as the app does.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Resource)]
+struct LogFileGuard(#[allow(dead_code)] tracing_appender::non_blocking::WorkerGuard);
+
+/// For [`bevy::log::LogPlugin::custom_layer`]: also writes everything logged
+/// to a file in [`log_directory`], starting a new one each day and keeping a
+/// week of them. Browsers have nowhere to put one.
+pub fn file_layer(app: &mut App) -> Option<BoxedLayer> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = app;
+        None
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use bevy::log::tracing_subscriber::{Layer, fmt};
+        use tracing_appender::rolling::{Builder, Rotation};
+
+        let appender = Builder::new()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("slingcraft")
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(log_directory())
+            // Logging isn't up yet to report it
+            .inspect_err(|err| eprintln!("failed to open log file: {err}"))
+            .ok()?;
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+        app.insert_resource(LogFileGuard(guard));
+        Some(fmt::layer().with_ansi(false).with_writer(writer).boxed())
+    }
+}
+
+/// Shows the log directory in the system's file manager.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn open_log_directory() {
+    let directory = log_directory();
+    let opener = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    let opened = std::fs::create_dir_all(&directory)
+        .and_then(|()| std::process::Command::new(opener).arg(&directory).spawn());
+    if let Err(err) = opened {
+        log::error!("failed to open {}: {err}", directory.display());
+    }
+}
+
+/// Warns when total energy jumps between ticks without bodies coming or
+/// going, a sign the integrator is struggling.
+fn watch_energy(
+    energy: Res<TotalEnergy>,
+    bodies: Query<(), With<Mass>>,
+    mut previous: Local<Option<(f64, usize)>>,
+) {
+    let count = bodies.iter().count();
+    if let Some((before, before_count)) = previous.replace((energy.0, count))
+        && before_count == count
+        && before != 0.
+    {
+        let change = (energy.0 - before) / before.abs();
+        if change.abs() > ENERGY_SPIKE {
+            warn!(before, after = energy.0, change, "total energy spiked");
+        }
+    }
+}
+
+/// Launches, crashes, captures, combat losses and bodies destroyed, by name.
+fn log_events(
+    names: Query<&Name>,
+    mut launches: EventReader<CraftLaunched>,
+    mut crashes: EventReader<CraftCrashed>,
+    mut captures: EventReader<BodyCaptured>,
+    mut losses: EventReader<CraftsLost>,
+    mut destroyed: EventReader<BodyDestroyed>,
+) {
+    let name = |entity: Entity| names.get(entity).map_or("?", Name::as_str);
+    for launch in launches.read() {
+        info!(
+            craft = name(launch.craft),
+            from = name(launch.from),
+            "craft launched"
+        );
+    }
+    for crash in crashes.read() {
+        info!(
+            craft = name(crash.craft),
+            body = name(crash.body),
+            speed = crash.speed,
+            "craft crashed"
+        );
+    }
+    for capture in captures.read() {
+        info!(body = name(capture.body), by = ?capture.by, "body captured");
+    }
+    for loss in losses.read() {
+        info!(faction = ?loss.faction, count = loss.count, "crafts lost");
+    }
+    for body in destroyed.read() {
+        let (cause, other) = match body.cause {
+            Destruction::TornApart { primary } => ("torn apart", primary),
+            Destruction::Shattered { against } => ("shattered", against),
+            Destruction::Merged { into } => ("merged", into),
+        };
+        info!(body = %body.name, cause, other = name(other), "body destroyed");
+    }
+}
@@ -0,0 +1,119 @@
+use crate::app_state::AppState;
+use crate::bodies::Body;
+use crate::crafts::{Craft, Fuel};
+use crate::orbital_mechanics::strongest_attractor;
+use crate::physics::{Mass, ScratchBody, SimPosition, SimVelocity};
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+/// Fires planned burns when crafts reach their maneuver nodes.
+pub struct ManeuverPlugin;
+
+impl Plugin for ManeuverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedPostUpdate,
+            execute_maneuvers.run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+/// A burn planned for later, in the frame of the body pulling hardest on
+/// the craft when it fires.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct ManeuverNode {
+    /// Simulation time of the burn, in seconds on the fixed clock.
+    pub time: f64,
+    pub prograde: f32,
+    /// Positive away from the body.
+    pub radial: f32,
+}
+
+impl ManeuverNode {
+    pub fn delta_v(&self) -> f32 {
+        self.prograde.hypot(self.radial)
+    }
+}
+
+/// Burn directions for a craft at some instant.
+#[derive(Clone, Copy, Debug)]
+pub struct BurnFrame {
+    /// Along the velocity relative to the dominant body.
+    pub prograde: DVec3,
+    /// Perpendicular to prograde, away from the dominant body.
+    pub radial: DVec3,
+}
+
+impl BurnFrame {
+    /// The frame relative to whichever of `bodies` pulls hardest on
+    /// `position`, or `None` if the craft is at rest relative to it.
+    pub fn new(position: DVec3, velocity: DVec3, bodies: &[ScratchBody]) -> Option<Self> {
+        let dominant = &bodies[strongest_attractor(position, bodies)?];
+        let prograde = (velocity - dominant.velocity).try_normalize()?;
+        let perpendicular = DVec3::new(-prograde.y, prograde.x, 0.);
+        let radial = if perpendicular.dot(position - dominant.position) >= 0. {
+            perpendicular
+        } else {
+            -perpendicular
+        };
+        Some(Self { prograde, radial })
+    }
+
+    pub fn delta_v(&self, node: &ManeuverNode) -> DVec3 {
+        self.prograde * node.prograde as f64 + self.radial * node.radial as f64
+    }
+}
+
+/// Bodies as [`ScratchBody`]s, for computing burn frames and predictions.
+pub fn scratch_bodies<'a>(
+    bodies: impl IntoIterator<Item = (&'a SimPosition, &'a SimVelocity, &'a Mass)>,
+) -> Vec<ScratchBody> {
+    bodies
+        .into_iter()
+        .map(|(position, velocity, mass)| ScratchBody {
+            position: position.0,
+            velocity: velocity.0,
+            mass: mass.0 as f64,
+        })
+        .collect()
+}
+
+type BodyState = (&'static SimPosition, &'static SimVelocity, &'static Mass);
+
+type CraftState = (
+    Entity,
+    &'static Name,
+    &'static SimPosition,
+    &'static mut SimVelocity,
+    &'static mut Fuel,
+    &'static ManeuverNode,
+);
+
+fn execute_maneuvers(
+    mut commands: Commands,
+    time: Res<Time>,
+    bodies: Query<BodyState, (With<Body>, Without<Craft>)>,
+    mut crafts: Query<CraftState, With<Craft>>,
+) {
+    let mut scratch = None;
+    for (craft, name, position, mut velocity, mut fuel, node) in &mut crafts {
+        if time.elapsed_secs_f64() < node.time {
+            continue;
+        }
+        commands.entity(craft).remove::<ManeuverNode>();
+
+        let scratch = scratch.get_or_insert_with(|| scratch_bodies(bodies.iter()));
+        let Some(frame) = BurnFrame::new(position.0, velocity.0, scratch) else {
+            log::info!("{name} has no frame for its maneuver burn");
+            continue;
+        };
+        let delta_v = node.delta_v();
+        if delta_v > fuel.0 {
+            log::info!("{name} lacks the fuel for its maneuver burn");
+            continue;
+        }
+        fuel.0 -= delta_v;
+        velocity.0 += frame.delta_v(node);
+        log::info!("{name} executed a {delta_v:.2} maneuver burn");
+    }
+}
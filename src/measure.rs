@@ -0,0 +1,111 @@
+use crate::crafts::Craft;
+use crate::interaction::InteractionMode;
+use crate::physics::{SimPosition, SimVelocity};
+use bevy::ecs::system::SystemParam;
+use bevy::math::{DVec2, DVec3};
+use bevy::prelude::*;
+
+/// The ruler of [`InteractionMode::Measure`]: two clicks on the plot, each on
+/// empty space or on a body or craft to follow, measure between them.
+pub struct MeasurePlugin;
+
+impl Plugin for MeasurePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Measurement>()
+            .add_systems(OnExit(InteractionMode::Measure), forget_measurement);
+    }
+}
+
+/// One end of a measurement.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MeasureEnd {
+    /// A fixed spot in space.
+    Point(DVec2),
+    /// A body or craft, followed as it moves.
+    Entity(Entity),
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct Measurement {
+    pub start: Option<MeasureEnd>,
+    pub end: Option<MeasureEnd>,
+}
+
+impl Measurement {
+    /// Places the next end, starting over once both are placed.
+    pub fn place(&mut self, end: MeasureEnd) {
+        if self.start.is_none() || self.end.is_some() {
+            self.start = Some(end);
+            self.end = None;
+        } else {
+            self.end = Some(end);
+        }
+    }
+}
+
+/// What a measurement found between its ends.
+pub struct Measured {
+    pub from: DVec3,
+    pub to: DVec3,
+    pub distance: f64,
+    /// How fast the ends are closing (negative) or separating (positive).
+    pub closing_speed: f64,
+    /// Speed of one end relative to the other, in any direction.
+    pub relative_speed: f64,
+    /// Time to cover the distance at the speed crafts are flying, if any are.
+    pub travel_time: Option<f64>,
+}
+
+/// The measurement, and where its ends and the crafts are.
+#[derive(SystemParam)]
+pub struct MeasureTool<'w, 's> {
+    pub measurement: ResMut<'w, Measurement>,
+    motions: Query<'w, 's, (&'static SimPosition, &'static SimVelocity)>,
+    crafts: Query<'w, 's, (Entity, &'static SimVelocity), With<Craft>>,
+}
+
+impl MeasureTool<'_, '_> {
+    /// Where an end is and how fast it's moving, if it still exists.
+    pub fn locate(&self, end: MeasureEnd) -> Option<(DVec3, DVec3)> {
+        match end {
+            MeasureEnd::Point(point) => Some((point.extend(0.), DVec3::ZERO)),
+            MeasureEnd::Entity(entity) => self
+                .motions
+                .get(entity)
+                .ok()
+                .map(|(position, velocity)| (position.0, velocity.0)),
+        }
+    }
+
+    /// Measures between both ends, with travel time at the selected craft's
+    /// speed, or else the average speed of all crafts in flight.
+    pub fn measure(&self, selected_craft: Option<Entity>) -> Option<Measured> {
+        let (from, from_velocity) = self.locate(self.measurement.start?)?;
+        let (to, to_velocity) = self.locate(self.measurement.end?)?;
+        let offset = to - from;
+        let relative = to_velocity - from_velocity;
+        let distance = offset.length();
+
+        let craft_speed = match selected_craft.and_then(|craft| self.crafts.get(craft).ok()) {
+            Some((_, velocity)) => velocity.0.length(),
+            None => {
+                let count = self.crafts.iter().len();
+                let total: f64 = self.crafts.iter().map(|(_, v)| v.0.length()).sum();
+                if count == 0 { 0. } else { total / count as f64 }
+            }
+        };
+
+        Some(Measured {
+            from,
+            to,
+            distance,
+            closing_speed: relative.dot(offset.normalize_or_zero()),
+            relative_speed: relative.length(),
+            travel_time: (craft_speed > 0.).then(|| distance / craft_speed),
+        })
+    }
+}
+
+fn forget_measurement(mut measurement: ResMut<Measurement>) {
+    *measurement = Measurement::default();
+}
@@ -0,0 +1,272 @@
+use crate::app_state::AppState;
+use crate::i18n::{tr, tr_args};
+use crate::scenario::{Scenario, ScenarioError};
+use crate::settings::Settings;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use bevy_persistent::prelude::*;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Finds content packs in the `mods` folder next to `assets` and lets players
+/// turn them on and off. Enabled packs' scenarios show up in the scenario
+/// picker.
+///
+/// A pack is a directory with an optional `mod.toml` describing it, scenario
+/// files in `scenarios/`, Rhai scripts in `scripts/` and any images its
+/// scenarios use as textures:
+///
+/// ```text
+/// mods/comets/
+///     mod.toml
+///     scenarios/shower.toml
+///     scripts/shower.rhai
+///     textures/comet.png
+/// ```
+///
+/// A scenario without a `script` of its own runs the script with the same
+/// name, if there is one. Textures are looked up in the pack.
+pub struct ModsPlugin;
+
+impl Plugin for ModsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ModsWindow>()
+            .add_systems(Startup, scan_mods)
+            .add_systems(
+                EguiPrimaryContextPass,
+                mods_window.run_if(AppState::in_game),
+            );
+    }
+}
+
+/// Name of the asset source that reads from the `mods` folder, as in
+/// `mods://comets/textures/comet.png`.
+pub const ASSET_SOURCE: &str = "mods";
+
+/// What a pack's `mod.toml` says about it. Everything is optional.
+#[derive(Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct ModManifest {
+    /// Shown instead of the directory name.
+    pub name: Option<String>,
+    pub description: String,
+    pub author: String,
+    pub version: String,
+}
+
+/// A pack found in the `mods` folder.
+#[derive(Clone, Debug)]
+pub struct ModPack {
+    /// The directory name, which is what settings remember it by.
+    pub id: String,
+    pub manifest: ModManifest,
+    pub directory: PathBuf,
+    /// Why `mod.toml` couldn't be read, if it couldn't.
+    pub error: Option<String>,
+}
+
+impl ModPack {
+    fn new(directory: PathBuf) -> Self {
+        let id = directory
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let (manifest, error) = match std::fs::read_to_string(directory.join("mod.toml")) {
+            Ok(text) => match toml::from_str(&text) {
+                Ok(manifest) => (manifest, None),
+                Err(err) => (ModManifest::default(), Some(err.to_string())),
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                (ModManifest::default(), None)
+            }
+            Err(err) => (ModManifest::default(), Some(err.to_string())),
+        };
+        Self {
+            id,
+            manifest,
+            directory,
+            error,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.manifest.name.as_deref().unwrap_or(&self.id)
+    }
+
+    /// The pack's scenario files, sorted.
+    pub fn scenario_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<_> = std::fs::read_dir(self.directory.join("scenarios"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "toml")
+            })
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// Reads one of the pack's scenarios, with its script and textures
+    /// resolved against the pack.
+    pub fn load_scenario(&self, path: &Path) -> Result<Scenario, ScenarioError> {
+        let mut scenario = Scenario::load(path)?;
+        if scenario.script.is_none()
+            && let Some(stem) = path.file_stem()
+        {
+            let script = self
+                .directory
+                .join("scripts")
+                .join(stem)
+                .with_extension("rhai");
+            match std::fs::read_to_string(script) {
+                Ok(source) => scenario.script = Some(source),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        for body in &mut scenario.bodies {
+            let Some(texture) = body
+                .appearance
+                .as_mut()
+                .and_then(|appearance| appearance.texture.as_mut())
+            else {
+                continue;
+            };
+            if !texture.contains("://") {
+                *texture = format!("{ASSET_SOURCE}://{}/{texture}", self.id);
+            }
+        }
+        Ok(scenario)
+    }
+}
+
+/// Every pack in the `mods` folder, enabled or not.
+#[derive(Resource, Default)]
+pub struct ModPacks(pub Vec<ModPack>);
+
+impl ModPacks {
+    /// The packs the player hasn't turned off.
+    pub fn enabled<'a>(&'a self, settings: &'a Settings) -> impl Iterator<Item = &'a ModPack> {
+        self.0
+            .iter()
+            .filter(|pack| !settings.disabled_mods.contains(&pack.id))
+    }
+}
+
+/// Where packs live. Web builds have no folder to scan.
+pub fn mods_directory() -> PathBuf {
+    #[cfg(target_arch = "wasm32")]
+    let base = PathBuf::new();
+    #[cfg(not(target_arch = "wasm32"))]
+    let base = bevy::asset::io::file::FileAssetReader::get_base_path();
+    base.join(ASSET_SOURCE)
+}
+
+/// Every directory in the `mods` folder, by name.
+fn find_packs() -> Vec<ModPack> {
+    #[cfg(target_arch = "wasm32")]
+    return Vec::new();
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let mut directories: Vec<_> = std::fs::read_dir(mods_directory())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        directories.sort();
+        directories.into_iter().map(ModPack::new).collect()
+    }
+}
+
+fn scan_mods(mut commands: Commands) {
+    let packs = find_packs();
+    if !packs.is_empty() {
+        info!("found {} mod packs", packs.len());
+    }
+    commands.insert_resource(ModPacks(packs));
+}
+
+/// Whether the mods window is open.
+#[derive(Resource, Default)]
+pub struct ModsWindow(pub bool);
+
+fn mods_window(
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+    mut open: ResMut<ModsWindow>,
+    packs: Res<ModPacks>,
+    mut settings: ResMut<Persistent<Settings>>,
+) {
+    if !open.0 {
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    let mut edited = settings.get().clone();
+    let mut rescan = false;
+    egui::Window::new(tr("mods-title"))
+        .open(&mut open.0)
+        .resizable(false)
+        .show(ctx, |ui| {
+            if packs.0.is_empty() {
+                ui.label(tr_args(
+                    "mods-none",
+                    &[("path", mods_directory().display().to_string().into())],
+                ));
+            }
+            egui::Grid::new("mods").striped(true).show(ui, |ui| {
+                for pack in &packs.0 {
+                    let mut enabled = !edited.disabled_mods.contains(&pack.id);
+                    let checkbox = ui.checkbox(&mut enabled, pack.name());
+                    if checkbox.changed() {
+                        if enabled {
+                            edited.disabled_mods.retain(|id| *id != pack.id);
+                        } else {
+                            edited.disabled_mods.push(pack.id.clone());
+                        }
+                    }
+                    let ModManifest {
+                        version, author, ..
+                    } = &pack.manifest;
+                    ui.label(match (version.is_empty(), author.is_empty()) {
+                        (_, true) => version.clone(),
+                        (true, false) => tr_args("mods-by", &[("author", author.as_str().into())]),
+                        (false, false) => tr_args(
+                            "mods-version-by",
+                            &[
+                                ("version", version.as_str().into()),
+                                ("author", author.as_str().into()),
+                            ],
+                        ),
+                    });
+                    match &pack.error {
+                        Some(err) => {
+                            ui.colored_label(ui.visuals().error_fg_color, tr("mods-invalid"))
+                                .on_hover_text(err);
+                        }
+                        None => {
+                            ui.label(&pack.manifest.description);
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+            rescan = ui.button(tr("mods-rescan")).clicked();
+        });
+
+    if rescan {
+        commands.insert_resource(ModPacks(find_packs()));
+    }
+    if edited != *settings.get()
+        && let Err(err) = settings.set(edited)
+    {
+        log::error!("failed to save settings: {err}");
+    }
+}
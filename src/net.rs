@@ -0,0 +1,717 @@
+use crate::app_state::{AppState, GameOutcome};
+use crate::bodies::Body;
+use crate::crafts::{BurnCraft, Craft, Crafts, Destination, Fuel, LaunchCraft};
+use crate::factions::{Faction, Owner, Players};
+use crate::i18n::{tr, tr_args};
+use crate::physics::{SimPosition, SimVelocity};
+use crate::scenario::Scenario;
+use crate::websocket::{SocketError, WebSocket};
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use std::io;
+#[cfg(not(target_arch = "wasm32"))]
+use std::net::TcpListener;
+
+/// Two-player online matches. The host runs the simulation and streams
+/// snapshots of it to the player who joins, who flies the opponent's side:
+/// their launches and burns go to the host, and their plot mirrors whatever
+/// comes back.
+pub struct NetPlugin;
+
+impl Plugin for NetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Lobby>()
+            .add_event::<Received>()
+            .add_systems(
+                EguiPrimaryContextPass,
+                lobby_window.run_if(in_state(AppState::Menu)),
+            )
+            // Before the crafts plugin acts on this frame's orders
+            .add_systems(
+                PreUpdate,
+                (
+                    #[cfg(not(target_arch = "wasm32"))]
+                    accept_players,
+                    receive_messages,
+                    (
+                        start_matches,
+                        apply_snapshots,
+                        finish_matches,
+                        forward_orders,
+                        take_orders,
+                    ),
+                )
+                    .chain(),
+            )
+            .add_systems(Update, send_snapshots)
+            .add_systems(OnEnter(AppState::GameOver), announce_outcome)
+            .add_systems(OnEnter(AppState::Menu), leave_match);
+    }
+}
+
+pub const DEFAULT_PORT: u16 = 7777;
+/// Bumped whenever [`Message`] changes, so mismatched builds refuse to play.
+const PROTOCOL_VERSION: u32 = 2;
+/// Snapshots the host sends per second.
+const SNAPSHOT_RATE: f32 = 10.;
+
+/// Everything sent between host and client, one JSON object per line.
+#[derive(Serialize, Deserialize, Debug)]
+enum Message {
+    Hello {
+        version: u32,
+    },
+    /// From the host: the match is on, in this scenario.
+    Start(Scenario),
+    /// From the client, naming bodies since entities differ between machines.
+    Launch {
+        from: String,
+        destination: Option<String>,
+        direction: [f32; 2],
+        speed: f32,
+    },
+    /// From the client, for a craft the host told it about.
+    Burn {
+        craft: u64,
+        delta_v: [f32; 2],
+    },
+    Snapshot(Snapshot),
+    /// From the host once the match is over: how it went for the client, as
+    /// message ids so it reads in the client's language.
+    Over {
+        won: bool,
+        title: String,
+        details: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Snapshot {
+    bodies: Vec<BodyState>,
+    crafts: Vec<CraftState>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct BodyState {
+    name: String,
+    position: [f64; 2],
+    velocity: [f64; 2],
+    crafts: u32,
+    owner: Option<Faction>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CraftState {
+    /// The craft's entity on the host.
+    id: u64,
+    name: String,
+    class: String,
+    position: [f64; 2],
+    velocity: [f64; 2],
+    fuel: f32,
+    owner: Option<Faction>,
+    destination: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum NetError {
+    #[error(transparent)]
+    Socket(#[from] SocketError),
+    #[error("malformed message: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("the other side speaks protocol {0}, not {PROTOCOL_VERSION}")]
+    Version(u32),
+}
+
+/// A WebSocket carrying [`Message`]s, one per text frame.
+struct Connection {
+    socket: WebSocket,
+}
+
+impl Connection {
+    fn new(socket: WebSocket) -> Self {
+        let mut connection = Self { socket };
+        connection.send(&Message::Hello {
+            version: PROTOCOL_VERSION,
+        });
+        connection
+    }
+
+    /// Queues a message for the next [`Connection::poll`]. A broken socket
+    /// shows up there, so failures here are only logged.
+    fn send(&mut self, message: &Message) {
+        let text = serde_json::to_string(message).expect("messages always serialize");
+        if let Err(err) = self.socket.send(text) {
+            log::warn!("Couldn't send to the other side: {err}");
+        }
+    }
+
+    /// Sends whatever the socket will take and returns every message that has
+    /// arrived.
+    fn poll(&mut self) -> Result<Vec<Message>, NetError> {
+        let mut messages = Vec::new();
+        for text in self.socket.receive()? {
+            match serde_json::from_str(&text)? {
+                Message::Hello { version } if version != PROTOCOL_VERSION => {
+                    return Err(NetError::Version(version));
+                }
+                Message::Hello { .. } => {}
+                message => messages.push(message),
+            }
+        }
+        Ok(messages)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// Runs the simulation and plays [`Faction::Player`].
+    Host,
+    /// Mirrors the host and plays [`Faction::Opponent`].
+    Client,
+}
+
+/// A match in progress, or a client waiting for the host to start one.
+#[derive(Resource)]
+pub struct NetSession {
+    pub role: Role,
+    connection: Connection,
+    snapshots: Timer,
+}
+
+impl NetSession {
+    fn new(role: Role, connection: Connection) -> Self {
+        Self {
+            role,
+            connection,
+            snapshots: Timer::from_seconds(1. / SNAPSHOT_RATE, TimerMode::Repeating),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct Lobby {
+    address: String,
+    /// Waiting for someone to join. Browsers can't listen for connections.
+    #[cfg(not(target_arch = "wasm32"))]
+    listener: Option<TcpListener>,
+    /// What came of the last attempt to host or join.
+    status: String,
+}
+
+impl Default for Lobby {
+    fn default() -> Self {
+        Self {
+            address: format!("127.0.0.1:{DEFAULT_PORT}"),
+            #[cfg(not(target_arch = "wasm32"))]
+            listener: None,
+            status: String::new(),
+        }
+    }
+}
+
+/// A message from the other side, for the systems that act on it.
+#[derive(Event)]
+struct Received(Message);
+
+/// Crafts on a client, by their entity on the host.
+#[derive(Component)]
+struct Mirrored(u64);
+
+fn lobby_window(
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+    mut lobby: ResMut<Lobby>,
+    session: Option<Res<NetSession>>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    egui::Window::new(tr("net-online-match"))
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-16., -40.])
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            #[cfg(not(target_arch = "wasm32"))]
+            let hosting = lobby.listener.is_some();
+            #[cfg(target_arch = "wasm32")]
+            let hosting = false;
+            let idle = !hosting && session.is_none();
+            ui.add_enabled(idle, egui::TextEdit::singleline(&mut lobby.address))
+                .on_hover_text(tr("net-address-hint"));
+            ui.horizontal(|ui| {
+                if !idle {
+                    if ui.button(tr("cancel")).clicked() {
+                        #[cfg(not(target_arch = "wasm32"))]
+                        {
+                            lobby.listener = None;
+                        }
+                        commands.remove_resource::<NetSession>();
+                        lobby.status.clear();
+                    }
+                    return;
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if ui.button(tr("net-host")).clicked() {
+                    match host(&lobby.address) {
+                        Ok(listener) => {
+                            lobby.status = tr_args(
+                                "net-waiting-for-player",
+                                &[("address", lobby.address.as_str().into())],
+                            );
+                            lobby.listener = Some(listener);
+                        }
+                        Err(err) => {
+                            lobby.status =
+                                tr_args("net-host-failed", &[("error", err.to_string().into())])
+                        }
+                    }
+                }
+                if ui.button(tr("net-join")).clicked() {
+                    match join(&lobby.address) {
+                        Ok(connection) => {
+                            lobby.status = tr("net-waiting-for-host");
+                            commands.insert_resource(NetSession::new(Role::Client, connection));
+                        }
+                        Err(err) => {
+                            lobby.status =
+                                tr_args("net-join-failed", &[("error", err.to_string().into())])
+                        }
+                    }
+                }
+            });
+            if !lobby.status.is_empty() {
+                ui.label(&lobby.status);
+            }
+        });
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn host(address: &str) -> io::Result<TcpListener> {
+    let listener = TcpListener::bind(address)?;
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+fn join(address: &str) -> Result<Connection, SocketError> {
+    Ok(Connection::new(WebSocket::connect(address)?))
+}
+
+/// Starts a match in the current scenario as soon as someone joins.
+#[cfg(not(target_arch = "wasm32"))]
+fn accept_players(
+    mut commands: Commands,
+    mut lobby: ResMut<Lobby>,
+    scenario: Res<Scenario>,
+    mut players: ResMut<Players>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Some(listener) = &lobby.listener else {
+        return;
+    };
+    let (stream, address) = match listener.accept() {
+        Ok(accepted) => accepted,
+        Err(err) if err.kind() == io::ErrorKind::WouldBlock => return,
+        Err(err) => {
+            lobby.status = tr_args("net-stopped-hosting", &[("error", err.to_string().into())]);
+            lobby.listener = None;
+            return;
+        }
+    };
+    // Someone who can't finish the handshake doesn't end the wait
+    let mut connection = match WebSocket::accept(stream) {
+        Ok(socket) => Connection::new(socket),
+        Err(err) => {
+            log::warn!("Couldn't accept {address}: {err}");
+            return;
+        }
+    };
+    log::info!("{address} joined");
+    lobby.listener = None;
+    lobby.status.clear();
+    connection.send(&Message::Start(scenario.clone()));
+    commands.insert_resource(NetSession::new(Role::Host, connection));
+    // Reloading starts everyone from the same state
+    commands.insert_resource(scenario.clone());
+    *players = Players {
+        online: true,
+        ..default()
+    };
+    next_state.set(AppState::Playing);
+}
+
+fn receive_messages(
+    mut commands: Commands,
+    session: Option<ResMut<NetSession>>,
+    mut received: EventWriter<Received>,
+    mut lobby: ResMut<Lobby>,
+    state: Res<State<AppState>>,
+    mut outcome: ResMut<GameOutcome>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Some(mut session) = session else {
+        return;
+    };
+    match session.connection.poll() {
+        Ok(messages) => {
+            received.write_batch(messages.into_iter().map(Received));
+        }
+        Err(err) => {
+            log::warn!("Left the online match: {err}");
+            commands.remove_resource::<NetSession>();
+            if AppState::in_game(Some(state)) {
+                *outcome = GameOutcome {
+                    won: false,
+                    title: tr("net-connection-lost"),
+                    details: vec![err.to_string()],
+                };
+                next_state.set(AppState::GameOver);
+            } else {
+                lobby.status = tr_args("net-disconnected", &[("error", err.to_string().into())]);
+            }
+        }
+    }
+}
+
+fn start_matches(
+    mut commands: Commands,
+    mut received: EventReader<Received>,
+    mut players: ResMut<Players>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for Received(message) in received.read() {
+        if let Message::Start(scenario) = message {
+            commands.insert_resource(scenario.clone());
+            *players = Players {
+                online: true,
+                remote: true,
+                active: Faction::Opponent,
+                ..default()
+            };
+            next_state.set(AppState::Playing);
+        }
+    }
+}
+
+/// Sends the client's own launches and burns to the host rather than acting
+/// on them here.
+fn forward_orders(
+    session: Option<ResMut<NetSession>>,
+    mut launches: ResMut<Events<LaunchCraft>>,
+    mut burns: ResMut<Events<BurnCraft>>,
+    names: Query<&Name, With<Body>>,
+    mirrored: Query<&Mirrored>,
+) {
+    let Some(mut session) = session.filter(|session| session.role == Role::Client) else {
+        return;
+    };
+    let name = |entity: Entity| names.get(entity).ok().map(|name| name.to_string());
+    for launch in launches.drain() {
+        let Some(from) = name(launch.from) else {
+            continue;
+        };
+        session.connection.send(&Message::Launch {
+            from,
+            destination: launch.destination.and_then(name),
+            direction: launch.direction.into(),
+            speed: launch.speed,
+        });
+    }
+    for burn in burns.drain() {
+        if let Ok(craft) = mirrored.get(burn.craft) {
+            session.connection.send(&Message::Burn {
+                craft: craft.0,
+                delta_v: burn.delta_v.into(),
+            });
+        }
+    }
+}
+
+/// Carries out the client's orders on the host, as long as they're for the
+/// client's own side.
+fn take_orders(
+    session: Option<Res<NetSession>>,
+    mut received: EventReader<Received>,
+    bodies: Query<(Entity, &Name, Option<&Owner>), With<Body>>,
+    crafts: Query<&Owner, With<Craft>>,
+    mut launches: EventWriter<LaunchCraft>,
+    mut burns: EventWriter<BurnCraft>,
+) {
+    if session.is_none_or(|session| session.role != Role::Host) {
+        received.clear();
+        return;
+    }
+    let theirs = |owner: Option<&Owner>| owner == Some(&Owner(Faction::Opponent));
+    let body = |name: &str| {
+        bodies
+            .iter()
+            .find(|(_, body, _)| body.as_str() == name)
+            .map(|(entity, _, owner)| (entity, owner))
+    };
+    for Received(message) in received.read() {
+        match message {
+            Message::Launch {
+                from,
+                destination,
+                direction,
+                speed,
+            } => {
+                let Some((from, _)) = body(from).filter(|&(_, owner)| theirs(owner)) else {
+                    continue;
+                };
+                launches.write(LaunchCraft {
+                    from,
+                    destination: destination
+                        .as_deref()
+                        .and_then(body)
+                        .map(|(entity, _)| entity),
+                    direction: (*direction).into(),
+                    speed: *speed,
+                    scripted: false,
+                });
+            }
+            Message::Burn { craft, delta_v } => {
+                let Some(craft) = Entity::try_from_bits(*craft).ok() else {
+                    continue;
+                };
+                if crafts.get(craft).is_ok_and(|owner| theirs(Some(owner))) {
+                    burns.write(BurnCraft {
+                        craft,
+                        delta_v: (*delta_v).into(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+type BodySnapshotData = (
+    &'static Name,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Crafts,
+    Option<&'static Owner>,
+);
+
+fn send_snapshots(
+    time: Res<Time<Real>>,
+    session: Option<ResMut<NetSession>>,
+    bodies: Query<BodySnapshotData, With<Body>>,
+    crafts: Query<CraftSnapshotData>,
+    names: Query<&Name, With<Body>>,
+) {
+    let Some(mut session) = session.filter(|session| session.role == Role::Host) else {
+        return;
+    };
+    if !session.snapshots.tick(time.delta()).just_finished() {
+        return;
+    }
+    let snapshot = Snapshot {
+        bodies: bodies
+            .iter()
+            .map(|(name, position, velocity, crafts, owner)| BodyState {
+                name: name.to_string(),
+                position: planar(position.0),
+                velocity: planar(velocity.0),
+                crafts: crafts.0,
+                owner: owner.map(|owner| owner.0),
+            })
+            .collect(),
+        crafts: crafts
+            .iter()
+            .map(
+                |(entity, name, craft, position, velocity, fuel, owner, destination)| CraftState {
+                    id: entity.to_bits(),
+                    name: name.to_string(),
+                    class: craft.class.clone(),
+                    position: planar(position.0),
+                    velocity: planar(velocity.0),
+                    fuel: fuel.0,
+                    owner: owner.map(|owner| owner.0),
+                    destination: destination
+                        .and_then(|destination| names.get(destination.0).ok())
+                        .map(|name| name.to_string()),
+                },
+            )
+            .collect(),
+    };
+    session.connection.send(&Message::Snapshot(snapshot));
+}
+
+type CraftSnapshotData = (
+    Entity,
+    &'static Name,
+    &'static Craft,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Fuel,
+    Option<&'static Owner>,
+    Option<&'static Destination>,
+);
+
+type MirrorData = (
+    Entity,
+    &'static Mirrored,
+    &'static mut SimPosition,
+    &'static mut SimVelocity,
+    &'static mut Fuel,
+);
+
+type BodyMirrorData = (
+    Entity,
+    &'static Name,
+    &'static mut SimPosition,
+    &'static mut SimVelocity,
+    &'static mut Crafts,
+);
+
+/// Moves everything on a client to where the host has it. The local
+/// simulation carries on in between, so motion stays smooth.
+fn apply_snapshots(
+    mut commands: Commands,
+    mut received: EventReader<Received>,
+    mut bodies: Query<BodyMirrorData, (With<Body>, Without<Craft>)>,
+    mut crafts: Query<MirrorData, (With<Craft>, Without<Body>)>,
+) {
+    // Only the latest matters
+    let Some(snapshot) = received
+        .read()
+        .filter_map(|Received(message)| match message {
+            Message::Snapshot(snapshot) => Some(snapshot),
+            _ => None,
+        })
+        .last()
+    else {
+        return;
+    };
+
+    for state in &snapshot.bodies {
+        let Some((entity, _, mut position, mut velocity, mut crafts)) = bodies
+            .iter_mut()
+            .find(|(_, name, ..)| name.as_str() == state.name)
+        else {
+            continue;
+        };
+        position.0 = spatial(state.position);
+        velocity.0 = spatial(state.velocity);
+        crafts.0 = state.crafts;
+        set_owner(&mut commands, entity, state.owner);
+    }
+
+    let body = |name: &str| {
+        bodies
+            .iter()
+            .find(|(_, body, ..)| body.as_str() == name)
+            .map(|(entity, ..)| entity)
+    };
+    for (entity, mirrored, ..) in &crafts {
+        if !snapshot.crafts.iter().any(|state| state.id == mirrored.0) {
+            commands.entity(entity).despawn();
+        }
+    }
+    for state in &snapshot.crafts {
+        let existing = crafts
+            .iter_mut()
+            .find(|(_, mirrored, ..)| mirrored.0 == state.id);
+        if let Some((entity, _, mut position, mut velocity, mut fuel)) = existing {
+            position.0 = spatial(state.position);
+            velocity.0 = spatial(state.velocity);
+            fuel.0 = state.fuel;
+            set_owner(&mut commands, entity, state.owner);
+            continue;
+        }
+        let mut craft = commands.spawn((
+            Craft {
+                class: state.class.clone(),
+            },
+            Mirrored(state.id),
+            Name::new(state.name.clone()),
+            SimPosition(spatial(state.position)),
+            SimVelocity(spatial(state.velocity)),
+            Fuel(state.fuel),
+        ));
+        if let Some(owner) = state.owner {
+            craft.insert(Owner(owner));
+        }
+        if let Some(destination) = state.destination.as_deref().and_then(body) {
+            craft.insert(Destination(destination));
+        }
+    }
+}
+
+/// Ends the match on a client once the host says it's over, since only the
+/// host decides that.
+fn finish_matches(
+    mut received: EventReader<Received>,
+    mut outcome: ResMut<GameOutcome>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for Received(message) in received.read() {
+        if let Message::Over {
+            won,
+            title,
+            details,
+        } = message
+        {
+            *outcome = GameOutcome {
+                won: *won,
+                title: tr(title),
+                details: vec![tr(details)],
+            };
+            next_state.set(AppState::GameOver);
+        }
+    }
+}
+
+/// Tells the client how the match ended for them, the opposite of how it
+/// ended here unless neither side has anything left.
+fn announce_outcome(
+    session: Option<ResMut<NetSession>>,
+    outcome: Res<GameOutcome>,
+    bodies: Query<&Owner, With<Body>>,
+    crafts: Query<&Owner, With<Craft>>,
+) {
+    let Some(mut session) = session.filter(|session| session.role == Role::Host) else {
+        return;
+    };
+    let theirs = |owner: &Owner| owner.0 == Faction::Opponent;
+    let (won, title, details) = if outcome.won {
+        (false, "defeat", "defeat-opponent-won")
+    } else if bodies.iter().any(theirs) || crafts.iter().any(theirs) {
+        (true, "victory", "victory-eliminated")
+    } else {
+        (false, "out-of-crafts", "out-of-crafts-details")
+    };
+    session.connection.send(&Message::Over {
+        won,
+        title: title.to_string(),
+        details: details.to_string(),
+    });
+}
+
+fn set_owner(commands: &mut Commands, entity: Entity, owner: Option<Faction>) {
+    match owner {
+        Some(owner) => commands.entity(entity).insert(Owner(owner)),
+        None => commands.entity(entity).remove::<Owner>(),
+    };
+}
+
+fn planar(vector: DVec3) -> [f64; 2] {
+    [vector.x, vector.y]
+}
+
+fn spatial([x, y]: [f64; 2]) -> DVec3 {
+    DVec3::new(x, y, 0.)
+}
+
+fn leave_match(mut commands: Commands, mut players: ResMut<Players>) {
+    commands.remove_resource::<NetSession>();
+    if players.online {
+        *players = Players::default();
+    }
+}
@@ -0,0 +1,191 @@
+use crate::physics::ScratchBody;
+use bevy::math::DVec3;
+use std::f64::consts::TAU;
+
+/// Classical orbital elements of a body around its attractor, in the plane.
+#[derive(Clone, Copy, Debug)]
+pub struct OrbitalElements {
+    /// Negative for hyperbolic orbits and infinite for parabolic ones.
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    /// Closest distance to the attractor's center.
+    pub periapsis: f64,
+    /// Furthest distance, for bound orbits only.
+    pub apoapsis: Option<f64>,
+    /// Seconds per orbit, for bound orbits only.
+    pub period: Option<f64>,
+    /// Direction of periapsis, counterclockwise from +x in radians.
+    pub argument_of_periapsis: f64,
+}
+
+impl OrbitalElements {
+    /// Elements of an orbit with `position` and `velocity` relative to an
+    /// attractor with gravitational parameter `mu` = G * (M + m).
+    pub fn new(position: DVec3, velocity: DVec3, mu: f64) -> Option<Self> {
+        let distance = position.length();
+        if distance <= 0. || mu <= 0. {
+            return None;
+        }
+        let speed_sq = velocity.length_squared();
+        let specific_energy = speed_sq / 2. - mu / distance;
+        let angular_momentum = position.x * velocity.y - position.y * velocity.x;
+        let eccentricity_vector =
+            ((speed_sq - mu / distance) * position - position.dot(velocity) * velocity) / mu;
+        let eccentricity = eccentricity_vector.length();
+
+        let semi_major_axis = -mu / (2. * specific_energy);
+        let bound = eccentricity < 1.;
+        Some(Self {
+            semi_major_axis,
+            eccentricity,
+            periapsis: angular_momentum.powi(2) / mu / (1. + eccentricity),
+            apoapsis: bound.then_some(semi_major_axis * (1. + eccentricity)),
+            period: bound.then(|| TAU * (semi_major_axis.powi(3) / mu).sqrt()),
+            argument_of_periapsis: eccentricity_vector.y.atan2(eccentricity_vector.x),
+        })
+    }
+}
+
+/// Position and velocity relative to an attractor with gravitational
+/// parameter `mu`, `true_anomaly` radians past periapsis on a counterclockwise
+/// bound orbit. The reverse of [`OrbitalElements::new`].
+pub fn state_on_orbit(
+    semi_major_axis: f64,
+    eccentricity: f64,
+    argument_of_periapsis: f64,
+    true_anomaly: f64,
+    mu: f64,
+) -> (DVec3, DVec3) {
+    let semi_latus_rectum = semi_major_axis * (1. - eccentricity * eccentricity);
+    let distance = semi_latus_rectum / (1. + eccentricity * true_anomaly.cos());
+    let angle = argument_of_periapsis + true_anomaly;
+    let position = DVec3::new(angle.cos(), angle.sin(), 0.) * distance;
+    // Components along periapsis and 90° ahead of it, rotated by the
+    // argument of periapsis
+    let speed = (mu / semi_latus_rectum).sqrt();
+    let along = -speed * true_anomaly.sin();
+    let ahead = speed * (eccentricity + true_anomaly.cos());
+    let (sin, cos) = argument_of_periapsis.sin_cos();
+    let velocity = DVec3::new(along * cos - ahead * sin, along * sin + ahead * cos, 0.);
+    (position, velocity)
+}
+
+/// Index of whichever of `bodies` pulls hardest on something at `position`,
+/// such as a craft.
+pub fn strongest_attractor(position: DVec3, bodies: &[ScratchBody]) -> Option<usize> {
+    strongest_among(position, bodies.iter().enumerate())
+}
+
+/// Index of the body that `bodies[index]` orbits: whichever heavier body
+/// pulls on it hardest. `None` for the heaviest body.
+pub fn dominant_attractor(index: usize, bodies: &[ScratchBody]) -> Option<usize> {
+    let body = &bodies[index];
+    strongest_among(
+        body.position,
+        bodies
+            .iter()
+            .enumerate()
+            .filter(|&(other, attractor)| other != index && attractor.mass > body.mass),
+    )
+}
+
+fn strongest_among<'a>(
+    position: DVec3,
+    candidates: impl Iterator<Item = (usize, &'a ScratchBody)>,
+) -> Option<usize> {
+    candidates
+        .max_by(|(_, a), (_, b)| {
+            let pull = |attractor: &ScratchBody| {
+                attractor.mass / attractor.position.distance_squared(position)
+            };
+            pull(a).total_cmp(&pull(b))
+        })
+        .map(|(index, _)| index)
+}
+
+/// Elements of `bodies[index]` around its [`dominant_attractor`], along with
+/// the attractor's index.
+pub fn elements_around_attractor(
+    index: usize,
+    bodies: &[ScratchBody],
+    g: f64,
+) -> Option<(usize, OrbitalElements)> {
+    let attractor = dominant_attractor(index, bodies)?;
+    let (body, primary) = (&bodies[index], &bodies[attractor]);
+    let elements = OrbitalElements::new(
+        body.position - primary.position,
+        body.velocity - primary.velocity,
+        g * (body.mass + primary.mass),
+    )?;
+    Some((attractor, elements))
+}
+
+/// Newton steps allowed for [`propagate_kepler`] to converge.
+const KEPLER_ITERATIONS: usize = 50;
+
+/// Stumpff functions C(z) and S(z) of the universal variable formulation.
+fn stumpff(z: f64) -> (f64, f64) {
+    if z > 1e-6 {
+        let root = z.sqrt();
+        ((1. - root.cos()) / z, (root - root.sin()) / (root * z))
+    } else if z < -1e-6 {
+        let root = (-z).sqrt();
+        ((root.cosh() - 1.) / -z, (root.sinh() - root) / (root * -z))
+    } else {
+        // Series, as both forms lose precision near zero
+        (1. / 2. - z / 24., 1. / 6. - z / 120.)
+    }
+}
+
+/// Position and velocity `dt` seconds along the conic through `position` and
+/// `velocity` relative to an attractor with gravitational parameter `mu`.
+///
+/// Uses the universal variable formulation, so it handles elliptic,
+/// parabolic and hyperbolic orbits alike. `None` if it fails to converge.
+pub fn propagate_kepler(
+    position: DVec3,
+    velocity: DVec3,
+    mu: f64,
+    dt: f64,
+) -> Option<(DVec3, DVec3)> {
+    let distance = position.length();
+    if distance <= 0. || mu <= 0. {
+        return None;
+    }
+    let sqrt_mu = mu.sqrt();
+    let radial = position.dot(velocity) / sqrt_mu;
+    // Reciprocal of the semi-major axis, zero for parabolic orbits
+    let alpha = 2. / distance - velocity.length_squared() / mu;
+
+    let mut chi = if alpha > 1e-12 {
+        sqrt_mu * alpha * dt
+    } else {
+        sqrt_mu * dt / distance
+    };
+    for _ in 0..KEPLER_ITERATIONS {
+        let z = alpha * chi * chi;
+        let (c, s) = stumpff(z);
+        let chi_sq = chi * chi;
+        let time_error =
+            radial * chi_sq * c + (1. - alpha * distance) * chi_sq * chi * s + distance * chi
+                - sqrt_mu * dt;
+        // The time equation's slope in chi is the new distance
+        let new_distance =
+            radial * chi * (1. - z * s) + (1. - alpha * distance) * chi_sq * c + distance;
+        let step = time_error / new_distance;
+        chi -= step;
+        if step.abs() <= 1e-12 * (1. + chi.abs()) {
+            let z = alpha * chi * chi;
+            let (c, s) = stumpff(z);
+            let chi_sq = chi * chi;
+            let f = 1. - chi_sq / distance * c;
+            let g = dt - chi_sq * chi / sqrt_mu * s;
+            let new_position = f * position + g * velocity;
+            let new_distance = new_position.length();
+            let f_dot = sqrt_mu / (new_distance * distance) * (alpha * chi_sq * chi * s - chi);
+            let g_dot = 1. - chi_sq / new_distance * c;
+            return Some((new_position, f_dot * position + g_dot * velocity));
+        }
+    }
+    None
+}
@@ -0,0 +1,156 @@
+use crate::app_state::AppState;
+use crate::bindings::{Action, ActionInput};
+use crate::bodies::Body;
+use crate::i18n::tr;
+use crate::selection::{SelectedBody, SelectedCraft};
+use crate::ui::{ViewChange, ViewRequest};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+/// A quick-jump palette ([`Action::Palette`]) that fuzzy-searches body names,
+/// then selects the chosen body and centers the plot on it.
+pub struct PalettePlugin;
+
+impl Plugin for PalettePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Palette>()
+            .add_systems(Update, open_palette.run_if(AppState::in_game))
+            .add_systems(
+                EguiPrimaryContextPass,
+                palette_window.run_if(AppState::in_game),
+            );
+    }
+}
+
+/// Most matches the palette lists at once.
+const MAX_RESULTS: usize = 10;
+
+#[derive(Resource, Default)]
+pub struct Palette {
+    pub open: bool,
+    query: String,
+    /// Index into the matches of the one Enter would pick.
+    highlighted: usize,
+}
+
+impl Palette {
+    fn close(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// How well `query` matches `name`, if its characters appear in order.
+/// Consecutive characters and ones starting words score higher. Everything
+/// matches an empty query equally.
+fn fuzzy_score(query: &str, name: &str) -> Option<i32> {
+    if query.trim().is_empty() {
+        return Some(0);
+    }
+    let chars: Vec<char> = name.chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query.chars().filter(|c| !c.is_whitespace()) {
+        let index = next
+            + chars[next..]
+                .iter()
+                .position(|c| c.to_lowercase().eq(wanted.to_lowercase()))?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == index) {
+            score += 5;
+        }
+        if index == 0 || !chars[index - 1].is_alphanumeric() {
+            score += 8;
+        }
+        previous = Some(index);
+        next = index + 1;
+    }
+    // Prefer shorter names when otherwise tied
+    Some(score * 100 - chars.len() as i32)
+}
+
+fn open_palette(mut actions: ActionInput, mut palette: ResMut<Palette>) {
+    if actions.just_pressed(Action::Palette) {
+        palette.open = true;
+    }
+}
+
+fn palette_window(
+    mut contexts: EguiContexts,
+    mut palette: ResMut<Palette>,
+    bodies: Query<&Name, With<Body>>,
+    mut selected_body: ResMut<SelectedBody>,
+    mut selected_craft: ResMut<SelectedCraft>,
+    mut view: ResMut<ViewRequest>,
+) {
+    if !palette.open {
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    let mut matches: Vec<_> = bodies
+        .iter()
+        .filter_map(|name| Some((fuzzy_score(&palette.query, name)?, name.as_str())))
+        .collect();
+    matches.sort_by(|(a_score, a), (b_score, b)| b_score.cmp(a_score).then(a.cmp(b)));
+    matches.truncate(MAX_RESULTS);
+
+    let (up, down, enter, escape) = ctx.input(|input| {
+        (
+            input.key_pressed(egui::Key::ArrowUp),
+            input.key_pressed(egui::Key::ArrowDown),
+            input.key_pressed(egui::Key::Enter),
+            input.key_pressed(egui::Key::Escape),
+        )
+    });
+    if down {
+        palette.highlighted += 1;
+    }
+    if up {
+        palette.highlighted = palette.highlighted.saturating_sub(1);
+    }
+    palette.highlighted = palette.highlighted.min(matches.len().saturating_sub(1));
+
+    let mut chosen = None;
+    egui::Window::new("Go to body")
+        .anchor(egui::Align2::CENTER_TOP, [0., 80.])
+        .title_bar(false)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let field = ui.add(
+                egui::TextEdit::singleline(&mut palette.query)
+                    .hint_text(tr("go-to-body"))
+                    .desired_width(240.),
+            );
+            field.request_focus();
+            if field.changed() {
+                palette.highlighted = 0;
+            }
+            for (index, (_, name)) in matches.iter().enumerate() {
+                if ui
+                    .selectable_label(index == palette.highlighted, *name)
+                    .clicked()
+                {
+                    chosen = Some(*name);
+                }
+            }
+            if matches.is_empty() {
+                ui.weak(tr("no-matching-bodies"));
+            }
+        });
+
+    if enter {
+        chosen = chosen.or(matches.get(palette.highlighted).map(|(_, name)| *name));
+    }
+    if let Some(name) = chosen {
+        selected_body.0 = Some(name.to_string());
+        selected_craft.0 = None;
+        view.0 = Some(ViewChange::CenterSelection);
+        palette.close();
+    } else if escape || enter {
+        palette.close();
+    }
+}
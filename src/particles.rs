@@ -0,0 +1,273 @@
+use crate::accessibility::body_color;
+use crate::app_state::AppState;
+use crate::bodies::ScenarioSystems;
+use crate::collisions::BodyDestroyed;
+use crate::crafts::{Craft, CraftCrashed, CraftLaunched, Fuel};
+use crate::i18n::tr;
+use crate::physics::{SimPosition, SimVelocity};
+use crate::settings::Settings;
+use bevy::math::DVec3;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy_egui::egui::Color32;
+use bevy_persistent::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
+
+/// Exhaust plumes behind burning crafts and debris flung out of collisions
+/// and crashes, drifting in the same space as the bodies.
+///
+/// Purely cosmetic: particles don't feel gravity and draw on their own
+/// random numbers, so replays stay deterministic.
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Particles>()
+            .init_resource::<ParticleRng>()
+            .add_systems(Update, clear_particles.in_set(ScenarioSystems::Initialize))
+            .add_systems(
+                Update,
+                (emit_exhaust, emit_debris, emit_impacts, age_particles)
+                    .chain()
+                    .run_if(AppState::in_game),
+            );
+    }
+}
+
+/// Exhaust particles per unit of delta-v burned, at full quality.
+const EXHAUST_PER_DELTA_V: f32 = 4.;
+const MAX_EXHAUST: usize = 40;
+/// Exhaust speed relative to the craft.
+const EXHAUST_SPEED: f64 = 3.;
+/// Half the angle the plume spreads over, in radians.
+const EXHAUST_SPREAD: f64 = 0.35;
+const EXHAUST_LIFETIME: f32 = 0.8;
+const EXHAUST_COLOR: Color32 = Color32::from_rgb(255, 170, 60);
+/// Debris particles per body destroyed, at full quality.
+const DEBRIS_COUNT: f32 = 60.;
+/// Top debris speed relative to the destroyed body.
+const DEBRIS_SPEED: f64 = 2.;
+const DEBRIS_LIFETIME: f32 = 2.5;
+/// Sparks thrown up by a craft crashing into a body, at full quality.
+const IMPACT_COUNT: f32 = 20.;
+const IMPACT_SPEED: f64 = 1.5;
+const IMPACT_LIFETIME: f32 = 1.2;
+const IMPACT_COLOR: Color32 = Color32::from_rgb(255, 230, 180);
+
+/// How many particles are emitted.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ParticleQuality {
+    Off,
+    Low,
+    #[default]
+    High,
+}
+
+impl ParticleQuality {
+    pub const ALL: [Self; 3] = [Self::Off, Self::Low, Self::High];
+
+    pub fn label(self) -> String {
+        tr(match self {
+            Self::Off => "off",
+            Self::Low => "particles-low",
+            Self::High => "particles-high",
+        })
+    }
+
+    /// Fraction of the full particle count emitted.
+    fn density(self) -> f32 {
+        match self {
+            Self::Off => 0.,
+            Self::Low => 0.3,
+            Self::High => 1.,
+        }
+    }
+}
+
+/// Particles emitted together, which share a color and fade out together.
+pub struct Burst {
+    pub color: Color32,
+    /// Radius of each particle in screen points.
+    pub size: f32,
+    age: f32,
+    lifetime: f32,
+    particles: Vec<(DVec3, DVec3)>,
+}
+
+impl Burst {
+    /// From 1 when emitted down to 0 when it disappears.
+    pub fn opacity(&self) -> f32 {
+        1. - self.age / self.lifetime
+    }
+
+    pub fn positions(&self) -> Vec<[f64; 2]> {
+        self.particles
+            .iter()
+            .map(|(position, _)| [position.x, position.y])
+            .collect()
+    }
+}
+
+/// Every burst still visible.
+#[derive(Resource, Default)]
+pub struct Particles(pub Vec<Burst>);
+
+/// Kept apart from [`SimRng`](crate::physics::SimRng) so effects don't change
+/// the simulation.
+#[derive(Resource)]
+struct ParticleRng(ChaCha8Rng);
+
+impl Default for ParticleRng {
+    fn default() -> Self {
+        Self(ChaCha8Rng::seed_from_u64(0))
+    }
+}
+
+fn clear_particles(mut particles: ResMut<Particles>) {
+    particles.0.clear();
+}
+
+/// Puffs exhaust out of the back of crafts as they launch, and whenever
+/// they spend fuel afterwards, however they burned it.
+fn emit_exhaust(
+    crafts: Query<(Entity, &SimPosition, &SimVelocity, &Fuel), With<Craft>>,
+    homes: Query<&SimVelocity>,
+    mut launches: EventReader<CraftLaunched>,
+    mut last_seen: Local<HashMap<Entity, (f32, DVec3)>>,
+    settings: Res<Persistent<Settings>>,
+    mut rng: ResMut<ParticleRng>,
+    mut particles: ResMut<Particles>,
+) {
+    let density = settings.particles.density();
+    let mut plume = |position: DVec3, velocity: DVec3, thrust: DVec3, delta_v: f32| {
+        let count = ((delta_v * EXHAUST_PER_DELTA_V * density).ceil() as usize).min(MAX_EXHAUST);
+        if count == 0 {
+            return;
+        }
+        let Some(backward) = (-thrust).try_normalize() else {
+            return;
+        };
+        let heading = backward.y.atan2(backward.x);
+        let exhaust = (0..count)
+            .map(|_| {
+                let angle = heading + rng.0.random_range(-EXHAUST_SPREAD..=EXHAUST_SPREAD);
+                let speed = EXHAUST_SPEED * rng.0.random_range(0.5..=1.);
+                let direction = DVec3::new(angle.cos(), angle.sin(), 0.);
+                (position, velocity + direction * speed)
+            })
+            .collect();
+        particles.0.push(Burst {
+            color: EXHAUST_COLOR,
+            size: 1.5,
+            age: 0.,
+            lifetime: EXHAUST_LIFETIME,
+            particles: exhaust,
+        });
+    };
+
+    for launch in launches.read() {
+        let Ok((_, position, velocity, ..)) = crafts.get(launch.craft) else {
+            continue;
+        };
+        let home = homes.get(launch.from).map_or(DVec3::ZERO, |home| home.0);
+        let thrust = velocity.0 - home;
+        plume(position.0, velocity.0, thrust, thrust.length() as f32);
+    }
+
+    for (craft, position, velocity, fuel) in &crafts {
+        let Some((previous_fuel, previous_velocity)) =
+            last_seen.insert(craft, (fuel.0, velocity.0))
+        else {
+            continue;
+        };
+        let spent = previous_fuel - fuel.0;
+        if spent > 0. {
+            // Gravity's share of the change is small next to a burn's
+            plume(
+                position.0,
+                velocity.0,
+                velocity.0 - previous_velocity,
+                spent,
+            );
+        }
+    }
+    last_seen.retain(|craft, _| crafts.contains(*craft));
+}
+
+/// Scatters debris in the destroyed body's color.
+fn emit_debris(
+    mut destroyed: EventReader<BodyDestroyed>,
+    settings: Res<Persistent<Settings>>,
+    mut rng: ResMut<ParticleRng>,
+    mut particles: ResMut<Particles>,
+) {
+    let count = (DEBRIS_COUNT * settings.particles.density()).round() as usize;
+    if count == 0 {
+        destroyed.clear();
+        return;
+    }
+    for destroyed in destroyed.read() {
+        let debris = (0..count)
+            .map(|_| {
+                let angle = rng.0.random::<f64>() * TAU;
+                let speed = DEBRIS_SPEED * rng.0.random::<f64>();
+                let direction = DVec3::new(angle.cos(), angle.sin(), 0.);
+                (destroyed.position, destroyed.velocity + direction * speed)
+            })
+            .collect();
+        particles.0.push(Burst {
+            color: body_color(destroyed.fill),
+            size: 2.,
+            age: 0.,
+            lifetime: DEBRIS_LIFETIME,
+            particles: debris,
+        });
+    }
+}
+
+/// Sparks fanning out from where a craft crashed.
+fn emit_impacts(
+    mut crashes: EventReader<CraftCrashed>,
+    settings: Res<Persistent<Settings>>,
+    mut rng: ResMut<ParticleRng>,
+    mut particles: ResMut<Particles>,
+) {
+    let count = (IMPACT_COUNT * settings.particles.density()).round() as usize;
+    if count == 0 {
+        crashes.clear();
+        return;
+    }
+    for crash in crashes.read() {
+        let sparks = (0..count)
+            .map(|_| {
+                let angle = rng.0.random::<f64>() * TAU;
+                let speed = IMPACT_SPEED * rng.0.random::<f64>();
+                let direction = DVec3::new(angle.cos(), angle.sin(), 0.);
+                (crash.position, crash.velocity + direction * speed)
+            })
+            .collect();
+        particles.0.push(Burst {
+            color: IMPACT_COLOR,
+            size: 1.5,
+            age: 0.,
+            lifetime: IMPACT_LIFETIME,
+            particles: sparks,
+        });
+    }
+}
+
+/// Moves particles along in a straight line, pausing with the simulation,
+/// and drops bursts that have faded out.
+fn age_particles(time: Res<Time<Virtual>>, mut particles: ResMut<Particles>) {
+    let delta = time.delta_secs();
+    for burst in &mut particles.0 {
+        burst.age += delta;
+        for (position, velocity) in &mut burst.particles {
+            *position += *velocity * delta as f64;
+        }
+    }
+    particles.0.retain(|burst| burst.age < burst.lifetime);
+}
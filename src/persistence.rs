@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_persistent::prelude::*;
+use bevy_persistent_windows::prelude::*;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Spawns the primary window with its size and position persisted across runs.
+pub struct PersistencePlugin;
+
+impl Plugin for PersistencePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(PersistentWindowsPlugin)
+            .add_systems(Startup, spawn_persistent_window);
+    }
+}
+
+/// Where persisted state lives between runs. Web builds keep it in the
+/// browser's local storage, as they do the files below.
+pub fn state_directory() -> PathBuf {
+    #[cfg(target_arch = "wasm32")]
+    let base = PathBuf::from("local");
+    #[cfg(not(target_arch = "wasm32"))]
+    let base = dirs::data_dir().unwrap_or_else(|| std::env::current_dir().unwrap());
+    base.join("slingcraft").join("state")
+}
+
+/// The first of `stem-1.extension`, `stem-2.extension` and so on that doesn't
+/// exist yet in `directory`.
+pub fn next_free_path(directory: &Path, stem: &str, extension: &str) -> PathBuf {
+    (1..)
+        .map(|number| directory.join(format!("{stem}-{number}.{extension}")))
+        .find(|path| !file_exists(path))
+        .unwrap_or_default()
+}
+
+// Saved files that work the same on the web, where there's no filesystem.
+// Web builds keep each file in local storage, keyed by its path, like the
+// `Persistent` resources under `state_directory`.
+
+pub fn read_file(path: &Path) -> io::Result<String> {
+    #[cfg(target_arch = "wasm32")]
+    return local_storage()?
+        .get_item(&path.to_string_lossy())
+        .map_err(js_error)?
+        .ok_or_else(|| io::ErrorKind::NotFound.into());
+    #[cfg(not(target_arch = "wasm32"))]
+    std::fs::read_to_string(path)
+}
+
+/// Writes the file, creating its directory if need be.
+pub fn write_file(path: &Path, contents: &str) -> io::Result<()> {
+    #[cfg(target_arch = "wasm32")]
+    return local_storage()?
+        .set_item(&path.to_string_lossy(), contents)
+        .map_err(js_error);
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents)
+    }
+}
+
+pub fn file_exists(path: &Path) -> bool {
+    #[cfg(target_arch = "wasm32")]
+    return read_file(path).is_ok();
+    #[cfg(not(target_arch = "wasm32"))]
+    path.exists()
+}
+
+/// The files directly in `directory`, sorted.
+pub fn list_files(directory: &Path) -> Vec<PathBuf> {
+    #[cfg(target_arch = "wasm32")]
+    let mut paths: Vec<_> = {
+        let storage = local_storage().ok();
+        let length = storage
+            .as_ref()
+            .and_then(|storage| storage.length().ok())
+            .unwrap_or(0);
+        (0..length)
+            .filter_map(|index| storage.as_ref()?.key(index).ok().flatten())
+            .map(PathBuf::from)
+            .filter(|path| path.parent() == Some(directory))
+            .collect()
+    };
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut paths: Vec<_> = std::fs::read_dir(directory)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> io::Result<web_sys::Storage> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .ok_or_else(|| io::Error::other("local storage is unavailable"))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn js_error(err: wasm_bindgen::JsValue) -> io::Error {
+    io::Error::other(format!("{err:?}"))
+}
+
+fn spawn_persistent_window(mut commands: Commands) {
+    commands.spawn((
+        PrimaryWindow,
+        PersistentWindowBundle {
+            window: Window {
+                title: "SlingCraft".to_string(),
+                canvas: Some("#bevy".to_string()),
+                prevent_default_event_handling: false,
+                fit_canvas_to_parent: true,
+                ..default()
+            },
+            state: Persistent::<WindowState>::builder()
+                .name("main_window")
+                .format(StorageFormat::Toml)
+                .path(state_directory().join("main-window.toml"))
+                .default(WindowState::windowed(800, 600))
+                .build()
+                .expect("failed to initialize persistent window state"),
+        },
+    ));
+}
@@ -0,0 +1,576 @@
+use crate::app_state::AppState;
+use crate::i18n::tr;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::tasks::{ComputeTaskPool, TaskPool};
+use bevy_simple_subsecond_system::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// N-body gravity, integration and the derived energy/center-of-mass readouts.
+///
+/// Integration runs in `FixedUpdate`, so the simulation is independent of the
+/// frame rate, and in double precision on [`SimPosition`]/[`SimVelocity`] so
+/// long runs don't accumulate float error. `Transform` only mirrors the
+/// position for display. Contains no rendering, so it can run headless under
+/// `MinimalPlugins`.
+pub struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhysicsConstants>()
+            .insert_resource(PotentialEnergy(0.))
+            .insert_resource(KineticEnergy(0.))
+            .insert_resource(TotalEnergy(0.))
+            .insert_resource(CenterOfMass(DVec3::ZERO))
+            .init_resource::<SimRng>()
+            .init_resource::<GravitySolver>()
+            .init_resource::<Substeps>()
+            .configure_sets(FixedUpdate, GravityStep.before(motion))
+            .configure_sets(
+                FixedUpdate,
+                Propagation
+                    .after(motion)
+                    .before(regulate_energy)
+                    .before(calculate_center_of_mass),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    (
+                        gravity.run_if(not(adaptive_steps)),
+                        adaptive_gravity.run_if(adaptive_steps),
+                    )
+                        .in_set(GravityStep)
+                        .run_if(resource_equals(GravitySolver::Direct)),
+                    motion.in_set(Motion),
+                    (regulate_energy, calculate_center_of_mass),
+                    correct_frame,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(PostUpdate, sync_transforms);
+    }
+}
+
+/// Where gravity is added to velocities, before anything moves. Other
+/// [`GravitySolver`]s run their systems here.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GravityStep;
+
+/// Where bodies and crafts move by their velocities.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Motion;
+
+/// Where bodies moving analytically rather than by integration are put back
+/// on their paths, after everything has moved and before energy is summed.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Propagation;
+
+/// How the pull of gravity on every body and test particle is summed up.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GravitySolver {
+    /// Every pair on the CPU, in double precision, deterministically.
+    #[default]
+    Direct,
+    /// Every pair on the GPU in a compute shader, in single precision, for
+    /// tens of thousands of test particles. Games played this way may not
+    /// replay the same. Only built with the `gpu_gravity` feature.
+    #[cfg(feature = "gpu_gravity")]
+    Gpu,
+}
+
+impl GravitySolver {
+    pub const ALL: &[Self] = &[
+        Self::Direct,
+        #[cfg(feature = "gpu_gravity")]
+        Self::Gpu,
+    ];
+
+    pub fn label(self) -> String {
+        tr(match self {
+            Self::Direct => "solver-cpu",
+            #[cfg(feature = "gpu_gravity")]
+            Self::Gpu => "solver-gpu",
+        })
+    }
+}
+
+/// Tunable constants shared by every physics system.
+///
+/// Scenarios may override them; see [`crate::scenario::Scenario::physics`].
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct PhysicsConstants {
+    /// Gravitational constant (adjusted for better energy balance).
+    pub g: f32,
+    /// Mass per unit volume of bodies that don't set their own.
+    pub density: f32,
+    /// Plummer softening length ε: gravity acts as if separations were
+    /// √(r² + ε²), which keeps close passes smooth instead of singular.
+    pub softening: f32,
+    /// Impact energy per unit of colliding mass above which the smaller body
+    /// shatters instead of merging.
+    pub breakup_energy: f32,
+    /// Pull from anything but a body's primary, relative to the primary's,
+    /// below which the body follows an exact Kepler orbit instead of being
+    /// integrated. Zero integrates everything.
+    pub kepler_threshold: f32,
+    /// Largest position error per substep, estimated by step doubling,
+    /// before ticks are split into more, shorter substeps for close
+    /// encounters. Zero takes one step per tick. Only the direct solver
+    /// splits ticks.
+    pub step_tolerance: f32,
+    /// Keeps the system as a whole from drifting away over long runs.
+    pub frame: FrameCorrection,
+}
+
+/// How the reference frame is kept on the system. Bodies start on orbits
+/// around a star that starts still, so the system has net momentum and
+/// slowly drifts off unless corrected.
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameCorrection {
+    /// Everything moves as simulated.
+    #[default]
+    Off,
+    /// The bodies' net momentum is taken out of every velocity, so the
+    /// barycenter stays where it is.
+    Momentum,
+    /// As with `Momentum`, and everything is moved so the barycenter stays
+    /// at the origin.
+    Recenter,
+}
+
+impl FrameCorrection {
+    pub const ALL: [Self; 3] = [Self::Off, Self::Momentum, Self::Recenter];
+
+    pub fn label(self) -> String {
+        tr(match self {
+            Self::Off => "off",
+            Self::Momentum => "frame-zero-momentum",
+            Self::Recenter => "frame-recenter",
+        })
+    }
+}
+
+impl Default for PhysicsConstants {
+    fn default() -> Self {
+        Self {
+            g: 50.,
+            density: 2.0e-2,
+            softening: 0.5,
+            breakup_energy: 1.,
+            kepler_threshold: 0.,
+            step_tolerance: 0.,
+            frame: FrameCorrection::Off,
+        }
+    }
+}
+
+/// Simulation position; the f32 `Transform` is derived from it.
+#[derive(Component, Reflect, Default, Clone, Copy, Debug)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct SimPosition(pub DVec3);
+
+impl SimPosition {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self(DVec3::new(x, y, 0.))
+    }
+}
+
+#[derive(Component, Reflect, Default, Clone, Copy, Debug)]
+#[reflect(Component)]
+pub struct SimVelocity(pub DVec3);
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Mass(pub f32);
+
+/// Accelerated by gravity without attracting anything itself, for objects
+/// such as crafts whose mass is negligible next to the bodies.
+#[derive(Component, Default)]
+pub struct TestParticle;
+
+/// Test particles that haven't been given a mass of their own.
+pub type MasslessParticles = (With<TestParticle>, Without<Mass>);
+
+/// Source of all randomness in the simulation, so runs are reproducible from a seed.
+#[derive(Resource)]
+pub struct SimRng(pub ChaCha8Rng);
+
+impl SimRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(ChaCha8Rng::seed_from_u64(seed))
+    }
+}
+
+impl Default for SimRng {
+    fn default() -> Self {
+        Self::seeded(0)
+    }
+}
+
+#[derive(Resource, Debug)]
+pub struct PotentialEnergy(pub f64);
+
+/// Substeps the last tick was split into by [`PhysicsConstants::step_tolerance`].
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct Substeps(pub u32);
+
+impl Default for Substeps {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+/// Most substeps a tick is split into, however close the encounter.
+pub const MAX_SUBSTEPS: u32 = 64;
+
+#[derive(Resource, Debug)]
+pub struct KineticEnergy(pub f64);
+
+#[derive(Resource, Debug)]
+pub struct TotalEnergy(pub f64);
+
+#[derive(Resource, Debug)]
+pub struct CenterOfMass(pub DVec3);
+
+/// Plummer-softened gravitational acceleration toward a body of `mass` at
+/// `offset`: a = G * m * r / (r² + ε²)^(3/2).
+pub fn gravity_acceleration(offset: DVec3, mass: f64, softening: f64, g: f64) -> DVec3 {
+    let softened_sq = offset.length_squared() + softening * softening;
+    if softened_sq <= 0. {
+        return DVec3::ZERO;
+    }
+    offset * g * mass / (softened_sq * softened_sq.sqrt())
+}
+
+/// Plummer-softened potential energy of a pair: U = -G * m1 * m2 / √(r² + ε²),
+/// consistent with [`gravity_acceleration`].
+pub fn pair_potential_energy(offset: DVec3, mass_product: f64, softening: f64, g: f64) -> f64 {
+    let softened_sq = offset.length_squared() + softening * softening;
+    if softened_sq <= 0. {
+        return 0.;
+    }
+    -g * mass_product / softened_sq.sqrt()
+}
+
+/// A body as plain data, for simulating outside the ECS.
+#[derive(Clone, Copy, Debug)]
+pub struct ScratchBody {
+    pub position: DVec3,
+    pub velocity: DVec3,
+    pub mass: f64,
+}
+
+/// Bodies whose pair forces one task adds up. Fixed rather than split by
+/// the number of threads, so sums come out the same on every machine.
+const ROWS_PER_TASK: usize = 32;
+
+/// The acceleration of each of `bodies`, given as position and mass, toward
+/// all the others, and the potential energy of the system.
+///
+/// Each pair's force is computed once and applied to both bodies. Pairs are
+/// summed in blocks of rows, in parallel on `pool` if given, and the blocks
+/// then combined in order, so the result is the same either way.
+pub fn pairwise_gravity(
+    bodies: &[(DVec3, f64)],
+    softening: f64,
+    g: f64,
+    pool: Option<&TaskPool>,
+) -> (Vec<DVec3>, f64) {
+    let starts = (0..bodies.len()).step_by(ROWS_PER_TASK);
+    let block = |start: usize| {
+        let rows = start..(start + ROWS_PER_TASK).min(bodies.len());
+        pair_block(bodies, rows, softening, g)
+    };
+    let blocks = match pool {
+        Some(pool) => pool.scope(|scope| {
+            for start in starts {
+                scope.spawn(async move { block(start) });
+            }
+        }),
+        None => starts.map(block).collect(),
+    };
+
+    let mut accelerations = vec![DVec3::ZERO; bodies.len()];
+    let mut potential_energy = 0.;
+    for (block_accelerations, block_potential_energy) in blocks {
+        for (total, acceleration) in accelerations.iter_mut().zip(block_accelerations) {
+            *total += acceleration;
+        }
+        potential_energy += block_potential_energy;
+    }
+    (accelerations, potential_energy)
+}
+
+/// Forces between each body in `rows` and every body after it.
+fn pair_block(
+    bodies: &[(DVec3, f64)],
+    rows: Range<usize>,
+    softening: f64,
+    g: f64,
+) -> (Vec<DVec3>, f64) {
+    let mut accelerations = vec![DVec3::ZERO; bodies.len()];
+    let mut potential_energy = 0.;
+    for i in rows {
+        let (position1, mass1) = bodies[i];
+        for (j, &(position2, mass2)) in bodies.iter().enumerate().skip(i + 1) {
+            let offset = position2 - position1;
+            // Per unit of the other's mass, pulling each toward the other
+            let pull = gravity_acceleration(offset, 1., softening, g);
+            accelerations[i] += pull * mass2;
+            accelerations[j] -= pull * mass1;
+            potential_energy += pair_potential_energy(offset, mass1 * mass2, softening, g);
+        }
+    }
+    (accelerations, potential_energy)
+}
+
+/// Advances `bodies` by one step, the same way the `gravity` and `motion`
+/// systems advance entities.
+pub fn step_scratch(bodies: &mut [ScratchBody], constants: &PhysicsConstants, dt: f64) {
+    let masses: Vec<_> = bodies
+        .iter()
+        .map(|body| (body.position, body.mass))
+        .collect();
+    let (accelerations, _) = pairwise_gravity(
+        &masses,
+        constants.softening as f64,
+        constants.g as f64,
+        None,
+    );
+
+    for (body, acceleration) in bodies.iter_mut().zip(accelerations) {
+        body.velocity += acceleration * dt;
+        body.position += body.velocity * dt;
+    }
+}
+
+/// How far apart one step of `dt` and two of half that leave any of
+/// `bodies`, an estimate of the error of the step.
+fn step_error(bodies: &[ScratchBody], constants: &PhysicsConstants, dt: f64) -> f64 {
+    let mut whole = bodies.to_vec();
+    step_scratch(&mut whole, constants, dt);
+    let mut halves = bodies.to_vec();
+    step_scratch(&mut halves, constants, dt / 2.);
+    step_scratch(&mut halves, constants, dt / 2.);
+    whole
+        .iter()
+        .zip(&halves)
+        .map(|(a, b)| a.position.distance(b.position))
+        .fold(0., f64::max)
+}
+
+/// Substeps to split a tick of `dt` into so each stays within `tolerance`.
+///
+/// Starts from half the `previous` count, so steps grow back once bodies
+/// are far apart, and doubles until the error is small enough.
+pub fn choose_substeps(
+    bodies: &[ScratchBody],
+    constants: &PhysicsConstants,
+    dt: f64,
+    tolerance: f64,
+    previous: u32,
+) -> u32 {
+    let mut substeps = (previous / 2).max(1);
+    while substeps < MAX_SUBSTEPS && step_error(bodies, constants, dt / substeps as f64) > tolerance
+    {
+        substeps *= 2;
+    }
+    substeps
+}
+
+fn motion(mut query: Query<(&SimVelocity, &mut SimPosition)>, time: Res<Time>) {
+    for (velocity, mut position) in &mut query {
+        position.0 += velocity.0 * time.delta_secs_f64();
+    }
+}
+
+fn sync_transforms(mut query: Query<(&SimPosition, &mut Transform), Changed<SimPosition>>) {
+    for (position, mut transform) in &mut query {
+        transform.translation = position.0.as_vec3();
+    }
+}
+
+#[hot]
+fn gravity(
+    mut bodies: Query<(&SimPosition, &mut SimVelocity, &Mass)>,
+    mut particles: Query<(&SimPosition, &mut SimVelocity), MasslessParticles>,
+    mut potential_energy: ResMut<PotentialEnergy>,
+    constants: Res<PhysicsConstants>,
+    time: Res<Time>,
+) {
+    let g = constants.g as f64;
+    let softening = constants.softening as f64;
+    let dt = time.delta_secs_f64();
+
+    let masses: Vec<_> = bodies
+        .iter()
+        .map(|(position, _, mass)| (position.0, mass.0 as f64))
+        .collect();
+    let (accelerations, new_potential_energy) =
+        pairwise_gravity(&masses, softening, g, Some(ComputeTaskPool::get()));
+    potential_energy.0 = new_potential_energy;
+
+    // Test particles feel every body but pull on nothing, so each is its own
+    particles
+        .par_iter_mut()
+        .for_each(|(position, mut velocity)| {
+            let acceleration: DVec3 = masses
+                .iter()
+                .map(|&(body_position, mass)| {
+                    gravity_acceleration(body_position - position.0, mass, softening, g)
+                })
+                .sum();
+            velocity.0 += acceleration * dt;
+        });
+
+    for ((_, mut velocity, _), acceleration) in bodies.iter_mut().zip(accelerations) {
+        velocity.0 += acceleration * dt;
+    }
+}
+
+fn adaptive_steps(constants: Res<PhysicsConstants>) -> bool {
+    constants.step_tolerance > 0.
+}
+
+/// Like [`gravity`], but moves bodies and test particles through as many
+/// substeps as [`choose_substeps`] asks for, rather than only kicking them.
+fn adaptive_gravity(
+    mut bodies: Query<(&mut SimPosition, &mut SimVelocity, &Mass)>,
+    mut particles: Query<(&mut SimPosition, &mut SimVelocity), MasslessParticles>,
+    mut potential_energy: ResMut<PotentialEnergy>,
+    mut substeps: ResMut<Substeps>,
+    constants: Res<PhysicsConstants>,
+    time: Res<Time>,
+) {
+    let g = constants.g as f64;
+    let softening = constants.softening as f64;
+    let dt = time.delta_secs_f64();
+
+    let mut scratch: Vec<_> = bodies
+        .iter()
+        .map(|(position, velocity, mass)| ScratchBody {
+            position: position.0,
+            velocity: velocity.0,
+            mass: mass.0 as f64,
+        })
+        .collect();
+    let tolerance = constants.step_tolerance as f64;
+    substeps.0 = choose_substeps(&scratch, &constants, dt, tolerance, substeps.0);
+    let step = dt / substeps.0 as f64;
+
+    for substep in 0..substeps.0 {
+        let masses: Vec<_> = scratch
+            .iter()
+            .map(|body| (body.position, body.mass))
+            .collect();
+        let (accelerations, new_potential_energy) =
+            pairwise_gravity(&masses, softening, g, Some(ComputeTaskPool::get()));
+        // Measured where the tick starts, as the fixed step does
+        if substep == 0 {
+            potential_energy.0 = new_potential_energy;
+        }
+        particles
+            .par_iter_mut()
+            .for_each(|(mut position, mut velocity)| {
+                let acceleration: DVec3 = masses
+                    .iter()
+                    .map(|&(body_position, mass)| {
+                        gravity_acceleration(body_position - position.0, mass, softening, g)
+                    })
+                    .sum();
+                velocity.0 += acceleration * step;
+                position.0 += velocity.0 * step;
+            });
+        for (body, acceleration) in scratch.iter_mut().zip(accelerations) {
+            body.velocity += acceleration * step;
+            body.position += body.velocity * step;
+        }
+    }
+
+    // `motion` drifts everything by the whole tick afterwards, so take that
+    // back off where the substeps left each one
+    for ((mut position, mut velocity, _), body) in bodies.iter_mut().zip(&scratch) {
+        velocity.0 = body.velocity;
+        position.0 = body.position - body.velocity * dt;
+    }
+    particles
+        .par_iter_mut()
+        .for_each(|(mut position, velocity)| position.0 -= velocity.0 * dt);
+}
+
+#[hot]
+fn regulate_energy(
+    bodies: Query<(&SimVelocity, &Mass)>,
+    potential_energy: Res<PotentialEnergy>,
+    mut kinetic_energy: ResMut<KineticEnergy>,
+    mut total_energy: ResMut<TotalEnergy>,
+) {
+    let mut new_ke = 0.;
+    for (velocity, mass) in bodies {
+        let speed_sq = velocity.0.length_squared();
+        new_ke += 0.5 * mass.0 as f64 * speed_sq;
+    }
+    kinetic_energy.0 = new_ke;
+    total_energy.0 = kinetic_energy.0 + potential_energy.0;
+}
+
+fn calculate_center_of_mass(
+    bodies: Query<(&SimPosition, &Mass)>,
+    mut center_of_mass: ResMut<CenterOfMass>,
+) {
+    let mut total_mass = 0.0;
+    let mut weighted_position = DVec3::ZERO;
+
+    for (position, mass) in bodies.iter() {
+        weighted_position += position.0 * mass.0 as f64;
+        total_mass += mass.0 as f64;
+    }
+
+    if total_mass > 0.0 {
+        center_of_mass.0 = weighted_position / total_mass;
+    } else {
+        center_of_mass.0 = DVec3::ZERO;
+    }
+}
+
+/// Shifts everything by the same velocity, and position, so the bodies'
+/// barycenter stays put as [`PhysicsConstants::frame`] asks. Motion relative
+/// to one another is untouched.
+fn correct_frame(
+    mut moving: Query<(&mut SimPosition, &mut SimVelocity, Option<&Mass>)>,
+    mut center_of_mass: ResMut<CenterOfMass>,
+    constants: Res<PhysicsConstants>,
+) {
+    if constants.frame == FrameCorrection::Off {
+        return;
+    }
+    let (momentum, total_mass) = moving
+        .iter()
+        .filter_map(|(_, velocity, mass)| Some((velocity.0, mass?.0 as f64)))
+        .fold((DVec3::ZERO, 0.), |(momentum, total), (velocity, mass)| {
+            (momentum + velocity * mass, total + mass)
+        });
+    if total_mass <= 0. {
+        return;
+    }
+    let drift = momentum / total_mass;
+    let offset = if constants.frame == FrameCorrection::Recenter {
+        center_of_mass.0
+    } else {
+        DVec3::ZERO
+    };
+    for (mut position, mut velocity, _) in &mut moving {
+        velocity.0 -= drift;
+        position.0 -= offset;
+    }
+    center_of_mass.0 -= offset;
+}
@@ -0,0 +1,525 @@
+use crate::app_state::AppState;
+use crate::atmosphere::Atmosphere;
+use crate::bodies::{Appearance, Body, Density, Fill, Radius, Spin};
+use crate::comets::Comet;
+use crate::crafts::{Craft, Crafts, Destination, Fuel, Home};
+use crate::events::Toasts;
+use crate::factions::{CombatRules, Owner};
+use crate::i18n::{format_number, tr, tr_args};
+use crate::kepler::Rails;
+use crate::mods::{ModPack, ModPacks};
+use crate::persistence::{list_files, next_free_path};
+use crate::physics::{PhysicsConstants, SimPosition, SimVelocity};
+use crate::scenario::{
+    BodySpec, ComplexityEstimate, CraftSpec, LINK_PREFIX, Scenario, ScenarioError, ScenarioLoader,
+};
+use crate::settings::Settings;
+use crate::stats::RunStats;
+use crate::victory::WinConditions;
+use bevy::asset::LoadState;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::tasks::{IoTaskPool, Task, block_on, futures_lite::future};
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use bevy_persistent::prelude::*;
+use std::path::PathBuf;
+
+/// Lists the available scenarios with complexity estimates and loads the
+/// chosen one, and saves the game as it stands as a new scenario.
+///
+/// Scenario files load through the asset server, so the ones shipped with
+/// the game are listed on the web too, where scenarios of the player's own can
+/// be imported with a file picker instead.
+pub struct ScenarioPickerPlugin;
+
+impl Plugin for ScenarioPickerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Scenario>()
+            .init_asset_loader::<ScenarioLoader>()
+            .init_resource::<ScenarioPicker>()
+            .add_event::<ExportScenario>()
+            .add_systems(Update, (export_scenario, finish_loading, finish_import))
+            .add_systems(
+                EguiPrimaryContextPass,
+                (scenario_picker_window, share_link_window).run_if(AppState::in_game),
+            );
+    }
+}
+
+/// Scenario files shipped in `assets/scenarios`, listed here since web builds
+/// can't list the folder.
+const BUNDLED_SCENARIOS: [&str; 2] = ["default.toml", "binary.toml"];
+
+/// Request to save the bodies where they are now, moving as they are now, as
+/// a scenario alongside the others.
+#[derive(Event)]
+pub struct ExportScenario {
+    /// Keep the crafts in flight too.
+    pub crafts: bool,
+    /// Make a link that opens it in the web build, rather than saving a file.
+    pub share: bool,
+}
+
+/// Where the web build is played, for links to shared scenarios made
+/// elsewhere.
+const WEB_BUILD_URL: &str = "https://samcarey.github.io/slingcraft/";
+
+/// Links longer than this get cut off by some browsers and chat apps.
+const MAX_LINK_LENGTH: usize = 8000;
+
+/// A file's name and contents.
+type PickedFile = (String, Vec<u8>);
+
+#[derive(Resource, Default)]
+pub struct ScenarioPicker {
+    pub open: bool,
+    /// Whether exporting a scenario keeps the crafts in flight.
+    pub export_crafts: bool,
+    /// The last shared scenario's link, shown until dismissed.
+    share_link: Option<String>,
+    /// Scanned when the picker opens, since estimating runs a short simulation.
+    entries: Option<Vec<ScenarioEntry>>,
+    /// Scenarios picked from outside the game, kept for the session.
+    imported: Vec<(String, Scenario)>,
+    /// The open file picker, if any, and then reading the chosen file.
+    import: Option<Task<Option<PickedFile>>>,
+}
+
+struct ScenarioEntry {
+    name: String,
+    scenario: EntryState,
+}
+
+enum EntryState {
+    Loading(Handle<Scenario>),
+    Ready(Scenario, ComplexityEstimate),
+    Invalid(String),
+}
+
+impl ScenarioEntry {
+    fn new(name: String, scenario: Result<Scenario, String>) -> Self {
+        Self {
+            name,
+            scenario: match scenario {
+                Ok(scenario) => {
+                    let estimate = scenario.estimate_complexity();
+                    EntryState::Ready(scenario, estimate)
+                }
+                Err(err) => EntryState::Invalid(err),
+            },
+        }
+    }
+}
+
+/// Where exported scenarios go, and players' own are found. Web builds keep
+/// them in local storage.
+fn scenario_directory() -> PathBuf {
+    #[cfg(target_arch = "wasm32")]
+    return crate::persistence::state_directory().with_file_name("scenarios");
+    #[cfg(not(target_arch = "wasm32"))]
+    bevy::asset::io::file::FileAssetReader::get_base_path()
+        .join("assets")
+        .join("scenarios")
+}
+
+/// The built-in scenario, then every scenario file in `assets/scenarios`,
+/// then those of each enabled mod pack, then any imported ones.
+fn scan_scenarios<'a>(
+    asset_server: &AssetServer,
+    packs: impl Iterator<Item = &'a ModPack>,
+    imported: &[(String, Scenario)],
+) -> Vec<ScenarioEntry> {
+    let mut entries = vec![ScenarioEntry::new(
+        tr("picker-built-in"),
+        Ok(Scenario::default()),
+    )];
+
+    #[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
+    let mut files: Vec<String> = BUNDLED_SCENARIOS.map(String::from).to_vec();
+    let saved = list_files(&scenario_directory())
+        .into_iter()
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "toml")
+        });
+    // Native builds save next to the bundled scenarios, so the asset server
+    // can load them too
+    #[cfg(not(target_arch = "wasm32"))]
+    files.extend(saved.filter_map(|path| Some(path.file_name()?.to_string_lossy().into_owned())));
+    files.sort();
+    files.dedup();
+    entries.extend(files.into_iter().map(|file| ScenarioEntry {
+        name: file.trim_end_matches(".toml").to_string(),
+        scenario: EntryState::Loading(asset_server.load(format!("scenarios/{file}"))),
+    }));
+    #[cfg(target_arch = "wasm32")]
+    entries.extend(saved.map(|path| {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        ScenarioEntry::new(name, Scenario::load(&path).map_err(|err| err.to_string()))
+    }));
+
+    for pack in packs {
+        entries.extend(pack.scenario_paths().into_iter().map(|path| {
+            let name = path
+                .file_stem()
+                .map(|stem| format!("{}: {}", pack.name(), stem.to_string_lossy()))
+                .unwrap_or_default();
+            ScenarioEntry::new(
+                name,
+                pack.load_scenario(&path).map_err(|err| err.to_string()),
+            )
+        }));
+    }
+
+    entries.extend(
+        imported
+            .iter()
+            .map(|(name, scenario)| ScenarioEntry::new(name.clone(), Ok(scenario.clone()))),
+    );
+    entries
+}
+
+/// Estimates scenario files once the asset server has them.
+fn finish_loading(
+    mut picker: ResMut<ScenarioPicker>,
+    scenarios: Res<Assets<Scenario>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Some(entries) = &mut picker.entries else {
+        return;
+    };
+    for entry in entries {
+        let EntryState::Loading(handle) = &entry.scenario else {
+            continue;
+        };
+        if let Some(scenario) = scenarios.get(handle) {
+            let estimate = scenario.estimate_complexity();
+            entry.scenario = EntryState::Ready(scenario.clone(), estimate);
+        } else if let Some(LoadState::Failed(err)) = asset_server.get_load_state(handle) {
+            entry.scenario = EntryState::Invalid(err.to_string());
+        }
+    }
+}
+
+/// Opens the system's file picker, or the browser's, for a scenario file.
+fn pick_scenario_file() -> Task<Option<PickedFile>> {
+    IoTaskPool::get().spawn(async {
+        let file = rfd::AsyncFileDialog::new()
+            .add_filter(tr("picker-scenario"), &["toml"])
+            .pick_file()
+            .await?;
+        Some((file.file_name(), file.read().await))
+    })
+}
+
+/// Loads an imported scenario, and keeps it in the list.
+fn finish_import(
+    mut commands: Commands,
+    mut picker: ResMut<ScenarioPicker>,
+    mut toasts: ResMut<Toasts>,
+    time: Res<Time<Real>>,
+) {
+    let Some(task) = &mut picker.import else {
+        return;
+    };
+    let Some(picked) = block_on(future::poll_once(task)) else {
+        return;
+    };
+    picker.import = None;
+    // Cancelled
+    let Some((file, bytes)) = picked else {
+        return;
+    };
+    match Scenario::from_bytes(&bytes) {
+        Ok(scenario) => {
+            let name = tr_args(
+                "picker-imported",
+                &[("file", file.trim_end_matches(".toml").into())],
+            );
+            picker.imported.push((name, scenario.clone()));
+            picker.entries = None;
+            picker.open = false;
+            commands.insert_resource(scenario);
+        }
+        Err(err) => toasts.push(format!("{file}: {err}"), time.elapsed_secs_f64()),
+    }
+}
+
+fn scenario_picker_window(
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+    mut picker: ResMut<ScenarioPicker>,
+    asset_server: Res<AssetServer>,
+    packs: Option<Res<ModPacks>>,
+    settings: Option<Res<Persistent<Settings>>>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    if !picker.open {
+        picker.entries = None;
+        return;
+    }
+
+    let ScenarioPicker {
+        open,
+        entries,
+        imported,
+        import,
+        ..
+    } = &mut *picker;
+    let entries = entries.get_or_insert_with(|| match (&packs, &settings) {
+        (Some(packs), Some(settings)) => {
+            scan_scenarios(&asset_server, packs.enabled(settings), imported)
+        }
+        _ => scan_scenarios(&asset_server, std::iter::empty(), imported),
+    });
+    let mut chosen = None;
+    let mut rescan = false;
+    egui::Window::new(tr("picker-scenarios"))
+        .open(open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("scenarios").striped(true).show(ui, |ui| {
+                ui.strong(tr("picker-scenario"));
+                ui.strong(tr("bodies"));
+                ui.strong(tr("picker-chaos"));
+                ui.strong(tr("picker-session"));
+                ui.end_row();
+
+                for entry in entries.iter() {
+                    ui.label(&entry.name);
+                    match &entry.scenario {
+                        EntryState::Loading(_) => {
+                            ui.weak(tr("loading"));
+                        }
+                        EntryState::Ready(scenario, estimate) => {
+                            ui.label(estimate.body_count.to_string());
+                            ui.label(estimate.chaos_label()).on_hover_text(tr_args(
+                                "picker-divergence",
+                                &[("rate", format_number(estimate.divergence_rate, 3).into())],
+                            ));
+                            ui.label(tr_args(
+                                "picker-minutes",
+                                &[("minutes", estimate.session_minutes.ceil().into())],
+                            ));
+                            if ui.button(tr("load")).clicked() {
+                                chosen = Some(scenario.clone());
+                            }
+                        }
+                        EntryState::Invalid(err) => {
+                            ui.colored_label(ui.visuals().error_fg_color, tr("picker-invalid"))
+                                .on_hover_text(err);
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+            ui.horizontal(|ui| {
+                rescan = ui.button(tr("picker-refresh")).clicked();
+                if ui
+                    .add_enabled(import.is_none(), egui::Button::new(tr("picker-import")))
+                    .on_hover_text(tr("picker-import-hint"))
+                    .clicked()
+                {
+                    *import = Some(pick_scenario_file());
+                }
+            });
+        });
+
+    if rescan {
+        picker.entries = None;
+    }
+    if let Some(scenario) = chosen {
+        commands.insert_resource(scenario);
+        picker.open = false;
+    }
+}
+
+type BodyExportData = (
+    &'static Name,
+    &'static Radius,
+    &'static Density,
+    &'static Fill,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Crafts,
+    Option<&'static Owner>,
+    Option<&'static Appearance>,
+    Option<&'static Spin>,
+    Option<&'static Atmosphere>,
+    Has<Rails>,
+    Option<&'static Comet>,
+);
+
+type CraftExportData = (
+    &'static Craft,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Fuel,
+    Option<&'static Owner>,
+    Option<&'static Home>,
+    Option<&'static Destination>,
+);
+
+/// The bodies and crafts a snapshot saves.
+#[derive(SystemParam)]
+struct ExportedWorld<'w, 's> {
+    bodies: Query<'w, 's, BodyExportData, With<Body>>,
+    crafts: Query<'w, 's, CraftExportData>,
+    names: Query<'w, 's, &'static Name, With<Body>>,
+}
+
+/// The rules and running totals a snapshot keeps, and the scenario it was
+/// taken from.
+#[derive(SystemParam)]
+struct ExportedRules<'w> {
+    current: Res<'w, Scenario>,
+    physics: Res<'w, PhysicsConstants>,
+    combat: Res<'w, CombatRules>,
+    victory: Res<'w, WinConditions>,
+    stats: Res<'w, RunStats>,
+}
+
+fn export_scenario(
+    mut exports: EventReader<ExportScenario>,
+    exported: ExportedWorld,
+    rules: ExportedRules,
+    mut picker: ResMut<ScenarioPicker>,
+    mut toasts: ResMut<Toasts>,
+    time: Res<Time<Real>>,
+) {
+    for export in exports.read() {
+        let xy = |vector: bevy::math::DVec3| [vector.x as f32, vector.y as f32];
+        let name = |body: Entity| exported.names.get(body).ok().map(ToString::to_string);
+        let scenario = Scenario {
+            physics: *rules.physics,
+            combat: *rules.combat,
+            victory: *rules.victory,
+            // Whoever opens a link starts afresh
+            stats: (!export.share).then_some(*rules.stats),
+            bodies: exported
+                .bodies
+                .iter()
+                .map(
+                    |(
+                        name,
+                        radius,
+                        density,
+                        fill,
+                        position,
+                        velocity,
+                        docked,
+                        owner,
+                        appearance,
+                        spin,
+                        atmosphere,
+                        on_rails,
+                        comet,
+                    )| BodySpec {
+                        name: name.to_string(),
+                        radius: radius.0,
+                        color: [fill.0.r(), fill.0.g(), fill.0.b()],
+                        position: xy(position.0),
+                        density: (density.0 != rules.physics.density).then_some(density.0),
+                        owner: owner.map(|owner| owner.0),
+                        velocity: Some(xy(velocity.0)),
+                        crafts: Some(docked.0),
+                        appearance: appearance.cloned(),
+                        spin: spin.copied(),
+                        atmosphere: atmosphere.copied(),
+                        on_rails,
+                        comet: comet.copied(),
+                        orbit: None,
+                    },
+                )
+                .collect(),
+            crafts: exported
+                .crafts
+                .iter()
+                .filter(|_| export.crafts)
+                .map(
+                    |(craft, position, velocity, fuel, owner, home, destination)| CraftSpec {
+                        class: craft.class.clone(),
+                        position: xy(position.0),
+                        velocity: xy(velocity.0),
+                        fuel: fuel.0,
+                        owner: owner.map(|owner| owner.0),
+                        home: home.and_then(|home| name(home.0)),
+                        destination: destination.and_then(|destination| name(destination.0)),
+                    },
+                )
+                .collect(),
+            // Timed from the snapshot on, when it's loaded
+            script: rules.current.script.clone(),
+        };
+
+        if export.share {
+            match share_link(&scenario) {
+                Ok(link) if link.len() <= MAX_LINK_LENGTH => picker.share_link = Some(link),
+                Ok(_) => toasts.push(tr("link-too-long"), time.elapsed_secs_f64()),
+                Err(err) => toasts.push(
+                    tr_args("link-not-made", &[("error", err.to_string().into())]),
+                    time.elapsed_secs_f64(),
+                ),
+            }
+            continue;
+        }
+
+        let path = next_free_path(&scenario_directory(), "snapshot", "toml");
+        let message = match scenario.save(&path) {
+            Ok(()) => tr_args(
+                "scenario-saved",
+                &[("path", path.display().to_string().into())],
+            ),
+            Err(err) => tr_args("scenario-not-saved", &[("error", err.to_string().into())]),
+        };
+        toasts.push(message, time.elapsed_secs_f64());
+        // List the new file next time the picker opens
+        picker.entries = None;
+    }
+}
+
+/// A link to this page, or to the web build from elsewhere, that opens
+/// `scenario`.
+fn share_link(scenario: &Scenario) -> Result<String, ScenarioError> {
+    #[cfg(target_arch = "wasm32")]
+    let page = web_sys::window()
+        .and_then(|window| {
+            let location = window.location();
+            Some(location.origin().ok()? + &location.pathname().ok()?)
+        })
+        .unwrap_or_else(|| WEB_BUILD_URL.to_string());
+    #[cfg(not(target_arch = "wasm32"))]
+    let page = WEB_BUILD_URL;
+    Ok(format!(
+        "{page}#{LINK_PREFIX}{}",
+        scenario.to_link_fragment()?
+    ))
+}
+
+fn share_link_window(mut contexts: EguiContexts, mut picker: ResMut<ScenarioPicker>) {
+    let Some(link) = &picker.share_link else {
+        return;
+    };
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    let mut open = true;
+    egui::Window::new(tr("share-link"))
+        .open(&mut open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(tr("share-link-explanation"));
+            ui.add(egui::TextEdit::singleline(&mut link.as_str()).desired_width(f32::INFINITY));
+            if ui.button(tr("copy")).clicked() {
+                ui.ctx().copy_text(link.clone());
+            }
+        });
+    if !open {
+        picker.share_link = None;
+    }
+}
@@ -0,0 +1,75 @@
+use crate::app_state::AppState;
+use crate::bodies::{Body, Radius, ScenarioSystems};
+use crate::crafts::Crafts;
+use crate::factions::{Owner, authoritative};
+use crate::scenario::Scenario;
+use bevy::prelude::*;
+
+/// Owned bodies build new crafts over time, bigger bodies faster.
+pub struct ProductionPlugin;
+
+impl Plugin for ProductionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, stock_bodies.in_set(ScenarioSystems::Initialize))
+            .add_systems(
+                FixedPostUpdate,
+                produce_crafts
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(authoritative),
+            );
+    }
+}
+
+/// Crafts built per second per unit of surface area (radius squared), so a
+/// body of radius 5 builds one every 10 seconds.
+const PRODUCTION_PER_AREA: f32 = 0.004;
+/// Owned bodies start a scenario with this many seconds of production docked.
+const STARTING_STOCK: f32 = 60.;
+
+/// Progress toward a body's next craft.
+#[derive(Component, Default)]
+pub struct Production {
+    /// Fraction of the next craft already built.
+    pub progress: f32,
+}
+
+/// Crafts per second a body builds while it has an owner.
+pub fn production_rate(radius: &Radius) -> f32 {
+    PRODUCTION_PER_AREA * radius.0.powi(2)
+}
+
+fn stock_bodies(
+    mut bodies: Query<(&Name, &Radius, &mut Crafts, Option<&Owner>), With<Body>>,
+    scenario: Res<Scenario>,
+) {
+    for (name, radius, mut crafts, owner) in &mut bodies {
+        let given = scenario
+            .bodies
+            .iter()
+            .find(|spec| spec.name == name.as_str())
+            .and_then(|spec| spec.crafts);
+        crafts.0 = given.unwrap_or(match owner {
+            Some(_) => (production_rate(radius) * STARTING_STOCK).round() as u32,
+            None => 0,
+        });
+    }
+}
+
+/// Only owned bodies build crafts.
+fn produce_crafts(
+    time: Res<Time>,
+    mut bodies: Query<(&Radius, &mut Crafts, &mut Production, Option<&Owner>), With<Body>>,
+) {
+    for (radius, mut crafts, mut production, owner) in &mut bodies {
+        if owner.is_none() {
+            production.progress = 0.;
+            continue;
+        }
+        production.progress += production_rate(radius) * time.delta_secs();
+        if production.progress >= 1. {
+            let built = production.progress.floor();
+            crafts.0 += built as u32;
+            production.progress -= built;
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use crate::bindings::{Action, ActionInput};
+use crate::bodies::Body;
+use crate::crafts::Craft;
+use crate::i18n::{format_number, tr, tr_args};
+use crate::physics::{GravityStep, Motion, TestParticle};
+use crate::ui::UiSystems;
+use bevy::ecs::entity::Entities;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// A performance overlay ([`Action::Profiler`]) with the frame rate, a graph
+/// of recent frame times, how long gravity, motion and the UI take, and how
+/// many entities there are, with a button to copy it all for bug reports.
+pub struct ProfilerPlugin;
+
+impl Plugin for ProfilerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Profiler>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    start(Stage::Gravity).before(GravityStep),
+                    stop(Stage::Gravity).after(GravityStep),
+                    start(Stage::Motion).before(Motion).after(GravityStep),
+                    stop(Stage::Motion).after(Motion),
+                ),
+            )
+            .add_systems(
+                EguiPrimaryContextPass,
+                (
+                    start(Stage::Ui).before(UiSystems),
+                    stop(Stage::Ui).after(UiSystems),
+                    profiler_window.after(UiSystems),
+                ),
+            )
+            .add_systems(Update, toggle_profiler)
+            .add_systems(Last, end_frame);
+    }
+}
+
+/// Frames the graph covers.
+const HISTORY: usize = 240;
+/// How much each frame moves the averaged timings, to steady the numbers.
+const SMOOTHING: f32 = 0.05;
+
+/// Parts of a frame that are timed, as the wall time from just before their
+/// systems to just after, summed over however many ticks ran.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Stage {
+    Gravity,
+    Motion,
+    Ui,
+}
+
+impl Stage {
+    const ALL: [Self; 3] = [Self::Gravity, Self::Motion, Self::Ui];
+
+    fn label(self) -> String {
+        tr(match self {
+            Self::Gravity => "profiler-gravity",
+            Self::Motion => "profiler-motion",
+            Self::Ui => "profiler-ui",
+        })
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct StageTiming {
+    started: Option<Instant>,
+    this_frame: Duration,
+    /// Milliseconds per frame, averaged.
+    average: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct Profiler {
+    pub open: bool,
+    /// Milliseconds per frame, oldest first.
+    frame_times: VecDeque<f32>,
+    stages: [StageTiming; 3],
+}
+
+impl Profiler {
+    fn timing(&mut self, stage: Stage) -> &mut StageTiming {
+        &mut self.stages[stage as usize]
+    }
+
+    /// Milliseconds per frame, averaged over the graph.
+    fn average_frame_time(&self) -> f32 {
+        self.frame_times.iter().sum::<f32>() / self.frame_times.len().max(1) as f32
+    }
+
+    fn worst_frame_time(&self) -> f32 {
+        self.frame_times.iter().copied().fold(0., f32::max)
+    }
+}
+
+/// Starts timing `stage`. Only times while the overlay is open.
+fn start(stage: Stage) -> impl FnMut(ResMut<Profiler>) + Send + Sync + 'static {
+    move |mut profiler| {
+        if profiler.open {
+            profiler.timing(stage).started = Some(Instant::now());
+        }
+    }
+}
+
+fn stop(stage: Stage) -> impl FnMut(ResMut<Profiler>) + Send + Sync + 'static {
+    move |mut profiler| {
+        let timing = profiler.timing(stage);
+        if let Some(started) = timing.started.take() {
+            timing.this_frame += started.elapsed();
+        }
+    }
+}
+
+fn toggle_profiler(mut actions: ActionInput, mut profiler: ResMut<Profiler>) {
+    if actions.just_pressed(Action::Profiler) {
+        profiler.open = !profiler.open;
+    }
+}
+
+fn end_frame(mut profiler: ResMut<Profiler>, time: Res<Time<Real>>) {
+    if !profiler.open {
+        return;
+    }
+    profiler.frame_times.push_back(time.delta_secs() * 1000.);
+    if profiler.frame_times.len() > HISTORY {
+        profiler.frame_times.pop_front();
+    }
+    for timing in &mut profiler.stages {
+        let milliseconds = std::mem::take(&mut timing.this_frame).as_secs_f32() * 1000.;
+        timing.average += (milliseconds - timing.average) * SMOOTHING;
+    }
+}
+
+/// What the overlay shows, as plain text for pasting into a report.
+fn report(profiler: &Profiler, counts: &[(String, usize)]) -> String {
+    let average = profiler.average_frame_time();
+    let mut text = tr_args(
+        "profiler-frames",
+        &[
+            (
+                "fps",
+                format_number(1000. / average.max(f32::EPSILON) as f64, 0).into(),
+            ),
+            ("average", format_number(average as f64, 1).into()),
+            (
+                "worst",
+                format_number(profiler.worst_frame_time() as f64, 1).into(),
+            ),
+        ],
+    );
+    text.push('\n');
+    for stage in Stage::ALL {
+        let timing = profiler.stages[stage as usize];
+        let average = format_number(timing.average as f64, 2);
+        let _ = writeln!(text, "{}: {average} ms", stage.label());
+    }
+    for (label, count) in counts {
+        let _ = writeln!(text, "{label}: {count}");
+    }
+    text
+}
+
+fn profiler_window(
+    mut contexts: EguiContexts,
+    mut profiler: ResMut<Profiler>,
+    bodies: Query<(), With<Body>>,
+    crafts: Query<(), With<Craft>>,
+    particles: Query<(), (With<TestParticle>, Without<Craft>)>,
+    entities: &Entities,
+) {
+    if !profiler.open {
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    let counts = [
+        (tr("bodies"), bodies.iter().count()),
+        (tr("profiler-crafts"), crafts.iter().count()),
+        (tr("profiler-test-particles"), particles.iter().count()),
+        (tr("profiler-entities"), entities.len() as usize),
+    ];
+    let text = report(&profiler, &counts);
+    let mut open = profiler.open;
+    egui::Window::new(tr("profiler"))
+        .open(&mut open)
+        .anchor(egui::Align2::RIGHT_TOP, [-8., 40.])
+        .resizable(false)
+        .default_width(260.)
+        .show(ctx, |ui| {
+            ui.monospace(&text);
+            let points: Vec<_> = profiler
+                .frame_times
+                .iter()
+                .enumerate()
+                .map(|(index, &milliseconds)| [index as f64, milliseconds as f64])
+                .collect();
+            egui_plot::Plot::new("frame_times")
+                .height(80.)
+                .allow_drag(false)
+                .allow_zoom(false)
+                .allow_scroll(false)
+                .show_x(false)
+                .include_y(0.)
+                .include_x(HISTORY as f64)
+                .x_axis_formatter(|_, _| String::new())
+                .y_axis_formatter(|mark, _| format!("{:.0} ms", mark.value))
+                .show(ui, |plot| {
+                    plot.line(egui_plot::Line::new(tr("profiler-frame-time"), points));
+                });
+            if ui.button(tr("copy")).clicked() {
+                ui.ctx().copy_text(text.clone());
+            }
+        });
+    profiler.open = open;
+}
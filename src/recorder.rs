@@ -0,0 +1,232 @@
+use crate::app_state::AppState;
+use crate::bodies::{Body, ScenarioSystems};
+use crate::persistence::{next_free_path, state_directory};
+use crate::physics::{Mass, PhysicsConstants, SimPosition, SimVelocity, pair_potential_energy};
+use bevy::prelude::*;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Writes the state of every body after each physics tick, when a [`Recorder`]
+/// resource is present (see `--record`), and samples [`Telemetry`] for export.
+pub struct RecorderPlugin;
+
+impl Plugin for RecorderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Telemetry>()
+            .add_systems(
+                FixedPostUpdate,
+                (
+                    record_bodies.run_if(resource_exists::<Recorder>),
+                    sample_telemetry,
+                )
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(Update, reset_telemetry.in_set(ScenarioSystems::Initialize))
+            .add_systems(Last, export_telemetry_on_exit);
+    }
+}
+
+/// CSV sink with one row per body per tick.
+#[derive(Resource)]
+pub struct Recorder {
+    writer: BufWriter<File>,
+    tick: u64,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "tick,time,body,x,y,vx,vy")?;
+        Ok(Self { writer, tick: 0 })
+    }
+}
+
+fn record_bodies(
+    mut commands: Commands,
+    mut recorder: ResMut<Recorder>,
+    bodies: Query<(&Name, &SimPosition, &SimVelocity), With<Body>>,
+    time: Res<Time>,
+) {
+    let tick = recorder.tick;
+    let result = bodies.iter().try_for_each(|(name, position, velocity)| {
+        writeln!(
+            recorder.writer,
+            "{tick},{},{name},{},{},{},{}",
+            time.elapsed_secs_f64(),
+            position.0.x,
+            position.0.y,
+            velocity.0.x,
+            velocity.0.y,
+        )
+    });
+    recorder.tick += 1;
+
+    if let Err(err) = result {
+        log::error!("Stopped recording: {err}");
+        commands.remove_resource::<Recorder>();
+    }
+}
+
+/// Every body's motion and energy, sampled at an interval of simulated time
+/// while recording and kept in memory until exported as CSV or JSON.
+#[derive(Resource)]
+pub struct Telemetry {
+    pub recording: bool,
+    /// Simulated seconds between samples.
+    pub interval: f64,
+    /// Exported to when the app exits (see `--record-telemetry`).
+    pub output: Option<PathBuf>,
+    samples: Vec<TelemetrySample>,
+    /// Simulated seconds since the scenario loaded.
+    elapsed: f64,
+    next_sample: f64,
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Self {
+            recording: false,
+            interval: 0.1,
+            output: None,
+            samples: Vec::new(),
+            elapsed: 0.,
+            next_sample: 0.,
+        }
+    }
+}
+
+/// One body at one instant.
+#[derive(Serialize, Clone, Debug)]
+pub struct TelemetrySample {
+    /// Simulated seconds since the scenario loaded.
+    pub time: f64,
+    pub body: String,
+    pub x: f64,
+    pub y: f64,
+    pub vx: f64,
+    pub vy: f64,
+    pub kinetic: f64,
+    /// Potential energy with every other body, so summing it over bodies
+    /// counts each pair twice.
+    pub potential: f64,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TelemetryError {
+    #[error("could not write telemetry: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not write telemetry: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("telemetry can only be exported as .csv or .json")]
+    Format,
+}
+
+impl Telemetry {
+    pub fn sample_count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Writes every sample so far, as CSV or JSON by the file extension.
+    pub fn export(&self, path: &Path) -> Result<(), TelemetryError> {
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        if !matches!(extension, Some("csv" | "json")) {
+            return Err(TelemetryError::Format);
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut writer = BufWriter::new(File::create(path)?);
+        if extension == Some("json") {
+            serde_json::to_writer(&mut writer, &self.samples)?;
+        } else {
+            writeln!(writer, "time,body,x,y,vx,vy,kinetic,potential")?;
+            for sample in &self.samples {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{},{},{}",
+                    sample.time,
+                    sample.body,
+                    sample.x,
+                    sample.y,
+                    sample.vx,
+                    sample.vy,
+                    sample.kinetic,
+                    sample.potential,
+                )?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Exports to the next free file of the given extension in the telemetry
+    /// folder next to the saved state, returning where it went.
+    pub fn export_numbered(&self, extension: &str) -> Result<PathBuf, TelemetryError> {
+        let directory = state_directory().with_file_name("telemetry");
+        let path = next_free_path(&directory, "telemetry", extension);
+        self.export(&path)?;
+        Ok(path)
+    }
+}
+
+fn reset_telemetry(mut telemetry: ResMut<Telemetry>) {
+    telemetry.samples.clear();
+    telemetry.elapsed = 0.;
+    telemetry.next_sample = 0.;
+}
+
+fn sample_telemetry(
+    mut telemetry: ResMut<Telemetry>,
+    bodies: Query<(Entity, &Name, &SimPosition, &SimVelocity, &Mass), With<Body>>,
+    constants: Res<PhysicsConstants>,
+    time: Res<Time>,
+) {
+    telemetry.elapsed += time.delta_secs_f64();
+    if !telemetry.recording || telemetry.elapsed < telemetry.next_sample {
+        return;
+    }
+    let time = telemetry.elapsed;
+    telemetry.next_sample = time + telemetry.interval;
+
+    let softening = constants.softening as f64;
+    let g = constants.g as f64;
+    for (body, name, position, velocity, mass) in &bodies {
+        let mass = mass.0 as f64;
+        let potential = bodies
+            .iter()
+            .filter(|(other, ..)| *other != body)
+            .map(|(_, _, other_position, _, other_mass)| {
+                pair_potential_energy(
+                    other_position.0 - position.0,
+                    mass * other_mass.0 as f64,
+                    softening,
+                    g,
+                )
+            })
+            .sum();
+        telemetry.samples.push(TelemetrySample {
+            time,
+            body: name.to_string(),
+            x: position.0.x,
+            y: position.0.y,
+            vx: velocity.0.x,
+            vy: velocity.0.y,
+            kinetic: 0.5 * mass * velocity.0.length_squared(),
+            potential,
+        });
+    }
+}
+
+fn export_telemetry_on_exit(mut exits: EventReader<AppExit>, telemetry: Res<Telemetry>) {
+    if exits.read().next().is_none() {
+        return;
+    }
+    if let Some(path) = &telemetry.output {
+        match telemetry.export(path) {
+            Ok(()) => log::info!("Wrote telemetry to {}", path.display()),
+            Err(err) => log::error!("{err}"),
+        }
+    }
+}
@@ -0,0 +1,363 @@
+use crate::app_state::AppState;
+use crate::atmosphere::Atmosphere;
+use crate::bodies::{Appearance, Body, Fill, Radius, Spin, Sunward};
+use crate::comets::CometTail;
+use crate::crafts::Craft;
+use crate::factions::Owner;
+use crate::selection::SelectedCraft;
+use crate::settings::Settings;
+use crate::ui::{PlotViewport, Trail, ViewSettings};
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::transform::TransformSystem;
+use bevy::window::PrimaryWindow;
+use bevy_egui::egui::Color32;
+use bevy_persistent::prelude::*;
+use std::f32::consts::FRAC_PI_2;
+
+/// Draws bodies as meshes, and crafts and trails as gizmos, with the Bevy
+/// camera under the see-through egui plot.
+///
+/// Only built with the `mesh_render` feature while it replaces drawing with
+/// `egui_plot`. The plot still handles input and overlays, and the camera
+/// follows whatever part of space it shows.
+pub struct RendererPlugin;
+
+impl Plugin for RendererPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ClearColor(Color::BLACK))
+            .add_systems(Startup, create_body_mesh)
+            .add_systems(
+                Update,
+                (
+                    attach_body_meshes,
+                    resize_body_meshes,
+                    recolor_body_meshes,
+                    spin_body_meshes,
+                    turn_night_sides,
+                    (draw_trails, draw_comet_tails, draw_crafts).run_if(AppState::in_game),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                follow_plot.before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// Craft markers are drawn this many egui points across, whatever the zoom.
+const CRAFT_MARKER: f64 = 4.;
+const SELECTED_CRAFT_MARKER: f64 = 6.;
+
+/// A unit circle, scaled to each body's radius, and the half of it darkened
+/// on the night side.
+#[derive(Resource)]
+struct BodyMesh {
+    disc: Handle<Mesh>,
+    night_side: Handle<Mesh>,
+    shade: Handle<ColorMaterial>,
+}
+
+/// The child entity showing a body's surface.
+#[derive(Component)]
+pub struct BodyShape;
+
+/// A child entity drawing a body's glow, rings or atmosphere, scaled to its
+/// radius.
+#[derive(Component)]
+pub struct BodyDecoration;
+
+/// The child entity darkening the side of a body facing away from the star.
+#[derive(Component)]
+pub struct NightSide;
+
+/// How dark night sides are, from 0 for not at all to 1 for black.
+const NIGHT_SHADE: f32 = 0.55;
+
+/// Triangles around the edge of a circle, and from the edge in to the middle.
+const DISC_RESOLUTION: u32 = 64;
+
+/// Converts the egui colors used throughout into Bevy's.
+pub fn bevy_color(color: Color32) -> Color {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    Color::srgba_u8(r, g, b, a)
+}
+
+fn create_body_mesh(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.insert_resource(BodyMesh {
+        disc: meshes.add(Circle::new(1.).mesh().resolution(DISC_RESOLUTION)),
+        // Faces +y until turned away from the star
+        night_side: meshes.add(
+            CircularSector::new(1., FRAC_PI_2)
+                .mesh()
+                .resolution(DISC_RESOLUTION / 2),
+        ),
+        shade: materials.add(Color::BLACK.with_alpha(NIGHT_SHADE)),
+    });
+}
+
+/// A disc shaded from `center` out to `rim`, with texture coordinates
+/// spanning the square around it.
+fn disc(radius: f32, center: Color, rim: Color) -> Mesh {
+    let mut positions = vec![[0., 0., 0.]];
+    let mut uvs = vec![[0.5, 0.5]];
+    let mut colors = vec![center.to_linear().to_f32_array()];
+    for i in 0..DISC_RESOLUTION {
+        let (sin, cos) = (i as f32 / DISC_RESOLUTION as f32 * std::f32::consts::TAU).sin_cos();
+        positions.push([cos * radius, sin * radius, 0.]);
+        uvs.push([0.5 + cos / 2., 0.5 - sin / 2.]);
+        colors.push(rim.to_linear().to_f32_array());
+    }
+    let indices = (1..=DISC_RESOLUTION)
+        .flat_map(|i| [0, i, i % DISC_RESOLUTION + 1])
+        .collect();
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+type BodyMeshData = (
+    Entity,
+    &'static Radius,
+    &'static Fill,
+    Option<&'static Appearance>,
+    Option<&'static Atmosphere>,
+);
+
+fn attach_body_meshes(
+    mut commands: Commands,
+    bodies: Query<BodyMeshData, Added<Body>>,
+    mesh: Res<BodyMesh>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (body, radius, fill, appearance, atmosphere) in &bodies {
+        let appearance = appearance.cloned().unwrap_or_default();
+        let fill = bevy_color(fill.color());
+        let scale = Transform::from_scale(Vec3::splat(radius.0));
+        let surface = match appearance.gradient {
+            Some([r, g, b]) => meshes.add(disc(1., fill, Color::srgb_u8(r, g, b))),
+            None => mesh.disc.clone(),
+        };
+        // Textures and gradients carry their own colors
+        let tint = if appearance.texture.is_some() || appearance.gradient.is_some() {
+            Color::WHITE
+        } else {
+            fill
+        };
+        let material = ColorMaterial {
+            color: tint,
+            texture: appearance.texture.map(|path| asset_server.load(path)),
+            ..default()
+        };
+        let mut entity = commands.entity(body);
+        entity
+            .insert(Visibility::default())
+            .with_child((
+                BodyShape,
+                Mesh2d(surface),
+                MeshMaterial2d(materials.add(material)),
+                scale,
+            ))
+            .with_child((
+                NightSide,
+                Mesh2d(mesh.night_side.clone()),
+                MeshMaterial2d(mesh.shade.clone()),
+                scale.with_translation(Vec3::Z * 0.05),
+                Visibility::Hidden,
+            ));
+        if let Some(glow) = appearance.glow {
+            let halo = disc(1. + glow, fill.with_alpha(0.6), fill.with_alpha(0.));
+            entity.with_child((
+                BodyDecoration,
+                Mesh2d(meshes.add(halo)),
+                MeshMaterial2d(materials.add(Color::WHITE)),
+                scale.with_translation(Vec3::Z * -0.1),
+            ));
+        }
+        if let Some(atmosphere) = atmosphere {
+            let air = Circle::new(atmosphere.radius)
+                .mesh()
+                .resolution(DISC_RESOLUTION);
+            entity.with_child((
+                BodyDecoration,
+                Mesh2d(meshes.add(air)),
+                MeshMaterial2d(materials.add(fill.with_alpha(0.12))),
+                scale.with_translation(Vec3::Z * -0.2),
+            ));
+        }
+        if let Some(rings) = appearance.rings {
+            let [r, g, b] = rings.color;
+            entity.with_child((
+                BodyDecoration,
+                Mesh2d(meshes.add(Annulus::new(rings.inner, rings.outer))),
+                MeshMaterial2d(materials.add(Color::srgba_u8(r, g, b, 150))),
+                scale.with_translation(Vec3::Z * 0.1),
+            ));
+        }
+    }
+}
+
+type BodyParts = Or<(With<BodyShape>, With<BodyDecoration>, With<NightSide>)>;
+
+fn resize_body_meshes(
+    bodies: Query<(&Radius, &Children), Changed<Radius>>,
+    mut shapes: Query<&mut Transform, BodyParts>,
+) {
+    for (radius, children) in &bodies {
+        let mut shapes = shapes.iter_many_mut(children);
+        while let Some(mut transform) = shapes.fetch_next() {
+            transform.scale = Vec3::splat(radius.0);
+        }
+    }
+}
+
+/// Recolors bodies whose fill or the color scheme changed.
+fn recolor_body_meshes(
+    bodies: Query<(Ref<Fill>, &Children), Without<Appearance>>,
+    shapes: Query<&MeshMaterial2d<ColorMaterial>, With<BodyShape>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    settings: Res<Persistent<Settings>>,
+) {
+    for (fill, children) in &bodies {
+        if !fill.is_changed() && !settings.is_changed() {
+            continue;
+        }
+        for material in shapes.iter_many(children) {
+            if let Some(material) = materials.get_mut(&material.0) {
+                material.color = bevy_color(fill.color());
+            }
+        }
+    }
+}
+
+/// Turns body surfaces with their spin, so textures rotate too.
+fn spin_body_meshes(
+    bodies: Query<(&Spin, &Children), Changed<Spin>>,
+    mut shapes: Query<&mut Transform, With<BodyShape>>,
+) {
+    for (spin, children) in &bodies {
+        let mut shapes = shapes.iter_many_mut(children);
+        while let Some(mut transform) = shapes.fetch_next() {
+            transform.rotation = Quat::from_rotation_z(spin.angle);
+        }
+    }
+}
+
+fn turn_night_sides(
+    bodies: Query<(&Sunward, &Children)>,
+    mut night_sides: Query<(&mut Transform, &mut Visibility), With<NightSide>>,
+    view: Res<ViewSettings>,
+) {
+    for (sunward, children) in &bodies {
+        let mut night_sides = night_sides.iter_many_mut(children);
+        while let Some((mut transform, mut visibility)) = night_sides.fetch_next() {
+            let Some(sunward) = sunward.0.filter(|_| view.night_sides) else {
+                visibility.set_if_neq(Visibility::Hidden);
+                continue;
+            };
+            visibility.set_if_neq(Visibility::Inherited);
+            transform.rotation = Quat::from_rotation_z((-sunward).to_angle() - FRAC_PI_2);
+        }
+    }
+}
+
+fn draw_trails(
+    mut gizmos: Gizmos,
+    trails: Query<(&Trail, Option<&Fill>, Option<&Owner>)>,
+    view: Res<ViewSettings>,
+    settings: Res<Persistent<Settings>>,
+) {
+    if !view.trails || settings.trail_length <= 0. {
+        return;
+    }
+    for (trail, fill, owner) in &trails {
+        let color = fill
+            .map(Fill::color)
+            .unwrap_or_else(|| owner.map_or(Color32::LIGHT_GRAY, |owner| owner.0.color()));
+        gizmos.linestrip_2d(
+            trail.0.iter().map(|&[x, y]| Vec2::new(x as f32, y as f32)),
+            bevy_color(color.gamma_multiply(0.4)),
+        );
+    }
+}
+
+/// Lines fanning out from comets along their tails.
+fn draw_comet_tails(mut gizmos: Gizmos, tails: Query<(&Transform, &Radius, &Fill, &CometTail)>) {
+    for (transform, radius, fill, tail) in &tails {
+        let center = transform.translation.truncate();
+        let across = tail.0.perp().normalize_or_zero() * radius.0;
+        let color = bevy_color(fill.color().gamma_multiply(0.35));
+        for offset in [-1., -0.5, 0., 0.5, 1.] {
+            gizmos.line_2d(
+                center + across * offset,
+                center + tail.0 + across * offset * 2.,
+                color,
+            );
+        }
+    }
+}
+
+fn draw_crafts(
+    mut gizmos: Gizmos,
+    crafts: Query<(Entity, &Transform, Option<&Owner>), With<Craft>>,
+    selected: Res<SelectedCraft>,
+    viewport: Res<PlotViewport>,
+) {
+    for (craft, transform, owner) in &crafts {
+        let (color, size) = if selected.0 == Some(craft) {
+            (Color32::YELLOW, SELECTED_CRAFT_MARKER)
+        } else {
+            let color = owner.map_or(Color32::LIGHT_GRAY, |owner| owner.0.color());
+            (color, CRAFT_MARKER)
+        };
+        let radius = size / 2. * viewport.world_per_point;
+        gizmos.circle_2d(
+            transform.translation.truncate(),
+            radius as f32,
+            bevy_color(color),
+        );
+    }
+}
+
+/// Moves and zooms the camera so the world lines up with the plot on top.
+fn follow_plot(
+    viewport: Res<PlotViewport>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&mut Transform, &mut Projection), With<Camera2d>>,
+) {
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    if viewport.world_per_point <= 0. {
+        return;
+    }
+    // egui points are usually, but not always, logical pixels
+    let logical_per_point = (viewport.pixels_per_point / window.scale_factor()) as f64;
+    let window_center = window.size().as_dvec2() / 2. / logical_per_point;
+    let rect_center = viewport.rect.center();
+    // Screen y points down, world y up
+    let offset = DVec2::new(
+        rect_center.x as f64 - window_center.x,
+        window_center.y - rect_center.y as f64,
+    );
+    let center = viewport.center - offset * viewport.world_per_point;
+    for (mut transform, mut projection) in &mut cameras {
+        transform.translation = center.as_vec2().extend(transform.translation.z);
+        if let Projection::Orthographic(orthographic) = &mut *projection {
+            orthographic.scale = (viewport.world_per_point / logical_per_point) as f32;
+        }
+    }
+}
@@ -0,0 +1,615 @@
+use crate::app_state::AppState;
+use crate::autopilot::Autopilot;
+use crate::bodies::{Body, ScenarioSystems};
+use crate::crafts::{BurnCraft, Craft, CraftOrders, LaunchCraft};
+use crate::engines::Throttle;
+use crate::factions::{CombatRules, Faction, Owner, Players};
+use crate::maneuver::ManeuverNode;
+use crate::persistence::{read_file, write_file};
+use crate::physics::{PhysicsConstants, SimPosition, SimRng};
+use crate::sails::SolarSail;
+use crate::scenario::Scenario;
+use crate::target::Target;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+/// Logs every order given during a game so it can be exported as a
+/// [`Replay`], and plays replays back on the fixed-step simulation.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplayLog>()
+            .add_systems(Update, start_log.in_set(ScenarioSystems::Initialize))
+            .add_systems(
+                FixedFirst,
+                play_back.run_if(resource_exists::<Playback>.and(in_state(AppState::Playing))),
+            )
+            .add_systems(
+                FixedPreUpdate,
+                (log_rules, log_launches, log_burns, log_craft_plans)
+                    .before(CraftOrders)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(
+                FixedLast,
+                (number_crafts, count_tick)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+/// Bumped whenever old replays would no longer play back the same.
+pub const REPLAY_VERSION: u32 = 2;
+
+/// A shareable record of a game: the scenario and seed it started from, and
+/// every order given after, stamped with the physics tick it took effect on.
+///
+/// Playing the orders back on the same fixed timestep reproduces the game.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Replay {
+    pub version: u32,
+    /// [`scenario_hash`] of the scenario, to catch edited replays.
+    pub scenario_hash: String,
+    pub seed: u64,
+    /// Physics timestep in seconds.
+    pub timestep: f64,
+    pub scenario: Scenario,
+    #[serde(default)]
+    pub orders: Vec<TimedOrder>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TimedOrder {
+    /// Physics ticks since the scenario loaded.
+    pub tick: u64,
+    #[serde(flatten)]
+    pub order: Order,
+}
+
+/// Anything a player can do that changes how the game plays out. Bodies are
+/// referred to by name and crafts by launch number, counting from 0.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "order", rename_all = "snake_case")]
+pub enum Order {
+    Launch {
+        from: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        destination: Option<String>,
+        direction: [f32; 2],
+        speed: f32,
+    },
+    Burn {
+        craft: u32,
+        delta_v: [f32; 2],
+    },
+    /// Sets or clears a craft's autopilot.
+    Autopilot {
+        craft: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        autopilot: Option<AutopilotOrder>,
+    },
+    /// Sets or clears a craft's maneuver node.
+    Maneuver {
+        craft: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        node: Option<NodeOrder>,
+    },
+    /// Sets a craft's engine throttle.
+    Throttle {
+        craft: u32,
+        throttle: Throttle,
+    },
+    /// Deploys or retracts a craft's solar sail.
+    Sail {
+        craft: u32,
+        deployed: bool,
+    },
+    Physics(PhysicsConstants),
+    Combat(CombatRules),
+    /// Whether the computer plays the opponent.
+    Opponent {
+        ai: bool,
+    },
+}
+
+/// An [`Autopilot`] with bodies named.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AutopilotOrder {
+    HoldOrbit,
+    Intercept(String),
+    ReturnHome,
+    StationKeep { body: String, point: usize },
+}
+
+/// A [`ManeuverNode`] timed from the tick it was placed on.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct NodeOrder {
+    /// Seconds until the burn.
+    pub delay: f64,
+    pub prograde: f32,
+    pub radial: f32,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("could not read or write replay: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse replay: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("could not write replay: {0}")]
+    Write(#[from] toml::ser::Error),
+    #[error("replay is version {0}, but this build plays version {REPLAY_VERSION}")]
+    Version(u32),
+    #[error("replay scenario does not match its hash")]
+    Hash,
+}
+
+/// FNV-1a over the scenario's TOML, which is stable across platforms and
+/// builds, unlike the standard library's hasher.
+pub fn scenario_hash(scenario: &Scenario) -> Result<String, toml::ser::Error> {
+    let hash = toml::to_string(scenario)?
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+    Ok(format!("{hash:016x}"))
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        Self::parse(&read_file(path)?)
+    }
+
+    /// Reads a replay from the contents of a file, checking it can be played.
+    pub fn parse(text: &str) -> Result<Self, ReplayError> {
+        let replay: Self = toml::from_str(text)?;
+        if replay.version != REPLAY_VERSION {
+            return Err(ReplayError::Version(replay.version));
+        }
+        if scenario_hash(&replay.scenario)? != replay.scenario_hash {
+            return Err(ReplayError::Hash);
+        }
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        write_file(path, &toml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// The replay of the game in progress, up to the current tick.
+#[derive(Resource, Default)]
+pub struct ReplayLog {
+    replay: Option<Replay>,
+    /// Physics ticks since the scenario loaded.
+    tick: u64,
+    next_craft: u32,
+    /// The last settings logged, so only changes are.
+    physics: Option<PhysicsConstants>,
+    combat: Option<CombatRules>,
+    ai_opponent: Option<bool>,
+}
+
+impl ReplayLog {
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Writes the game so far to the replays folder next to the saved state,
+    /// returning where it went.
+    pub fn export(&self) -> Result<PathBuf, ReplayError> {
+        let Some(replay) = &self.replay else {
+            return Err(std::io::Error::other("no game to export").into());
+        };
+        let name = format!("{}-{}.toml", &replay.scenario_hash[..8], self.tick);
+        let path = crate::persistence::state_directory()
+            .with_file_name("replays")
+            .join(name);
+        replay.save(&path)?;
+        Ok(path)
+    }
+
+    fn push(&mut self, order: Order) {
+        if let Some(replay) = &mut self.replay {
+            replay.orders.push(TimedOrder {
+                tick: self.tick,
+                order,
+            });
+        }
+    }
+}
+
+/// A replay being played back. Orders still run as they would have live, so
+/// the player can take over at any point.
+#[derive(Resource)]
+pub struct Playback {
+    seed: u64,
+    orders: VecDeque<TimedOrder>,
+    /// Whether its scenario has loaded. Loading another ends the playback.
+    started: bool,
+}
+
+impl Playback {
+    /// Loads the replay's scenario and settings to play it back from the
+    /// start.
+    pub fn start(replay: Replay, world: &mut World) {
+        world.insert_resource(replay.scenario);
+        world.insert_resource(Time::<Fixed>::from_seconds(replay.timestep));
+        world.insert_resource(Self {
+            seed: replay.seed,
+            orders: replay.orders.into(),
+            started: false,
+        });
+    }
+
+    /// Orders not yet played.
+    pub fn remaining(&self) -> usize {
+        self.orders.len()
+    }
+}
+
+/// Crafts' launch numbers within a game, for referring to them in orders.
+#[derive(Component, Clone, Copy)]
+#[require(LoggedPlans)]
+pub struct LaunchNumber(pub u32);
+
+/// A craft's plans as last logged.
+#[derive(Component, Default)]
+struct LoggedPlans {
+    autopilot: Option<Autopilot>,
+    node: Option<ManeuverNode>,
+    throttle: Option<Throttle>,
+    sail: bool,
+}
+
+/// Starts a new log, reseeding the simulation so the log can record the seed.
+fn start_log(
+    mut commands: Commands,
+    mut log: ResMut<ReplayLog>,
+    mut rng: ResMut<SimRng>,
+    playback: Option<ResMut<Playback>>,
+    scenario: Res<Scenario>,
+    time: Res<Time<Fixed>>,
+) {
+    let seed = match playback {
+        Some(mut playback) if !playback.started => {
+            playback.started = true;
+            playback.seed
+        }
+        playback => {
+            if playback.is_some() {
+                commands.remove_resource::<Playback>();
+            }
+            // Small enough for TOML's signed integers
+            rng.0.random::<u32>() as u64
+        }
+    };
+    *rng = SimRng::seeded(seed);
+    let replay = scenario_hash(&scenario)
+        .inspect_err(|err| log::error!("Cannot record a replay: {err}"))
+        .ok()
+        .map(|scenario_hash| Replay {
+            version: REPLAY_VERSION,
+            scenario_hash,
+            seed,
+            timestep: time.timestep().as_secs_f64(),
+            scenario: scenario.clone(),
+            orders: Vec::new(),
+        });
+    *log = ReplayLog {
+        replay,
+        ..default()
+    };
+}
+
+fn count_tick(mut log: ResMut<ReplayLog>) {
+    log.tick += 1;
+}
+
+/// Numbers crafts launched this tick. Crafts launched on the same tick are
+/// numbered by position, which doesn't depend on spawn order.
+type Unnumbered = (With<Craft>, Without<LaunchNumber>);
+
+fn number_crafts(
+    mut commands: Commands,
+    crafts: Query<(Entity, &SimPosition), Unnumbered>,
+    mut log: ResMut<ReplayLog>,
+) {
+    let mut new: Vec<_> = crafts.iter().collect();
+    new.sort_by(|(_, a), (_, b)| a.0.x.total_cmp(&b.0.x).then(a.0.y.total_cmp(&b.0.y)));
+    for (craft, _) in new {
+        commands.entity(craft).insert(LaunchNumber(log.next_craft));
+        log.next_craft += 1;
+    }
+}
+
+/// Whether orders for something owned this way come from a person rather than
+/// the simulation's own opponent, which plays back by itself.
+fn from_player(players: &Players, owner: Option<&Owner>) -> bool {
+    !players.ai_opponent() || owner.is_none_or(|owner| owner.0 != Faction::Opponent)
+}
+
+fn log_rules(
+    mut log: ResMut<ReplayLog>,
+    physics: Res<PhysicsConstants>,
+    combat: Res<CombatRules>,
+    players: Res<Players>,
+) {
+    if log.physics.is_some_and(|logged| logged != *physics) {
+        log.push(Order::Physics(*physics));
+    }
+    if log.combat.is_some_and(|logged| logged != *combat) {
+        log.push(Order::Combat(*combat));
+    }
+    if log.ai_opponent != Some(players.ai_opponent()) {
+        log.push(Order::Opponent {
+            ai: players.ai_opponent(),
+        });
+    }
+    log.physics = Some(*physics);
+    log.combat = Some(*combat);
+    log.ai_opponent = Some(players.ai_opponent());
+}
+
+fn log_launches(
+    mut launches: EventReader<LaunchCraft>,
+    bodies: Query<(&Name, Option<&Owner>), With<Body>>,
+    target: Res<Target>,
+    players: Res<Players>,
+    mut log: ResMut<ReplayLog>,
+) {
+    for launch in launches.read() {
+        let Ok((from, owner)) = bodies.get(launch.from) else {
+            continue;
+        };
+        if launch.scripted || !from_player(&players, owner) {
+            continue;
+        }
+        // The target only matters to the launch, so log what it resolved to
+        let destination = launch
+            .destination
+            .or(target.0)
+            .and_then(|body| bodies.get(body).ok())
+            .map(|(name, _)| name.to_string());
+        log.push(Order::Launch {
+            from: from.to_string(),
+            destination,
+            direction: launch.direction.to_array(),
+            speed: launch.speed,
+        });
+    }
+}
+
+fn log_burns(
+    mut burns: EventReader<BurnCraft>,
+    crafts: Query<(&LaunchNumber, Option<&Owner>)>,
+    players: Res<Players>,
+    mut log: ResMut<ReplayLog>,
+) {
+    for burn in burns.read() {
+        if let Ok((number, owner)) = crafts.get(burn.craft)
+            && from_player(&players, owner)
+        {
+            log.push(Order::Burn {
+                craft: number.0,
+                delta_v: burn.delta_v.to_array(),
+            });
+        }
+    }
+}
+
+type CraftPlanData = (
+    &'static LaunchNumber,
+    &'static mut LoggedPlans,
+    Option<&'static Autopilot>,
+    Option<&'static ManeuverNode>,
+    Option<&'static Throttle>,
+    Has<SolarSail>,
+    Option<&'static Owner>,
+);
+
+/// Logs autopilot and maneuver node changes. Ones the simulation makes itself
+/// get logged too, which is harmless since playing them back changes nothing.
+fn log_craft_plans(
+    mut crafts: Query<CraftPlanData>,
+    names: Query<&Name, With<Body>>,
+    players: Res<Players>,
+    time: Res<Time>,
+    mut log: ResMut<ReplayLog>,
+) {
+    let name = |body: Entity| names.get(body).map(ToString::to_string).ok();
+    for (number, mut logged, autopilot, node, throttle, sail, owner) in &mut crafts {
+        if logged.autopilot.as_ref() != autopilot && from_player(&players, owner) {
+            let order = autopilot.and_then(|autopilot| match *autopilot {
+                Autopilot::HoldOrbit => Some(AutopilotOrder::HoldOrbit),
+                Autopilot::Intercept(body) => name(body).map(AutopilotOrder::Intercept),
+                Autopilot::ReturnHome => Some(AutopilotOrder::ReturnHome),
+                Autopilot::StationKeep { body, point } => {
+                    name(body).map(|body| AutopilotOrder::StationKeep { body, point })
+                }
+            });
+            log.push(Order::Autopilot {
+                craft: number.0,
+                autopilot: order,
+            });
+        }
+        if logged.node.as_ref() != node && from_player(&players, owner) {
+            log.push(Order::Maneuver {
+                craft: number.0,
+                node: node.map(|node| NodeOrder {
+                    delay: node.time - time.elapsed_secs_f64(),
+                    prograde: node.prograde,
+                    radial: node.radial,
+                }),
+            });
+        }
+        if let Some(throttle) = throttle
+            && logged.throttle.as_ref() != Some(throttle)
+            && from_player(&players, owner)
+        {
+            log.push(Order::Throttle {
+                craft: number.0,
+                throttle: *throttle,
+            });
+        }
+        if logged.sail != sail && from_player(&players, owner) {
+            log.push(Order::Sail {
+                craft: number.0,
+                deployed: sail,
+            });
+        }
+        logged.autopilot = autopilot.copied();
+        logged.node = node.copied();
+        logged.throttle = throttle.copied();
+        logged.sail = sail;
+    }
+}
+
+/// What replayed orders act on besides the crafts themselves.
+#[derive(SystemParam)]
+struct Orders<'w> {
+    launches: EventWriter<'w, LaunchCraft>,
+    burns: EventWriter<'w, BurnCraft>,
+    physics: ResMut<'w, PhysicsConstants>,
+    combat: ResMut<'w, CombatRules>,
+    players: ResMut<'w, Players>,
+}
+
+/// Gives this tick's orders the same way the player gave them.
+fn play_back(
+    mut commands: Commands,
+    mut playback: ResMut<Playback>,
+    log: Res<ReplayLog>,
+    bodies: Query<(Entity, &Name), With<Body>>,
+    crafts: Query<(Entity, &LaunchNumber)>,
+    mut orders: Orders,
+    time: Res<Time>,
+) {
+    let body = |name: &str| {
+        bodies
+            .iter()
+            .find(|(_, body_name)| body_name.as_str() == name)
+            .map(|(entity, _)| entity)
+    };
+    let craft = |number: u32| {
+        crafts
+            .iter()
+            .find(|(_, craft_number)| craft_number.0 == number)
+            .map(|(entity, _)| entity)
+    };
+    while playback
+        .orders
+        .front()
+        .is_some_and(|order| order.tick <= log.tick)
+    {
+        let Some(TimedOrder { order, .. }) = playback.orders.pop_front() else {
+            break;
+        };
+        match order {
+            Order::Launch {
+                from,
+                destination,
+                direction,
+                speed,
+            } => {
+                if let Some(from) = body(&from) {
+                    orders.launches.write(LaunchCraft {
+                        from,
+                        destination: destination.as_deref().and_then(body),
+                        direction: Vec2::from_array(direction),
+                        speed,
+                        scripted: false,
+                    });
+                }
+            }
+            Order::Burn {
+                craft: number,
+                delta_v,
+            } => {
+                if let Some(craft) = craft(number) {
+                    orders.burns.write(BurnCraft {
+                        craft,
+                        delta_v: Vec2::from_array(delta_v),
+                    });
+                }
+            }
+            Order::Autopilot {
+                craft: number,
+                autopilot,
+            } => {
+                let Some(craft) = craft(number) else {
+                    continue;
+                };
+                let autopilot = autopilot.and_then(|autopilot| match autopilot {
+                    AutopilotOrder::HoldOrbit => Some(Autopilot::HoldOrbit),
+                    AutopilotOrder::Intercept(name) => body(&name).map(Autopilot::Intercept),
+                    AutopilotOrder::ReturnHome => Some(Autopilot::ReturnHome),
+                    AutopilotOrder::StationKeep { body: name, point } => {
+                        body(&name).map(|body| Autopilot::StationKeep { body, point })
+                    }
+                });
+                match autopilot {
+                    Some(autopilot) => commands.entity(craft).insert(autopilot),
+                    None => commands.entity(craft).remove::<Autopilot>(),
+                };
+            }
+            Order::Maneuver {
+                craft: number,
+                node,
+            } => {
+                let Some(craft) = craft(number) else {
+                    continue;
+                };
+                match node {
+                    Some(node) => commands.entity(craft).insert(ManeuverNode {
+                        time: time.elapsed_secs_f64() + node.delay,
+                        prograde: node.prograde,
+                        radial: node.radial,
+                    }),
+                    None => commands.entity(craft).remove::<ManeuverNode>(),
+                };
+            }
+            Order::Throttle {
+                craft: number,
+                throttle,
+            } => {
+                if let Some(craft) = craft(number) {
+                    commands.entity(craft).insert(throttle);
+                }
+            }
+            Order::Sail {
+                craft: number,
+                deployed,
+            } => {
+                let Some(craft) = craft(number) else {
+                    continue;
+                };
+                if deployed {
+                    commands.entity(craft).insert(SolarSail);
+                } else {
+                    commands.entity(craft).remove::<SolarSail>();
+                }
+            }
+            Order::Physics(constants) => *orders.physics = constants,
+            Order::Combat(rules) => *orders.combat = rules,
+            Order::Opponent { ai } => {
+                if ai != orders.players.ai_opponent() {
+                    // Whoever gave the opponent's orders live, they're in the log
+                    orders.players.set_hot_seat(!ai);
+                }
+            }
+        }
+    }
+    if playback.orders.is_empty() {
+        log::info!("Replay finished");
+        commands.remove_resource::<Playback>();
+    }
+}
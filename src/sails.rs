@@ -0,0 +1,58 @@
+use crate::app_state::AppState;
+use crate::bodies::Body;
+use crate::crafts::Craft;
+use crate::physics::{Mass, PhysicsConstants, SimPosition, SimVelocity};
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+/// Light from the star, the heaviest body, pushing outward on crafts that
+/// have their [`SolarSail`] deployed: slow, but free of fuel.
+pub struct SailsPlugin;
+
+impl Plugin for SailsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedPostUpdate,
+            push_sails.run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+/// A sail's push as a fraction of the star's pull. Both fall off with the
+/// square of the distance, so this holds anywhere.
+const LIGHTNESS: f64 = 0.1;
+
+/// Marks a craft with its sail deployed. Retracting it removes this.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SolarSail;
+
+/// The acceleration a deployed sail at `position` gets from a star of
+/// `star_mass` at `star`.
+pub fn sail_acceleration(
+    position: DVec3,
+    star: DVec3,
+    star_mass: f64,
+    constants: &PhysicsConstants,
+) -> DVec3 {
+    let offset = position - star;
+    let softening = constants.softening as f64;
+    let distance_squared = offset.length_squared() + softening * softening;
+    offset.normalize_or_zero() * LIGHTNESS * constants.g as f64 * star_mass / distance_squared
+}
+
+type CraftState = (&'static SimPosition, &'static mut SimVelocity);
+
+fn push_sails(
+    mut crafts: Query<CraftState, (With<Craft>, With<SolarSail>)>,
+    bodies: Query<(&SimPosition, &Mass), With<Body>>,
+    constants: Res<PhysicsConstants>,
+    time: Res<Time>,
+) {
+    let Some((star, star_mass)) = bodies.iter().max_by(|(_, a), (_, b)| a.0.total_cmp(&b.0)) else {
+        return;
+    };
+    for (position, mut velocity) in &mut crafts {
+        let acceleration = sail_acceleration(position.0, star.0, star_mass.0 as f64, &constants);
+        velocity.0 += acceleration * time.delta_secs_f64();
+    }
+}
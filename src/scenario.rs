@@ -0,0 +1,491 @@
+use crate::atmosphere::Atmosphere;
+use crate::bodies::{Appearance, Spin, body_mass, orbit_velocities};
+use crate::comets::Comet;
+use crate::factions::{CombatRules, Faction};
+use crate::i18n::tr;
+use crate::orbital_mechanics::state_on_orbit;
+use crate::persistence::{read_file, write_file};
+use crate::physics::{PhysicsConstants, ScratchBody, step_scratch};
+use crate::stats::RunStats;
+use crate::victory::WinConditions;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// The initial layout of a solar system.
+///
+/// Bodies are put on circular orbits around their local attractor once their
+/// masses are known, unless the scenario gives them a velocity of their own.
+/// See [`crate::bodies::circular_orbit_velocities`].
+#[derive(Resource, Asset, TypePath, Serialize, Deserialize, Debug, Clone)]
+pub struct Scenario {
+    /// Defaults apply to any constant left out of the file.
+    #[serde(default)]
+    pub physics: PhysicsConstants,
+    /// How captures are fought, if the scenario isn't happy with attrition.
+    #[serde(default)]
+    pub combat: CombatRules,
+    /// What it takes to win, beyond finishing a campaign level's goal.
+    #[serde(default)]
+    pub victory: WinConditions,
+    /// Running totals of a saved game, carried on when it's loaded again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<RunStats>,
+    pub bodies: Vec<BodySpec>,
+    /// Crafts already in flight when the scenario starts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub crafts: Vec<CraftSpec>,
+    /// Rhai source run alongside the game, for scripted events. Ignored
+    /// unless built with the `scripting` feature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BodySpec {
+    pub name: String,
+    pub radius: f32,
+    /// RGB fill color.
+    pub color: [u8; 3],
+    /// Worked out on loading for bodies with an `orbit`.
+    #[serde(default)]
+    pub position: [f32; 2],
+    /// Overrides [`PhysicsConstants::density`] for this body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub density: Option<f32>,
+    /// Neutral if unset. A scenario that sets no owners at all gives the
+    /// player every body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Faction>,
+    /// Overrides the circular orbit the body would otherwise start on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub velocity: Option<[f32; 2]>,
+    /// Docked crafts at the start, overriding the usual starting stock.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crafts: Option<u32>,
+    /// Gradients, glow, rings or a texture, rather than a flat color.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub appearance: Option<Appearance>,
+    /// Rotation that carries the launch site around. Bodies without one
+    /// launch in any direction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spin: Option<Spin>,
+    /// Air that slows and heats crafts passing through it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub atmosphere: Option<Atmosphere>,
+    /// Follows a fixed Kepler orbit around whatever it starts orbiting,
+    /// undisturbed by anything else.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub on_rails: bool,
+    /// Starts on a long, thin orbit and trails a tail.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comet: Option<Comet>,
+    /// Places the body on an orbit around another, setting its position and
+    /// velocity from those of the other body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orbit: Option<OrbitSpec>,
+}
+
+/// Where a body starts, relative to the body it orbits.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OrbitSpec {
+    /// Name of the body orbited, which may itself be placed by an orbit.
+    pub around: String,
+    /// Semi-major axis.
+    pub distance: f32,
+    #[serde(default)]
+    pub eccentricity: f32,
+    /// Degrees around the orbit from periapsis to where the body starts.
+    #[serde(default)]
+    pub phase: f32,
+    /// Direction of periapsis, in degrees counterclockwise from +x.
+    #[serde(default)]
+    pub periapsis: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CraftSpec {
+    /// Key into the balance table.
+    pub class: String,
+    pub position: [f32; 2],
+    pub velocity: [f32; 2],
+    /// Delta-v left for burns.
+    pub fuel: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Faction>,
+    /// Name of the body the craft was launched from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home: Option<String>,
+    /// Name of the body the craft is headed for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+}
+
+impl BodySpec {
+    pub fn density(&self, physics: &PhysicsConstants) -> f32 {
+        self.density.unwrap_or(physics.density)
+    }
+
+    /// The velocity the scenario gives the body, if it doesn't start on a
+    /// circular orbit.
+    pub fn initial_velocity(&self) -> Option<DVec3> {
+        self.velocity
+            .map(|[x, y]| DVec3::new(x as f64, y as f64, 0.))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScenarioError {
+    #[error("could not read scenario: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse scenario: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("could not write scenario: {0}")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("scenario is not valid UTF-8: {0}")]
+    Utf8(#[from] std::str::Utf8Error),
+    #[error("could not decode shared scenario: {0}")]
+    Link(#[from] base64::DecodeError),
+    #[error("shared scenario is larger than {MAX_LINK_SCENARIO_BYTES} bytes")]
+    LinkTooLarge,
+    #[error("{body} can't orbit {around}: {reason}")]
+    Orbit {
+        body: String,
+        around: String,
+        reason: &'static str,
+    },
+}
+
+/// Starts the URL fragment of a link that opens a scenario, as in
+/// `#scenario=...`.
+pub const LINK_PREFIX: &str = "scenario=";
+
+/// Largest scenario a link may unpack to, so a malicious one can't exhaust
+/// memory.
+pub const MAX_LINK_SCENARIO_BYTES: u64 = 1 << 20;
+
+impl Scenario {
+    /// Reads a scenario from a TOML file.
+    pub fn load(path: &Path) -> Result<Self, ScenarioError> {
+        Self::from_bytes(read_file(path)?.as_bytes())
+    }
+
+    /// Reads a scenario from the contents of a TOML file.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ScenarioError> {
+        let mut scenario: Self = toml::from_str(std::str::from_utf8(bytes)?)?;
+        scenario.place_orbits()?;
+        Ok(scenario)
+    }
+
+    /// Sets the position and velocity of every body with an [`OrbitSpec`],
+    /// after those of the body it orbits.
+    pub fn place_orbits(&mut self) -> Result<(), ScenarioError> {
+        let masses: Vec<f64> = self
+            .bodies
+            .iter()
+            .map(|spec| body_mass(spec.radius, spec.density(&self.physics)) as f64)
+            .collect();
+        let mut placed: Vec<bool> = self
+            .bodies
+            .iter()
+            .map(|spec| spec.orbit.is_none())
+            .collect();
+        // Bodies on orbits in the order they were placed, with what they
+        // orbit and their velocity relative to it
+        let mut orbits = Vec::new();
+        loop {
+            let before = orbits.len();
+            for body in 0..self.bodies.len() {
+                let Some(orbit) = self.bodies[body].orbit.clone().filter(|_| !placed[body]) else {
+                    continue;
+                };
+                let error = |reason| ScenarioError::Orbit {
+                    body: self.bodies[body].name.clone(),
+                    around: orbit.around.clone(),
+                    reason,
+                };
+                let Some(around) = self
+                    .bodies
+                    .iter()
+                    .position(|spec| spec.name == orbit.around)
+                else {
+                    return Err(error("there's no body by that name"));
+                };
+                if orbit.distance <= 0. {
+                    return Err(error("the distance has to be more than zero"));
+                }
+                if orbit.eccentricity < 0. || orbit.eccentricity >= 1. {
+                    return Err(error("the eccentricity has to be at least 0 and under 1"));
+                }
+                if !placed[around] {
+                    continue;
+                }
+                let (position, velocity) = state_on_orbit(
+                    orbit.distance as f64,
+                    orbit.eccentricity as f64,
+                    (orbit.periapsis as f64).to_radians(),
+                    (orbit.phase as f64).to_radians(),
+                    self.physics.g as f64 * (masses[body] + masses[around]),
+                );
+                let [x, y] = self.bodies[around].position;
+                self.bodies[body].position = [x + position.x as f32, y + position.y as f32];
+                placed[body] = true;
+                orbits.push((body, around, velocity));
+            }
+            if orbits.len() == before {
+                break;
+            }
+        }
+        if let Some(body) = placed.iter().position(|&placed| !placed) {
+            let spec = &self.bodies[body];
+            return Err(ScenarioError::Orbit {
+                body: spec.name.clone(),
+                around: spec
+                    .orbit
+                    .as_ref()
+                    .map_or_else(String::new, |orbit| orbit.around.clone()),
+                reason: "the bodies orbit each other in a loop",
+            });
+        }
+
+        // Now everything's somewhere, the rest start moving as they would
+        let mut velocities: Vec<DVec3> = self
+            .scratch_bodies()
+            .into_iter()
+            .map(|body| body.velocity)
+            .collect();
+        for (body, around, velocity) in orbits {
+            velocities[body] = velocities[around] + velocity;
+            self.bodies[body].velocity =
+                Some([velocities[body].x as f32, velocities[body].y as f32]);
+        }
+        Ok(())
+    }
+
+    /// The scenario compressed and base64-encoded, to share in a link after
+    /// [`LINK_PREFIX`].
+    pub fn to_link_fragment(&self) -> Result<String, ScenarioError> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(toml::to_string(self)?.as_bytes())?;
+        Ok(URL_SAFE_NO_PAD.encode(encoder.finish()?))
+    }
+
+    /// Reads a scenario shared with [`Scenario::to_link_fragment`].
+    pub fn from_link_fragment(fragment: &str) -> Result<Self, ScenarioError> {
+        let compressed = URL_SAFE_NO_PAD.decode(fragment.trim())?;
+        let mut bytes = Vec::new();
+        // One byte over the limit tells a scenario that fills it from one
+        // cut short
+        DeflateDecoder::new(compressed.as_slice())
+            .take(MAX_LINK_SCENARIO_BYTES + 1)
+            .read_to_end(&mut bytes)?;
+        if bytes.len() as u64 > MAX_LINK_SCENARIO_BYTES {
+            return Err(ScenarioError::LinkTooLarge);
+        }
+        Self::from_bytes(&bytes)
+    }
+
+    /// Writes the scenario to a TOML file.
+    pub fn save(&self, path: &Path) -> Result<(), ScenarioError> {
+        write_file(path, &toml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// Loads scenario files through the [`AssetServer`], which can fetch them
+/// on the web, where there's no filesystem to read.
+#[derive(Default)]
+pub struct ScenarioLoader;
+
+impl AssetLoader for ScenarioLoader {
+    type Asset = Scenario;
+    type Settings = ();
+    type Error = ScenarioError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Scenario, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Scenario::from_bytes(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["toml"]
+    }
+}
+
+/// How long the divergence test runs, in simulated seconds.
+const DIVERGENCE_TEST_DURATION: f64 = 30.;
+const DIVERGENCE_TEST_STEP: f64 = 1. / 64.;
+const PERTURBATION: f64 = 1e-6;
+/// Orbits of the slowest body that make up a typical session.
+const ORBITS_PER_SESSION: f64 = 3.;
+
+/// Rough metrics for choosing a scenario without loading it.
+#[derive(Clone, Debug)]
+pub struct ComplexityEstimate {
+    pub body_count: usize,
+    /// Growth rate of a tiny perturbation per simulated second, as a
+    /// finite-time Lyapunov exponent. Higher is more chaotic.
+    pub divergence_rate: f64,
+    /// Expected play time in minutes.
+    pub session_minutes: f64,
+}
+
+impl ComplexityEstimate {
+    pub fn chaos_label(&self) -> String {
+        tr(match self.divergence_rate {
+            rate if rate < 0.1 => "chaos-calm",
+            rate if rate < 0.3 => "chaos-lively",
+            _ => "chaos-chaotic",
+        })
+    }
+}
+
+impl Scenario {
+    /// The bodies as they are right after loading, with masses and circular
+    /// orbital velocities derived the same way the ECS systems do.
+    pub fn scratch_bodies(&self) -> Vec<ScratchBody> {
+        let states: Vec<_> = self
+            .bodies
+            .iter()
+            .map(|spec| {
+                let [x, y] = spec.position;
+                (
+                    DVec3::new(x as f64, y as f64, 0.),
+                    body_mass(spec.radius, spec.density(&self.physics)) as f64,
+                    spec.comet.as_ref().map_or(1., Comet::apoapsis_speed),
+                )
+            })
+            .collect();
+        let velocities = orbit_velocities(&states, &self.physics);
+        states
+            .into_iter()
+            .zip(velocities)
+            .zip(&self.bodies)
+            .map(|(((position, mass, _), velocity), spec)| ScratchBody {
+                position,
+                velocity: spec.initial_velocity().unwrap_or(velocity),
+                mass,
+            })
+            .collect()
+    }
+
+    /// Simulates the scenario twice, once with a tiny nudge, to see how fast
+    /// the two runs diverge, and sizes a session by the slowest orbit.
+    pub fn estimate_complexity(&self) -> ComplexityEstimate {
+        let mut reference = self.scratch_bodies();
+        let mut perturbed = reference.clone();
+        if let Some(body) = perturbed.last_mut() {
+            body.position.x += PERTURBATION;
+        }
+
+        let steps = (DIVERGENCE_TEST_DURATION / DIVERGENCE_TEST_STEP) as usize;
+        for _ in 0..steps {
+            step_scratch(&mut reference, &self.physics, DIVERGENCE_TEST_STEP);
+            step_scratch(&mut perturbed, &self.physics, DIVERGENCE_TEST_STEP);
+        }
+        let divergence = reference
+            .iter()
+            .zip(&perturbed)
+            .map(|(a, b)| a.position.distance(b.position))
+            .fold(PERTURBATION, f64::max);
+        let divergence_rate = (divergence / PERTURBATION).ln() / DIVERGENCE_TEST_DURATION;
+
+        let initial = self.scratch_bodies();
+        let central = initial
+            .iter()
+            .max_by(|a, b| a.mass.total_cmp(&b.mass))
+            .copied();
+        let longest_period = central.map_or(0., |central| {
+            let mu = self.physics.g as f64 * central.mass;
+            initial
+                .iter()
+                .map(|body| body.position.distance(central.position))
+                .filter(|&distance| distance > 0.)
+                .map(|distance| 2. * std::f64::consts::PI * (distance.powi(3) / mu).sqrt())
+                .fold(0., f64::max)
+        });
+
+        ComplexityEstimate {
+            body_count: self.bodies.len(),
+            divergence_rate,
+            session_minutes: ORBITS_PER_SESSION * longest_period / 60.,
+        }
+    }
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            physics: PhysicsConstants::default(),
+            combat: CombatRules::default(),
+            victory: WinConditions::default(),
+            stats: None,
+            bodies: vec![
+                BodySpec {
+                    name: "Gliblot".into(),
+                    radius: 5.,
+                    color: [255, 0, 0],
+                    position: [0., 0.],
+                    density: None,
+                    owner: Some(Faction::Player),
+                    velocity: None,
+                    crafts: None,
+                    appearance: None,
+                    spin: None,
+                    atmosphere: None,
+                    on_rails: false,
+                    comet: None,
+                    orbit: None,
+                },
+                BodySpec {
+                    name: "Moon".into(),
+                    radius: 2.,
+                    color: [0, 0, 255],
+                    position: [20., 0.],
+                    density: None,
+                    owner: Some(Faction::Opponent),
+                    velocity: None,
+                    crafts: None,
+                    appearance: None,
+                    spin: None,
+                    atmosphere: None,
+                    on_rails: false,
+                    comet: None,
+                    orbit: None,
+                },
+                BodySpec {
+                    name: "Moon2".into(),
+                    radius: 1.,
+                    color: [0, 255, 0],
+                    position: [0., 40.],
+                    density: None,
+                    owner: None,
+                    velocity: None,
+                    crafts: None,
+                    appearance: None,
+                    spin: None,
+                    atmosphere: None,
+                    on_rails: false,
+                    comet: None,
+                    orbit: None,
+                },
+            ],
+            crafts: Vec::new(),
+            script: None,
+        }
+    }
+}
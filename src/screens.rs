@@ -0,0 +1,170 @@
+use crate::app_state::{AppState, GameOutcome};
+use crate::bindings::{Action, ActionInput, KeyBindings};
+use crate::campaign::{Campaign, CurrentLevel};
+use crate::i18n::{format_number, tr, tr_args};
+use crate::idle::IdleState;
+use crate::scenario::Scenario;
+use crate::victory::GameStats;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use bevy_persistent::prelude::*;
+
+/// Pausing and speed keys, the pause overlay and the end-of-game summary.
+pub struct ScreensPlugin;
+
+impl Plugin for ScreensPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (toggle_pause, change_speed).run_if(AppState::in_game),
+        )
+        .add_systems(
+            EguiPrimaryContextPass,
+            (
+                pause_overlay.run_if(in_state(AppState::Paused)),
+                game_over_screen.run_if(in_state(AppState::GameOver)),
+            ),
+        );
+    }
+}
+
+/// Slowest and fastest the speed keys go, relative to real time.
+const SPEED_RANGE: (f32, f32) = (1. / 16., 16.);
+
+fn toggle_pause(
+    mut actions: ActionInput,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if actions.just_pressed(Action::Pause) {
+        next_state.set(match state.get() {
+            AppState::Playing => AppState::Paused,
+            _ => AppState::Playing,
+        });
+    }
+}
+
+/// Doubles or halves the simulation speed.
+fn change_speed(mut actions: ActionInput, mut time: ResMut<Time<Virtual>>) {
+    let factor = if actions.just_pressed(Action::SpeedUp) {
+        2.
+    } else if actions.just_pressed(Action::SlowDown) {
+        0.5
+    } else {
+        return;
+    };
+    let (slowest, fastest) = SPEED_RANGE;
+    let speed = (time.relative_speed() * factor).clamp(slowest, fastest);
+    time.set_relative_speed(speed);
+}
+
+fn pause_overlay(
+    mut contexts: EguiContexts,
+    bindings: Res<Persistent<KeyBindings>>,
+    idle: Option<Res<IdleState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    // The idle summary has its own resume button
+    if idle.is_some_and(|idle| idle.summary.is_some()) {
+        return;
+    }
+
+    egui::Window::new(tr("paused"))
+        .anchor(egui::Align2::CENTER_CENTER, [0., 0.])
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(tr_args(
+                "resume-hint",
+                &[("key", bindings.binding(Action::Pause).to_string().into())],
+            ));
+            ui.horizontal(|ui| {
+                if ui.button(tr("resume")).clicked() {
+                    next_state.set(AppState::Playing);
+                }
+                if ui.button(tr("main-menu")).clicked() {
+                    next_state.set(AppState::Menu);
+                }
+            });
+        });
+}
+
+/// The campaign being played, if any, and which of its levels.
+#[derive(SystemParam)]
+struct CampaignLevel<'w> {
+    campaign: Option<Res<'w, Campaign>>,
+    current: Option<ResMut<'w, CurrentLevel>>,
+}
+
+fn game_over_screen(
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+    outcome: Res<GameOutcome>,
+    stats: Res<GameStats>,
+    scenario: Res<Scenario>,
+    mut level: CampaignLevel,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    let next_level = level
+        .current
+        .as_ref()
+        .and_then(|current| current.0)
+        .map(|index| index + 1)
+        .filter(|&index| outcome.won && level.campaign.as_ref().is_some_and(|c| index < c.0.len()));
+
+    egui::Window::new(&outcome.title)
+        .anchor(egui::Align2::CENTER_CENTER, [0., 0.])
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            for line in &outcome.details {
+                ui.label(line);
+            }
+            ui.separator();
+            egui::Grid::new("game_stats").show(ui, |ui| {
+                let seconds = stats.elapsed as u64;
+                ui.label(tr("time"));
+                ui.label(format!("{}:{:02}", seconds / 60, seconds % 60));
+                ui.end_row();
+                ui.label(tr("delta-v-used"));
+                ui.label(format_number(stats.delta_v as f64, 1));
+                ui.end_row();
+                ui.label(tr("gravity-assists"));
+                ui.label(format_number(stats.gravity_assists as f64, 0));
+                ui.end_row();
+                ui.label(tr("crafts-lost"));
+                ui.label(format_number(stats.crafts_lost as f64, 0));
+                ui.end_row();
+            });
+            ui.separator();
+            ui.horizontal(|ui| {
+                if let Some(index) = next_level
+                    && let Some(campaign) = &level.campaign
+                    && ui.button(tr("next-level")).clicked()
+                {
+                    commands.insert_resource(campaign.0[index].1.clone());
+                    if let Some(current_level) = &mut level.current {
+                        current_level.0 = Some(index);
+                    }
+                    next_state.set(AppState::Playing);
+                }
+                if outcome.won && ui.button(tr("keep-playing")).clicked() {
+                    next_state.set(AppState::Playing);
+                }
+                if ui.button(tr("restart")).clicked() {
+                    commands.insert_resource(scenario.clone());
+                    next_state.set(AppState::Playing);
+                }
+                if ui.button(tr("main-menu")).clicked() {
+                    next_state.set(AppState::Menu);
+                }
+            });
+        });
+}
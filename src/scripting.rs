@@ -0,0 +1,432 @@
+use crate::app_state::AppState;
+use crate::bodies::{Body, ScenarioSystems, body_bundle};
+use crate::collisions::BodyDestroyed;
+use crate::crafts::{BodyCaptured, CraftLaunched, Crafts, LaunchCraft};
+use crate::events::Toasts;
+use crate::factions::{Faction, Owner};
+use crate::physics::{Mass, PhysicsConstants, SimPosition, SimVelocity};
+use crate::replay::ReplayLog;
+use crate::scenario::Scenario;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use rhai::{AST, Array, CallFnOptions, Dynamic, Engine, FLOAT, Scope};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Runs the scenario's [`Scenario::script`], written in Rhai, so scenario
+/// authors can stage events such as a comet arriving five minutes in.
+///
+/// Only built with the `scripting` feature. The script's top level runs when
+/// the scenario loads. After that, each physics tick runs the functions it
+/// scheduled with `at(seconds, "name")` once their time comes, then its
+/// `on_tick(time)`, `on_launch(body)`, `on_capture(body, faction)` and
+/// `on_destroyed(body)` functions, if it defines them. Scripts see the
+/// system as it was when called, and what they do takes effect afterwards.
+/// They run in step with the simulation, so replays play them back too.
+///
+/// Scripts can call:
+/// - `time()`: simulated seconds since the scenario started
+/// - `bodies()`: every body's name
+/// - `position(body)`, `velocity(body)`: `[x, y]`, or `()` if there's no such body
+/// - `mass(body)`, and `owner(body)`: "player", "opponent" or ""
+/// - `spawn_body(name, radius, x, y, vx, vy)`
+/// - `set_velocity(body, vx, vy)`
+/// - `launch(body, dx, dy, speed)`, and `launch_to(body, destination, dx, dy, speed)`
+/// - `at(seconds, "function")`, and `toast(text)` to tell the player something
+pub struct ScriptingPlugin;
+
+impl Plugin for ScriptingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            load_script
+                .after(ScenarioSystems::Initialize)
+                .run_if(resource_changed::<Scenario>),
+        )
+        .add_systems(
+            FixedLast,
+            run_script
+                .run_if(resource_exists::<LoadedScript>)
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+/// Color of bodies spawned by scripts.
+const SPAWNED_COLOR: [u8; 3] = [170, 170, 180];
+
+/// The script's view of the system and what it asked for, shared with the
+/// functions it calls.
+#[derive(Default)]
+struct ScriptWorld {
+    time: f64,
+    bodies: Vec<BodyView>,
+    requests: Vec<Request>,
+    /// Functions waiting to run, and when.
+    timers: Vec<(f64, String)>,
+}
+
+struct BodyView {
+    name: String,
+    position: DVec3,
+    velocity: DVec3,
+    mass: f64,
+    owner: Option<Faction>,
+}
+
+enum Request {
+    SpawnBody {
+        name: String,
+        radius: f32,
+        position: DVec3,
+        velocity: DVec3,
+    },
+    SetVelocity {
+        body: String,
+        velocity: DVec3,
+    },
+    Launch {
+        from: String,
+        destination: Option<String>,
+        direction: Vec2,
+        speed: f32,
+    },
+    Toast(String),
+}
+
+type Shared = Arc<Mutex<ScriptWorld>>;
+
+fn lock(world: &Shared) -> MutexGuard<'_, ScriptWorld> {
+    // A panic mid-script leaves nothing half-written worth giving up on
+    world
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The compiled script of the scenario in play.
+#[derive(Resource)]
+struct LoadedScript {
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    world: Shared,
+}
+
+impl LoadedScript {
+    fn defines(&self, function: &str) -> bool {
+        self.ast.iter_functions().any(|f| f.name == function)
+    }
+
+    /// Calls one of the script's functions, reporting any error.
+    fn call(&mut self, function: &str, args: impl rhai::FuncArgs) {
+        let options = CallFnOptions::new().eval_ast(false);
+        let result = self.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut self.scope,
+            &self.ast,
+            function,
+            args,
+        );
+        if let Err(error) = result {
+            let message = format!("Script error in {function}: {error}");
+            log::error!("{message}");
+            lock(&self.world).requests.push(Request::Toast(message));
+        }
+    }
+}
+
+fn engine(world: &Shared) -> Engine {
+    let mut engine = Engine::new();
+    let xy = |vector: DVec3| -> Dynamic {
+        let array: Array = vec![Dynamic::from(vector.x), Dynamic::from(vector.y)];
+        array.into()
+    };
+    let body = |world: &Shared, name: &str, read: &dyn Fn(&BodyView) -> Dynamic| {
+        lock(world)
+            .bodies
+            .iter()
+            .find(|body| body.name == name)
+            .map_or(Dynamic::UNIT, read)
+    };
+
+    let shared = world.clone();
+    engine.register_fn("time", move || lock(&shared).time);
+    let shared = world.clone();
+    engine.register_fn("bodies", move || -> Array {
+        lock(&shared)
+            .bodies
+            .iter()
+            .map(|body| body.name.clone().into())
+            .collect()
+    });
+    let shared = world.clone();
+    engine.register_fn("position", move |name: &str| {
+        body(&shared, name, &|body| xy(body.position))
+    });
+    let shared = world.clone();
+    engine.register_fn("velocity", move |name: &str| {
+        body(&shared, name, &|body| xy(body.velocity))
+    });
+    let shared = world.clone();
+    engine.register_fn("mass", move |name: &str| {
+        body(&shared, name, &|body| body.mass.into())
+    });
+    let shared = world.clone();
+    engine.register_fn("owner", move |name: &str| {
+        body(&shared, name, &|body| {
+            match body.owner {
+                Some(Faction::Player) => "player",
+                Some(Faction::Opponent) => "opponent",
+                None => "",
+            }
+            .into()
+        })
+    });
+
+    let shared = world.clone();
+    engine.register_fn(
+        "spawn_body",
+        move |name: &str, radius: FLOAT, x: FLOAT, y: FLOAT, vx: FLOAT, vy: FLOAT| {
+            lock(&shared).requests.push(Request::SpawnBody {
+                name: name.into(),
+                radius: radius as f32,
+                position: DVec3::new(x, y, 0.),
+                velocity: DVec3::new(vx, vy, 0.),
+            });
+        },
+    );
+    let shared = world.clone();
+    engine.register_fn("set_velocity", move |name: &str, vx: FLOAT, vy: FLOAT| {
+        lock(&shared).requests.push(Request::SetVelocity {
+            body: name.into(),
+            velocity: DVec3::new(vx, vy, 0.),
+        });
+    });
+    let shared = world.clone();
+    engine.register_fn(
+        "launch",
+        move |from: &str, dx: FLOAT, dy: FLOAT, speed: FLOAT| {
+            lock(&shared).requests.push(Request::Launch {
+                from: from.into(),
+                destination: None,
+                direction: Vec2::new(dx as f32, dy as f32),
+                speed: speed as f32,
+            });
+        },
+    );
+    let shared = world.clone();
+    engine.register_fn(
+        "launch_to",
+        move |from: &str, destination: &str, dx: FLOAT, dy: FLOAT, speed: FLOAT| {
+            lock(&shared).requests.push(Request::Launch {
+                from: from.into(),
+                destination: Some(destination.into()),
+                direction: Vec2::new(dx as f32, dy as f32),
+                speed: speed as f32,
+            });
+        },
+    );
+    let shared = world.clone();
+    engine.register_fn("at", move |seconds: FLOAT, function: &str| {
+        lock(&shared).timers.push((seconds, function.into()));
+    });
+    let shared = world.clone();
+    engine.register_fn("toast", move |text: &str| {
+        lock(&shared).requests.push(Request::Toast(text.into()));
+    });
+    engine
+}
+
+/// Everything the script may look at, as of now.
+fn snapshot(bodies: &Query<BodyState, With<Body>>) -> Vec<BodyView> {
+    bodies
+        .iter()
+        .map(|(name, position, velocity, mass, owner)| BodyView {
+            name: name.to_string(),
+            position: position.0,
+            velocity: velocity.0,
+            mass: mass.0 as f64,
+            owner: owner.map(|owner| owner.0),
+        })
+        .collect()
+}
+
+#[derive(bevy::ecs::system::SystemParam)]
+struct Requests<'w, 's> {
+    commands: Commands<'w, 's>,
+    names: Query<'w, 's, (Entity, &'static Name), With<Body>>,
+    launches: EventWriter<'w, LaunchCraft>,
+    constants: Res<'w, PhysicsConstants>,
+    toasts: Option<ResMut<'w, Toasts>>,
+    time: Res<'w, Time<Real>>,
+}
+
+impl Requests<'_, '_> {
+    fn body(&self, name: &str) -> Option<Entity> {
+        self.names
+            .iter()
+            .find(|(_, body)| body.as_str() == name)
+            .map(|(entity, _)| entity)
+    }
+
+    fn body_name(&self, entity: Entity) -> Option<String> {
+        self.names
+            .get(entity)
+            .ok()
+            .map(|(_, name)| name.to_string())
+    }
+
+    fn apply(&mut self, requests: Vec<Request>) {
+        for request in requests {
+            match request {
+                Request::SpawnBody {
+                    name,
+                    radius,
+                    position,
+                    velocity,
+                } => {
+                    self.commands.spawn((
+                        body_bundle(name, radius, self.constants.density, SPAWNED_COLOR),
+                        SimPosition(position),
+                        SimVelocity(velocity),
+                        Crafts(0),
+                    ));
+                }
+                Request::SetVelocity { body, velocity } => {
+                    if let Some(entity) = self.body(&body) {
+                        self.commands.entity(entity).insert(SimVelocity(velocity));
+                    }
+                }
+                Request::Launch {
+                    from,
+                    destination,
+                    direction,
+                    speed,
+                } => {
+                    if let Some(from) = self.body(&from) {
+                        let destination = destination.and_then(|name| self.body(&name));
+                        self.launches.write(LaunchCraft {
+                            from,
+                            destination,
+                            direction,
+                            speed,
+                            scripted: true,
+                        });
+                    }
+                }
+                Request::Toast(text) => {
+                    log::info!("{text}");
+                    if let Some(toasts) = &mut self.toasts {
+                        toasts.push(text, self.time.elapsed_secs_f64());
+                    }
+                }
+            }
+        }
+    }
+}
+
+type BodyState = (
+    &'static Name,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Mass,
+    Option<&'static Owner>,
+);
+
+fn load_script(
+    scenario: Res<Scenario>,
+    bodies: Query<BodyState, With<Body>>,
+    mut requests: Requests,
+) {
+    requests.commands.remove_resource::<LoadedScript>();
+    let Some(source) = &scenario.script else {
+        return;
+    };
+    let world = Shared::default();
+    let engine = engine(&world);
+    let ast = match engine.compile(source) {
+        Ok(ast) => ast,
+        Err(error) => {
+            let message = format!("Could not compile the scenario script: {error}");
+            log::error!("{message}");
+            requests.apply(vec![Request::Toast(message)]);
+            return;
+        }
+    };
+    lock(&world).bodies = snapshot(&bodies);
+    let mut scope = Scope::new();
+    if let Err(error) = engine.run_ast_with_scope(&mut scope, &ast) {
+        let message = format!("Script error: {error}");
+        log::error!("{message}");
+        lock(&world).requests.push(Request::Toast(message));
+    }
+    let pending = std::mem::take(&mut lock(&world).requests);
+    requests.apply(pending);
+    requests.commands.insert_resource(LoadedScript {
+        engine,
+        ast,
+        scope,
+        world,
+    });
+}
+
+/// Events scripts have handlers for.
+#[derive(bevy::ecs::system::SystemParam)]
+struct ScriptEvents<'w, 's> {
+    launched: EventReader<'w, 's, CraftLaunched>,
+    captured: EventReader<'w, 's, BodyCaptured>,
+    destroyed: EventReader<'w, 's, BodyDestroyed>,
+}
+
+fn run_script(
+    mut script: ResMut<LoadedScript>,
+    bodies: Query<BodyState, With<Body>>,
+    mut events: ScriptEvents,
+    log: Res<ReplayLog>,
+    time: Res<Time>,
+    mut requests: Requests,
+) {
+    let script = &mut *script;
+    let now = log.tick() as f64 * time.delta_secs_f64();
+    let due = {
+        let mut world = lock(&script.world);
+        world.time = now;
+        world.bodies = snapshot(&bodies);
+        // Earliest first, and in the order scheduled when at the same time
+        world.timers.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        let due = world.timers.partition_point(|(at, _)| *at <= now);
+        world.timers.drain(..due).collect::<Vec<_>>()
+    };
+    for (_, function) in due {
+        script.call(&function, ());
+    }
+    if script.defines("on_tick") {
+        script.call("on_tick", (now,));
+    }
+
+    let mut calls: Vec<(&str, Vec<Dynamic>)> = Vec::new();
+    for launch in events.launched.read() {
+        if let Some(body) = requests.body_name(launch.from) {
+            calls.push(("on_launch", vec![body.into()]));
+        }
+    }
+    for capture in events.captured.read() {
+        if let Some(body) = requests.body_name(capture.body) {
+            let faction = match capture.by {
+                Faction::Player => "player",
+                Faction::Opponent => "opponent",
+            };
+            calls.push(("on_capture", vec![body.into(), faction.into()]));
+        }
+    }
+    for body in events.destroyed.read() {
+        calls.push(("on_destroyed", vec![body.name.clone().into()]));
+    }
+    for (function, args) in calls {
+        if script.defines(function) {
+            script.call(function, args);
+        }
+    }
+
+    let pending = std::mem::take(&mut lock(&script.world).requests);
+    requests.apply(pending);
+}
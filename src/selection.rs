@@ -0,0 +1,140 @@
+use crate::bindings::{Action, ActionInput};
+use crate::bodies::Body;
+use crate::crafts::Craft;
+use crate::interaction::InteractionMode;
+use bevy::prelude::*;
+use std::collections::BTreeSet;
+
+/// Tracks which body the pointer is over and which body or craft the player
+/// has selected, or which bodies, if several.
+pub struct SelectionPlugin;
+
+impl Plugin for SelectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HoveredBody>()
+            .init_resource::<SelectedBody>()
+            .init_resource::<SelectedCraft>()
+            .init_resource::<SelectedBodies>()
+            // Deselecting leaves any other mode before it clears the selection
+            .add_systems(
+                Update,
+                (
+                    deselect.run_if(in_state(InteractionMode::Inspect)),
+                    cycle_selection,
+                    forget_missing_craft,
+                    narrow_selection,
+                ),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct HoveredBody(pub Option<String>);
+
+#[derive(Resource, Default)]
+pub struct SelectedBody(pub Option<String>);
+
+/// Every selected body while more than one is, the [`SelectedBody`] among
+/// them. Selecting any other way goes back to selecting a single body.
+#[derive(Resource, Default)]
+pub struct SelectedBodies(pub BTreeSet<String>);
+
+impl SelectedBodies {
+    /// Adds `name` to the selection, or takes it out if it's already in.
+    pub fn toggle(&mut self, selected: &mut SelectedBody, name: &str) {
+        if let Some(selected) = selected.0.take() {
+            self.0.insert(selected);
+        }
+        if !self.0.remove(name) {
+            self.0.insert(name.to_string());
+            selected.0 = Some(name.to_string());
+        }
+        self.settle(selected);
+    }
+
+    /// Selects exactly `names`.
+    pub fn replace(&mut self, selected: &mut SelectedBody, names: BTreeSet<String>) {
+        self.0 = names;
+        selected.0 = None;
+        self.settle(selected);
+    }
+
+    /// Keeps [`SelectedBody`] in the set, and the set empty unless it holds
+    /// more than one body.
+    fn settle(&mut self, selected: &mut SelectedBody) {
+        if selected
+            .0
+            .as_ref()
+            .is_none_or(|name| !self.0.contains(name))
+        {
+            selected.0 = self.0.last().cloned();
+        }
+        if self.0.len() < 2 {
+            self.0.clear();
+        }
+    }
+}
+
+/// A craft in flight; cleared once it docks.
+#[derive(Resource, Default)]
+pub struct SelectedCraft(pub Option<Entity>);
+
+fn deselect(
+    mut actions: ActionInput,
+    mut selected_body: ResMut<SelectedBody>,
+    mut selected_craft: ResMut<SelectedCraft>,
+) {
+    if actions.just_pressed(Action::Deselect) {
+        selected_body.0 = None;
+        selected_craft.0 = None;
+    }
+}
+
+/// Steps the selection through the bodies in name order.
+fn cycle_selection(
+    mut actions: ActionInput,
+    bodies: Query<&Name, With<Body>>,
+    mut selected_body: ResMut<SelectedBody>,
+) {
+    let step = if actions.just_pressed(Action::NextBody) {
+        1
+    } else if actions.just_pressed(Action::PreviousBody) {
+        -1
+    } else {
+        return;
+    };
+    let mut names: Vec<_> = bodies.iter().map(Name::as_str).collect();
+    if names.is_empty() {
+        return;
+    }
+    names.sort_unstable();
+    let next = match selected_body
+        .0
+        .as_deref()
+        .and_then(|selected| names.iter().position(|name| *name == selected))
+    {
+        Some(index) => (index as isize + step).rem_euclid(names.len() as isize) as usize,
+        None if step > 0 => 0,
+        None => names.len() - 1,
+    };
+    selected_body.0 = Some(names[next].to_string());
+}
+
+fn narrow_selection(selected_body: Res<SelectedBody>, mut selected: ResMut<SelectedBodies>) {
+    let outside = selected_body
+        .0
+        .as_ref()
+        .is_none_or(|name| !selected.0.contains(name));
+    if selected_body.is_changed() && outside && !selected.0.is_empty() {
+        selected.0.clear();
+    }
+}
+
+fn forget_missing_craft(mut selected_craft: ResMut<SelectedCraft>, crafts: Query<(), With<Craft>>) {
+    if selected_craft
+        .0
+        .is_some_and(|craft| !crafts.contains(craft))
+    {
+        selected_craft.0 = None;
+    }
+}
@@ -0,0 +1,283 @@
+use crate::accessibility::{self, ColorScheme};
+use crate::bindings::{KeyBindings, Rebinding, bindings_page};
+use crate::i18n::{self, Language, tr};
+use crate::particles::ParticleQuality;
+use crate::persistence::state_directory;
+use bevy::prelude::*;
+use bevy_egui::{EguiContext, EguiContextSettings, EguiContexts, EguiPrimaryContextPass, egui};
+use bevy_persistent::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Player preferences, saved next to the window state so they survive
+/// restarts, and the window for changing them.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = Persistent::<Settings>::builder()
+            .name("settings")
+            .format(StorageFormat::Toml)
+            .path(state_directory().join("settings.toml"))
+            .default(Settings::default())
+            .revertible(true)
+            .revert_to_default_on_deserialization_errors(true)
+            .build()
+            .expect("failed to initialize settings");
+
+        // `--speed` is applied after the plugins, so it still wins
+        if let Some(mut time) = app.world_mut().get_resource_mut::<Time<Virtual>>() {
+            time.set_relative_speed(settings.sim_speed);
+        }
+
+        app.insert_resource(settings)
+            .init_resource::<SettingsWindow>()
+            .add_systems(Update, apply_settings)
+            .add_systems(EguiPrimaryContextPass, settings_window);
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Settings {
+    pub language: Language,
+    pub theme: Theme,
+    pub colors: ColorScheme,
+    /// Tell factions apart by line patterns and symbols, not just colors.
+    pub faction_patterns: bool,
+    /// Seconds of past motion drawn behind bodies and crafts. Zero hides trails.
+    pub trail_length: f32,
+    /// Draw each body's and craft's velocity as an arrow.
+    pub velocity_vectors: bool,
+    /// How much exhaust and debris is drawn.
+    pub particles: ParticleQuality,
+    /// Simulation speed relative to real time at startup.
+    pub sim_speed: f32,
+    /// Multiplies the size of everything egui draws.
+    pub ui_scale: f32,
+    /// Multiplies the size of text, on top of the UI scale.
+    pub text_scale: f32,
+    /// From 0, silent, to 1.
+    pub music_volume: f32,
+    /// Launches, landings, collisions and flybys, from 0 to 1.
+    pub effects_volume: f32,
+    /// Mod packs, by directory name, that are turned off. New packs start
+    /// enabled.
+    pub disabled_mods: Vec<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            language: Language::default(),
+            theme: Theme::System,
+            colors: ColorScheme::default(),
+            faction_patterns: false,
+            trail_length: 0.,
+            velocity_vectors: false,
+            particles: ParticleQuality::default(),
+            sim_speed: 1.,
+            ui_scale: 1.,
+            text_scale: 1.,
+            music_volume: 0.5,
+            effects_volume: 0.8,
+            disabled_mods: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Theme {
+    /// Follows the operating system or browser.
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Self; 3] = [Self::System, Self::Light, Self::Dark];
+
+    pub fn label(self) -> String {
+        tr(match self {
+            Self::System => "theme-system",
+            Self::Light => "theme-light",
+            Self::Dark => "theme-dark",
+        })
+    }
+
+    fn preference(self) -> egui::ThemePreference {
+        match self {
+            Self::System => egui::ThemePreference::System,
+            Self::Light => egui::ThemePreference::Light,
+            Self::Dark => egui::ThemePreference::Dark,
+        }
+    }
+}
+
+/// Whether the settings window is open.
+#[derive(Resource, Default)]
+pub struct SettingsWindow(pub bool);
+
+#[derive(Clone, Copy, PartialEq, Default)]
+enum SettingsPage {
+    #[default]
+    General,
+    Keys,
+}
+
+/// Applies the language and colors, and the theme, UI scale and text size to
+/// every egui context, including ones created after the settings last
+/// changed.
+fn apply_settings(
+    settings: Res<Persistent<Settings>>,
+    mut contexts: Query<(Mut<EguiContextSettings>, &mut EguiContext)>,
+) {
+    if settings.is_changed() {
+        i18n::set_language(settings.language);
+        accessibility::set_color_scheme(settings.colors);
+        accessibility::set_faction_patterns(settings.faction_patterns);
+    }
+    for (mut egui_settings, mut context) in &mut contexts {
+        if !settings.is_changed() && !egui_settings.is_added() {
+            continue;
+        }
+        if egui_settings.scale_factor != settings.ui_scale {
+            egui_settings.scale_factor = settings.ui_scale;
+        }
+        let context = context.get_mut();
+        context.set_theme(settings.theme.preference());
+        // Scaled from egui's own sizes so repeated changes don't compound
+        let sizes = egui::Style::default().text_styles;
+        context.all_styles_mut(|style| {
+            for (text_style, font) in &mut style.text_styles {
+                if let Some(size) = sizes.get(text_style) {
+                    font.size = size.size * settings.text_scale;
+                }
+            }
+        });
+    }
+}
+
+fn settings_window(
+    mut contexts: EguiContexts,
+    mut open: ResMut<SettingsWindow>,
+    mut settings: ResMut<Persistent<Settings>>,
+    mut time: ResMut<Time<Virtual>>,
+    mut bindings: ResMut<Persistent<KeyBindings>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut page: Local<SettingsPage>,
+) {
+    if !open.0 {
+        rebinding.0 = None;
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    // Edit a copy so the file is only written on actual edits
+    let mut edited = settings.get().clone();
+    egui::Window::new(tr("settings"))
+        .open(&mut open.0)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut *page, SettingsPage::General, tr("settings-general"));
+                ui.selectable_value(&mut *page, SettingsPage::Keys, tr("settings-keys"));
+            });
+            ui.separator();
+            if *page == SettingsPage::Keys {
+                bindings_page(ui, &mut bindings, &mut rebinding);
+                return;
+            }
+            rebinding.0 = None;
+            egui::Grid::new("settings").num_columns(2).show(ui, |ui| {
+                ui.label(tr("language"));
+                ui.horizontal(|ui| {
+                    for language in Language::ALL {
+                        ui.selectable_value(&mut edited.language, language, language.label());
+                    }
+                });
+                ui.end_row();
+
+                ui.label(tr("theme"));
+                ui.horizontal(|ui| {
+                    for theme in Theme::ALL {
+                        ui.selectable_value(&mut edited.theme, theme, theme.label());
+                    }
+                });
+                ui.end_row();
+
+                ui.label(tr("colors"));
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        for scheme in ColorScheme::ALL {
+                            ui.selectable_value(&mut edited.colors, scheme, scheme.label());
+                        }
+                    });
+                    ui.checkbox(&mut edited.faction_patterns, tr("faction-patterns"));
+                });
+                ui.end_row();
+
+                ui.label(tr("trail-length"));
+                ui.add(
+                    egui::Slider::new(&mut edited.trail_length, 0.0..=60.0)
+                        .suffix(" s")
+                        .text(tr("trail-length-hint")),
+                );
+                ui.end_row();
+
+                ui.label(tr("vectors"));
+                ui.checkbox(&mut edited.velocity_vectors, tr("velocity"));
+                ui.end_row();
+
+                ui.label(tr("particles"));
+                ui.horizontal(|ui| {
+                    for quality in ParticleQuality::ALL {
+                        ui.selectable_value(&mut edited.particles, quality, quality.label());
+                    }
+                });
+                ui.end_row();
+
+                ui.label(tr("sim-speed"));
+                ui.add(
+                    egui::Slider::new(&mut edited.sim_speed, 0.1..=10.0)
+                        .logarithmic(true)
+                        .suffix("×"),
+                )
+                .on_hover_text(tr("sim-speed-hint"));
+                ui.end_row();
+
+                ui.label(tr("ui-scale"));
+                ui.add(egui::Slider::new(&mut edited.ui_scale, 0.5..=3.0).step_by(0.25));
+                ui.end_row();
+
+                ui.label(tr("text-scale"));
+                ui.add(egui::Slider::new(&mut edited.text_scale, 0.75..=2.0).step_by(0.05));
+                ui.end_row();
+
+                #[cfg(feature = "audio")]
+                {
+                    ui.label(tr("music"));
+                    ui.add(egui::Slider::new(&mut edited.music_volume, 0.0..=1.0));
+                    ui.end_row();
+
+                    ui.label(tr("effects"));
+                    ui.add(egui::Slider::new(&mut edited.effects_volume, 0.0..=1.0));
+                    ui.end_row();
+                }
+            });
+            if ui.button(tr("reset-defaults")).clicked() {
+                edited = Settings::default();
+            }
+        });
+
+    if edited == *settings.get() {
+        return;
+    }
+    if edited.sim_speed != settings.sim_speed {
+        time.set_relative_speed(edited.sim_speed);
+    }
+    if let Err(err) = settings.set(edited) {
+        log::error!("failed to save settings: {err}");
+    }
+}
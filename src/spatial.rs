@@ -0,0 +1,117 @@
+use crate::app_state::AppState;
+use crate::bodies::{Body, Radius};
+use crate::crafts::Craft;
+use crate::physics::SimPosition;
+use bevy::math::{DVec2, IVec2};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+/// Buckets bodies and crafts into a grid of cells by position, so finding
+/// what's near a point only looks at the few entities around it rather
+/// than every one.
+///
+/// Rebuilt after every physics tick for docking, and every frame for the
+/// pointer, so bodies moved while paused are found where they are.
+pub struct SpatialPlugin;
+
+impl Plugin for SpatialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialGrid>()
+            .add_systems(
+                FixedPostUpdate,
+                index_positions
+                    .in_set(SpatialIndex)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(Update, index_positions.run_if(AppState::in_game));
+    }
+}
+
+/// Rebuilds the [`SpatialGrid`] after the physics has moved everything.
+/// Systems looking things up in it on the same tick run after this.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpatialIndex;
+
+/// World units across each cell, around the size of a large body.
+const CELL_SIZE: f64 = 8.;
+
+/// Radius past which a body is kept out of the cells and always returned,
+/// so a giant or a bad radius can't fill millions of them.
+const MAX_INDEXED_RADIUS: f64 = CELL_SIZE * 16.;
+
+#[derive(Resource, Default)]
+pub struct SpatialGrid {
+    /// Every body whose disc reaches into each cell, so a body can be in
+    /// several.
+    bodies: HashMap<IVec2, Vec<Entity>>,
+    /// Bodies too large to index by cell, checked on every lookup.
+    large_bodies: Vec<Entity>,
+    /// The crafts in each cell.
+    crafts: HashMap<IVec2, Vec<Entity>>,
+}
+
+impl SpatialGrid {
+    fn cell(point: DVec2) -> IVec2 {
+        (point / CELL_SIZE).floor().as_ivec2()
+    }
+
+    /// Every cell within `reach` of `point`, row by row.
+    fn cells_around(point: DVec2, reach: f64) -> impl Iterator<Item = IVec2> {
+        let min = Self::cell(point - reach);
+        let max = Self::cell(point + reach);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+    }
+
+    /// Bodies whose surface may be within `reach` of `point`, each once, in
+    /// the order they were indexed within a cell. Callers still check the
+    /// actual distance.
+    pub fn bodies_near(&self, point: DVec2, reach: f64) -> Vec<Entity> {
+        let mut found = Vec::new();
+        for cell in Self::cells_around(point, reach) {
+            for &body in self.bodies.get(&cell).into_iter().flatten() {
+                if !found.contains(&body) {
+                    found.push(body);
+                }
+            }
+        }
+        found.extend_from_slice(&self.large_bodies);
+        found
+    }
+
+    /// Crafts that may be within `reach` of `point`.
+    pub fn crafts_near(&self, point: DVec2, reach: f64) -> impl Iterator<Item = Entity> + '_ {
+        Self::cells_around(point, reach)
+            .filter_map(|cell| self.crafts.get(&cell))
+            .flatten()
+            .copied()
+    }
+}
+
+fn index_positions(
+    bodies: Query<(Entity, &SimPosition, &Radius), With<Body>>,
+    crafts: Query<(Entity, &SimPosition), With<Craft>>,
+    mut grid: ResMut<SpatialGrid>,
+) {
+    let grid = &mut *grid;
+    grid.bodies.values_mut().for_each(Vec::clear);
+    grid.crafts.values_mut().for_each(Vec::clear);
+    grid.large_bodies.clear();
+    for (body, position, radius) in &bodies {
+        let radius = radius.0 as f64;
+        if !radius.is_finite() || radius > MAX_INDEXED_RADIUS {
+            grid.large_bodies.push(body);
+            continue;
+        }
+        let center = position.0.truncate();
+        for cell in SpatialGrid::cells_around(center, radius) {
+            grid.bodies.entry(cell).or_default().push(body);
+        }
+    }
+    for (craft, position) in &crafts {
+        let cell = SpatialGrid::cell(position.0.truncate());
+        grid.crafts.entry(cell).or_default().push(craft);
+    }
+    // Drop cells left empty
+    grid.bodies.retain(|_, cell| !cell.is_empty());
+    grid.crafts.retain(|_, cell| !cell.is_empty());
+}
@@ -0,0 +1,102 @@
+use crate::camera::PlotCamera;
+use bevy::prelude::*;
+use bevy_egui::egui::{self, Color32};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// A procedurally generated backdrop of stars behind the space plot, in
+/// layers that drift slightly as the view pans and zooms to suggest depth.
+///
+/// Painted by egui under the plot, so it isn't shown by the `mesh_render`
+/// renderer yet.
+pub struct StarfieldPlugin;
+
+impl Plugin for StarfieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Starfield>();
+    }
+}
+
+/// How much each layer moves with the view, from fixed at 0 to moving with
+/// the bodies at 1, how many stars it has per tile, and their largest radius
+/// in screen points. Farther layers have more, fainter stars.
+const LAYERS: [(f64, usize, f32); 3] = [(0.02, 120, 0.8), (0.06, 50, 1.2), (0.15, 20, 1.8)];
+/// Screen points across the square each layer repeats over.
+const TILE: f32 = 512.;
+const BACKDROP: Color32 = Color32::from_rgb(4, 5, 14);
+const SEED: u64 = 0x5ab1e;
+
+struct Star {
+    /// Within the tile, from 0 to 1 either way.
+    position: egui::Vec2,
+    radius: f32,
+    brightness: u8,
+}
+
+struct Layer {
+    parallax: f64,
+    stars: Vec<Star>,
+}
+
+#[derive(Resource)]
+pub struct Starfield {
+    layers: Vec<Layer>,
+}
+
+impl Default for Starfield {
+    fn default() -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(SEED);
+        let layers = LAYERS
+            .into_iter()
+            .map(|(parallax, count, max_radius)| Layer {
+                parallax,
+                stars: (0..count)
+                    .map(|_| Star {
+                        position: egui::vec2(rng.random(), rng.random()),
+                        radius: max_radius * rng.random_range(0.4..=1.),
+                        brightness: rng.random_range(90..=255),
+                    })
+                    .collect(),
+            })
+            .collect();
+        Self { layers }
+    }
+}
+
+impl Starfield {
+    /// Fills `rect` with the backdrop and the stars, placed for where the
+    /// camera looks.
+    pub fn paint(&self, painter: &egui::Painter, rect: egui::Rect, camera: &PlotCamera) {
+        painter.rect_filled(rect, 0., BACKDROP);
+        if !camera.is_placed() {
+            return;
+        }
+        for layer in &self.layers {
+            // Near layers spread out a little more when zooming in
+            let tile = TILE * (camera.scale.powf(-layer.parallax) as f32).clamp(0.5, 2.);
+            // Screen y points down, world y up
+            let shift = camera.center / camera.scale * layer.parallax;
+            let offset = egui::vec2(
+                (-shift.x).rem_euclid(tile as f64) as f32,
+                shift.y.rem_euclid(tile as f64) as f32,
+            );
+            let columns = (rect.width() / tile).ceil() as i32;
+            let rows = (rect.height() / tile).ceil() as i32;
+            for column in -1..=columns {
+                for row in -1..=rows {
+                    let origin = rect.min + offset + egui::vec2(column as f32, row as f32) * tile;
+                    for star in &layer.stars {
+                        let position = origin + star.position * tile;
+                        if rect.contains(position) {
+                            painter.circle_filled(
+                                position,
+                                star.radius,
+                                Color32::from_gray(star.brightness),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
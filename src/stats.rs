@@ -0,0 +1,198 @@
+use crate::app_state::AppState;
+use crate::assists::GravityAssist;
+use crate::bodies::{Body, Radius, ScenarioSystems};
+use crate::collisions::{BodyDestroyed, Destruction};
+use crate::crafts::{
+    Craft, CraftCrashed, CraftDocked, CraftLaunched, CraftsLost, Destination, Fuel, Home,
+};
+use crate::i18n::{format_number, tr, tr_args};
+use crate::logbook::Logbook;
+use crate::physics::SimPosition;
+use crate::scenario::Scenario;
+use crate::ui::{UiState, craft_log};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use serde::{Deserialize, Serialize};
+
+/// Keeps running totals for the scenario in play, and the window showing
+/// them. Saved scenarios carry the totals so far, and pick them back up when
+/// loaded.
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_systems(Update, restore_stats.in_set(ScenarioSystems::Initialize))
+            .add_systems(
+                Update,
+                (count_events, count_fuel_spent).run_if(AppState::in_game),
+            )
+            .add_systems(
+                FixedPostUpdate,
+                (count_sim_time, track_flybys).run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(
+                EguiPrimaryContextPass,
+                stats_window.run_if(AppState::in_game),
+            );
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct RunStats {
+    pub crafts_launched: u32,
+    pub crafts_landed: u32,
+    /// Destroyed in combat or crashes, on either side.
+    pub crafts_lost: u32,
+    /// Hit a body too fast to land.
+    pub crafts_crashed: u32,
+    /// Fuel burned by crafts after launch.
+    pub delta_v_spent: f64,
+    pub biggest_assist: f32,
+    /// Nearest any craft came to a body's surface, other than where it was
+    /// launched from or headed for.
+    pub closest_flyby: Option<f64>,
+    /// Bodies that merged or shattered on impact.
+    pub collisions: u32,
+    /// Seconds.
+    pub sim_time: f64,
+}
+
+fn restore_stats(scenario: Res<Scenario>, mut stats: ResMut<RunStats>) {
+    *stats = scenario.stats.unwrap_or_default();
+}
+
+fn count_events(
+    mut launches: EventReader<CraftLaunched>,
+    mut landings: EventReader<CraftDocked>,
+    mut crashes: EventReader<CraftCrashed>,
+    mut losses: EventReader<CraftsLost>,
+    mut destroyed: EventReader<BodyDestroyed>,
+    mut assists: EventReader<GravityAssist>,
+    mut stats: ResMut<RunStats>,
+) {
+    stats.crafts_launched += launches.read().count() as u32;
+    stats.crafts_landed += landings.read().count() as u32;
+    stats.crafts_crashed += crashes.read().count() as u32;
+    stats.crafts_lost += losses.read().map(|loss| loss.count).sum::<u32>();
+    stats.collisions += destroyed
+        .read()
+        .filter(|destroyed| !matches!(destroyed.cause, Destruction::TornApart { .. }))
+        .count() as u32;
+    for assist in assists.read() {
+        stats.biggest_assist = stats.biggest_assist.max(assist.delta_v);
+    }
+}
+
+/// Adds up drops in crafts' fuel, however they burned it.
+fn count_fuel_spent(
+    crafts: Query<(Entity, &Fuel), Changed<Fuel>>,
+    mut last_seen: Local<HashMap<Entity, f32>>,
+    all_crafts: Query<(), With<Craft>>,
+    mut stats: ResMut<RunStats>,
+) {
+    for (craft, fuel) in &crafts {
+        if let Some(previous) = last_seen.insert(craft, fuel.0) {
+            stats.delta_v_spent += (previous - fuel.0).max(0.) as f64;
+        }
+    }
+    last_seen.retain(|craft, _| all_crafts.contains(*craft));
+}
+
+fn count_sim_time(time: Res<Time>, mut stats: ResMut<RunStats>) {
+    stats.sim_time += time.delta_secs_f64();
+}
+
+type CraftState = (
+    &'static SimPosition,
+    Option<&'static Home>,
+    Option<&'static Destination>,
+);
+
+fn track_flybys(
+    crafts: Query<CraftState, With<Craft>>,
+    bodies: Query<(Entity, &SimPosition, &Radius), With<Body>>,
+    mut stats: ResMut<RunStats>,
+) {
+    for (craft, home, destination) in &crafts {
+        let passing = bodies.iter().filter(|(body, ..)| {
+            home.is_none_or(|home| home.0 != *body)
+                && destination.is_none_or(|destination| destination.0 != *body)
+        });
+        for (_, position, radius) in passing {
+            let altitude = craft.0.distance(position.0) - radius.0 as f64;
+            if altitude > 0. && stats.closest_flyby.is_none_or(|closest| altitude < closest) {
+                stats.closest_flyby = Some(altitude);
+            }
+        }
+    }
+}
+
+fn stats_window(
+    mut contexts: EguiContexts,
+    mut ui_state: ResMut<UiState>,
+    stats: Res<RunStats>,
+    logbook: Res<Logbook>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    egui::Window::new(tr("statistics"))
+        .open(&mut ui_state.show_stats)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("stats")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    let count = |count: u32| format_number(count as f64, 0);
+                    let rows = [
+                        ("crafts-launched", count(stats.crafts_launched)),
+                        ("crafts-landed", count(stats.crafts_landed)),
+                        ("crafts-lost", count(stats.crafts_lost)),
+                        ("crafts-crashed", count(stats.crafts_crashed)),
+                        ("delta-v-spent", format_number(stats.delta_v_spent, 2)),
+                        (
+                            "biggest-assist",
+                            format_number(stats.biggest_assist as f64, 2),
+                        ),
+                        (
+                            "closest-flyby",
+                            stats
+                                .closest_flyby
+                                .map_or("—".to_string(), |closest| format_number(closest, 2)),
+                        ),
+                        ("collisions", count(stats.collisions)),
+                        (
+                            "simulated-time",
+                            format!("{} s", format_number(stats.sim_time, 0)),
+                        ),
+                    ];
+                    for (label, value) in rows {
+                        ui.label(tr(label));
+                        ui.monospace(value);
+                        ui.end_row();
+                    }
+                });
+            if logbook.0.is_empty() {
+                return;
+            }
+            egui::CollapsingHeader::new(tr_args(
+                "past-crafts",
+                &[("count", logbook.0.len().into())],
+            ))
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(300.)
+                    .show(ui, |ui| {
+                        for past in &logbook.0 {
+                            ui.strong(&past.name);
+                            craft_log(ui, &past.log);
+                        }
+                    });
+            });
+        });
+}
@@ -0,0 +1,23 @@
+use crate::bodies::Body;
+use bevy::prelude::*;
+
+/// Tracks the body the player is aiming for, which crafts and planning tools
+/// fall back on when nothing more specific is given.
+pub struct TargetPlugin;
+
+impl Plugin for TargetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Target>()
+            .add_systems(PostUpdate, forget_missing_target);
+    }
+}
+
+/// The designated target body, if any.
+#[derive(Resource, Default)]
+pub struct Target(pub Option<Entity>);
+
+fn forget_missing_target(mut target: ResMut<Target>, bodies: Query<(), With<Body>>) {
+    if target.0.is_some_and(|body| !bodies.contains(body)) {
+        target.0 = None;
+    }
+}
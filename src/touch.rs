@@ -0,0 +1,59 @@
+use bevy_egui::egui::{Context, Pos2, Vec2};
+
+/// Screen points around a body or craft that still count as tapping it on a
+/// touchscreen, where a finger covers far more than a mouse pointer.
+const TOUCH_HIT_SLOP: f32 = 24.;
+/// Seconds a finger has to rest on a body before dragging aims a launch.
+const LONG_PRESS: f64 = 0.5;
+/// Screen points a resting finger can wander and still count as still.
+const LONG_PRESS_SLOP: f32 = 8.;
+
+/// What fingers on the space plot are doing, between frames.
+///
+/// One finger pans and taps select, as with a mouse. Two fingers pinch to
+/// zoom and pan together. A long press on a body the player owns, then a
+/// drag, aims a launch from it that fires when the finger lifts.
+#[derive(Default)]
+pub struct TouchGestures {
+    /// Set once the plot has been touched, after which hit targets grow.
+    pub touchscreen: bool,
+    /// The body a long press is aiming a launch from.
+    pub aiming_from: Option<String>,
+}
+
+impl TouchGestures {
+    /// Notes whether this frame's input came from a touchscreen.
+    pub fn update(&mut self, ctx: &Context) {
+        self.touchscreen |= ctx.input(|input| input.any_touches());
+    }
+
+    /// Extra screen points to accept around bodies and crafts.
+    pub fn hit_slop(&self) -> f32 {
+        if self.touchscreen { TOUCH_HIT_SLOP } else { 0. }
+    }
+
+    /// How far two fingers moved together this frame, while pinching.
+    pub fn pinch_pan(ctx: &Context) -> Option<Vec2> {
+        ctx.input(|input| input.multi_touch())
+            .map(|touch| touch.translation_delta)
+    }
+
+    /// Where a single finger has rested long enough to start aiming.
+    pub fn long_press(ctx: &Context) -> Option<Pos2> {
+        ctx.input(|input| {
+            if !input.any_touches() || input.multi_touch().is_some() {
+                return None;
+            }
+            let pointer = &input.pointer;
+            let origin = pointer.press_origin().filter(|_| pointer.primary_down())?;
+            let held = input.time - pointer.press_start_time()?;
+            let moved = pointer.latest_pos()?.distance(origin);
+            (held >= LONG_PRESS && moved <= LONG_PRESS_SLOP).then_some(origin)
+        })
+    }
+
+    /// Whether the finger aiming a launch has just lifted.
+    pub fn aim_released(&self, ctx: &Context) -> bool {
+        self.aiming_from.is_some() && !ctx.input(|input| input.pointer.primary_down())
+    }
+}
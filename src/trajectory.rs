@@ -0,0 +1,334 @@
+use crate::app_state::AppState;
+use crate::autopilot::Autopilot;
+use crate::bodies::{Body, Radius};
+use crate::crafts::{Craft, Destination, Home};
+use crate::influence::{governing_body, sphere_of_influence_radii};
+use crate::maneuver::{BurnFrame, ManeuverNode, scratch_bodies};
+use crate::orbital_mechanics::{dominant_attractor, strongest_attractor};
+use crate::physics::{
+    Mass, PhysicsConstants, ScratchBody, SimPosition, SimVelocity, gravity_acceleration,
+    step_scratch,
+};
+use crate::selection::SelectedBody;
+use crate::target::Target;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+/// Predicts where each body and craft is headed by simulating ahead of the
+/// real simulation, with and without crafts' planned maneuvers.
+pub struct TrajectoryPlugin;
+
+impl Plugin for TrajectoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TrajectorySettings>()
+            .add_systems(Update, predict_trajectories.run_if(AppState::in_game));
+    }
+}
+
+/// How far ahead trajectories are predicted, in seconds.
+pub const HORIZON: f64 = 60.;
+/// Simulation steps between recorded points.
+const SAMPLE_EVERY: usize = 8;
+/// How much coarser and further-reaching patched-conic predictions are.
+const PATCHED_CONIC_SCALE: f64 = 4.;
+
+#[derive(Resource, Default)]
+pub struct TrajectorySettings {
+    /// Only let the body whose sphere of influence a craft is in pull on it,
+    /// which allows a coarser step and so a longer horizon.
+    pub patched_conics: bool,
+}
+
+#[derive(Component, Default)]
+pub struct PredictedTrajectory {
+    /// Positions from now on, `interval` apart, if the craft makes no burns.
+    /// Ends early if the craft reaches a body.
+    pub coast: Vec<DVec3>,
+    /// Closest and furthest points of the coast from whatever it orbits.
+    pub apsides: Vec<Apsis>,
+    /// Of a craft's coast to its autopilot's goal, its destination, or else
+    /// the [`Target`].
+    pub closest_approach: Option<ClosestApproach>,
+    /// Always `None` for bodies.
+    pub planned: Option<PlannedTrajectory>,
+    /// Seconds between recorded points.
+    pub interval: f64,
+}
+
+/// The path after a craft's [`ManeuverNode`].
+pub struct PlannedTrajectory {
+    /// Where the burn happens.
+    pub position: DVec3,
+    pub frame: BurnFrame,
+    /// Positions after the burn, as far apart as those of the coast.
+    pub points: Vec<DVec3>,
+    pub apsides: Vec<Apsis>,
+    pub closest_approach: Option<ClosestApproach>,
+}
+
+/// Where a craft passes nearest to a body.
+#[derive(Clone, Copy, Debug)]
+pub struct ClosestApproach {
+    /// Seconds from now.
+    pub time: f64,
+    /// Distance from the body's surface; zero or less is an impact.
+    pub distance: f64,
+    pub craft_position: DVec3,
+    pub body_position: DVec3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApsisKind {
+    Periapsis,
+    Apoapsis,
+}
+
+/// A turning point in the distance from the attractor along a trajectory.
+#[derive(Clone, Copy, Debug)]
+pub struct Apsis {
+    pub kind: ApsisKind,
+    pub position: DVec3,
+    /// Height above the attractor's surface.
+    pub altitude: f64,
+}
+
+type CraftState = (
+    &'static SimPosition,
+    &'static SimVelocity,
+    Option<&'static ManeuverNode>,
+    Option<&'static Destination>,
+    Option<(&'static Autopilot, Option<&'static Home>)>,
+    &'static mut PredictedTrajectory,
+);
+
+type BodyState = (
+    Entity,
+    &'static SimPosition,
+    &'static SimVelocity,
+    &'static Mass,
+    &'static Radius,
+    &'static mut PredictedTrajectory,
+);
+
+fn predict_trajectories(
+    mut bodies: Query<BodyState, (With<Body>, Without<Craft>)>,
+    mut crafts: Query<CraftState, With<Craft>>,
+    constants: Res<PhysicsConstants>,
+    settings: Res<TrajectorySettings>,
+    time: Res<Time<Fixed>>,
+    target: Res<Target>,
+    selected: Option<Res<SelectedBody>>,
+) {
+    // Only crafts and the selected body's drawn orbit use predictions
+    if crafts.is_empty() && selected.is_none_or(|selected| selected.0.is_none()) {
+        return;
+    }
+    // Step as the simulation does, so predictions match what happens
+    let step = time.timestep().as_secs_f64();
+    let (step, horizon) = if settings.patched_conics {
+        (step * PATCHED_CONIC_SCALE, HORIZON * PATCHED_CONIC_SCALE)
+    } else {
+        (step, HORIZON)
+    };
+    let acceleration = Acceleration {
+        g: constants.g as f64,
+        softening: constants.softening as f64,
+    };
+
+    // The bodies' future is shared by every craft, so simulate it once
+    let steps = (horizon / step) as usize;
+    let entities: Vec<_> = bodies.iter().map(|(entity, ..)| entity).collect();
+    let radii: Vec<_> = bodies
+        .iter()
+        .map(|(_, _, _, _, radius, _)| radius.0 as f64)
+        .collect();
+    let mut scratch = scratch_bodies(bodies.iter().map(|(_, p, v, m, ..)| (p, v, m)));
+    let mut futures = Vec::with_capacity(steps);
+    for _ in 0..steps {
+        futures.push(Future::new(&scratch, settings.patched_conics));
+        step_scratch(&mut scratch, &constants, step);
+    }
+    let interval = step * SAMPLE_EVERY as f64;
+    let Some(now) = futures.first() else {
+        return;
+    };
+
+    for (index, (.., mut trajectory)) in bodies.iter_mut().enumerate() {
+        trajectory.interval = interval;
+        trajectory.coast = futures
+            .iter()
+            .step_by(SAMPLE_EVERY)
+            .map(|future| future.bodies[index].position)
+            .collect();
+        trajectory.apsides = dominant_attractor(index, &now.bodies)
+            .map(|attractor| apsides(&trajectory.coast, 0, attractor, &futures, &radii))
+            .unwrap_or_default();
+    }
+
+    // Closest approach to `target` is tracked at every step, not just recorded points
+    let flight =
+        |from_step: usize, mut position: DVec3, mut velocity: DVec3, target: Option<usize>| {
+            let mut points = Vec::new();
+            let mut closest_approach: Option<ClosestApproach> = None;
+            for (index, future) in futures.iter().enumerate().skip(from_step) {
+                if (index - from_step) % SAMPLE_EVERY == 0 {
+                    points.push(position);
+                }
+                if let Some(target) = target {
+                    let body_position = future.bodies[target].position;
+                    let distance = position.distance(body_position) - radii[target];
+                    if closest_approach.is_none_or(|closest| distance < closest.distance) {
+                        closest_approach = Some(ClosestApproach {
+                            time: index as f64 * step,
+                            distance,
+                            craft_position: position,
+                            body_position,
+                        });
+                    }
+                }
+                if future
+                    .bodies
+                    .iter()
+                    .zip(&radii)
+                    .any(|(body, radius)| body.position.distance(position) <= *radius)
+                {
+                    break;
+                }
+                velocity += acceleration.of(position, future) * step;
+                position += velocity * step;
+            }
+            (points, closest_approach)
+        };
+
+    for (position, velocity, node, destination, autopilot, mut trajectory) in &mut crafts {
+        let target = autopilot
+            .and_then(|(autopilot, home)| autopilot.goal(home))
+            .or(destination.map(|destination| destination.0))
+            .or(target.0)
+            .and_then(|target| entities.iter().position(|&e| e == target));
+        trajectory.interval = interval;
+        (trajectory.coast, trajectory.closest_approach) = flight(0, position.0, velocity.0, target);
+        trajectory.apsides = strongest_attractor(position.0, &now.bodies)
+            .map(|attractor| apsides(&trajectory.coast, 0, attractor, &futures, &radii))
+            .unwrap_or_default();
+        trajectory.planned = node.and_then(|node| {
+            let node_step = ((node.time - time.elapsed_secs_f64()) / step).max(0.) as usize;
+            let (position, velocity) = coast_to(
+                position.0,
+                velocity.0,
+                &futures,
+                node_step,
+                &acceleration,
+                step,
+            )?;
+            let bodies = &futures[node_step].bodies;
+            let frame = BurnFrame::new(position, velocity, bodies)?;
+            let (points, closest_approach) =
+                flight(node_step, position, velocity + frame.delta_v(node), target);
+            let apsides = strongest_attractor(position, bodies)
+                .map(|attractor| apsides(&points, node_step, attractor, &futures, &radii))
+                .unwrap_or_default();
+            Some(PlannedTrajectory {
+                position,
+                frame,
+                points,
+                apsides,
+                closest_approach,
+            })
+        });
+    }
+}
+
+/// Where `points`, recorded from `from_step` on, come closest to and furthest
+/// from `attractor`.
+fn apsides(
+    points: &[DVec3],
+    from_step: usize,
+    attractor: usize,
+    futures: &[Future],
+    radii: &[f64],
+) -> Vec<Apsis> {
+    let distances: Vec<_> = points
+        .iter()
+        .enumerate()
+        .map(|(k, point)| {
+            let attractor = &futures[from_step + k * SAMPLE_EVERY].bodies[attractor];
+            point.distance(attractor.position)
+        })
+        .collect();
+    distances
+        .windows(3)
+        .enumerate()
+        .filter_map(|(k, window)| {
+            let &[before, distance, after] = window else {
+                return None;
+            };
+            let kind = if distance < before && distance <= after {
+                ApsisKind::Periapsis
+            } else if distance > before && distance >= after {
+                ApsisKind::Apoapsis
+            } else {
+                return None;
+            };
+            Some(Apsis {
+                kind,
+                position: points[k + 1],
+                altitude: distance - radii[attractor],
+            })
+        })
+        .collect()
+}
+
+/// The bodies at one step of a prediction.
+struct Future {
+    bodies: Vec<ScratchBody>,
+    /// Sphere-of-influence radii, when predicting with patched conics.
+    spheres_of_influence: Option<Vec<f64>>,
+}
+
+impl Future {
+    fn new(bodies: &[ScratchBody], patched_conics: bool) -> Self {
+        Self {
+            bodies: bodies.to_vec(),
+            spheres_of_influence: patched_conics.then(|| sphere_of_influence_radii(bodies)),
+        }
+    }
+}
+
+/// How a predicted craft is accelerated.
+struct Acceleration {
+    g: f64,
+    softening: f64,
+}
+
+impl Acceleration {
+    fn of(&self, position: DVec3, future: &Future) -> DVec3 {
+        let pull = |body: &ScratchBody| {
+            gravity_acceleration(body.position - position, body.mass, self.softening, self.g)
+        };
+        match &future.spheres_of_influence {
+            Some(radii) => governing_body(position, &future.bodies, radii)
+                .map_or(DVec3::ZERO, |index| pull(&future.bodies[index])),
+            None => future.bodies.iter().map(pull).sum(),
+        }
+    }
+}
+
+/// Position and velocity after `steps` steps, or `None` past the horizon.
+fn coast_to(
+    mut position: DVec3,
+    mut velocity: DVec3,
+    futures: &[Future],
+    steps: usize,
+    acceleration: &Acceleration,
+    step: f64,
+) -> Option<(DVec3, DVec3)> {
+    if steps >= futures.len() {
+        return None;
+    }
+    for future in &futures[..steps] {
+        velocity += acceleration.of(position, future) * step;
+        position += velocity * step;
+    }
+    Some((position, velocity))
+}
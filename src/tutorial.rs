@@ -0,0 +1,228 @@
+use crate::app_state::AppState;
+use crate::crafts::{CraftDocked, CraftLaunched};
+use crate::i18n::{tr, tr_args};
+use crate::persistence::state_directory;
+use crate::scenario::Scenario;
+use crate::selection::SelectedBody;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use bevy_persistent::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Walks a new player through selecting a body, setting up and aiming a
+/// launch, and landing on a moon. Each step waits for the player to do it,
+/// with what to use highlighted. Skipping or finishing marks the tutorial
+/// completed.
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        let progress = Persistent::<TutorialProgress>::builder()
+            .name("tutorial")
+            .format(StorageFormat::Toml)
+            .path(state_directory().join("tutorial.toml"))
+            .default(TutorialProgress::default())
+            .revertible(true)
+            .revert_to_default_on_deserialization_errors(true)
+            .build()
+            .expect("failed to initialize tutorial progress");
+
+        app.insert_resource(progress)
+            .init_resource::<Tutorial>()
+            .add_systems(OnEnter(AppState::Menu), end_tutorial)
+            .add_systems(Update, advance_tutorial.run_if(in_state(AppState::Playing)))
+            .add_systems(
+                EguiPrimaryContextPass,
+                tutorial_window.run_if(AppState::in_game),
+            );
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct TutorialProgress {
+    pub completed: bool,
+}
+
+/// Embedded so the tutorial works on the web and from any directory.
+const SCENARIO: &str = include_str!("../assets/tutorial.toml");
+/// Bodies in the tutorial scenario the steps refer to.
+const HOME: &str = "Gliblot";
+const MOON: &str = "Moon";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TutorialStep {
+    SelectBody,
+    AdjustLaunch,
+    Launch,
+    Land,
+    Done,
+}
+
+impl TutorialStep {
+    /// Steps the player has to do, not counting [`TutorialStep::Done`].
+    const COUNT: usize = 4;
+
+    fn number(self) -> usize {
+        self as usize + 1
+    }
+
+    fn instructions(self) -> String {
+        tr(match self {
+            Self::SelectBody => "tutorial-select-body",
+            Self::AdjustLaunch => "tutorial-adjust-launch",
+            Self::Launch => "tutorial-launch",
+            Self::Land => "tutorial-land",
+            Self::Done => "tutorial-done",
+        })
+    }
+}
+
+/// Parts of the UI a step can point the player at.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TutorialTarget {
+    SpeedSlider,
+    AngleSlider,
+    LaunchButton,
+}
+
+/// The step the player is on, while the tutorial is running.
+#[derive(Resource, Default)]
+pub struct Tutorial {
+    pub step: Option<TutorialStep>,
+}
+
+impl Tutorial {
+    /// Loads the tutorial scenario and starts from the first step. The
+    /// caller moves to [`AppState::Playing`].
+    pub fn start(&mut self, commands: &mut Commands) {
+        let scenario =
+            Scenario::from_bytes(SCENARIO.as_bytes()).expect("bundled tutorial is invalid");
+        commands.insert_resource(scenario);
+        self.step = Some(TutorialStep::SelectBody);
+    }
+
+    /// The body the player should select, to be highlighted on the plot.
+    pub fn highlighted_body(&self) -> Option<&'static str> {
+        match self.step? {
+            TutorialStep::SelectBody => Some(HOME),
+            TutorialStep::Land => Some(MOON),
+            _ => None,
+        }
+    }
+
+    /// Outlines `response` if the current step points at `target`, and moves
+    /// on once the player has used it.
+    pub fn highlight(&mut self, ui: &egui::Ui, response: &egui::Response, target: TutorialTarget) {
+        let targets: &[TutorialTarget] = match self.step {
+            Some(TutorialStep::AdjustLaunch) => &[TutorialTarget::SpeedSlider],
+            Some(TutorialStep::Launch) => {
+                &[TutorialTarget::AngleSlider, TutorialTarget::LaunchButton]
+            }
+            _ => &[],
+        };
+        if !targets.contains(&target) {
+            return;
+        }
+        ui.painter().rect_stroke(
+            response.rect.expand(3.),
+            4.,
+            egui::Stroke::new(2., pulse(ui.ctx())),
+            egui::StrokeKind::Outside,
+        );
+        if target == TutorialTarget::SpeedSlider && response.changed() {
+            self.step = Some(TutorialStep::Launch);
+        }
+    }
+}
+
+/// A highlight color that brightens and fades, to catch the eye.
+pub fn pulse(ctx: &egui::Context) -> egui::Color32 {
+    ctx.request_repaint();
+    let time = ctx.input(|input| input.time);
+    let brightness = 0.6 + 0.4 * (time * std::f64::consts::TAU).sin() as f32;
+    egui::Color32::from_rgb(255, 200, 40).gamma_multiply(brightness)
+}
+
+fn advance_tutorial(
+    mut tutorial: ResMut<Tutorial>,
+    mut progress: ResMut<Persistent<TutorialProgress>>,
+    selected: Res<SelectedBody>,
+    mut launches: EventReader<CraftLaunched>,
+    mut landings: EventReader<CraftDocked>,
+    names: Query<&Name>,
+) {
+    let launched = launches.read().count() > 0;
+    let landed = landings.read().any(|landing| {
+        names
+            .get(landing.body)
+            .is_ok_and(|name| name.as_str() == MOON)
+    });
+    let next = match tutorial.step {
+        Some(TutorialStep::SelectBody) if selected.0.as_deref() == Some(HOME) => {
+            TutorialStep::AdjustLaunch
+        }
+        // Aiming on the plot skips straight past the sliders
+        Some(TutorialStep::AdjustLaunch | TutorialStep::Launch) if launched => TutorialStep::Land,
+        Some(TutorialStep::Land) if landed => TutorialStep::Done,
+        _ => return,
+    };
+    tutorial.step = Some(next);
+    if next == TutorialStep::Done {
+        complete(&mut progress);
+    }
+}
+
+fn complete(progress: &mut Persistent<TutorialProgress>) {
+    if !progress.completed
+        && let Err(err) = progress.update(|progress| progress.completed = true)
+    {
+        log::error!("failed to save tutorial progress: {err}");
+    }
+}
+
+fn end_tutorial(mut tutorial: ResMut<Tutorial>) {
+    tutorial.step = None;
+}
+
+fn tutorial_window(
+    mut contexts: EguiContexts,
+    mut tutorial: ResMut<Tutorial>,
+    mut progress: ResMut<Persistent<TutorialProgress>>,
+) {
+    let Some(step) = tutorial.step else {
+        return;
+    };
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    let mut close = false;
+    egui::Window::new(tr("tutorial"))
+        .anchor(egui::Align2::CENTER_TOP, [0., 40.])
+        .collapsible(false)
+        .resizable(false)
+        .default_width(320.)
+        .show(ctx, |ui| {
+            if step != TutorialStep::Done {
+                ui.weak(tr_args(
+                    "tutorial-step",
+                    &[
+                        ("step", step.number().into()),
+                        ("count", TutorialStep::COUNT.into()),
+                    ],
+                ));
+            }
+            ui.label(step.instructions());
+            ui.separator();
+            close = if step == TutorialStep::Done {
+                ui.button(tr("tutorial-finish")).clicked()
+            } else {
+                ui.button(tr("tutorial-skip")).clicked()
+            };
+        });
+
+    if close {
+        tutorial.step = None;
+        complete(&mut progress);
+    }
+}
@@ -0,0 +1,519 @@
+use crate::app_state::AppState;
+use crate::assists::AssistTally;
+use crate::autopilot::Autopilot;
+use crate::balance::Balance;
+use crate::bindings::{Action, ActionInput};
+use crate::bodies::{Body, Fill, Radius, ScenarioSystems};
+use crate::crafts::{Craft, Crafts, Destination, Fuel, LaunchCraft};
+use crate::engines::{THROTTLE_STEP, Throttle};
+use crate::factions::{Faction, Owner, Players};
+use crate::i18n::{format_number, tr, tr_args};
+use crate::logbook::CraftLog;
+use crate::maneuver::ManeuverNode;
+use crate::physics::{Mass, SimVelocity};
+use crate::selection::SelectedCraft;
+use crate::settings::Settings;
+use crate::trajectory::PredictedTrajectory;
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use bevy_egui::{
+    EguiPrimaryContextPass,
+    egui::{self, Frame, InnerResponse, Ui, scroll_area::ScrollAreaOutput},
+};
+use bevy_persistent::prelude::*;
+use std::collections::VecDeque;
+
+mod card;
+mod controls;
+mod draw;
+mod menu_bar;
+mod plot;
+mod tables;
+mod windows;
+
+pub use draw::legend_entries;
+
+/// Draws the space plot and the overlay panels with egui.
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiState>()
+            .init_resource::<PlotPan>()
+            .init_resource::<ViewSettings>()
+            .init_resource::<ViewRequest>()
+            .init_resource::<PlotViewport>()
+            .init_resource::<AimedLaunch>()
+            .init_resource::<LaunchForm>()
+            .add_systems(Startup, spawn_camera)
+            .add_systems(
+                Update,
+                (
+                    assign_ids,
+                    hand_over_selection,
+                    (
+                        toggle_follow,
+                        zoom_keys,
+                        fire_aimed_launch,
+                        throttle_keys,
+                        record_trails,
+                        note_burns,
+                    )
+                        .run_if(AppState::in_game),
+                ),
+            )
+            .add_systems(Update, fit_new_scenario.in_set(ScenarioSystems::Initialize))
+            .add_systems(
+                EguiPrimaryContextPass,
+                (
+                    menu_bar::menu_bar,
+                    plot::status_bar,
+                    card::overlay_card,
+                    plot::space_plot,
+                    windows::physics_constants_window,
+                    windows::scoreboard_window,
+                )
+                    .chain()
+                    .in_set(UiSystems)
+                    .run_if(AppState::in_game),
+            );
+    }
+}
+
+/// Where the menu bar, plot and panels are drawn.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UiSystems;
+
+#[derive(Component)]
+pub struct EguiId(pub egui::Id);
+
+/// Fractions of the plot's width and height to pan by on the next frame.
+#[derive(Resource, Default)]
+pub struct PlotPan(pub Vec2);
+
+/// A one-off change to the plot's view, made on the next frame.
+#[derive(Resource, Default)]
+pub struct ViewRequest(pub Option<ViewChange>);
+
+/// The part of space the plot showed last frame, and where on screen, for
+/// drawing the world underneath it.
+#[derive(Resource, Clone, Copy, Default)]
+pub struct PlotViewport {
+    pub center: DVec2,
+    /// Zero until the plot has been shown.
+    pub world_per_point: f64,
+    /// In egui points.
+    pub rect: egui::Rect,
+    pub pixels_per_point: f32,
+    /// Where the pointer was over the plot last frame, in world coordinates.
+    pub cursor: Option<DVec2>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ViewChange {
+    /// Center on the selected craft, or else body, keeping the zoom.
+    CenterSelection,
+    /// Zoom to fit every body.
+    FitAll,
+    /// Zoom in on the selected craft, or else body.
+    FrameSelection,
+}
+
+/// The launch that clicking the plot or [`Action::Fire`] would make.
+#[derive(Resource, Default)]
+pub struct AimedLaunch(pub Option<LaunchCraft>);
+
+/// Seconds between the points of a [`Trail`].
+const TRAIL_INTERVAL: f64 = 0.1;
+
+/// Where a body or craft has been, oldest first, [`TRAIL_INTERVAL`] apart.
+#[derive(Component, Default)]
+pub struct Trail(pub VecDeque<[f64; 2]>);
+
+/// A craft is drawn as burning for this many seconds after it spends fuel.
+const BURN_DISPLAY: f64 = 1.;
+/// Fuel below this counts as none left.
+const EMPTY_FUEL: f32 = 1e-3;
+
+/// When a craft last spent fuel, in seconds of simulated time.
+#[derive(Component)]
+pub struct LastBurn(f64);
+
+/// What a craft is doing, as its marker and trail show it.
+#[derive(Clone, Copy, PartialEq)]
+enum CraftState {
+    Coasting,
+    Burning,
+    OutOfFuel,
+}
+
+impl CraftState {
+    const ALL: [Self; 3] = [Self::Coasting, Self::Burning, Self::OutOfFuel];
+
+    fn of(fuel: &Fuel, last_burn: Option<&LastBurn>, now: f64) -> Self {
+        if last_burn.is_some_and(|burn| now - burn.0 < BURN_DISPLAY) {
+            Self::Burning
+        } else if fuel.0 < EMPTY_FUEL {
+            Self::OutOfFuel
+        } else {
+            Self::Coasting
+        }
+    }
+
+    fn label(self) -> String {
+        tr(match self {
+            Self::Coasting => "craft-coasting",
+            Self::Burning => "craft-burning",
+            Self::OutOfFuel => "craft-out-of-fuel",
+        })
+    }
+
+    /// Stands in for the marker in the legend.
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Coasting => "●",
+            Self::Burning => "✱",
+            Self::OutOfFuel => "○",
+        }
+    }
+
+    fn marker(self) -> egui_plot::MarkerShape {
+        match self {
+            Self::Burning => egui_plot::MarkerShape::Asterisk,
+            Self::Coasting | Self::OutOfFuel => egui_plot::MarkerShape::Circle,
+        }
+    }
+
+    fn trail_style(self) -> egui_plot::LineStyle {
+        match self {
+            Self::OutOfFuel => egui_plot::LineStyle::dotted_dense(),
+            Self::Coasting | Self::Burning => egui_plot::LineStyle::Solid,
+        }
+    }
+}
+
+/// Which optional windows and plot overlays are shown.
+#[derive(Resource)]
+pub struct UiState {
+    pub show_physics_constants: bool,
+    pub show_spheres_of_influence: bool,
+    pub show_lagrange_points: bool,
+    /// Where the selected craft doesn't have the energy to go, in the frame
+    /// turning with the pair of bodies it moves among.
+    pub show_zero_velocity_curves: bool,
+    pub show_hill_spheres: bool,
+    pub show_roche_limits: bool,
+    pub show_scoreboard: bool,
+    pub show_event_log: bool,
+    pub show_stats: bool,
+    /// Keep the selected craft, or else body, in the middle of the plot.
+    pub follow_selection: bool,
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self {
+            show_physics_constants: false,
+            show_spheres_of_influence: true,
+            show_lagrange_points: true,
+            show_zero_velocity_curves: false,
+            show_hill_spheres: false,
+            show_roche_limits: false,
+            show_scoreboard: true,
+            show_event_log: false,
+            show_stats: false,
+            follow_selection: false,
+        }
+    }
+}
+
+/// What the space plot draws besides bodies and crafts.
+#[derive(Resource)]
+pub struct ViewSettings {
+    pub grid: bool,
+    pub axes: bool,
+    pub body_labels: bool,
+    /// The number of crafts docked at each body.
+    pub craft_counts: bool,
+    pub center_of_mass: bool,
+    /// Past motion, as long as [`Settings::trail_length`] says.
+    pub trails: bool,
+    /// What the colors and shapes of crafts mean.
+    pub legend: bool,
+    /// Stars behind everything, for a sense of depth and motion.
+    pub starfield: bool,
+    /// Bodies darkened on the side facing away from the star.
+    pub night_sides: bool,
+    /// Shading by gravitational potential, deepest at the bodies.
+    pub gravity_wells: bool,
+}
+
+impl Default for ViewSettings {
+    fn default() -> Self {
+        Self {
+            grid: true,
+            axes: false,
+            body_labels: false,
+            craft_counts: true,
+            center_of_mass: true,
+            trails: true,
+            legend: false,
+            starfield: true,
+            night_sides: true,
+            gravity_wells: false,
+        }
+    }
+}
+
+impl ViewSettings {
+    fn checkboxes(&mut self, ui: &mut Ui) {
+        ui.checkbox(&mut self.grid, tr("grid"));
+        ui.checkbox(&mut self.axes, tr("axes"));
+        ui.checkbox(&mut self.body_labels, tr("body-names"));
+        ui.checkbox(&mut self.craft_counts, tr("craft-counts"));
+        ui.checkbox(&mut self.center_of_mass, tr("center-of-mass"));
+        ui.checkbox(&mut self.trails, tr("trails"));
+        ui.checkbox(&mut self.legend, tr("craft-legend"));
+        ui.checkbox(&mut self.starfield, tr("starfield"));
+        ui.checkbox(&mut self.night_sides, tr("night-sides"));
+        ui.checkbox(&mut self.gravity_wells, tr("gravity-wells"));
+    }
+}
+
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn(Camera2d);
+}
+
+fn assign_ids(mut commands: Commands, bodies: Query<Entity, (With<Body>, Without<EguiId>)>) {
+    for entity in bodies.iter() {
+        // Create a unique egui Id from the entity
+        commands
+            .entity(entity)
+            .insert(EguiId(egui::Id::new(entity)));
+    }
+}
+
+type BodyUiData = (
+    &'static Name,
+    &'static Radius,
+    &'static Fill,
+    &'static Transform,
+    &'static Crafts,
+    &'static Mass,
+    &'static SimVelocity,
+    Option<&'static EguiId>,
+    Option<&'static Owner>,
+);
+
+type CraftUiData = (
+    Entity,
+    &'static Name,
+    &'static Craft,
+    &'static Transform,
+    &'static SimVelocity,
+    &'static Fuel,
+    Option<&'static Destination>,
+    &'static AssistTally,
+    Option<&'static Autopilot>,
+    Option<&'static Owner>,
+    &'static PredictedTrajectory,
+    Option<&'static ManeuverNode>,
+);
+/// The launch the body card's controls set up, which aiming on the plot also
+/// updates.
+#[derive(Resource)]
+pub struct LaunchForm {
+    pub destination: Option<Entity>,
+    pub speed: f32,
+    /// Degrees counterclockwise from the +x axis.
+    pub angle: f32,
+}
+
+impl Default for LaunchForm {
+    fn default() -> Self {
+        Self {
+            destination: None,
+            speed: 10.,
+            angle: 0.,
+        }
+    }
+}
+
+fn owner_label(ui: &mut Ui, owner: Option<&Owner>) {
+    match owner {
+        Some(owner) => ui.colored_label(
+            owner.0.color(),
+            tr_args(
+                "owner",
+                &[(
+                    "owner",
+                    format!("{} {}", owner.0.symbol(), owner.0.name()).into(),
+                )],
+            ),
+        ),
+        None => ui.label(tr_args("owner", &[("owner", tr("neutral").into())])),
+    };
+}
+
+/// Message `id` with its `{ $value }` written as the current language writes
+/// numbers.
+fn readout(id: &str, value: f64, decimals: usize) -> String {
+    tr_args(id, &[("value", format_number(value, decimals).into())])
+}
+
+/// A craft's history, one line per event.
+pub fn craft_log(ui: &mut Ui, log: &CraftLog) {
+    for entry in &log.0 {
+        ui.monospace(entry.to_string());
+    }
+}
+
+/// Clears the craft selection between hot-seat turns, so nobody inherits the
+/// other side's craft.
+fn hand_over_selection(
+    players: Res<Players>,
+    mut last_active: Local<Option<Faction>>,
+    mut selected_craft: ResMut<SelectedCraft>,
+) {
+    if last_active.replace(players.active) != Some(players.active) {
+        selected_craft.0 = None;
+    }
+}
+
+pub fn framed_list<R>(
+    ui: &mut Ui,
+    add_contents: impl FnOnce(&mut Ui) -> R,
+) -> InnerResponse<ScrollAreaOutput<R>> {
+    Frame::new()
+        .stroke(ui.style().visuals.window_stroke())
+        .corner_radius(3.)
+        .inner_margin(5.)
+        .fill(ui.style().visuals.code_bg_color.gamma_multiply(0.5))
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .auto_shrink(false)
+                .show(ui, add_contents)
+        })
+}
+
+/// Draws `add_contents` as a region screen readers announce as `name`, so
+/// panels can be told apart and skipped between.
+pub fn accessible_region<R>(ui: &mut Ui, name: &str, add_contents: impl FnOnce(&mut Ui) -> R) -> R {
+    let id = ui.id().with(name);
+    let ctx = ui.ctx().clone();
+    ctx.accesskit_node_builder(id, |node| {
+        node.set_role(egui::accesskit::Role::Region);
+        node.set_label(name);
+    });
+    ctx.with_accessibility_parent(id, || add_contents(ui))
+}
+
+type Untracked = (Or<(With<Body>, With<Craft>)>, Without<Trail>);
+
+/// Samples the positions of bodies and crafts into their [`Trail`]s, keeping
+/// as many seconds as the settings ask for.
+fn record_trails(
+    mut commands: Commands,
+    untracked: Query<Entity, Untracked>,
+    mut trails: Query<(&Transform, &mut Trail)>,
+    settings: Res<Persistent<Settings>>,
+    time: Res<Time<Virtual>>,
+    mut next_sample: Local<f64>,
+) {
+    for entity in &untracked {
+        commands.entity(entity).insert(Trail::default());
+    }
+    let now = time.elapsed_secs_f64();
+    if now < *next_sample {
+        return;
+    }
+    *next_sample = now + TRAIL_INTERVAL;
+    let length = (settings.trail_length as f64 / TRAIL_INTERVAL).round() as usize;
+    for (transform, mut trail) in &mut trails {
+        let Vec3 { x, y, .. } = transform.translation;
+        trail.0.push_back([x as f64, y as f64]);
+        while trail.0.len() > length {
+            trail.0.pop_front();
+        }
+    }
+}
+
+/// Opens and closes the selected craft's throttle, if it has an engine and
+/// is the active side's to fly.
+fn throttle_keys(
+    mut commands: Commands,
+    mut actions: ActionInput,
+    selected: Res<SelectedCraft>,
+    crafts: Query<(&Craft, Option<&Throttle>, Option<&Owner>)>,
+    balance: Balance,
+    players: Res<Players>,
+) {
+    let step = if actions.just_pressed(Action::ThrottleUp) {
+        THROTTLE_STEP
+    } else if actions.just_pressed(Action::ThrottleDown) {
+        -THROTTLE_STEP
+    } else {
+        return;
+    };
+    let Some((craft, (class, throttle, owner))) = selected
+        .0
+        .and_then(|craft| Some((craft, crafts.get(craft).ok()?)))
+    else {
+        return;
+    };
+    let has_engine = balance
+        .class(&class.class)
+        .is_some_and(|class| class.engine.is_some());
+    if !has_engine || !players.controls(owner) {
+        return;
+    }
+    let mut throttle = throttle.copied().unwrap_or_default();
+    throttle.level = (throttle.level + step).clamp(0., 1.);
+    commands.entity(craft).insert(throttle);
+}
+
+/// Stamps crafts with when they last spent fuel, however they spent it.
+fn note_burns(
+    mut commands: Commands,
+    crafts: Query<(Entity, Ref<Fuel>), With<Craft>>,
+    time: Res<Time<Fixed>>,
+) {
+    for (entity, fuel) in &crafts {
+        if fuel.is_changed() && !fuel.is_added() {
+            commands
+                .entity(entity)
+                .insert(LastBurn(time.elapsed_secs_f64()));
+        }
+    }
+}
+
+fn zoom_keys(mut actions: ActionInput, mut view: ResMut<ViewRequest>) {
+    if actions.just_pressed(Action::FitAll) {
+        view.0 = Some(ViewChange::FitAll);
+    } else if actions.just_pressed(Action::FrameSelection) {
+        view.0 = Some(ViewChange::FrameSelection);
+    }
+}
+
+/// Starts every scenario with all of its bodies in view.
+fn fit_new_scenario(mut view: ResMut<ViewRequest>) {
+    view.0 = Some(ViewChange::FitAll);
+}
+
+fn toggle_follow(mut actions: ActionInput, mut ui_state: ResMut<UiState>) {
+    if actions.just_pressed(Action::Follow) {
+        ui_state.follow_selection = !ui_state.follow_selection;
+    }
+}
+
+fn fire_aimed_launch(
+    mut actions: ActionInput,
+    aimed: Res<AimedLaunch>,
+    mut launches: EventWriter<LaunchCraft>,
+) {
+    if let Some(aimed) = &aimed.0
+        && actions.just_pressed(Action::Fire)
+    {
+        launches.write(aimed.clone());
+    }
+}
@@ -0,0 +1,715 @@
+use super::controls::{
+    BurnForm, autopilot_controls, burn_controls, engine_controls, maneuver_controls, sail_controls,
+};
+use super::draw::closest_approach_label;
+use super::tables::{BodyTable, CraftTable, body_table, craft_table};
+use super::{
+    BodyUiData, CraftUiData, LaunchForm, ViewChange, ViewRequest, craft_log, framed_list,
+    owner_label, readout,
+};
+use crate::atmosphere::Heat;
+use crate::balance::Balance;
+use crate::bodies::{Body, Density};
+use crate::crafts::{Approach, BurnCraft, Home, LaunchCraft};
+use crate::editor::{Edit, EditorAction};
+use crate::engines::Throttle;
+use crate::factions::Players;
+use crate::i18n::{format_number, tr, tr_args};
+use crate::influence::LagrangePoints;
+use crate::kepler::Rails;
+use crate::logbook::CraftLog;
+use crate::orbital_mechanics::elements_around_attractor;
+use crate::physics::{PhysicsConstants, ScratchBody};
+use crate::production::production_rate;
+use crate::sails::{SolarSail, sail_acceleration};
+use crate::selection::{SelectedBodies, SelectedBody, SelectedCraft};
+use crate::target::Target;
+use crate::tutorial::{Tutorial, TutorialTarget};
+use bevy::ecs::system::SystemParam;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy_egui::{
+    EguiContexts,
+    egui::{self, Align, Align2, Color32, Frame, RichText, Ui, WidgetInfo, WidgetType},
+};
+use bevy_simple_subsecond_system::prelude::*;
+use std::collections::BTreeSet;
+
+/// Crafts within this many body radii of a body's center show how they'd
+/// touch down on it.
+const APPROACH_RANGE: f32 = 3.;
+
+/// Bodies, which are selected, and what the card can edit.
+#[derive(SystemParam)]
+pub struct BodyCard<'w, 's> {
+    bodies: Query<'w, 's, BodyUiData>,
+    selected: ResMut<'w, SelectedBody>,
+    multi: Res<'w, SelectedBodies>,
+    densities: Query<'w, 's, &'static Density>,
+    pinned: Query<'w, 's, (), With<Rails>>,
+    target: ResMut<'w, Target>,
+    edits: EventWriter<'w, EditorAction>,
+    table: Local<'s, BodyTable>,
+}
+
+/// Crafts in flight, and the card for the selected one.
+#[derive(SystemParam)]
+pub struct CraftCard<'w, 's> {
+    crafts: Query<'w, 's, CraftUiData>,
+    selected: ResMut<'w, SelectedCraft>,
+    burns: EventWriter<'w, BurnCraft>,
+    form: Local<'s, BurnForm>,
+    commands: Commands<'w, 's>,
+    time: Res<'w, Time<Fixed>>,
+    homes: Query<'w, 's, &'static Home>,
+    table: Local<'s, CraftTable>,
+    heat: Query<'w, 's, &'static Heat>,
+    throttles: Query<'w, 's, &'static Throttle>,
+    sails: Query<'w, 's, (), With<SolarSail>>,
+    logs: Query<'w, 's, &'static CraftLog>,
+}
+
+/// Everything the launch controls in the body card need.
+#[derive(SystemParam)]
+pub struct LaunchControls<'w, 's> {
+    launches: EventWriter<'w, LaunchCraft>,
+    balance: Balance<'w>,
+    bodies: Query<'w, 's, (Entity, &'static Name), With<Body>>,
+    form: ResMut<'w, LaunchForm>,
+    players: Res<'w, Players>,
+    tutorial: ResMut<'w, Tutorial>,
+}
+
+/// The card in the corner of the plot, for the selected craft or bodies, or
+/// else listing every body and craft.
+#[hot]
+pub fn overlay_card(
+    mut contexts: EguiContexts,
+    mut body_card: BodyCard,
+    mut craft_card: CraftCard,
+    mut launch: LaunchControls,
+    constants: Res<PhysicsConstants>,
+    lagrange_points: Query<&LagrangePoints>,
+    mut view: ResMut<ViewRequest>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    if let Some(selected) = &body_card.selected.0 {
+        body_card.table.reference = Some(selected.clone());
+    }
+
+    // The body list needs room for its columns
+    let listing = craft_card.selected.0.is_none() && body_card.selected.0.is_none();
+    let window_size = [
+        if listing { 400. } else { 200. },
+        ctx.available_rect().height() * 0.4,
+    ];
+    egui::Window::new("overlay_window")
+        .anchor(Align2::LEFT_BOTTOM, [16.0, -40.0])
+        .min_size(window_size)
+        .max_size(window_size)
+        .title_bar(false)
+        .resizable(false)
+        .frame(
+            Frame::window(&ctx.style()).fill(ctx.style().visuals.window_fill.gamma_multiply(0.8)),
+        )
+        .show(ctx, |ui| {
+            ui.with_layout(egui::Layout::top_down_justified(Align::Min), |ui| {
+                ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+
+                let selected_craft = craft_card
+                    .selected
+                    .0
+                    .filter(|&entity| craft_card.crafts.contains(entity));
+                if let Some(entity) = selected_craft {
+                    craft_details(
+                        ui,
+                        entity,
+                        &mut craft_card,
+                        &body_card,
+                        &launch,
+                        &constants,
+                        &lagrange_points,
+                    );
+                } else if body_card.multi.0.len() > 1 {
+                    multi_body_card(
+                        ui,
+                        &body_card.multi.0,
+                        &body_card.bodies,
+                        &mut launch,
+                        body_card.target.0,
+                    );
+                } else if let Some(selected) = body_card.selected.0.clone() {
+                    body_details(ui, &selected, &mut body_card, &mut launch, &constants);
+                } else {
+                    lists(
+                        ui,
+                        &mut body_card,
+                        &mut craft_card,
+                        &launch.bodies,
+                        &mut view,
+                    );
+                }
+            });
+        });
+}
+
+/// The selected craft's card: where it's headed, how it would touch down,
+/// and for the side giving orders, its controls.
+fn craft_details(
+    ui: &mut Ui,
+    entity: Entity,
+    craft_card: &mut CraftCard,
+    body_card: &BodyCard,
+    launch: &LaunchControls,
+    constants: &PhysicsConstants,
+    lagrange_points: &Query<&LagrangePoints>,
+) {
+    let Ok((
+        _,
+        name,
+        craft,
+        transform,
+        velocity,
+        fuel,
+        destination,
+        assists,
+        autopilot,
+        craft_owner,
+        trajectory,
+        node,
+    )) = craft_card.crafts.get(entity)
+    else {
+        return;
+    };
+    let bodies = &body_card.bodies;
+    let target = body_card.target.0;
+    let target_body = target.and_then(|target| bodies.get(target).ok());
+
+    ui.heading(name.to_string());
+    framed_list(ui, |ui| {
+        owner_label(ui, craft_owner);
+        ui.label(tr_args(
+            "craft-class",
+            &[("class", craft.class.as_str().into())],
+        ));
+        if let Some((_, destination)) = destination.and_then(|d| launch.bodies.get(d.0).ok()) {
+            ui.label(tr_args(
+                "craft-destination",
+                &[("body", destination.as_str().into())],
+            ));
+            if let Some(closest_approach) = &trajectory.closest_approach {
+                ui.label(tr_args(
+                    "closest-approach-readout",
+                    &[("approach", closest_approach_label(closest_approach).into())],
+                ));
+            }
+            let planned = trajectory
+                .planned
+                .as_ref()
+                .and_then(|planned| planned.closest_approach.as_ref());
+            if let Some(closest_approach) = planned {
+                ui.label(tr_args(
+                    "after-maneuver",
+                    &[("approach", closest_approach_label(closest_approach).into())],
+                ));
+            }
+        }
+        ui.label(readout("readout-speed", velocity.0.length(), 2));
+        let approach = bodies
+            .iter()
+            .map(
+                |(body, radius, _, body_transform, .., body_velocity, _, _)| {
+                    let distance = transform.translation.distance(body_transform.translation);
+                    let approach = Approach::new(
+                        transform.translation.as_dvec3(),
+                        velocity.0,
+                        body_transform.translation.as_dvec3(),
+                        body_velocity.0,
+                    );
+                    (distance / radius.0, body, approach)
+                },
+            )
+            .filter(|(radii, ..)| *radii < APPROACH_RANGE)
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        if let (Some((_, body, approach)), Some(class)) =
+            (approach, launch.balance.class(&craft.class))
+            && approach.angle < 90.
+        {
+            let color = if approach.lands(class) {
+                ui.visuals().text_color()
+            } else {
+                ui.visuals().error_fg_color
+            };
+            ui.colored_label(
+                color,
+                tr_args(
+                    "landing-on",
+                    &[
+                        ("body", body.as_str().into()),
+                        ("speed", format_number(approach.speed as f64, 2).into()),
+                        (
+                            "max",
+                            format_number(class.max_landing_speed as f64, 2).into(),
+                        ),
+                    ],
+                ),
+            );
+            if let Some(max) = class.max_landing_angle {
+                ui.colored_label(
+                    color,
+                    tr_args(
+                        "approach-angle",
+                        &[
+                            ("angle", format_number(approach.angle as f64, 0).into()),
+                            ("max", format_number(max as f64, 0).into()),
+                        ],
+                    ),
+                );
+            }
+        }
+        if let Some((target, _, _, target_transform, _, _, target_velocity, ..)) = target_body {
+            let relative_velocity = velocity.0 - target_velocity.0;
+            let distance = transform.translation.distance(target_transform.translation);
+            ui.label(tr_args(
+                "target-distance",
+                &[
+                    ("target", target.as_str().into()),
+                    ("distance", format_number(distance as f64, 1).into()),
+                ],
+            ));
+            ui.label(readout("relative-velocity", relative_velocity.length(), 2));
+        }
+        ui.label(readout("delta-v-remaining", fuel.0 as f64, 2));
+        if let Ok(heat) = craft_card.heat.get(entity)
+            && heat.0 > 0.
+        {
+            let color = if heat.0 > 0.5 {
+                Color32::ORANGE
+            } else {
+                Color32::WHITE
+            };
+            ui.colored_label(color, readout("hull-heat", heat.0 as f64 * 100., 0));
+        }
+        if assists.count > 0 {
+            ui.label(tr_args(
+                "craft-assists",
+                &[
+                    ("count", assists.count.into()),
+                    ("delta_v", format_number(assists.delta_v as f64, 2).into()),
+                ],
+            ));
+        }
+        if let Ok(log) = craft_card.logs.get(entity)
+            && !log.0.is_empty()
+        {
+            egui::CollapsingHeader::new(tr("craft-log"))
+                .id_salt("craft log")
+                .show(ui, |ui| craft_log(ui, log));
+        }
+        // Only the side giving orders can fly it
+        if !launch.players.controls(craft_owner) {
+            return;
+        }
+        ui.separator();
+        burn_controls(
+            ui,
+            &mut craft_card.burns,
+            &mut craft_card.form,
+            entity,
+            velocity.0.truncate().as_vec2(),
+            fuel.0,
+        );
+        // Hosts only take launches and burns from afar
+        if launch.players.remote {
+            return;
+        }
+        ui.separator();
+        let class = launch.balance.class(&craft.class);
+        if let Some((engine, class)) = class.and_then(|class| Some((class.engine?, class))) {
+            engine_controls(
+                ui,
+                &mut craft_card.commands,
+                entity,
+                craft_card.throttles.get(entity).ok(),
+                engine.acceleration(class.mass, fuel.0),
+            );
+            ui.separator();
+        }
+        let star = bodies
+            .iter()
+            .max_by(|a, b| a.5.0.total_cmp(&b.5.0))
+            .map(|(_, _, _, star, _, mass, ..)| (star, mass));
+        let sail_push = star.map_or(0., |(star, mass)| {
+            sail_acceleration(
+                transform.translation.as_dvec3(),
+                star.translation.as_dvec3(),
+                mass.0 as f64,
+                constants,
+            )
+            .length()
+        });
+        sail_controls(
+            ui,
+            &mut craft_card.commands,
+            entity,
+            craft_card.sails.contains(entity),
+            sail_push,
+        );
+        ui.separator();
+        maneuver_controls(
+            ui,
+            &mut craft_card.commands,
+            entity,
+            node,
+            fuel.0,
+            craft_card.time.elapsed_secs_f64(),
+        );
+        ui.separator();
+        let aim = target.or(destination.map(|d| d.0));
+        let station = aim.filter(|&body| {
+            lagrange_points
+                .get(body)
+                .is_ok_and(|points| points.0.is_some())
+        });
+        autopilot_controls(
+            ui,
+            &mut craft_card.commands,
+            entity,
+            autopilot,
+            aim,
+            station,
+        );
+    });
+}
+
+/// The selected body's card: what it is, where it orbits, edits to it, and
+/// for its owner, launches from it.
+fn body_details(
+    ui: &mut Ui,
+    selected: &str,
+    body_card: &mut BodyCard,
+    launch: &mut LaunchControls,
+    constants: &PhysicsConstants,
+) {
+    let Some((name, radius, fill, _, crafts, mass, velocity, _, owner)) = body_card
+        .bodies
+        .iter()
+        .find(|(name, ..)| name.as_str() == selected)
+    else {
+        return;
+    };
+    ui.heading(RichText::new(name.to_string()).color(fill.color()))
+        .widget_info(|| {
+            WidgetInfo::labeled(
+                WidgetType::Label,
+                true,
+                tr_args("selected-body", &[("name", name.as_str().into())]),
+            )
+        });
+    framed_list(ui, |ui| {
+        owner_label(ui, owner);
+        ui.label(readout("readout-radius", radius.0 as f64, 1));
+        let rate = production_rate(radius) as f64 * 60.;
+        match owner {
+            Some(_) => ui.label(readout("production", rate, 1)),
+            None => ui.label(readout("production-unowned", rate, 1)),
+        };
+        let entity = launch
+            .bodies
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(entity, _)| entity);
+        let density = entity.and_then(|entity| body_card.densities.get(entity).ok());
+        if let Some(density) = density {
+            let mut edited = density.0;
+            ui.horizontal(|ui| {
+                let label = ui.label(tr("density"));
+                ui.add(
+                    egui::DragValue::new(&mut edited)
+                        .speed(1e-3)
+                        .range(1e-4..=f32::MAX),
+                )
+                .labelled_by(label.id);
+            });
+            if edited != density.0 {
+                body_card.edits.write(EditorAction::Apply(Edit::SetDensity {
+                    body: name.to_string(),
+                    density: edited,
+                }));
+            }
+        }
+        let on_rails = entity.map(|entity| body_card.pinned.contains(entity));
+        if let Some(on_rails) = on_rails
+            && !launch.players.online
+        {
+            let mut edited = on_rails;
+            ui.checkbox(&mut edited, tr("on-rails"))
+                .on_hover_text(tr("on-rails-hint"));
+            if edited != on_rails {
+                body_card.edits.write(EditorAction::Apply(Edit::SetRails {
+                    body: name.to_string(),
+                    on_rails: edited,
+                }));
+            }
+        }
+        ui.label(readout("readout-mass", mass.0 as f64, 2));
+        ui.label(readout("readout-speed", velocity.0.length(), 2));
+        let ke = 0.5 * mass.0 as f64 * velocity.0.length_squared();
+        ui.label(readout("kinetic-energy", ke, 2));
+        ui.separator();
+        orbit_readout(ui, &body_card.bodies, name, constants.g as f64);
+        if let Some(entity) = entity {
+            if body_card.target.0 == Some(entity) {
+                if ui.button(tr("clear-target")).clicked() {
+                    body_card.target.0 = None;
+                }
+            } else if ui.button(tr("set-target")).clicked() {
+                body_card.target.0 = Some(entity);
+            }
+        }
+        if !launch.players.online && ui.button(tr("delete-body")).clicked() {
+            body_card
+                .edits
+                .write(EditorAction::Apply(Edit::Delete(name.to_string())));
+        }
+        ui.separator();
+        if owner.is_some_and(|owner| owner.0 == launch.players.active) {
+            launch_controls(ui, launch, name, crafts.0);
+        } else {
+            ui.label(readout("docked-crafts", crafts.0 as f64, 0));
+            ui.label(tr("only-owned-launch"));
+        }
+    });
+}
+
+/// Every body, or every craft, to pick one from.
+fn lists(
+    ui: &mut Ui,
+    body_card: &mut BodyCard,
+    craft_card: &mut CraftCard,
+    bodies: &Query<(Entity, &Name), With<Body>>,
+    view: &mut ViewRequest,
+) {
+    let showing = &mut craft_card.table.showing;
+    ui.horizontal(|ui| {
+        ui.selectable_value(showing, false, RichText::new(tr("bodies")).heading());
+        let crafts = tr_args(
+            "crafts-tab",
+            &[("count", craft_card.crafts.iter().len().into())],
+        );
+        ui.selectable_value(showing, true, RichText::new(crafts).heading());
+    });
+    if craft_card.table.showing {
+        let picked = craft_table(
+            ui,
+            &mut craft_card.table,
+            &craft_card.crafts,
+            &craft_card.homes,
+            bodies,
+        );
+        if picked.is_some() {
+            craft_card.selected.0 = picked;
+            view.0 = Some(ViewChange::CenterSelection);
+        }
+    } else {
+        body_table(
+            ui,
+            &mut body_card.table,
+            &body_card.bodies,
+            &mut body_card.selected,
+        );
+    }
+}
+
+/// Orbital elements of the named body around its dominant attractor.
+fn orbit_readout(ui: &mut Ui, bodies: &Query<BodyUiData>, name: &Name, g: f64) {
+    let (names, scratch): (Vec<_>, Vec<_>) = bodies
+        .iter()
+        .map(|(name, _, _, transform, _, mass, velocity, ..)| {
+            (
+                name,
+                ScratchBody {
+                    position: transform.translation.as_dvec3(),
+                    velocity: velocity.0,
+                    mass: mass.0 as f64,
+                },
+            )
+        })
+        .unzip();
+    let Some(index) = names.iter().position(|n| *n == name) else {
+        return;
+    };
+    let Some((attractor, elements)) = elements_around_attractor(index, &scratch, g) else {
+        ui.label(tr("not-orbiting"));
+        return;
+    };
+
+    ui.label(tr_args(
+        "orbiting",
+        &[("body", names[attractor].as_str().into())],
+    ));
+    ui.label(readout("semi-major-axis", elements.semi_major_axis, 2));
+    ui.label(readout("eccentricity", elements.eccentricity, 3));
+    ui.label(readout("periapsis", elements.periapsis, 2));
+    match (elements.apoapsis, elements.period) {
+        (Some(apoapsis), Some(period)) => {
+            ui.label(readout("apoapsis", apoapsis, 2));
+            ui.label(readout("period", period, 1));
+        }
+        _ => {
+            ui.label(tr("escaping"));
+        }
+    }
+}
+
+/// The overlay card for several selected bodies: their totals, and orders
+/// given to all of them at once.
+fn multi_body_card(
+    ui: &mut Ui,
+    names: &BTreeSet<String>,
+    bodies: &Query<BodyUiData>,
+    launch: &mut LaunchControls,
+    target: Option<Entity>,
+) {
+    let selected: Vec<_> = bodies
+        .iter()
+        .filter(|(name, ..)| names.contains(name.as_str()))
+        .collect();
+    let total_mass: f64 = selected
+        .iter()
+        .map(|(.., mass, _, _, _)| mass.0 as f64)
+        .sum();
+    let total_crafts: u32 = selected
+        .iter()
+        .map(|(_, _, _, _, crafts, ..)| crafts.0)
+        .sum();
+    let barycenter = selected
+        .iter()
+        .map(|(_, _, _, transform, _, mass, ..)| transform.translation.as_dvec3() * mass.0 as f64)
+        .sum::<DVec3>()
+        / total_mass;
+
+    ui.heading(tr_args(
+        "bodies-selected",
+        &[("count", selected.len().into())],
+    ));
+    framed_list(ui, |ui| {
+        ui.label(readout("total-mass", total_mass, 2));
+        ui.label(readout("total-crafts", total_crafts as f64, 0));
+        if barycenter.is_finite() {
+            ui.label(tr_args(
+                "barycenter",
+                &[
+                    ("x", format_number(barycenter.x, 2).into()),
+                    ("y", format_number(barycenter.y, 2).into()),
+                ],
+            ));
+        }
+        ui.separator();
+        for (name, _, fill, ..) in &selected {
+            ui.colored_label(fill.color(), name.as_str());
+        }
+        ui.separator();
+
+        let target_position = target
+            .and_then(|target| launch.bodies.get(target).ok())
+            .and_then(|(_, target)| bodies.iter().find(|(name, ..)| *name == target))
+            .map(|(_, _, _, transform, ..)| transform.translation.truncate());
+        // Bodies the side giving orders owns, with a craft to spare
+        let launchers: Vec<_> = selected
+            .iter()
+            .filter(|(_, _, _, _, crafts, .., owner)| {
+                crafts.0 > 0 && owner.is_some_and(|owner| owner.0 == launch.players.active)
+            })
+            .filter_map(|(name, _, _, transform, ..)| {
+                let (entity, _) = launch.bodies.iter().find(|(_, n)| n == name)?;
+                Some((entity, transform.translation.truncate()))
+            })
+            .filter(|(entity, _)| Some(*entity) != target)
+            .collect();
+        let button = ui
+            .add_enabled(
+                target_position.is_some() && !launchers.is_empty(),
+                egui::Button::new(tr_args(
+                    "launch-from-each",
+                    &[("count", launchers.len().into())],
+                )),
+            )
+            .on_hover_text(tr("launch-from-each-hint"))
+            .on_disabled_hover_text(tr("launch-from-each-disabled"));
+        if button.clicked()
+            && let Some(target_position) = target_position
+        {
+            for (from, position) in launchers {
+                launch.launches.write(LaunchCraft {
+                    from,
+                    destination: None,
+                    direction: (target_position - position).normalize_or_zero(),
+                    speed: launch.form.speed,
+                    scripted: false,
+                });
+            }
+        }
+    });
+}
+
+fn launch_controls(ui: &mut Ui, launch: &mut LaunchControls, from: &Name, docked: u32) {
+    let LaunchControls {
+        launches,
+        balance,
+        bodies,
+        form,
+        tutorial,
+        ..
+    } = launch;
+    let Some((class_name, class)) = balance.default_class() else {
+        ui.label(tr("loading-craft-classes"));
+        return;
+    };
+    let Some((from, _)) = bodies.iter().find(|(_, name)| *name == from) else {
+        return;
+    };
+
+    ui.label(readout("docked-crafts", docked as f64, 0));
+    let destination_name = form
+        .destination
+        .and_then(|entity| bodies.get(entity).ok())
+        .map_or_else(|| tr("target"), |(_, name)| name.to_string());
+    egui::ComboBox::from_label(tr("destination"))
+        .selected_text(destination_name)
+        .show_ui(ui, |ui| {
+            // Crafts launched without a destination head for the target
+            ui.selectable_value(&mut form.destination, None, tr("target"));
+            for (entity, name) in bodies.iter().filter(|(entity, _)| *entity != from) {
+                ui.selectable_value(&mut form.destination, Some(entity), name.as_str());
+            }
+        });
+    let speed = ui.add(
+        egui::Slider::new(&mut form.speed, 0.0..=class.launch_speed_limit()).text(tr("speed")),
+    );
+    tutorial.highlight(ui, &speed, TutorialTarget::SpeedSlider);
+    ui.label(readout(
+        "delta-v-after-launch",
+        (class.fuel - form.speed) as f64,
+        2,
+    ));
+    let angle = ui.add(egui::Slider::new(&mut form.angle, 0.0..=360.0).text(tr("angle")));
+    tutorial.highlight(ui, &angle, TutorialTarget::AngleSlider);
+
+    let button = ui.add_enabled(
+        docked > 0,
+        egui::Button::new(tr_args("launch-class", &[("class", class_name.into())])),
+    );
+    tutorial.highlight(ui, &button, TutorialTarget::LaunchButton);
+    if button.clicked() {
+        launches.write(LaunchCraft {
+            from,
+            destination: form.destination,
+            direction: Vec2::from_angle(form.angle.to_radians()),
+            speed: form.speed,
+            scripted: false,
+        });
+    }
+}
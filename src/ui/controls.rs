@@ -0,0 +1,195 @@
+use super::readout;
+use crate::autopilot::Autopilot;
+use crate::crafts::BurnCraft;
+use crate::engines::{BurnDirection, THROTTLE_STEP, Throttle};
+use crate::i18n::{format_number, tr, tr_args};
+use crate::interaction::InteractionMode;
+use crate::maneuver::ManeuverNode;
+use crate::sails::SolarSail;
+use bevy::prelude::*;
+use bevy_egui::egui::{self, Color32, Ui};
+
+/// The burn the selected craft's card is set to make.
+pub struct BurnForm {
+    direction: BurnDirection,
+    delta_v: f32,
+}
+
+impl Default for BurnForm {
+    fn default() -> Self {
+        Self {
+            direction: BurnDirection::Prograde,
+            delta_v: 1.,
+        }
+    }
+}
+
+pub fn burn_controls(
+    ui: &mut Ui,
+    burns: &mut EventWriter<BurnCraft>,
+    form: &mut BurnForm,
+    craft: Entity,
+    velocity: Vec2,
+    fuel: f32,
+) {
+    if fuel <= 0. {
+        ui.label(tr("craft-out-of-fuel"));
+        return;
+    }
+    egui::ComboBox::from_label(tr("direction"))
+        .selected_text(form.direction.label())
+        .show_ui(ui, |ui| {
+            for direction in BurnDirection::ALL {
+                ui.selectable_value(&mut form.direction, direction, direction.label());
+            }
+        });
+    form.delta_v = form.delta_v.min(fuel);
+    ui.add(egui::Slider::new(&mut form.delta_v, 0.0..=fuel).text("Δv"));
+
+    let direction = form.direction.relative_to(velocity);
+    let button = ui.add_enabled(
+        direction.is_some() && form.delta_v > 0.,
+        egui::Button::new(tr("burn")),
+    );
+    if let Some(direction) = direction
+        && button.clicked()
+    {
+        burns.write(BurnCraft {
+            craft,
+            delta_v: direction * form.delta_v,
+        });
+    }
+}
+
+pub fn maneuver_controls(
+    ui: &mut Ui,
+    commands: &mut Commands,
+    craft: Entity,
+    node: Option<&ManeuverNode>,
+    fuel: f32,
+    now: f64,
+) {
+    let Some(node) = node else {
+        ui.label(tr_args(
+            "maneuver-hint",
+            &[("mode", InteractionMode::Maneuver.label().into())],
+        ));
+        return;
+    };
+    let mut edited = *node;
+    ui.label(readout("maneuver-in", node.time - now, 1));
+    ui.horizontal(|ui| {
+        ui.label(tr("burn-prograde"));
+        ui.add(egui::DragValue::new(&mut edited.prograde).speed(0.05));
+    });
+    ui.horizontal(|ui| {
+        ui.label(tr("radial"));
+        ui.add(egui::DragValue::new(&mut edited.radial).speed(0.05));
+    });
+    let delta_v = edited.delta_v();
+    if delta_v > fuel {
+        ui.colored_label(
+            Color32::RED,
+            readout("maneuver-not-enough-fuel", delta_v as f64, 2),
+        );
+    } else {
+        ui.label(readout("maneuver-delta-v", delta_v as f64, 2));
+    }
+    if ui.button(tr("remove-node")).clicked() {
+        commands.entity(craft).remove::<ManeuverNode>();
+    } else if edited.prograde != node.prograde || edited.radial != node.radial {
+        commands.entity(craft).insert(edited);
+    }
+}
+
+/// Picks the craft's autopilot. Intercepting needs a target or destination,
+/// and station-keeping needs one with Lagrange points.
+pub fn autopilot_controls(
+    ui: &mut Ui,
+    commands: &mut Commands,
+    craft: Entity,
+    autopilot: Option<&Autopilot>,
+    aim: Option<Entity>,
+    station: Option<Entity>,
+) {
+    let mut options = vec![Autopilot::HoldOrbit, Autopilot::ReturnHome];
+    options.extend(aim.map(Autopilot::Intercept));
+    options.extend(
+        station
+            .into_iter()
+            .flat_map(|body| (0..5).map(move |point| Autopilot::StationKeep { body, point })),
+    );
+
+    let mut edited = autopilot.copied();
+    egui::ComboBox::from_label(tr("autopilot"))
+        .selected_text(edited.map_or_else(|| tr("off"), |autopilot| autopilot.label()))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut edited, None, tr("off"));
+            for option in options {
+                ui.selectable_value(&mut edited, Some(option), option.label());
+            }
+        });
+    if edited.as_ref() != autopilot {
+        match edited {
+            Some(autopilot) => commands.entity(craft).insert(autopilot),
+            None => commands.entity(craft).remove::<Autopilot>(),
+        };
+    }
+}
+
+/// Throttle and thrust direction for a craft's engine, which keeps firing
+/// until throttled back or out of fuel.
+pub fn engine_controls(
+    ui: &mut Ui,
+    commands: &mut Commands,
+    craft: Entity,
+    throttle: Option<&Throttle>,
+    acceleration: f32,
+) {
+    let throttle = throttle.copied().unwrap_or_default();
+    let mut edited = throttle;
+    egui::ComboBox::from_label(tr("thrust"))
+        .selected_text(edited.direction.label())
+        .show_ui(ui, |ui| {
+            for direction in BurnDirection::ALL {
+                ui.selectable_value(&mut edited.direction, direction, direction.label());
+            }
+        });
+    ui.add(
+        egui::Slider::new(&mut edited.level, 0.0..=1.)
+            .step_by(THROTTLE_STEP as f64 / 2.)
+            .custom_formatter(|level, _| format!("{}%", format_number(level * 100., 0)))
+            .text(tr("throttle")),
+    );
+    ui.label(readout(
+        "acceleration",
+        (acceleration * edited.level) as f64,
+        2,
+    ));
+    if edited != throttle {
+        commands.entity(craft).insert(edited);
+    }
+}
+
+/// Deploys or retracts a craft's solar sail.
+pub fn sail_controls(
+    ui: &mut Ui,
+    commands: &mut Commands,
+    craft: Entity,
+    deployed: bool,
+    push: f64,
+) {
+    let mut edited = deployed;
+    ui.checkbox(&mut edited, tr("solar-sail"))
+        .on_hover_text(tr("solar-sail-hint"));
+    if edited {
+        ui.label(readout("sail-push", push, 3));
+    }
+    if edited != deployed {
+        if edited {
+            commands.entity(craft).insert(SolarSail);
+        } else {
+            commands.entity(craft).remove::<SolarSail>();
+        }
+    }
+}
@@ -0,0 +1,502 @@
+use super::plot::NodeHandle;
+use super::{BodyUiData, CraftState, readout};
+use crate::bodies::{Appearance, Fill, Radius, Spin};
+use crate::factions::Faction;
+use crate::i18n::{format_number, tr, tr_args};
+use crate::maneuver::ManeuverNode;
+use crate::measure::{MeasureTool, Measured};
+use crate::trajectory::{Apsis, ApsisKind, ClosestApproach, PredictedTrajectory};
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy_egui::egui::{self, Align2, Color32, Frame, RichText, Stroke, Ui, vec2};
+use std::f32::consts::PI;
+
+/// Screen points the scale bar spans at most.
+const SCALE_BAR_WIDTH: f64 = 120.;
+
+/// A closed polygon approximating a circle in the plot.
+pub fn circle_points(center: DVec3, radius: f64) -> Vec<[f64; 2]> {
+    (0..=90)
+        .map(|i| (i * 4) as f64 * std::f64::consts::PI / 180.)
+        .map(|angle| {
+            [
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            ]
+        })
+        .collect()
+}
+
+/// How dark the side of a body facing away from the star is.
+const NIGHT_SHADE: u8 = 140;
+
+/// Darkens the half of a body facing away from `sunward`.
+pub fn plot_night_side(ui: &mut egui_plot::PlotUi, center: Vec2, radius: f32, sunward: Vec2) {
+    let facing = sunward.to_angle();
+    let half_disc = (0..=45)
+        .map(|i| facing + PI / 2. + i as f32 * PI / 45.)
+        .map(|angle| center + Vec2::from_angle(angle) * radius)
+        .map(|point| [point.x as f64, point.y as f64])
+        .collect::<Vec<_>>();
+    ui.polygon(
+        egui_plot::Polygon::new("", half_disc)
+            .fill_color(Color32::from_black_alpha(NIGHT_SHADE))
+            .stroke(Stroke::NONE),
+    );
+}
+
+/// A tail streaming off a comet, widening away from it, brightest along the
+/// middle.
+pub fn plot_comet_tail(
+    ui: &mut egui_plot::PlotUi,
+    center: Vec2,
+    radius: f32,
+    tail: Vec2,
+    color: Color32,
+) {
+    let across = tail.perp().normalize_or_zero() * radius;
+    for (reach, width, opacity) in [(1., 2., 0.15), (0.6, 1., 0.25)] {
+        let tip = center + tail * reach;
+        let outline = [
+            center + across,
+            tip + across * width,
+            tip - across * width,
+            center - across,
+        ]
+        .map(|point| [point.x as f64, point.y as f64]);
+        ui.polygon(
+            egui_plot::Polygon::new("", outline.to_vec())
+                .fill_color(color.gamma_multiply(opacity))
+                .stroke(Stroke::NONE),
+        );
+    }
+}
+
+/// Marks where a spinning body's launch site is, so its rotation shows.
+pub fn plot_launch_site(ui: &mut egui_plot::PlotUi, center: Vec2, radius: f32, spin: &Spin) {
+    let normal = spin.site_normal();
+    let [inner, site] = [0.6, 1.].map(|reach| {
+        let point = center + normal * radius * reach;
+        [point.x as f64, point.y as f64]
+    });
+    ui.line(
+        egui_plot::Line::new("", vec![inner, site])
+            .color(Color32::WHITE.gamma_multiply(0.7))
+            .width(2.),
+    );
+    ui.points(
+        egui_plot::Points::new("", site)
+            .color(Color32::WHITE)
+            .radius(2.5),
+    );
+}
+
+/// Translucent discs stacked beyond the surface, fading out toward the edge.
+const GLOW_STEPS: usize = 6;
+/// Shades a gradient goes through from the rim to the middle.
+const GRADIENT_STEPS: usize = 8;
+
+/// Draws what bodies' [`Appearance`]s add to their flat fill: glows `behind`
+/// the body polygons, or gradients and rings over them.
+pub fn plot_appearances(
+    ui: &mut egui_plot::PlotUi,
+    appearances: &Query<(&Transform, &Radius, &Fill, &Appearance)>,
+    world_per_point: f64,
+    behind: bool,
+) {
+    for (transform, radius, fill, appearance) in appearances {
+        let center = transform.translation.as_dvec3();
+        let radius = radius.0 as f64;
+        if behind {
+            let Some(glow) = appearance.glow else {
+                continue;
+            };
+            for step in 1..=GLOW_STEPS {
+                let reach = 1. + glow as f64 * step as f64 / GLOW_STEPS as f64;
+                ui.polygon(
+                    egui_plot::Polygon::new("", circle_points(center, radius * reach))
+                        .fill_color(fill.color().gamma_multiply(0.08))
+                        .stroke(Stroke::NONE),
+                );
+            }
+            continue;
+        }
+        if let Some([r, g, b]) = appearance.gradient {
+            let rim = Color32::from_rgb(r, g, b);
+            for step in 0..GRADIENT_STEPS {
+                let t = step as f32 / GRADIENT_STEPS as f32;
+                ui.polygon(
+                    egui_plot::Polygon::new("", circle_points(center, radius * (1. - t as f64)))
+                        .fill_color(mix(rim, fill.color(), t))
+                        .stroke(Stroke::NONE),
+                );
+            }
+        }
+        if let Some(rings) = appearance.rings {
+            let [r, g, b] = rings.color;
+            let middle = (rings.inner + rings.outer) as f64 / 2.;
+            let width = (rings.outer - rings.inner) as f64 * radius / world_per_point;
+            ui.line(
+                egui_plot::Line::new("", circle_points(center, radius * middle))
+                    .color(Color32::from_rgb(r, g, b).gamma_multiply(0.6))
+                    .width(width as f32),
+            );
+        }
+    }
+}
+
+fn mix(from: Color32, to: Color32, t: f32) -> Color32 {
+    let channel = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * t).round() as u8;
+    Color32::from_rgb(
+        channel(from.r(), to.r()),
+        channel(from.g(), to.g()),
+        channel(from.b(), to.b()),
+    )
+}
+
+/// Marks periapses and apoapses with their altitudes.
+pub fn plot_apsides(ui: &mut egui_plot::PlotUi, apsides: &[Apsis], color: Color32) {
+    for apsis in apsides {
+        let (shape, id) = match apsis.kind {
+            ApsisKind::Periapsis => (egui_plot::MarkerShape::Down, "apsis-periapsis"),
+            ApsisKind::Apoapsis => (egui_plot::MarkerShape::Up, "apsis-apoapsis"),
+        };
+        let position = [apsis.position.x, apsis.position.y];
+        ui.points(
+            egui_plot::Points::new("", position)
+                .shape(shape)
+                .filled(true)
+                .color(color)
+                .radius(4.),
+        );
+        ui.text(
+            egui_plot::Text::new(
+                "",
+                egui_plot::PlotPoint::from(position),
+                RichText::new(readout(id, apsis.altitude, 1)).size(plot_text_size(ui.ctx(), 11.)),
+            )
+            .color(color)
+            .anchor(Align2::LEFT_TOP),
+        );
+    }
+}
+
+/// Marks where a craft and its destination will be when closest, on both
+/// their trajectories.
+fn plot_closest_approach(ui: &mut egui_plot::PlotUi, closest_approach: &ClosestApproach) {
+    let craft = [
+        closest_approach.craft_position.x,
+        closest_approach.craft_position.y,
+    ];
+    let body = [
+        closest_approach.body_position.x,
+        closest_approach.body_position.y,
+    ];
+    ui.line(
+        egui_plot::Line::new("", vec![craft, body])
+            .color(Color32::KHAKI.gamma_multiply(0.6))
+            .style(egui_plot::LineStyle::dotted_dense()),
+    );
+    for position in [craft, body] {
+        ui.points(
+            egui_plot::Points::new(tr("closest-approach"), position)
+                .shape(egui_plot::MarkerShape::Diamond)
+                .color(Color32::KHAKI)
+                .radius(4.),
+        );
+    }
+    ui.text(
+        egui_plot::Text::new(
+            "",
+            egui_plot::PlotPoint::from(craft),
+            RichText::new(readout(
+                "closest-approach-marker",
+                closest_approach.distance.max(0.),
+                1,
+            ))
+            .size(plot_text_size(ui.ctx(), 11.)),
+        )
+        .color(Color32::KHAKI)
+        .anchor(Align2::LEFT_BOTTOM),
+    );
+}
+
+pub fn closest_approach_label(closest_approach: &ClosestApproach) -> String {
+    let time = format_number(closest_approach.time, 1);
+    if closest_approach.distance <= 0. {
+        tr_args("impact-in", &[("time", time.into())])
+    } else {
+        tr_args(
+            "approach-in",
+            &[
+                (
+                    "distance",
+                    format_number(closest_approach.distance, 2).into(),
+                ),
+                ("time", time.into()),
+            ],
+        )
+    }
+}
+
+/// Labels at the edge of the view are this opaque, fading in toward the middle.
+const LABEL_EDGE_OPACITY: f32 = 0.35;
+
+/// Names each body beside it on screen, trying a few spots around it and
+/// leaving out labels with nowhere to go that doesn't cover another label.
+/// Bodies appearing larger claim their spots first.
+pub fn draw_body_labels(
+    ui: &Ui,
+    plot_response: &egui_plot::PlotResponse<()>,
+    bodies: &Query<BodyUiData>,
+) {
+    let rect = plot_response.response.rect;
+    let painter = ui.painter_at(rect);
+    let font = egui::FontId::proportional(plot_text_size(ui.ctx(), 12.));
+    let half_diagonal = rect.size().length() / 2.;
+
+    let mut on_screen: Vec<_> = bodies
+        .iter()
+        .map(|(name, radius, fill, transform, ..)| {
+            let Vec3 { x, y, .. } = transform.translation;
+            let center = plot_response
+                .transform
+                .position_from_point(&egui_plot::PlotPoint::new(x, y));
+            let radius = radius.0 * plot_response.transform.dpos_dvalue_x() as f32;
+            (name, fill, center, radius)
+        })
+        .filter(|(_, _, center, radius)| rect.expand(*radius).contains(*center))
+        .collect();
+    on_screen.sort_by(|(.., a), (.., b)| b.total_cmp(a));
+
+    let mut placed: Vec<egui::Rect> = Vec::new();
+    for (name, fill, center, radius) in on_screen {
+        let fade = (center.distance(rect.center()) / half_diagonal).min(1.);
+        let color = fill
+            .color()
+            .gamma_multiply(1. - fade * (1. - LABEL_EDGE_OPACITY));
+        let galley = painter.layout_no_wrap(name.to_string(), font.clone(), color);
+        let spots = [
+            (Align2::CENTER_TOP, center + vec2(0., radius + 2.)),
+            (Align2::CENTER_BOTTOM, center - vec2(0., radius + 2.)),
+            (Align2::LEFT_CENTER, center + vec2(radius + 4., 0.)),
+            (Align2::RIGHT_CENTER, center - vec2(radius + 4., 0.)),
+        ];
+        let free = spots
+            .into_iter()
+            .map(|(align, anchor)| align.anchor_size(anchor, galley.size()))
+            .find(|spot| !placed.iter().any(|other| other.intersects(*spot)));
+        if let Some(spot) = free {
+            painter.galley(spot.min, galley, color);
+            placed.push(spot);
+        }
+    }
+}
+
+/// Explains craft colors and markers, below the scale bar.
+pub fn craft_legend(ui: &Ui, plot_rect: egui::Rect) {
+    egui::Area::new(egui::Id::new("craft_legend"))
+        .fixed_pos(plot_rect.left_top() + vec2(16., 48.))
+        .interactable(false)
+        .show(ui.ctx(), |ui| {
+            Frame::popup(ui.style()).show(ui, legend_entries);
+        });
+}
+
+/// What craft colors and markers mean, one per line.
+pub fn legend_entries(ui: &mut Ui) {
+    for faction in [Faction::Player, Faction::Opponent] {
+        ui.colored_label(
+            faction.color(),
+            format!("{} {}", faction.symbol(), faction.name()),
+        );
+    }
+    ui.colored_label(Color32::LIGHT_GRAY, format!("● {}", tr("unowned")));
+    ui.separator();
+    for state in CraftState::ALL {
+        ui.label(format!("{} {}", state.symbol(), state.label()));
+    }
+}
+
+/// Plot text `size`, in points at egui's default text size, grown or shrunk
+/// with the text size setting.
+pub fn plot_text_size(ctx: &egui::Context, size: f32) -> f32 {
+    let body = |style: &egui::Style| {
+        style
+            .text_styles
+            .get(&egui::TextStyle::Body)
+            .map_or(1., |font| font.size)
+    };
+    size * body(&ctx.style()) / body(&egui::Style::default())
+}
+
+/// Draws a bar of a round length in the plot's top left corner.
+pub fn draw_scale_bar(ui: &Ui, plot_response: &egui_plot::PlotResponse<()>) {
+    let points_per_unit = plot_response.transform.dpos_dvalue_x();
+    let Some((length, decimals)) = round_length(SCALE_BAR_WIDTH / points_per_unit) else {
+        return;
+    };
+    let rect = plot_response.response.rect;
+    let left = rect.left_top() + vec2(16., 24.);
+    let right = left + vec2((length * points_per_unit) as f32, 0.);
+    let color = ui.visuals().text_color();
+    let stroke = Stroke::new(1.5, color);
+    let painter = ui.painter_at(rect);
+    painter.line_segment([left, right], stroke);
+    for end in [left, right] {
+        painter.line_segment([end - vec2(0., 4.), end + vec2(0., 4.)], stroke);
+    }
+    painter.text(
+        left.lerp(right, 0.5) - vec2(0., 6.),
+        Align2::CENTER_BOTTOM,
+        format_number(length, decimals),
+        egui::FontId::proportional(plot_text_size(ui.ctx(), 12.)),
+        color,
+    );
+}
+
+/// The longest of 1, 2 or 5 times a power of ten that fits in `max`, and the
+/// decimal places needed to print it.
+fn round_length(max: f64) -> Option<(f64, usize)> {
+    if !max.is_finite() || max <= 0. {
+        return None;
+    }
+    let exponent = max.log10().floor() as i32;
+    let magnitude = 10f64.powi(exponent);
+    let step = [5., 2., 1.]
+        .into_iter()
+        .find(|step| step * magnitude <= max)
+        .unwrap_or(1.);
+    Some((step * magnitude, (-exponent).max(0) as usize))
+}
+
+/// Draws the ruler with its readings, or while only one end is placed, a
+/// line from it to the pointer.
+pub fn plot_measurement(
+    ui: &mut egui_plot::PlotUi,
+    tool: &MeasureTool,
+    selected_craft: Option<Entity>,
+) {
+    let color = Color32::LIGHT_BLUE;
+    let ends: Vec<_> = [tool.measurement.start, tool.measurement.end]
+        .into_iter()
+        .flatten()
+        .filter_map(|end| tool.locate(end))
+        .map(|(position, _)| [position.x, position.y])
+        .collect();
+    ui.points(egui_plot::Points::new("", ends).color(color).radius(3.));
+
+    let Some(Measured {
+        from,
+        to,
+        distance,
+        closing_speed,
+        relative_speed,
+        travel_time,
+    }) = tool.measure(selected_craft)
+    else {
+        let start = tool.measurement.start.and_then(|start| tool.locate(start));
+        if let (Some((from, _)), Some(pointer)) = (start, ui.pointer_coordinate()) {
+            ui.line(
+                egui_plot::Line::new("", vec![[from.x, from.y], [pointer.x, pointer.y]])
+                    .color(color.gamma_multiply(0.6))
+                    .style(egui_plot::LineStyle::dashed_dense()),
+            );
+        }
+        return;
+    };
+
+    ui.line(
+        egui_plot::Line::new(tr("measurement"), vec![[from.x, from.y], [to.x, to.y]])
+            .color(color)
+            .width(1.5),
+    );
+    let relative = tr_args(
+        if closing_speed < 0. {
+            "measure-closing"
+        } else {
+            "measure-separating"
+        },
+        &[
+            ("relative", format_number(relative_speed, 2).into()),
+            ("speed", format_number(closing_speed.abs(), 2).into()),
+        ],
+    );
+    let timing = match travel_time {
+        Some(time) => readout("measure-travel-time", time, 1),
+        None => tr("measure-no-crafts"),
+    };
+    let label = format!(
+        "{}\n{relative}\n{timing}",
+        readout("measure-apart", distance, 2)
+    );
+    let middle = (from + to) / 2.;
+    ui.text(
+        egui_plot::Text::new(
+            "",
+            egui_plot::PlotPoint::new(middle.x, middle.y),
+            RichText::new(label).size(plot_text_size(ui.ctx(), 12.)),
+        )
+        .color(color)
+        .anchor(Align2::LEFT_BOTTOM),
+    );
+}
+
+/// Draws a craft's predicted path, and its maneuver node with handles.
+pub fn plot_trajectory(
+    ui: &mut egui_plot::PlotUi,
+    trajectory: &PredictedTrajectory,
+    node: Option<&ManeuverNode>,
+) {
+    let points =
+        |positions: &[DVec3]| -> Vec<[f64; 2]> { positions.iter().map(|p| [p.x, p.y]).collect() };
+    ui.line(
+        egui_plot::Line::new(tr("trajectory"), points(&trajectory.coast))
+            .color(Color32::LIGHT_GRAY.gamma_multiply(0.5))
+            .style(egui_plot::LineStyle::dashed_loose()),
+    );
+    let (Some(planned), Some(node)) = (&trajectory.planned, node) else {
+        plot_apsides(ui, &trajectory.apsides, Color32::LIGHT_GRAY);
+        if let Some(closest_approach) = &trajectory.closest_approach {
+            plot_closest_approach(ui, closest_approach);
+        }
+        return;
+    };
+    if let Some(closest_approach) = &planned.closest_approach {
+        plot_closest_approach(ui, closest_approach);
+    }
+    ui.line(
+        egui_plot::Line::new(tr("planned-trajectory"), points(&planned.points))
+            .color(Color32::ORANGE),
+    );
+    plot_apsides(ui, &planned.apsides, Color32::ORANGE);
+    ui.points(
+        egui_plot::Points::new(
+            tr("maneuver-node"),
+            [planned.position.x, planned.position.y],
+        )
+        .color(Color32::ORANGE)
+        .radius(4.),
+    );
+    for (handle, color) in [
+        (NodeHandle::Prograde, Color32::GREEN),
+        (NodeHandle::Radial, Color32::LIGHT_BLUE),
+    ] {
+        let (position, _) = handle.placement(planned, node);
+        ui.line(
+            egui_plot::Line::new(
+                "",
+                vec![
+                    [planned.position.x, planned.position.y],
+                    [position.x, position.y],
+                ],
+            )
+            .color(color.gamma_multiply(0.5)),
+        );
+        ui.points(
+            egui_plot::Points::new("", [position.x, position.y])
+                .color(color)
+                .radius(5.),
+        );
+    }
+}
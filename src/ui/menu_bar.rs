@@ -0,0 +1,411 @@
+use super::{UiState, ViewChange, ViewRequest, ViewSettings, accessible_region};
+use crate::app_state::AppState;
+use crate::bindings::{Action, KeyBindings};
+use crate::capture::{Capture, GifRecording};
+use crate::editor::{EditHistory, EditorAction};
+use crate::events::Toasts;
+use crate::factions::{CombatResolution, CombatRules, Players};
+use crate::help::HelpWindow;
+use crate::i18n::{tr, tr_args};
+use crate::idle::IdleSettings;
+use crate::mods::ModsWindow;
+use crate::palette::Palette;
+use crate::picker::{ExportScenario, ScenarioPicker};
+use crate::recorder::Telemetry;
+use crate::replay::{Playback, ReplayLog};
+use crate::settings::SettingsWindow;
+use crate::trajectory::TrajectorySettings;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_egui::{
+    EguiContexts,
+    egui::{self, MenuBar, TopBottomPanel, Ui, WidgetInfo, WidgetType},
+};
+use bevy_persistent::prelude::*;
+
+/// Must run before the plot, since egui needs panels added before the
+/// central panel.
+pub fn menu_bar(
+    mut contexts: EguiContexts,
+    mut game: GameMenu,
+    mut edit: EditMenu,
+    mut view: ViewMenu,
+    mut exports: Exports,
+    mut windows: Windows,
+    bindings: Res<Persistent<KeyBindings>>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    TopBottomPanel::top("top_panel").show(ctx, |ui| {
+        accessible_region(ui, &tr("menu-bar-region"), |ui| {
+            MenuBar::new().ui(ui, |ui| {
+                ui.menu_button(tr("menu-game"), |ui| {
+                    game_menu(ui, &mut game, &mut exports, &bindings);
+                });
+                // Edits would desync an online match
+                ui.add_enabled_ui(!game.players.online, |ui| {
+                    ui.menu_button(tr("menu-edit"), |ui| edit_menu(ui, &mut edit, &bindings));
+                });
+                ui.menu_button(tr("menu-view"), |ui| view_menu(ui, &mut view, &bindings));
+                ui.menu_button(tr("menu-debug"), |ui| {
+                    debug_menu(ui, &mut view.ui_state, &mut exports);
+                });
+                match_status(ui, &mut game);
+                window_toggles(ui, &mut windows, &bindings);
+            })
+        });
+    });
+}
+
+/// What the Game menu and the match status beside the menus change. Web
+/// builds have no mods folder.
+#[derive(SystemParam)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct GameMenu<'w> {
+    idle_settings: ResMut<'w, IdleSettings>,
+    scenario_picker: ResMut<'w, ScenarioPicker>,
+    mods_window: ResMut<'w, ModsWindow>,
+    next_state: ResMut<'w, NextState<AppState>>,
+    combat: ResMut<'w, CombatRules>,
+    players: ResMut<'w, Players>,
+    playback: Option<Res<'w, Playback>>,
+}
+
+/// Undoing, redoing and adding bodies.
+#[derive(SystemParam)]
+pub struct EditMenu<'w> {
+    history: Res<'w, EditHistory>,
+    edits: EventWriter<'w, EditorAction>,
+}
+
+/// Files the menu bar can save, and toasts to say where they went. Web
+/// builds have nowhere to save them.
+#[derive(SystemParam)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct Exports<'w> {
+    replay_log: Res<'w, ReplayLog>,
+    scenarios: EventWriter<'w, ExportScenario>,
+    telemetry: ResMut<'w, Telemetry>,
+    toasts: ResMut<'w, Toasts>,
+    time: Res<'w, Time<Real>>,
+    captures: EventWriter<'w, Capture>,
+    gif: Res<'w, GifRecording>,
+}
+
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+impl Exports<'_> {
+    fn notify(&mut self, message: String) {
+        let now = self.time.elapsed_secs_f64();
+        self.toasts.push(message, now);
+    }
+}
+
+/// Windows the menu bar opens and closes.
+#[derive(SystemParam)]
+pub struct Windows<'w> {
+    settings: ResMut<'w, SettingsWindow>,
+    help: ResMut<'w, HelpWindow>,
+}
+
+/// What the View menu shows on the plot, and ways it changes the plot's view.
+#[derive(SystemParam)]
+pub struct ViewMenu<'w> {
+    ui_state: ResMut<'w, UiState>,
+    palette: ResMut<'w, Palette>,
+    view: ResMut<'w, ViewRequest>,
+    plot: ResMut<'w, ViewSettings>,
+    trajectory_settings: ResMut<'w, TrajectorySettings>,
+}
+
+#[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+fn game_menu(ui: &mut Ui, game: &mut GameMenu, exports: &mut Exports, bindings: &KeyBindings) {
+    if ui.button(tr("main-menu")).clicked() {
+        game.next_state.set(AppState::Menu);
+    }
+    if ui.button(tr("scenarios")).clicked() {
+        game.scenario_picker.open = true;
+    }
+    // Browsers have no mods folder
+    #[cfg(not(target_arch = "wasm32"))]
+    if ui.button(tr("mods")).clicked() {
+        game.mods_window.0 = true;
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if ui.button(tr("open-log-folder")).clicked() {
+        crate::logging::open_log_directory();
+    }
+    // Browsers could only keep it, not share it
+    #[cfg(not(target_arch = "wasm32"))]
+    if ui
+        .button(tr("export-replay"))
+        .on_hover_text(tr("export-replay-hint"))
+        .clicked()
+    {
+        let message = match exports.replay_log.export() {
+            Ok(path) => tr_args(
+                "replay-saved",
+                &[("path", path.display().to_string().into())],
+            ),
+            Err(err) => tr_args("replay-not-saved", &[("error", err.to_string().into())]),
+        };
+        exports.notify(message);
+        ui.close();
+    }
+    // Web builds keep it in local storage, for the picker
+    ui.horizontal(|ui| {
+        if ui
+            .button(tr("export-scenario"))
+            .on_hover_text(tr("export-scenario-hint"))
+            .clicked()
+        {
+            exports.scenarios.write(ExportScenario {
+                crafts: game.scenario_picker.export_crafts,
+                share: false,
+            });
+            ui.close();
+        }
+        ui.checkbox(&mut game.scenario_picker.export_crafts, tr("with-crafts"));
+    });
+    if ui
+        .button(tr("share-link"))
+        .on_hover_text(tr("share-link-hint"))
+        .clicked()
+    {
+        exports.scenarios.write(ExportScenario {
+            crafts: game.scenario_picker.export_crafts,
+            share: true,
+        });
+        ui.close();
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if ui
+            .add(
+                egui::Button::new(tr("screenshot"))
+                    .shortcut_text(bindings.binding(Action::Screenshot).to_string()),
+            )
+            .clicked()
+        {
+            exports.captures.write(Capture::Screenshot);
+            ui.close();
+        }
+        let gif = if exports.gif.is_recording() {
+            tr("stop-recording-gif")
+        } else {
+            tr("record-gif")
+        };
+        if ui
+            .add(
+                egui::Button::new(gif)
+                    .shortcut_text(bindings.binding(Action::RecordGif).to_string()),
+            )
+            .clicked()
+        {
+            exports.captures.write(Capture::ToggleGif);
+            ui.close();
+        }
+    }
+    ui.separator();
+    let idle_settings = &mut game.idle_settings;
+    ui.checkbox(&mut idle_settings.auto_pause, tr("pause-when-idle"));
+    ui.add_enabled(
+        idle_settings.auto_pause,
+        egui::Slider::new(&mut idle_settings.timeout, 10.0..=600.0).text(tr("idle-timeout")),
+    );
+    ui.separator();
+    // Edit a copy so the resource is only marked changed on actual edits
+    let mut edited = *game.combat;
+    egui::ComboBox::from_label(tr("combat"))
+        .selected_text(edited.resolution.label())
+        .show_ui(ui, |ui| {
+            for resolution in CombatResolution::ALL {
+                ui.selectable_value(&mut edited.resolution, resolution, resolution.label());
+            }
+        });
+    ui.add_enabled(
+        edited.resolution == CombatResolution::Duels,
+        egui::Slider::new(&mut edited.defender_advantage, 0.25..=4.0)
+            .logarithmic(true)
+            .text(tr("defender-advantage")),
+    );
+    if edited != *game.combat {
+        *game.combat = edited;
+    }
+    ui.separator();
+    let mut hot_seat = game.players.hot_seat;
+    ui.checkbox(&mut hot_seat, tr("hot-seat"))
+        .on_hover_text(tr("hot-seat-hint"));
+    if hot_seat != game.players.hot_seat {
+        game.players.set_hot_seat(hot_seat);
+    }
+}
+
+fn edit_menu(ui: &mut Ui, edit: &mut EditMenu, bindings: &KeyBindings) {
+    let undo = edit.history.next_undo();
+    if ui
+        .add_enabled(
+            undo.is_some(),
+            egui::Button::new(tr_args(
+                "undo",
+                &[("edit", undo.unwrap_or_default().into())],
+            ))
+            .shortcut_text(bindings.binding(Action::Undo).to_string()),
+        )
+        .clicked()
+    {
+        edit.edits.write(EditorAction::Undo);
+    }
+    let redo = edit.history.next_redo();
+    if ui
+        .add_enabled(
+            redo.is_some(),
+            egui::Button::new(tr_args(
+                "redo",
+                &[("edit", redo.unwrap_or_default().into())],
+            ))
+            .shortcut_text(bindings.binding(Action::Redo).to_string()),
+        )
+        .clicked()
+    {
+        edit.edits.write(EditorAction::Redo);
+    }
+    ui.separator();
+    if ui.button(tr("add-body")).clicked() {
+        edit.edits.write(EditorAction::AddBody);
+    }
+}
+
+fn view_menu(ui: &mut Ui, view: &mut ViewMenu, bindings: &KeyBindings) {
+    let ui_state = &mut view.ui_state;
+    ui.checkbox(
+        &mut ui_state.show_spheres_of_influence,
+        tr("spheres-of-influence"),
+    );
+    ui.checkbox(&mut ui_state.show_lagrange_points, tr("lagrange-points"));
+    ui.checkbox(
+        &mut ui_state.show_zero_velocity_curves,
+        tr("zero-velocity-curves"),
+    );
+    ui.checkbox(&mut ui_state.show_hill_spheres, tr("hill-spheres"));
+    ui.checkbox(&mut ui_state.show_roche_limits, tr("roche-limits"));
+    ui.checkbox(&mut ui_state.show_scoreboard, tr("scoreboard"));
+    ui.checkbox(&mut ui_state.show_event_log, tr("event-log"));
+    ui.checkbox(&mut ui_state.show_stats, tr("statistics"));
+    ui.checkbox(&mut ui_state.follow_selection, Action::Follow.label())
+        .on_hover_text(bindings.binding(Action::Follow).to_string());
+    ui.separator();
+    view.plot.checkboxes(ui);
+    ui.separator();
+    if ui
+        .add(
+            egui::Button::new(tr("go-to-body"))
+                .shortcut_text(bindings.binding(Action::Palette).to_string()),
+        )
+        .clicked()
+    {
+        view.palette.open = true;
+        ui.close();
+    }
+    for (action, change) in [
+        (Action::FitAll, ViewChange::FitAll),
+        (Action::FrameSelection, ViewChange::FrameSelection),
+    ] {
+        let button =
+            egui::Button::new(action.label()).shortcut_text(bindings.binding(action).to_string());
+        if ui.add(button).clicked() {
+            view.view.0 = Some(change);
+            ui.close();
+        }
+    }
+    ui.checkbox(
+        &mut view.trajectory_settings.patched_conics,
+        tr("patched-conics"),
+    )
+    .on_hover_text(tr("patched-conics-hint"));
+}
+
+fn debug_menu(ui: &mut Ui, ui_state: &mut UiState, exports: &mut Exports) {
+    ui.checkbox(
+        &mut ui_state.show_physics_constants,
+        tr("physics-constants"),
+    );
+    ui.separator();
+    let telemetry = &mut exports.telemetry;
+    ui.checkbox(&mut telemetry.recording, tr("record-telemetry"))
+        .on_hover_text(tr("record-telemetry-hint"));
+    ui.add(
+        egui::Slider::new(&mut telemetry.interval, 0.01..=10.0)
+            .logarithmic(true)
+            .text(tr("sample-interval")),
+    );
+    ui.label(tr_args(
+        "samples",
+        &[("count", telemetry.sample_count().into())],
+    ));
+    #[cfg(not(target_arch = "wasm32"))]
+    ui.horizontal(|ui| {
+        for extension in ["csv", "json"] {
+            let button = tr_args(
+                "export-format",
+                &[("format", extension.to_uppercase().into())],
+            );
+            if ui.button(button).clicked() {
+                let message = match exports.telemetry.export_numbered(extension) {
+                    Ok(path) => tr_args(
+                        "telemetry-saved",
+                        &[("path", path.display().to_string().into())],
+                    ),
+                    Err(err) => err.to_string(),
+                };
+                exports.notify(message);
+            }
+        }
+    });
+}
+
+/// Orders left in a replay, and whose turn it is in a hot-seat match.
+fn match_status(ui: &mut Ui, game: &mut GameMenu) {
+    if let Some(playback) = &game.playback {
+        ui.separator();
+        ui.label(tr_args(
+            "replay-orders-left",
+            &[("count", playback.remaining().into())],
+        ))
+        .on_hover_text(tr("replay-orders-left-hint"));
+    }
+    let players = &mut game.players;
+    if players.hot_seat {
+        ui.separator();
+        let active = players.active;
+        ui.colored_label(
+            active.color(),
+            tr_args("turn", &[("player", active.name().into())]),
+        );
+        ui.label(tr_args(
+            "seconds-left",
+            &[("seconds", players.turn.remaining_secs().ceil().into())],
+        ));
+        if ui.button(tr("end-turn")).clicked() {
+            players.end_turn();
+        }
+    }
+}
+
+fn window_toggles(ui: &mut Ui, windows: &mut Windows, bindings: &KeyBindings) {
+    // Named for screen readers, which can't make much of the symbols
+    let open = windows.help.0;
+    ui.toggle_value(&mut windows.help.0, "?")
+        .on_hover_text(tr_args(
+            "help-hint",
+            &[("key", bindings.binding(Action::Help).to_string().into())],
+        ))
+        .widget_info(|| WidgetInfo::selected(WidgetType::SelectableLabel, true, open, tr("help")));
+    let open = windows.settings.0;
+    ui.toggle_value(&mut windows.settings.0, "⚙")
+        .on_hover_text(tr("settings"))
+        .widget_info(|| {
+            WidgetInfo::selected(WidgetType::SelectableLabel, true, open, tr("settings"))
+        });
+}
@@ -0,0 +1,1432 @@
+use super::draw::{
+    circle_points, craft_legend, draw_body_labels, draw_scale_bar, plot_appearances, plot_apsides,
+    plot_comet_tail, plot_launch_site, plot_measurement, plot_night_side, plot_text_size,
+    plot_trajectory,
+};
+use super::{
+    AimedLaunch, BodyUiData, CraftState, CraftUiData, LastBurn, LaunchForm, PlotPan, PlotViewport,
+    Trail, UiState, ViewChange, ViewRequest, ViewSettings, accessible_region, owner_label, readout,
+};
+use crate::app_state::AppState;
+use crate::atmosphere::Atmosphere;
+use crate::balance::Balance;
+use crate::bodies::{Appearance, Body, Fill, Radius, Spin, Sunward};
+use crate::camera::PlotCamera;
+use crate::comets::CometTail;
+use crate::crafts::{Craft, Fuel, LaunchCraft};
+use crate::editor::{Edit, EditorAction};
+use crate::factions::{Owner, Players};
+use crate::gamepad::StickAim;
+use crate::i18n::{format_number, tr, tr_args};
+use crate::influence::{HillSphere, LagrangePoints, RocheLimit, SphereOfInfluence};
+use crate::interaction::InteractionMode;
+use crate::maneuver::{ManeuverNode, scratch_bodies};
+use crate::measure::{MeasureEnd, MeasureTool};
+use crate::particles::Particles;
+use crate::physics::{
+    CenterOfMass, KineticEnergy, Mass, PhysicsConstants, PotentialEnergy, SimPosition, SimVelocity,
+    TotalEnergy,
+};
+use crate::selection::{HoveredBody, SelectedBodies, SelectedBody, SelectedCraft};
+use crate::settings::Settings;
+use crate::spatial::SpatialGrid;
+use crate::starfield::Starfield;
+use crate::target::Target;
+use crate::touch::TouchGestures;
+use crate::trajectory::{PlannedTrajectory, PredictedTrajectory};
+use crate::tutorial::{Tutorial, pulse};
+use crate::wells::GravityWells;
+use crate::zero_velocity::RotatingFrame;
+use bevy::ecs::system::SystemParam;
+use bevy::math::{DVec2, DVec3};
+use bevy::prelude::*;
+use bevy_egui::{
+    EguiContexts,
+    egui::{
+        self, Align2, CentralPanel, Color32, Frame, RichText, Sense, Stroke, TopBottomPanel, vec2,
+    },
+};
+use bevy_persistent::prelude::*;
+use bevy_simple_subsecond_system::prelude::*;
+use egui_plot::Plot;
+use std::collections::BTreeSet;
+use std::f32::consts::PI;
+
+/// Space left around the bodies when zooming to fit, as a fraction of the
+/// span they cover.
+const FIT_MARGIN: f64 = 0.1;
+/// Zooming to a body shows this many of its radii either side of it.
+const FRAME_RADII: f64 = 4.;
+/// Zooming to a craft shows this far either side of it.
+const FRAME_CRAFT: f64 = 10.;
+
+type MotionData = (
+    &'static Transform,
+    &'static SimVelocity,
+    &'static Trail,
+    Option<&'static Fill>,
+    Option<&'static Owner>,
+    Option<&'static Fuel>,
+    Option<&'static LastBurn>,
+);
+
+/// Bodies and crafts on the plot, and which are hovered, selected or being
+/// dragged.
+#[derive(SystemParam)]
+pub struct PlotSelection<'w, 's> {
+    bodies: Query<'w, 's, BodyUiData>,
+    crafts: Query<'w, 's, CraftUiData>,
+    hovered: ResMut<'w, HoveredBody>,
+    selected_body: ResMut<'w, SelectedBody>,
+    selected_craft: ResMut<'w, SelectedCraft>,
+    multi: ResMut<'w, SelectedBodies>,
+    target: Res<'w, Target>,
+    grid: Res<'w, SpatialGrid>,
+    edits: EventWriter<'w, EditorAction>,
+    app_state: Res<'w, State<AppState>>,
+    dragged: Local<'s, Option<BodyDrag>>,
+    /// Where a box selection's drag began.
+    box_start: Local<'s, Option<DVec2>>,
+    commands: Commands<'w, 's>,
+    time: Res<'w, Time<Fixed>>,
+    dragged_handle: Local<'s, Option<NodeHandle>>,
+}
+
+/// Launches aimed by pointing at the plot, with the pointer, a finger or the
+/// right stick.
+#[derive(SystemParam)]
+pub struct LaunchAim<'w, 's> {
+    launches: EventWriter<'w, LaunchCraft>,
+    balance: Balance<'w>,
+    bodies: Query<'w, 's, (Entity, &'static Name), With<Body>>,
+    form: ResMut<'w, LaunchForm>,
+    players: Res<'w, Players>,
+    stick: Option<Res<'w, StickAim>>,
+    aimed: ResMut<'w, AimedLaunch>,
+    touch: Local<'s, TouchGestures>,
+    tutorial: Res<'w, Tutorial>,
+}
+
+/// Optional extras drawn on the plot.
+#[derive(SystemParam)]
+pub struct PlotOverlays<'w, 's> {
+    ui_state: Res<'w, UiState>,
+    view: Res<'w, ViewSettings>,
+    settings: Res<'w, Persistent<Settings>>,
+    particles: Res<'w, Particles>,
+    starfield: Res<'w, Starfield>,
+    wells: ResMut<'w, GravityWells>,
+    motions: Query<'w, 's, MotionData>,
+    spheres_of_influence: Query<
+        'w,
+        's,
+        (
+            &'static Transform,
+            &'static SphereOfInfluence,
+            &'static Fill,
+        ),
+    >,
+    lagrange_points: Query<'w, 's, (&'static LagrangePoints, &'static Fill)>,
+    spins: Query<'w, 's, (&'static Transform, &'static Radius, &'static Spin)>,
+    sunward: Query<'w, 's, (&'static Transform, &'static Radius, &'static Sunward)>,
+    atmospheres: Query<
+        'w,
+        's,
+        (
+            &'static Transform,
+            &'static Radius,
+            &'static Fill,
+            &'static Atmosphere,
+        ),
+    >,
+    appearances: Query<
+        'w,
+        's,
+        (
+            &'static Transform,
+            &'static Radius,
+            &'static Fill,
+            &'static Appearance,
+        ),
+    >,
+    tails: Query<
+        'w,
+        's,
+        (
+            &'static Transform,
+            &'static Radius,
+            &'static Fill,
+            &'static CometTail,
+        ),
+    >,
+    last_burns: Query<'w, 's, &'static LastBurn>,
+    trajectories:
+        Query<'w, 's, (&'static Name, &'static PredictedTrajectory, &'static Fill), With<Body>>,
+    tidal_limits: Query<
+        'w,
+        's,
+        (
+            &'static Transform,
+            &'static HillSphere,
+            &'static RocheLimit,
+            &'static Fill,
+        ),
+    >,
+    zero_velocity: ZeroVelocityCurves<'w, 's>,
+}
+
+/// What the selected craft's zero-velocity curves are traced from.
+#[derive(SystemParam)]
+pub struct ZeroVelocityCurves<'w, 's> {
+    bodies: Query<
+        'w,
+        's,
+        (
+            &'static Name,
+            &'static SimPosition,
+            &'static SimVelocity,
+            &'static Mass,
+        ),
+        With<Body>,
+    >,
+    crafts: Query<'w, 's, (&'static SimPosition, &'static SimVelocity), With<Craft>>,
+}
+
+impl ZeroVelocityCurves<'_, '_> {
+    /// The names of the pair the curves are for, and the curves within
+    /// `bounds`.
+    fn trace(
+        &self,
+        craft: Entity,
+        constants: &PhysicsConstants,
+        bounds: &egui_plot::PlotBounds,
+    ) -> Option<(String, Vec<Vec<DVec2>>)> {
+        let (position, velocity) = self.crafts.get(craft).ok()?;
+        let names: Vec<_> = self.bodies.iter().map(|(name, ..)| name).collect();
+        let scratch = scratch_bodies(self.bodies.iter().map(|(_, p, v, m)| (p, v, m)));
+        let frame = RotatingFrame::around(
+            position.0,
+            &scratch,
+            constants.g as f64,
+            constants.softening as f64,
+        )?;
+        let energy = frame.jacobi_energy(position.0, velocity.0);
+        let [min_x, min_y] = bounds.min();
+        let [max_x, max_y] = bounds.max();
+        let curves =
+            frame.zero_velocity_curves(energy, DVec2::new(min_x, min_y), DVec2::new(max_x, max_y));
+        let pair = format!("{}-{}", names[frame.primary], names[frame.secondary]);
+        Some((pair, curves))
+    }
+}
+
+/// Requested changes to where the plot looks, and where everything is.
+#[derive(SystemParam)]
+pub struct PlotView<'w, 's> {
+    pan: ResMut<'w, PlotPan>,
+    request: ResMut<'w, ViewRequest>,
+    bodies: Query<'w, 's, (&'static Name, &'static SimPosition, &'static Radius), With<Body>>,
+    crafts: Query<'w, 's, &'static SimPosition, With<Craft>>,
+    measure: MeasureTool<'w, 's>,
+    camera: ResMut<'w, PlotCamera>,
+    viewport: ResMut<'w, PlotViewport>,
+}
+
+impl PlotView<'_, '_> {
+    /// The bounds a zoom should set, if there's anything to zoom to.
+    fn zoom_bounds(
+        &self,
+        change: ViewChange,
+        selected_body: Option<&str>,
+        selected_craft: Option<Entity>,
+    ) -> Option<egui_plot::PlotBounds> {
+        let square = |center: DVec3, half: f64| {
+            egui_plot::PlotBounds::from_min_max(
+                [center.x - half, center.y - half],
+                [center.x + half, center.y + half],
+            )
+        };
+        match change {
+            ViewChange::CenterSelection => None,
+            ViewChange::FitAll => {
+                let (min, max) = self.bodies.iter().fold(
+                    (DVec3::INFINITY, DVec3::NEG_INFINITY),
+                    |(min, max), (_, position, radius)| {
+                        let radius = DVec3::splat(radius.0 as f64);
+                        (min.min(position.0 - radius), max.max(position.0 + radius))
+                    },
+                );
+                if !min.is_finite() || !max.is_finite() {
+                    return None;
+                }
+                let margin = (max - min) * FIT_MARGIN;
+                Some(egui_plot::PlotBounds::from_min_max(
+                    [min.x - margin.x, min.y - margin.y],
+                    [max.x + margin.x, max.y + margin.y],
+                ))
+            }
+            ViewChange::FrameSelection => {
+                if let Some(position) = selected_craft.and_then(|craft| self.crafts.get(craft).ok())
+                {
+                    return Some(square(position.0, FRAME_CRAFT));
+                }
+                let selected = selected_body?;
+                self.bodies
+                    .iter()
+                    .find(|(name, ..)| name.as_str() == selected)
+                    .map(|(_, position, radius)| square(position.0, radius.0 as f64 * FRAME_RADII))
+            }
+        }
+    }
+}
+
+/// Simulation-wide values displayed above the plot.
+#[derive(SystemParam)]
+pub struct Readouts<'w> {
+    potential_energy: Res<'w, PotentialEnergy>,
+    kinetic_energy: Res<'w, KineticEnergy>,
+    total_energy: Res<'w, TotalEnergy>,
+    center_of_mass: Res<'w, CenterOfMass>,
+    constants: Res<'w, PhysicsConstants>,
+}
+
+/// A body being dragged around the plot.
+pub struct BodyDrag {
+    body: String,
+    /// Set when Shift was held as the drag began, so it sets the body's
+    /// velocity rather than moving it.
+    velocity: bool,
+    /// The pointer's offset from the body's center when the drag began.
+    grab: DVec2,
+}
+
+/// Draggable handles setting a maneuver node's burn.
+#[derive(Clone, Copy)]
+pub enum NodeHandle {
+    Prograde,
+    Radial,
+}
+
+impl NodeHandle {
+    /// Plot distance between a node and its handles for a zero burn.
+    pub const OFFSET: f64 = 2.;
+
+    /// Where the handle sits, and the direction dragging it increases the burn.
+    pub fn placement(self, planned: &PlannedTrajectory, node: &ManeuverNode) -> (DVec3, DVec3) {
+        let (axis, delta_v) = match self {
+            Self::Prograde => (planned.frame.prograde, node.prograde),
+            Self::Radial => (planned.frame.radial, node.radial),
+        };
+        (
+            planned.position + axis * (Self::OFFSET + delta_v as f64),
+            axis,
+        )
+    }
+}
+
+/// The axes are hidden, so this and the scale bar give a sense of where and
+/// how big things are.
+pub fn status_bar(mut contexts: EguiContexts, viewport: Res<PlotViewport>) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+        accessible_region(ui, &tr("status-region"), |ui| {
+            ui.horizontal(|ui| match viewport.cursor {
+                Some(cursor) => ui.monospace(format!(
+                    "x: {}  y: {}",
+                    format_number(cursor.x, 2),
+                    format_number(cursor.y, 2)
+                )),
+                None => ui.weak(tr("cursor-hint")),
+            })
+        });
+    });
+}
+
+/// The space plot, and picking, dragging and aiming on it.
+#[hot]
+pub fn space_plot(
+    mut contexts: EguiContexts,
+    mode: Res<State<InteractionMode>>,
+    mut selection: PlotSelection,
+    readouts: Readouts,
+    mut launch: LaunchAim,
+    mut overlays: PlotOverlays,
+    mut view: PlotView,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    let mode = *mode.get();
+    // Bodies can be dragged around in Move mode, and while paused
+    let can_move = mode == InteractionMode::EditDrag
+        || (mode == InteractionMode::Inspect && *selection.app_state.get() == AppState::Paused);
+    let over_body = can_move && (selection.dragged.is_some() || selection.hovered.0.is_some());
+
+    launch.touch.update(ctx);
+    let touch_aiming = launch.touch.aiming_from.is_some();
+    let touch_released = launch.touch.aim_released(ctx);
+    let pinch_pan = TouchGestures::pinch_pan(ctx);
+    let last_aimed = launch.aimed.0.clone();
+
+    // In LaunchAim, or while a long press aims, the pointer's offset from the
+    // selected body, if the side giving orders owns it, sets the launch
+    let aim_from = selection
+        .selected_body
+        .0
+        .as_ref()
+        .filter(|_| mode == InteractionMode::LaunchAim || touch_aiming)
+        .and_then(|selected| {
+            selection
+                .bodies
+                .iter()
+                .find(|(name, ..)| name.as_str() == selected)
+        })
+        .filter(|(.., owner)| owner.is_some_and(|owner| owner.0 == launch.players.active))
+        .map(|(_, radius, _, transform, ..)| (transform.translation.truncate(), radius.0));
+    // Spinning bodies only launch within reach of their launch site
+    let aim_spin = aim_from
+        .and_then(|_| {
+            launch
+                .bodies
+                .iter()
+                .find(|(_, name)| Some(name.as_str()) == selection.selected_body.0.as_deref())
+        })
+        .and_then(|(entity, _)| overlays.spins.get(entity).ok())
+        .map(|(.., spin)| *spin);
+    let max_launch_speed = launch
+        .balance
+        .default_class()
+        .map(|(_, class)| class.launch_speed_limit());
+    let mut aim = None;
+
+    // The renderer draws bodies and crafts under a see-through plot
+    let plot_fill = if cfg!(feature = "mesh_render") {
+        Color32::TRANSPARENT
+    } else {
+        ctx.style().visuals.panel_fill
+    };
+    let central_panel =
+        CentralPanel::default().frame(Frame::central_panel(&ctx.style()).fill(plot_fill));
+    central_panel.show(ctx, |ui| {
+        ui.label(tr_args(
+            "energy",
+            &[
+                (
+                    "potential",
+                    format_number(readouts.potential_energy.0, 3).into(),
+                ),
+                (
+                    "kinetic",
+                    format_number(readouts.kinetic_energy.0, 3).into(),
+                ),
+                ("total", format_number(readouts.total_energy.0, 3).into()),
+            ],
+        ));
+        let focus = selection
+            .selected_craft
+            .0
+            .and_then(|entity| selection.crafts.get(entity).ok())
+            .map(|(_, _, _, transform, ..)| transform.translation)
+            .or_else(|| {
+                let selected = selection.selected_body.0.as_ref()?;
+                selection
+                    .bodies
+                    .iter()
+                    .find(|(name, ..)| name.as_str() == selected)
+                    .map(|(_, _, _, transform, ..)| transform.translation)
+            });
+        let request = view.request.0.take();
+        let zoom = request.and_then(|change| {
+            view.zoom_bounds(
+                change,
+                selection.selected_body.0.as_deref(),
+                selection.selected_craft.0,
+            )
+        });
+        let centered = focus.filter(|_| {
+            overlays.ui_state.follow_selection
+                || matches!(request, Some(ViewChange::CenterSelection))
+        });
+        let starfield = overlays.view.starfield && !cfg!(feature = "mesh_render");
+        if starfield {
+            let rect = ui.available_rect_before_wrap();
+            overlays.starfield.paint(ui.painter(), rect, &view.camera);
+        }
+        if overlays.view.gravity_wells {
+            let rect = ui.available_rect_before_wrap();
+            let masses = selection
+                .bodies
+                .iter()
+                .map(|(_, _, _, transform, _, mass, ..)| {
+                    (transform.translation.truncate().as_dvec2(), mass.0 as f64)
+                })
+                .collect();
+            overlays.wells.paint(
+                ui.painter(),
+                rect,
+                &view.camera,
+                masses,
+                readouts.constants.g as f64,
+                readouts.constants.softening as f64,
+            );
+        }
+        let can_pan = mode.allows_pan() && !touch_aiming && pinch_pan.is_none() && !over_body;
+        // The plot camera does all the navigating
+        let plot_response = Plot::new("space_plot")
+            .data_aspect(1.)
+            .allow_axis_zoom_drag(false)
+            .allow_boxed_zoom(false)
+            .allow_scroll(false)
+            .allow_drag(false)
+            .allow_zoom(false)
+            .set_margin_fraction(vec2(0.2, 0.2))
+            // Zooming to fit is explicit, so bodies flying off don't rescale the view
+            .auto_bounds(false)
+            .cursor_color(Color32::TRANSPARENT)
+            .show_grid(overlays.view.grid)
+            .show_axes(overlays.view.axes)
+            .show_x(false)
+            .show_y(false)
+            // The plot's own background would hide what's painted under it
+            .show_background(
+                !cfg!(feature = "mesh_render") && !starfield && !overlays.view.gravity_wells,
+            )
+            .sense(Sense::all())
+            .show(ui, |ui| {
+                let size = ui.response().rect.size();
+                let camera = &mut view.camera;
+                if !camera.is_placed() {
+                    camera.frame(&ui.plot_bounds(), size);
+                }
+                if let Some(delta) = pinch_pan {
+                    camera.drag(delta);
+                }
+                let pan = std::mem::take(&mut view.pan.0);
+                if let Some(bounds) = &zoom {
+                    camera.frame(bounds, size);
+                } else if let Some(center) = centered {
+                    camera.look_at(center.truncate().as_dvec2());
+                } else if pan != Vec2::ZERO {
+                    camera.pan(pan, size);
+                }
+                ui.set_plot_bounds(camera.bounds(size));
+
+                let now = selection.time.elapsed_secs_f64();
+                plot_overlays(
+                    ui,
+                    &overlays,
+                    &readouts.constants,
+                    selection.selected_craft.0,
+                    now,
+                );
+
+                // The body the tutorial asks for
+                let tutorial_body = launch.tutorial.highlighted_body().and_then(|highlighted| {
+                    selection
+                        .bodies
+                        .iter()
+                        .find(|(name, ..)| name.as_str() == highlighted)
+                });
+                if let Some((_, radius, _, transform, ..)) = tutorial_body {
+                    ui.line(
+                        egui_plot::Line::new(
+                            "",
+                            circle_points(transform.translation.as_dvec3(), radius.0 as f64 * 1.4),
+                        )
+                        .color(pulse(ui.ctx()))
+                        .width(2.),
+                    );
+                }
+
+                plot_bodies(ui, &selection.bodies, &overlays, view.camera.scale);
+
+                let target_body = selection
+                    .target
+                    .0
+                    .and_then(|entity| selection.bodies.get(entity).ok());
+                if let Some((name, radius, fill, transform, ..)) = target_body {
+                    ui.line(
+                        egui_plot::Line::new(
+                            "",
+                            circle_points(transform.translation.as_dvec3(), radius.0 as f64 * 1.4),
+                        )
+                        .name(tr_args("target-named", &[("name", name.as_str().into())]))
+                        .color(fill.color())
+                        .width(2.)
+                        .style(egui_plot::LineStyle::dashed_loose()),
+                    );
+                }
+
+                plot_crafts(
+                    ui,
+                    &selection.crafts,
+                    selection.selected_craft.0,
+                    &overlays.last_burns,
+                    now,
+                );
+
+                let selected_trajectory = selection.selected_body.0.as_ref().and_then(|selected| {
+                    overlays
+                        .trajectories
+                        .iter()
+                        .find(|(name, ..)| name.as_str() == selected)
+                });
+                if let Some((_, trajectory, fill)) = selected_trajectory {
+                    let points: Vec<_> = trajectory.coast.iter().map(|p| [p.x, p.y]).collect();
+                    ui.line(
+                        egui_plot::Line::new(tr("predicted-orbit"), points)
+                            .color(fill.color().gamma_multiply(0.5))
+                            .style(egui_plot::LineStyle::dashed_loose()),
+                    );
+                    plot_apsides(ui, &trajectory.apsides, fill.color());
+                }
+
+                if let Some((.., trajectory, node)) = selection
+                    .selected_craft
+                    .0
+                    .and_then(|entity| selection.crafts.get(entity).ok())
+                {
+                    plot_trajectory(ui, trajectory, node);
+                }
+
+                if mode == InteractionMode::Measure {
+                    plot_measurement(ui, &view.measure, selection.selected_craft.0);
+                }
+
+                plot_selection(ui, &selection);
+
+                if overlays.view.center_of_mass {
+                    let cm = &readouts.center_of_mass;
+                    ui.points(
+                        egui_plot::Points::new(tr("center-of-mass"), [cm.0.x, cm.0.y])
+                            .color(Color32::WHITE)
+                            .radius(3.),
+                    );
+                }
+
+                if let (Some(from), Some(max_speed)) = (aim_from, max_launch_speed) {
+                    aim = plot_aim(ui, from, max_speed, launch.stick.as_deref(), aim_spin);
+                }
+            });
+
+        let from = launch
+            .bodies
+            .iter()
+            .find(|(_, name)| Some(name.as_str()) == selection.selected_body.0.as_deref())
+            .map(|(entity, _)| entity);
+        launch.aimed.0 = aim.zip(from).map(|((direction, speed), from)| LaunchCraft {
+            from,
+            destination: launch.form.destination,
+            direction,
+            speed,
+            scripted: false,
+        });
+        if touch_released {
+            // The finger may already be gone from the plot, taking the aim with it
+            if let Some(aimed) = launch.aimed.0.clone().or(last_aimed) {
+                launch.launches.write(aimed);
+            }
+            launch.touch.aiming_from = None;
+        } else if let Some(aimed) = &launch.aimed.0
+            && plot_response.response.clicked()
+            && !touch_aiming
+        {
+            launch.form.angle = aimed.direction.to_angle().to_degrees().rem_euclid(360.);
+            launch.form.speed = aimed.speed;
+            launch.launches.write(aimed.clone());
+        }
+
+        let cursor = plot_response.response.hover_pos().map(|position| {
+            let point = plot_response.transform.value_from_position(position);
+            DVec2::new(point.x, point.y)
+        });
+        if can_pan
+            && plot_response
+                .response
+                .dragged_by(egui::PointerButton::Primary)
+        {
+            view.camera.drag(plot_response.response.drag_delta());
+        }
+        if plot_response.response.hovered() {
+            ui.input(|input| view.camera.zoom_from_input(input, cursor));
+        }
+        let bounds = plot_response.transform.bounds();
+        let rect = *plot_response.transform.frame();
+        *view.viewport = PlotViewport {
+            center: DVec2::new(bounds.center().x, bounds.center().y),
+            world_per_point: bounds.width() / rect.width() as f64,
+            rect,
+            pixels_per_point: ui.ctx().pixels_per_point(),
+            cursor,
+        };
+        draw_scale_bar(ui, &plot_response);
+        if overlays.view.body_labels {
+            draw_body_labels(ui, &plot_response, &selection.bodies);
+        }
+        if overlays.view.legend {
+            craft_legend(ui, plot_response.response.rect);
+        }
+
+        let clicked = plot_response.response.clicked() && !touch_aiming;
+        let hit_slop = launch.touch.hit_slop();
+        let picking = matches!(mode, InteractionMode::Inspect | InteractionMode::Measure);
+        // Fingers lifting can leave no hover position on the frame of the tap
+        let pointer_pos = plot_response
+            .response
+            .hover_pos()
+            .or_else(|| plot_response.response.interact_pointer_pos());
+        let (new_hovered_body, mut clicked_body, mut clicked_craft) = pointer_pos
+            .map(|pointer_pos| {
+                pick(
+                    &plot_response,
+                    &selection,
+                    pointer_pos,
+                    hit_slop,
+                    picking && clicked,
+                )
+            })
+            .unwrap_or_default();
+
+        let commandable = selection
+            .selected_craft
+            .0
+            .and_then(|entity| selection.crafts.get(entity).ok())
+            .is_some_and(|(.., owner, _, _)| launch.players.controls(owner));
+        if mode == InteractionMode::Maneuver && commandable && !launch.players.remote {
+            plan_maneuver(&plot_response, &mut selection);
+        }
+
+        // A long press on one of the player's bodies starts aiming from it
+        if !touch_aiming
+            && matches!(mode, InteractionMode::Inspect | InteractionMode::LaunchAim)
+            && let Some(origin) = TouchGestures::long_press(ctx)
+        {
+            let origin = plot_response.transform.value_from_position(origin);
+            let slop = hit_slop as f64 / plot_response.transform.dpos_dvalue_x();
+            let pressed = selection
+                .bodies
+                .iter()
+                .find(|(_, radius, _, transform, .., owner)| {
+                    let center = transform.translation;
+                    let distance = (origin.x - center.x as f64).hypot(origin.y - center.y as f64);
+                    distance <= radius.0 as f64 + slop
+                        && owner.is_some_and(|owner| owner.0 == launch.players.active)
+                });
+            if let Some((name, ..)) = pressed {
+                launch.touch.aiming_from = Some(name.to_string());
+                clicked_body = Some(name.to_string());
+            }
+        }
+
+        // Measuring picks the ruler's ends instead of selecting
+        if mode == InteractionMode::Measure
+            && clicked
+            && let Some(pointer_pos) = pointer_pos
+        {
+            let body = clicked_body.take().and_then(|clicked| {
+                launch
+                    .bodies
+                    .iter()
+                    .find(|(_, name)| name.as_str() == clicked)
+                    .map(|(entity, _)| entity)
+            });
+            let end = body.or(clicked_craft.take()).map_or_else(
+                || {
+                    let point = plot_response.transform.value_from_position(pointer_pos);
+                    MeasureEnd::Point(DVec2::new(point.x, point.y))
+                },
+                MeasureEnd::Entity,
+            );
+            view.measure.measurement.place(end);
+        }
+
+        // Handle body and craft selection
+        let shift = ctx.input(|input| input.modifiers.shift);
+        if let Some(ref clicked_name) = clicked_body {
+            if shift {
+                selection
+                    .multi
+                    .toggle(&mut selection.selected_body, clicked_name);
+            } else {
+                selection.selected_body.0 = Some(clicked_name.clone());
+            }
+            selection.selected_craft.0 = None;
+        } else if let Some(craft) = clicked_craft {
+            selection.selected_craft.0 = Some(craft);
+            selection.selected_body.0 = None;
+        }
+
+        // Update hover state for next frame
+        selection.hovered.0 = new_hovered_body;
+
+        drag_bodies(&plot_response, &mut selection, hit_slop, can_move);
+        if mode == InteractionMode::BoxSelect {
+            box_select(&plot_response, &mut selection, shift);
+        }
+        // Stays out of the way of aiming, which points near bodies
+        hover_outline(ui, &plot_response, &selection, aim.is_none());
+
+        // Clicks on the overlay card land on its window rather than the plot
+        if mode == InteractionMode::Inspect
+            && plot_response.response.clicked()
+            && clicked_body.is_none()
+            && clicked_craft.is_none()
+        {
+            selection.selected_body.0 = None;
+            selection.selected_craft.0 = None;
+        }
+    });
+}
+
+/// Spheres of influence, tidal limits, Lagrange points, zero-velocity curves,
+/// trails, velocity vectors and particles.
+fn plot_overlays(
+    ui: &mut egui_plot::PlotUi,
+    overlays: &PlotOverlays,
+    constants: &PhysicsConstants,
+    selected_craft: Option<Entity>,
+    now: f64,
+) {
+    if overlays.ui_state.show_spheres_of_influence {
+        for (transform, sphere, fill) in &overlays.spheres_of_influence {
+            if sphere.0.is_finite() {
+                ui.line(
+                    egui_plot::Line::new(
+                        "",
+                        circle_points(transform.translation.as_dvec3(), sphere.0),
+                    )
+                    .color(fill.color().gamma_multiply(0.25)),
+                );
+            }
+        }
+    }
+
+    for (transform, hill, roche, fill) in &overlays.tidal_limits {
+        if let Some(radius) = hill.0.filter(|_| overlays.ui_state.show_hill_spheres) {
+            ui.line(
+                egui_plot::Line::new("", circle_points(transform.translation.as_dvec3(), radius))
+                    .color(fill.color().gamma_multiply(0.4))
+                    .style(egui_plot::LineStyle::dotted_dense()),
+            );
+        }
+        let roche = roche.0.filter(|_| overlays.ui_state.show_roche_limits);
+        if let Some((primary, radius)) = roche
+            && let Ok((primary, ..)) = overlays.tidal_limits.get(primary)
+        {
+            // Drawn around the primary, in the color of the body at risk
+            ui.line(
+                egui_plot::Line::new("", circle_points(primary.translation.as_dvec3(), radius))
+                    .color(fill.color().gamma_multiply(0.6))
+                    .style(egui_plot::LineStyle::dashed_dense()),
+            );
+        }
+    }
+
+    if overlays.ui_state.show_lagrange_points {
+        for (LagrangePoints(points), fill) in &overlays.lagrange_points {
+            for (index, point) in points.iter().flatten().enumerate() {
+                ui.points(
+                    egui_plot::Points::new("", [point.x, point.y])
+                        .color(fill.color().gamma_multiply(0.6))
+                        .shape(egui_plot::MarkerShape::Cross)
+                        .radius(3.),
+                );
+                ui.text(
+                    egui_plot::Text::new(
+                        "",
+                        egui_plot::PlotPoint::new(point.x, point.y),
+                        RichText::new(format!("L{}", index + 1))
+                            .size(plot_text_size(ui.ctx(), 10.)),
+                    )
+                    .color(fill.color().gamma_multiply(0.6))
+                    .anchor(Align2::LEFT_BOTTOM),
+                );
+            }
+        }
+    }
+
+    let traced = selected_craft
+        .filter(|_| overlays.ui_state.show_zero_velocity_curves)
+        .and_then(|craft| {
+            overlays
+                .zero_velocity
+                .trace(craft, constants, &ui.plot_bounds())
+        });
+    if let Some((pair, curves)) = traced {
+        let name = tr_args(
+            "zero-velocity-curves-frame",
+            &[("pair", pair.to_string().into())],
+        );
+        for curve in curves {
+            ui.line(
+                egui_plot::Line::new(
+                    &name,
+                    curve
+                        .iter()
+                        .map(|point| [point.x, point.y])
+                        .collect::<Vec<_>>(),
+                )
+                .color(Color32::from_gray(170))
+                .style(egui_plot::LineStyle::dashed_loose()),
+            );
+        }
+    }
+
+    for (transform, velocity, trail, fill, owner, fuel, last_burn) in &overlays.motions {
+        let state = fuel.map(|fuel| CraftState::of(fuel, last_burn, now));
+        let color = fill
+            .map(Fill::color)
+            .unwrap_or_else(|| owner.map_or(Color32::LIGHT_GRAY, |owner| owner.0.color()));
+        if !cfg!(feature = "mesh_render")
+            && overlays.view.trails
+            && overlays.settings.trail_length > 0.
+            && trail.0.len() > 1
+        {
+            ui.line(
+                egui_plot::Line::new("", trail.0.iter().copied().collect::<Vec<_>>())
+                    .color(color.gamma_multiply(0.4))
+                    .style(state.map_or(egui_plot::LineStyle::Solid, CraftState::trail_style)),
+            );
+        }
+        if overlays.settings.velocity_vectors {
+            // Tips show where it'd be in a second at its current velocity
+            let origin = transform.translation.as_dvec3();
+            let tip = origin + velocity.0;
+            ui.arrows(
+                egui_plot::Arrows::new("", vec![[origin.x, origin.y]], vec![[tip.x, tip.y]])
+                    .color(color.gamma_multiply(0.8)),
+            );
+        }
+    }
+
+    for burst in &overlays.particles.0 {
+        ui.points(
+            egui_plot::Points::new("", burst.positions())
+                .color(burst.color.gamma_multiply(burst.opacity()))
+                .radius(burst.size),
+        );
+    }
+}
+
+/// Bodies, with their atmospheres, owners, docked crafts, tails, night sides
+/// and launch sites.
+fn plot_bodies(
+    ui: &mut egui_plot::PlotUi,
+    bodies: &Query<BodyUiData>,
+    overlays: &PlotOverlays,
+    world_per_point: f64,
+) {
+    let draw_appearances = !cfg!(feature = "mesh_render");
+    if draw_appearances {
+        for (transform, radius, fill, atmosphere) in &overlays.atmospheres {
+            let reach = (radius.0 * atmosphere.radius) as f64;
+            ui.polygon(
+                egui_plot::Polygon::new("", circle_points(transform.translation.as_dvec3(), reach))
+                    .fill_color(fill.color().gamma_multiply(0.12))
+                    .stroke(Stroke::new(1., fill.color().gamma_multiply(0.3))),
+            );
+        }
+        plot_appearances(ui, &overlays.appearances, world_per_point, true);
+    }
+
+    for (
+        name,
+        radius,
+        fill,
+        Transform {
+            translation: Vec3 { x, y, .. },
+            ..
+        },
+        crafts,
+        _mass,
+        _velocity,
+        egui_id,
+        owner,
+    ) in bodies
+    {
+        // Use entity-based ID as the polygon identifier string
+        let polygon_id = egui_id
+            .map(|id| format!("body_{:?}", id.0))
+            .unwrap_or_else(|| name.to_string());
+
+        // Create the circle points for the body
+        let body_points: Vec<_> = (0..90)
+            .map(|i| i * 4)
+            .map(|i| i as f32 * PI / 180.)
+            .map(|d| [radius.0 * d.cos(), radius.0 * d.sin()])
+            .map(|[x_edge, y_edge]| [x + x_edge, y + y_edge])
+            .map(|[x, y]| [x as f64, y as f64])
+            .collect();
+
+        // Draw the main body polygon
+        if !cfg!(feature = "mesh_render") {
+            ui.polygon(
+                egui_plot::Polygon::new(polygon_id.clone(), body_points.clone())
+                    .name(name)
+                    .fill_color(fill.color().gamma_multiply(0.75))
+                    .stroke(Stroke::new(2., fill.color().gamma_multiply(1.2))),
+            );
+        }
+
+        if let Some(owner) = owner {
+            ui.line(
+                egui_plot::Line::new(
+                    "",
+                    circle_points(DVec3::new(*x as f64, *y as f64, 0.), radius.0 as f64 * 1.15),
+                )
+                .color(owner.0.color())
+                .style(owner.0.line_style())
+                .width(1.5),
+            );
+        }
+
+        if overlays.view.craft_counts {
+            let offset = (radius.0 / 2f32.sqrt() + 0.1) as f64;
+            ui.text(
+                egui_plot::Text::new(
+                    "",
+                    egui_plot::PlotPoint::new(*x as f64 + offset, *y as f64 + offset),
+                    egui::RichText::new(crafts.0.to_string()).size(plot_text_size(ui.ctx(), 20.)),
+                )
+                .color(owner.map_or(Color32::WHITE, |owner| owner.0.color()))
+                .anchor(Align2::LEFT_BOTTOM),
+            );
+        }
+    }
+
+    if draw_appearances {
+        plot_appearances(ui, &overlays.appearances, world_per_point, false);
+    }
+    if draw_appearances {
+        for (transform, radius, fill, tail) in &overlays.tails {
+            plot_comet_tail(
+                ui,
+                transform.translation.truncate(),
+                radius.0,
+                tail.0,
+                fill.color(),
+            );
+        }
+    }
+    if draw_appearances && overlays.view.night_sides {
+        for (transform, radius, sunward) in &overlays.sunward {
+            if let Some(sunward) = sunward.0 {
+                plot_night_side(ui, transform.translation.truncate(), radius.0, sunward);
+            }
+        }
+    }
+    for (transform, radius, spin) in &overlays.spins {
+        plot_launch_site(ui, transform.translation.truncate(), radius.0, spin);
+    }
+}
+
+/// Crafts' markers, shaped by whether they're burning or out of fuel.
+fn plot_crafts(
+    ui: &mut egui_plot::PlotUi,
+    crafts: &Query<CraftUiData>,
+    selected_craft: Option<Entity>,
+    last_burns: &Query<&LastBurn>,
+    now: f64,
+) {
+    for (entity, name, _, transform, _, fuel, .., owner, _, _) in
+        crafts.iter().filter(|_| !cfg!(feature = "mesh_render"))
+    {
+        let Vec3 { x, y, .. } = transform.translation;
+        let selected = selected_craft == Some(entity);
+        let state = CraftState::of(fuel, last_burns.get(entity).ok(), now);
+        ui.points(
+            egui_plot::Points::new(name.as_str(), [x as f64, y as f64])
+                .color(if selected {
+                    Color32::YELLOW
+                } else {
+                    owner.map_or(Color32::LIGHT_GRAY, |owner| owner.0.color())
+                })
+                .shape(state.marker())
+                .filled(state != CraftState::OutOfFuel)
+                .radius(match (selected, state) {
+                    (true, _) => 3.,
+                    (false, CraftState::Burning) => 3.5,
+                    (false, _) => 2.,
+                }),
+        );
+    }
+}
+
+/// Rings around the selected bodies, the box being dragged out, and the
+/// velocity a drag is setting.
+fn plot_selection(ui: &mut egui_plot::PlotUi, selection: &PlotSelection) {
+    for (name, radius, _, transform, ..) in &selection.bodies {
+        if selection.multi.0.contains(name.as_str()) {
+            ui.line(
+                egui_plot::Line::new(
+                    "",
+                    circle_points(transform.translation.as_dvec3(), radius.0 as f64 * 1.25),
+                )
+                .color(Color32::WHITE.gamma_multiply(0.7))
+                .style(egui_plot::LineStyle::dashed_dense()),
+            );
+        }
+    }
+    if let Some(start) = *selection.box_start
+        && let Some(end) = ui.pointer_coordinate()
+    {
+        ui.polygon(
+            egui_plot::Polygon::new(
+                "",
+                vec![
+                    [start.x, start.y],
+                    [end.x, start.y],
+                    [end.x, end.y],
+                    [start.x, end.y],
+                ],
+            )
+            .fill_color(Color32::WHITE.gamma_multiply(0.05))
+            .stroke(Stroke::new(1., Color32::WHITE.gamma_multiply(0.6))),
+        );
+    }
+
+    // Velocities drag out like the velocity vectors, a second long
+    let velocity_drag = selection.dragged.as_ref().filter(|drag| drag.velocity);
+    if let Some(drag) = velocity_drag
+        && let Some((.., transform, _, _, _, _, _)) = selection
+            .bodies
+            .iter()
+            .find(|(name, ..)| name.as_str() == drag.body)
+        && let Some(pointer) = ui.pointer_coordinate()
+    {
+        let origin = transform.translation;
+        ui.arrows(
+            egui_plot::Arrows::new(
+                "",
+                vec![[origin.x as f64, origin.y as f64]],
+                vec![[pointer.x, pointer.y]],
+            )
+            .color(Color32::YELLOW),
+        );
+    }
+}
+
+/// The direction and speed of the launch the pointer or stick is aiming from
+/// the body with this center and radius.
+fn plot_aim(
+    ui: &mut egui_plot::PlotUi,
+    (center, radius): (Vec2, f32),
+    max_speed: f32,
+    stick: Option<&StickAim>,
+    spin: Option<Spin>,
+) -> Option<(Vec2, f32)> {
+    // The right stick overrides the pointer while it's aiming
+    let stick = stick.and_then(|stick| Some((stick.direction?, stick.speed.clamp(0., max_speed))));
+    let pointed = ui.pointer_coordinate().and_then(|pointer| {
+        let offset = Vec2::new(pointer.x as f32, pointer.y as f32) - center;
+        // Pointing further from the surface launches faster
+        let speed = (offset.length() - radius).clamp(0., max_speed);
+        Some((offset.try_normalize()?, speed))
+    });
+    let (direction, speed) = stick.or(pointed)?;
+    let direction = spin.map_or(direction, |spin| spin.launch_direction(direction));
+    let tip = center + direction * (radius + speed);
+    ui.line(
+        egui_plot::Line::new(
+            tr("launch-aim"),
+            vec![
+                [center.x as f64, center.y as f64],
+                [tip.x as f64, tip.y as f64],
+            ],
+        )
+        .color(Color32::YELLOW),
+    );
+    Some((direction, speed))
+}
+
+/// The body under the pointer, using geometric detection, and the body or
+/// craft a `click` there picked.
+fn pick(
+    plot_response: &egui_plot::PlotResponse<()>,
+    selection: &PlotSelection,
+    pointer_pos: egui::Pos2,
+    hit_slop: f32,
+    click: bool,
+) -> (Option<String>, Option<String>, Option<Entity>) {
+    let mut hovered_body = None;
+    let mut clicked_body = None;
+    let mut clicked_craft = None;
+
+    // Convert screen coordinates to plot coordinates
+    let plot_pos = plot_response.transform.value_from_position(pointer_pos);
+    let slop = hit_slop as f64 / plot_response.transform.dpos_dvalue_x();
+    let plot_point = DVec2::new(plot_pos.x, plot_pos.y);
+    // Check which body (if any) the pointer is over
+    for (name, radius, _fill, transform, _crafts, _mass, _velocity, _egui_id, _owner) in selection
+        .grid
+        .bodies_near(plot_point, slop)
+        .into_iter()
+        .filter_map(|body| selection.bodies.get(body).ok())
+    {
+        let body_center = [
+            transform.translation.x as f64,
+            transform.translation.y as f64,
+        ];
+        let distance =
+            ((plot_pos.x - body_center[0]).powi(2) + (plot_pos.y - body_center[1]).powi(2)).sqrt();
+
+        if distance <= radius.0 as f64 + slop {
+            hovered_body = Some(name.to_string());
+
+            // Check for click on this body
+            if click {
+                clicked_body = Some(name.to_string());
+            }
+            break; // Take the first body we find (in case of overlap)
+        }
+    }
+
+    // Crafts are tiny, so accept clicks within a few pixels of one
+    if click && clicked_body.is_none() {
+        let reach = hit_slop.max(8.) as f64 / plot_response.transform.dpos_dvalue_x();
+        clicked_craft = selection
+            .grid
+            .crafts_near(plot_point, reach)
+            .filter_map(|craft| selection.crafts.get(craft).ok())
+            .map(|(entity, _, _, transform, ..)| {
+                let Vec3 { x, y, .. } = transform.translation;
+                let screen = plot_response
+                    .transform
+                    .position_from_point(&egui_plot::PlotPoint::new(x, y));
+                (entity, screen.distance(pointer_pos))
+            })
+            .filter(|&(_, distance)| distance <= hit_slop.max(8.))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(entity, _)| entity);
+    }
+    (hovered_body, clicked_body, clicked_craft)
+}
+
+/// Dragging a body moves it, or with Shift held as the drag began, sets its
+/// velocity.
+fn drag_bodies(
+    plot_response: &egui_plot::PlotResponse<()>,
+    selection: &mut PlotSelection,
+    hit_slop: f32,
+    can_move: bool,
+) {
+    let response = &plot_response.response;
+    let ctx = &response.ctx;
+    if can_move
+        && response.drag_started()
+        && let Some(origin) = ctx.input(|input| input.pointer.press_origin())
+    {
+        let origin = plot_response.transform.value_from_position(origin);
+        let origin = DVec2::new(origin.x, origin.y);
+        let slop = hit_slop as f64 / plot_response.transform.dpos_dvalue_x();
+        let pressed = selection
+            .bodies
+            .iter()
+            .find(|(_, radius, _, transform, ..)| {
+                origin.distance(transform.translation.truncate().as_dvec2())
+                    <= radius.0 as f64 + slop
+            });
+        *selection.dragged = pressed.map(|(name, _, _, transform, ..)| BodyDrag {
+            body: name.to_string(),
+            velocity: ctx.input(|input| input.modifiers.shift),
+            grab: origin - transform.translation.truncate().as_dvec2(),
+        });
+    }
+    if let Some(drag) = selection.dragged.as_ref()
+        && let Some(pointer) = response.interact_pointer_pos()
+        && let Some((.., transform, _, _, _, _, _)) = selection
+            .bodies
+            .iter()
+            .find(|(name, ..)| name.as_str() == drag.body)
+    {
+        let pointer = plot_response.transform.value_from_position(pointer);
+        let pointer = DVec2::new(pointer.x, pointer.y);
+        let current = transform.translation.as_dvec3();
+        let edit = if drag.velocity {
+            Edit::SetVelocity {
+                body: drag.body.clone(),
+                velocity: (pointer - current.truncate()).extend(0.),
+            }
+        } else {
+            Edit::SetPosition {
+                body: drag.body.clone(),
+                position: (pointer - drag.grab).extend(current.z),
+            }
+        };
+        selection.edits.write(EditorAction::Apply(edit));
+    }
+    if !response.dragged() {
+        *selection.dragged = None;
+    }
+}
+
+/// Box selection picks the bodies whose centers it covers, adding them to the
+/// selection with Shift held.
+fn box_select(
+    plot_response: &egui_plot::PlotResponse<()>,
+    selection: &mut PlotSelection,
+    shift: bool,
+) {
+    let response = &plot_response.response;
+    let ctx = &response.ctx;
+    if response.drag_started()
+        && let Some(origin) = ctx.input(|input| input.pointer.press_origin())
+    {
+        let origin = plot_response.transform.value_from_position(origin);
+        *selection.box_start = Some(DVec2::new(origin.x, origin.y));
+    }
+    if response.drag_stopped()
+        && let Some(start) = selection.box_start.take()
+        && let Some(end) = response
+            .interact_pointer_pos()
+            .or_else(|| ctx.pointer_latest_pos())
+    {
+        let end = plot_response.transform.value_from_position(end);
+        let end = DVec2::new(end.x, end.y);
+        let (min, max) = (start.min(end), start.max(end));
+        let mut names: BTreeSet<_> = selection
+            .bodies
+            .iter()
+            .filter(|(_, _, _, transform, ..)| {
+                let center = transform.translation.truncate().as_dvec2();
+                center.cmpge(min).all() && center.cmple(max).all()
+            })
+            .map(|(name, ..)| name.to_string())
+            .collect();
+        if shift {
+            names.extend(selection.multi.0.iter().cloned());
+            names.extend(selection.selected_body.0.clone());
+        }
+        if !names.is_empty() {
+            selection.multi.replace(&mut selection.selected_body, names);
+            selection.selected_craft.0 = None;
+        }
+    }
+}
+
+/// An outline around the hovered body, and with `tooltip`, what it is.
+fn hover_outline(
+    ui: &egui::Ui,
+    plot_response: &egui_plot::PlotResponse<()>,
+    selection: &PlotSelection,
+    tooltip: bool,
+) {
+    let Some(hovered_name) = &selection.hovered.0 else {
+        return;
+    };
+    // Find the hovered body to get its position and radius
+    let Some((name, radius, fill, transform, crafts, mass, velocity, _, owner)) = selection
+        .bodies
+        .iter()
+        .find(|(name, ..)| &name.to_string() == hovered_name)
+    else {
+        return;
+    };
+    let body_center = [
+        transform.translation.x as f64,
+        transform.translation.y as f64,
+    ];
+    let hover_radius = radius.0 as f64; // Just slightly larger
+
+    // Convert body center from plot coordinates to screen coordinates
+    let center_screen = plot_response
+        .transform
+        .position_from_point(&egui_plot::PlotPoint::new(body_center[0], body_center[1]));
+
+    // Calculate the radius in screen space by checking a point on the edge
+    let edge_point = egui_plot::PlotPoint::new(body_center[0] + hover_radius, body_center[1]);
+    let edge_screen = plot_response.transform.position_from_point(&edge_point);
+    let screen_radius = (edge_screen.x - center_screen.x).abs();
+
+    // Draw circle outline on the main UI
+    let painter = ui.painter();
+    painter.circle_stroke(
+        center_screen,
+        screen_radius,
+        Stroke::new(1.0, Color32::WHITE),
+    );
+
+    if tooltip {
+        plot_response.response.clone().on_hover_ui_at_pointer(|ui| {
+            ui.strong(RichText::new(name.as_str()).color(fill.color()));
+            owner_label(ui, owner);
+            ui.label(readout("readout-mass", mass.0 as f64, 2));
+            ui.label(readout("readout-radius", radius.0 as f64, 1));
+            ui.label(readout("readout-speed", velocity.0.length(), 2));
+            ui.label(readout("readout-crafts", crafts.0 as f64, 0));
+        });
+    }
+}
+
+/// Handles [`InteractionMode::Maneuver`] gestures: clicking the selected
+/// craft's trajectory places its node, and dragging a handle sets the burn.
+fn plan_maneuver(plot_response: &egui_plot::PlotResponse<()>, selection: &mut PlotSelection) {
+    const GRAB_DISTANCE: f32 = 10.;
+
+    let response = &plot_response.response;
+    if response.drag_stopped() {
+        *selection.dragged_handle = None;
+    }
+    let Some(pointer) = response.interact_pointer_pos() else {
+        return;
+    };
+    let Some((craft, .., trajectory, node)) = selection
+        .selected_craft
+        .0
+        .and_then(|entity| selection.crafts.get(entity).ok())
+    else {
+        return;
+    };
+    let transform = &plot_response.transform;
+    let screen = |position: DVec3| {
+        transform.position_from_point(&egui_plot::PlotPoint::new(position.x, position.y))
+    };
+
+    if let (Some(planned), Some(node)) = (&trajectory.planned, node) {
+        if response.drag_started() {
+            *selection.dragged_handle = [NodeHandle::Prograde, NodeHandle::Radial]
+                .into_iter()
+                .find(|handle| {
+                    screen(handle.placement(planned, node).0).distance(pointer) <= GRAB_DISTANCE
+                });
+        }
+        if let Some(handle) = *selection.dragged_handle {
+            let value = transform.value_from_position(pointer);
+            let (_, axis) = handle.placement(planned, node);
+            let along = (DVec3::new(value.x, value.y, 0.) - planned.position).dot(axis);
+            let delta_v = (along - NodeHandle::OFFSET) as f32;
+            let mut node = *node;
+            match handle {
+                NodeHandle::Prograde => node.prograde = delta_v,
+                NodeHandle::Radial => node.radial = delta_v,
+            }
+            selection.commands.entity(craft).insert(node);
+            return;
+        }
+    }
+
+    if response.clicked() {
+        let nearest = trajectory
+            .coast
+            .iter()
+            .enumerate()
+            .map(|(index, &position)| (index, screen(position).distance(pointer)))
+            .filter(|&(_, distance)| distance <= GRAB_DISTANCE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((index, _)) = nearest {
+            let time = selection.time.elapsed_secs_f64() + index as f64 * trajectory.interval;
+            // Moving an existing node keeps its burn
+            let node = node.map_or(
+                ManeuverNode {
+                    time,
+                    prograde: 0.,
+                    radial: 0.,
+                },
+                |node| ManeuverNode { time, ..*node },
+            );
+            selection.commands.entity(craft).insert(node);
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use super::{BodyUiData, CraftUiData, accessible_region, framed_list, readout};
+use crate::bodies::Body;
+use crate::crafts::Home;
+use crate::i18n::{format_number, tr, tr_args};
+use crate::selection::SelectedBody;
+use bevy::prelude::*;
+use bevy_egui::egui::{self, Color32, Ui, WidgetInfo, WidgetType};
+
+/// Columns of the craft list, which sorts by any of them.
+#[derive(Clone, Copy, PartialEq, Default)]
+enum CraftColumn {
+    #[default]
+    Name,
+    Origin,
+    Destination,
+    Fuel,
+    Eta,
+}
+
+impl CraftColumn {
+    const ALL: [Self; 5] = [
+        Self::Name,
+        Self::Origin,
+        Self::Destination,
+        Self::Fuel,
+        Self::Eta,
+    ];
+
+    fn label(self) -> String {
+        tr(match self {
+            Self::Name => "column-name",
+            Self::Origin => "column-origin",
+            Self::Destination => "column-destination",
+            Self::Fuel => "column-fuel",
+            Self::Eta => "column-eta",
+        })
+    }
+}
+
+/// How the craft list is filtered and sorted.
+#[derive(Default)]
+pub struct CraftTable {
+    /// Whether the overlay lists crafts rather than bodies.
+    pub showing: bool,
+    filter: String,
+    sort: CraftColumn,
+    descending: bool,
+}
+
+/// Columns of the body list, which sorts by any of them.
+#[derive(Clone, Copy, PartialEq, Default)]
+enum BodyColumn {
+    #[default]
+    Name,
+    Mass,
+    Radius,
+    Crafts,
+    Owner,
+    Distance,
+}
+
+impl BodyColumn {
+    const ALL: [Self; 6] = [
+        Self::Name,
+        Self::Mass,
+        Self::Radius,
+        Self::Crafts,
+        Self::Owner,
+        Self::Distance,
+    ];
+
+    fn label(self) -> String {
+        tr(match self {
+            Self::Name => "column-name",
+            Self::Mass => "column-mass",
+            Self::Radius => "column-radius",
+            Self::Crafts => "column-crafts",
+            Self::Owner => "column-owner",
+            Self::Distance => "column-distance",
+        })
+    }
+}
+
+/// How the body list is filtered and sorted.
+#[derive(Default)]
+pub struct BodyTable {
+    filter: String,
+    sort: BodyColumn,
+    descending: bool,
+    /// The most recently selected body, which distances are measured from.
+    pub reference: Option<String>,
+}
+
+/// Every body, filtered by name and sorted by whichever column was clicked.
+/// Clicking a body selects it.
+pub fn body_table(
+    ui: &mut Ui,
+    table: &mut BodyTable,
+    bodies: &Query<BodyUiData>,
+    selected_body: &mut SelectedBody,
+) {
+    ui.add(
+        egui::TextEdit::singleline(&mut table.filter)
+            .hint_text(tr("filter-by-name"))
+            .desired_width(f32::INFINITY),
+    );
+    let reference = table
+        .reference
+        .as_ref()
+        .and_then(|reference| bodies.iter().find(|(name, ..)| name.as_str() == reference))
+        .map(|(.., transform, _, _, _, _, _)| transform.translation);
+    let filter = table.filter.to_lowercase();
+    let mut rows: Vec<_> = bodies
+        .iter()
+        .filter(|(name, ..)| name.to_lowercase().contains(&filter))
+        .map(
+            |(name, radius, fill, transform, crafts, mass, _, _, owner)| {
+                let distance = reference.map(|reference| transform.translation.distance(reference));
+                (
+                    name,
+                    radius.0,
+                    fill.color(),
+                    crafts.0,
+                    mass.0,
+                    owner,
+                    distance,
+                )
+            },
+        )
+        .collect();
+    rows.sort_by(|a, b| {
+        let (name_a, radius_a, _, crafts_a, mass_a, owner_a, distance_a) = a;
+        let (name_b, radius_b, _, crafts_b, mass_b, owner_b, distance_b) = b;
+        let ordering = match table.sort {
+            BodyColumn::Name => name_a.as_str().cmp(name_b.as_str()),
+            BodyColumn::Mass => mass_a.total_cmp(mass_b),
+            BodyColumn::Radius => radius_a.total_cmp(radius_b),
+            BodyColumn::Crafts => crafts_a.cmp(crafts_b),
+            BodyColumn::Owner => owner_a
+                .map(|owner| owner.0.name())
+                .cmp(&owner_b.map(|owner| owner.0.name())),
+            BodyColumn::Distance => distance_a
+                .unwrap_or(f32::INFINITY)
+                .total_cmp(&distance_b.unwrap_or(f32::INFINITY)),
+        };
+        if table.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+
+    accessible_region(ui, &tr("bodies"), |ui| {
+        framed_list(ui, |ui| {
+            egui::Grid::new("body_table")
+                .num_columns(BodyColumn::ALL.len())
+                .striped(true)
+                .show(ui, |ui| {
+                    for column in BodyColumn::ALL {
+                        let arrow = match (table.sort == column, table.descending) {
+                            (false, _) => "",
+                            (true, false) => " ⏶",
+                            (true, true) => " ⏷",
+                        };
+                        let header = ui
+                            .selectable_label(
+                                table.sort == column,
+                                format!("{}{arrow}", column.label()),
+                            )
+                            .on_hover_text(match (column, &table.reference) {
+                                (BodyColumn::Distance, Some(reference)) => {
+                                    tr_args("distance-from", &[("body", reference.as_str().into())])
+                                }
+                                _ => tr("click-to-sort"),
+                            });
+                        if header.clicked() {
+                            table.descending = table.sort == column && !table.descending;
+                            table.sort = column;
+                        }
+                    }
+                    ui.end_row();
+
+                    for (name, radius, fill, crafts, mass, owner, distance) in rows {
+                        let selected = selected_body.0.as_deref() == Some(name.as_str());
+                        // Screen readers get the whole row with the name
+                        let summary = tr_args(
+                            "body-summary",
+                            &[
+                                ("name", name.as_str().into()),
+                                ("mass", format_number(mass as f64, 1).into()),
+                                ("radius", format_number(radius as f64, 2).into()),
+                                ("crafts", crafts.into()),
+                                (
+                                    "owner",
+                                    owner
+                                        .map_or_else(|| tr("neutral"), |owner| owner.0.name())
+                                        .into(),
+                                ),
+                            ],
+                        );
+                        let clicked = ui
+                            .horizontal(|ui| {
+                                let swatch = ui.colored_label(fill, "⏺");
+                                let label = ui.selectable_label(selected, name.as_str());
+                                label.widget_info(|| {
+                                    WidgetInfo::selected(
+                                        WidgetType::SelectableLabel,
+                                        true,
+                                        selected,
+                                        &summary,
+                                    )
+                                });
+                                swatch.clicked() | label.clicked()
+                            })
+                            .inner;
+                        if clicked {
+                            selected_body.0 = Some(name.to_string());
+                        }
+                        ui.label(format_number(mass as f64, 1));
+                        ui.label(format_number(radius as f64, 2));
+                        ui.label(format_number(crafts as f64, 0));
+                        match owner {
+                            Some(owner) => ui.colored_label(
+                                owner.0.color(),
+                                format!("{} {}", owner.0.symbol(), owner.0.name()),
+                            ),
+                            None => ui.label(tr("neutral")),
+                        };
+                        ui.label(distance.map_or("—".to_string(), |distance| {
+                            format_number(distance as f64, 1)
+                        }));
+                        ui.end_row();
+                    }
+                });
+        })
+    });
+}
+
+/// Lists crafts in flight, returning the one clicked, if any.
+pub fn craft_table(
+    ui: &mut Ui,
+    table: &mut CraftTable,
+    crafts: &Query<CraftUiData>,
+    homes: &Query<&Home>,
+    bodies: &Query<(Entity, &Name), With<Body>>,
+) -> Option<Entity> {
+    ui.add(
+        egui::TextEdit::singleline(&mut table.filter)
+            .hint_text(tr("filter-by-name"))
+            .desired_width(f32::INFINITY),
+    );
+    let body_name = |entity: Option<Entity>| {
+        entity
+            .and_then(|entity| bodies.get(entity).ok())
+            .map_or("—", |(_, name)| name.as_str())
+    };
+    let filter = table.filter.to_lowercase();
+    let mut rows: Vec<_> = crafts
+        .iter()
+        .filter(|(_, name, ..)| name.to_lowercase().contains(&filter))
+        .map(
+            |(entity, name, _, _, _, fuel, destination, .., owner, trajectory, _)| {
+                let origin = body_name(homes.get(entity).ok().map(|home| home.0));
+                let destination = body_name(destination.map(|destination| destination.0));
+                // Only counts as arriving if the coast actually reaches it
+                let eta = trajectory
+                    .closest_approach
+                    .as_ref()
+                    .filter(|approach| approach.distance <= 0.)
+                    .map(|approach| approach.time);
+                (entity, name, origin, destination, fuel.0, eta, owner)
+            },
+        )
+        .collect();
+    rows.sort_by(|a, b| {
+        let (_, name_a, origin_a, destination_a, fuel_a, eta_a, _) = a;
+        let (_, name_b, origin_b, destination_b, fuel_b, eta_b, _) = b;
+        let ordering = match table.sort {
+            CraftColumn::Name => name_a.as_str().cmp(name_b.as_str()),
+            CraftColumn::Origin => origin_a.cmp(origin_b),
+            CraftColumn::Destination => destination_a.cmp(destination_b),
+            CraftColumn::Fuel => fuel_a.total_cmp(fuel_b),
+            CraftColumn::Eta => eta_a
+                .unwrap_or(f64::INFINITY)
+                .total_cmp(&eta_b.unwrap_or(f64::INFINITY)),
+        };
+        if table.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+
+    let mut picked = None;
+    framed_list(ui, |ui| {
+        if rows.is_empty() {
+            ui.weak(tr("no-crafts-in-flight"));
+            return;
+        }
+        egui::Grid::new("craft_table")
+            .num_columns(CraftColumn::ALL.len())
+            .striped(true)
+            .show(ui, |ui| {
+                for column in CraftColumn::ALL {
+                    let arrow = match (table.sort == column, table.descending) {
+                        (false, _) => "",
+                        (true, false) => " ⏶",
+                        (true, true) => " ⏷",
+                    };
+                    let header = ui
+                        .selectable_label(
+                            table.sort == column,
+                            format!("{}{arrow}", column.label()),
+                        )
+                        .on_hover_text(tr(match column {
+                            CraftColumn::Eta => "eta-hint",
+                            _ => "click-to-sort",
+                        }));
+                    if header.clicked() {
+                        table.descending = table.sort == column && !table.descending;
+                        table.sort = column;
+                    }
+                }
+                ui.end_row();
+
+                for (entity, name, origin, destination, fuel, eta, owner) in rows {
+                    let color = owner.map_or(Color32::LIGHT_GRAY, |owner| owner.0.color());
+                    let clicked = ui
+                        .horizontal(|ui| {
+                            ui.colored_label(color, "•").clicked()
+                                | ui.selectable_label(false, name.as_str()).clicked()
+                        })
+                        .inner;
+                    if clicked {
+                        picked = Some(entity);
+                    }
+                    ui.label(origin);
+                    ui.label(destination);
+                    ui.label(format_number(fuel as f64, 1));
+                    ui.label(eta.map_or("—".to_string(), |eta| readout("seconds", eta, 1)));
+                    ui.end_row();
+                }
+            });
+    });
+    picked
+}
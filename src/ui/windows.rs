@@ -0,0 +1,159 @@
+use super::UiState;
+use crate::bodies::{Body, Crafts};
+use crate::crafts::Craft;
+use crate::factions::{Faction, Owner};
+use crate::i18n::{tr, tr_args};
+use crate::physics::{FrameCorrection, GravitySolver, PhysicsConstants, Substeps};
+use bevy::prelude::*;
+use bevy_egui::{
+    EguiContexts,
+    egui::{self, Align2},
+};
+
+pub fn physics_constants_window(
+    mut contexts: EguiContexts,
+    mut ui_state: ResMut<UiState>,
+    mut constants: ResMut<PhysicsConstants>,
+    mut solver: ResMut<GravitySolver>,
+    substeps: Res<Substeps>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    // Edit copies so the resources are only marked changed on actual edits
+    let mut edited = *constants;
+    let mut edited_solver = *solver;
+    egui::Window::new(tr("physics-constants"))
+        .open(&mut ui_state.show_physics_constants)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("physics_constants").show(ui, |ui| {
+                ui.label("G");
+                ui.add(
+                    egui::DragValue::new(&mut edited.g)
+                        .speed(0.5)
+                        .range(0.0..=f32::MAX),
+                );
+                ui.end_row();
+                ui.label(tr("constant-density"));
+                ui.add(
+                    egui::DragValue::new(&mut edited.density)
+                        .speed(1e-4)
+                        .range(0.0..=f32::MAX),
+                );
+                ui.end_row();
+                ui.label(tr("constant-softening"));
+                ui.add(
+                    egui::DragValue::new(&mut edited.softening)
+                        .speed(0.05)
+                        .range(0.0..=f32::MAX),
+                );
+                ui.end_row();
+                ui.label(tr("constant-breakup-energy"));
+                ui.add(
+                    egui::DragValue::new(&mut edited.breakup_energy)
+                        .speed(0.05)
+                        .range(0.0..=f32::MAX),
+                );
+                ui.end_row();
+                ui.label(tr("constant-kepler-threshold"))
+                    .on_hover_text(tr("constant-kepler-threshold-hint"));
+                ui.add(
+                    egui::DragValue::new(&mut edited.kepler_threshold)
+                        .speed(1e-4)
+                        .range(0.0..=1.0),
+                );
+                ui.end_row();
+                ui.label(tr("constant-step-tolerance"))
+                    .on_hover_text(tr("constant-step-tolerance-hint"));
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut edited.step_tolerance)
+                            .speed(1e-4)
+                            .range(0.0..=1.0),
+                    );
+                    if constants.step_tolerance > 0. {
+                        ui.label(tr_args("substeps", &[("count", substeps.0.into())]));
+                    }
+                });
+                ui.end_row();
+                ui.label(tr("constant-frame"));
+                egui::ComboBox::from_id_salt("frame_correction")
+                    .selected_text(edited.frame.label())
+                    .show_ui(ui, |ui| {
+                        for frame in FrameCorrection::ALL {
+                            ui.selectable_value(&mut edited.frame, frame, frame.label());
+                        }
+                    })
+                    .response
+                    .on_hover_text(tr("constant-frame-hint"));
+                ui.end_row();
+                if GravitySolver::ALL.len() > 1 {
+                    ui.label(tr("gravity-solver"));
+                    egui::ComboBox::from_id_salt("gravity_solver")
+                        .selected_text(edited_solver.label())
+                        .show_ui(ui, |ui| {
+                            for &option in GravitySolver::ALL {
+                                ui.selectable_value(&mut edited_solver, option, option.label());
+                            }
+                        });
+                    ui.end_row();
+                }
+            });
+            if ui.button(tr("reset-defaults")).clicked() {
+                edited = PhysicsConstants::default();
+            }
+        });
+    if edited != *constants {
+        *constants = edited;
+    }
+    if edited_solver != *solver {
+        *solver = edited_solver;
+    }
+}
+
+/// Bodies and crafts held by each side, and how many bodies are still up
+/// for grabs.
+pub fn scoreboard_window(
+    mut contexts: EguiContexts,
+    mut ui_state: ResMut<UiState>,
+    bodies: Query<(&Crafts, Option<&Owner>), With<Body>>,
+    crafts: Query<&Owner, With<Craft>>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    egui::Window::new(tr("scoreboard"))
+        .open(&mut ui_state.show_scoreboard)
+        .resizable(false)
+        .anchor(Align2::RIGHT_TOP, [-8., 32.])
+        .show(ctx, |ui| {
+            egui::Grid::new("scoreboard").striped(true).show(ui, |ui| {
+                ui.label("");
+                ui.strong(tr("bodies"));
+                ui.strong(tr("docked"));
+                ui.strong(tr("in-flight"));
+                ui.end_row();
+                for faction in [Faction::Player, Faction::Opponent] {
+                    let owned = bodies
+                        .iter()
+                        .filter(|(_, owner)| owner.is_some_and(|owner| owner.0 == faction));
+                    ui.colored_label(
+                        faction.color(),
+                        format!("{} {}", faction.symbol(), faction.name()),
+                    );
+                    ui.label(owned.clone().count().to_string());
+                    ui.label(owned.map(|(crafts, _)| crafts.0).sum::<u32>().to_string());
+                    let flying = crafts.iter().filter(|owner| owner.0 == faction).count();
+                    ui.label(flying.to_string());
+                    ui.end_row();
+                }
+                let neutral = bodies.iter().filter(|(_, owner)| owner.is_none()).count();
+                ui.label(tr("neutral"));
+                ui.label(neutral.to_string());
+                ui.end_row();
+            });
+        });
+}
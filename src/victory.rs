@@ -0,0 +1,186 @@
+use crate::app_state::{AppState, GameOutcome};
+use crate::assists::GravityAssist;
+use crate::balance::Balance;
+use crate::bodies::{Body, ScenarioSystems};
+use crate::crafts::{Craft, Crafts, CraftsLost, Fuel};
+use crate::factions::{Faction, Owner, Players, authoritative};
+use crate::i18n::tr;
+use crate::scenario::Scenario;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Ends the game when the scenario's [`WinConditions`] are met or the player
+/// is wiped out, and keeps the statistics shown on the end screen.
+pub struct VictoryPlugin;
+
+impl Plugin for VictoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WinConditions>()
+            .init_resource::<GameStats>()
+            .add_systems(Update, reset_game.in_set(ScenarioSystems::Initialize))
+            .add_systems(
+                FixedPostUpdate,
+                (
+                    (tally_time, tally_delta_v, tally_assists, tally_losses),
+                    win_conditions.run_if(authoritative),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+/// Ways the player can win a scenario. Any one of them is enough.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct WinConditions {
+    /// Own every body in the system.
+    pub own_all_bodies: bool,
+    /// Have this many crafts, docked at owned bodies or in flight.
+    pub craft_count: Option<u32>,
+    /// Leave an opponent no bodies and no crafts.
+    pub eliminate_opponent: bool,
+}
+
+impl Default for WinConditions {
+    fn default() -> Self {
+        Self {
+            own_all_bodies: false,
+            craft_count: None,
+            eliminate_opponent: true,
+        }
+    }
+}
+
+/// How the game went for the side at this machine, for the end screen.
+#[derive(Resource, Default)]
+pub struct GameStats {
+    /// Simulated seconds since the scenario loaded.
+    pub elapsed: f64,
+    /// Spent by launches, burns and autopilots.
+    pub delta_v: f32,
+    pub gravity_assists: u32,
+    /// Crafts destroyed in combat, attacking or defending.
+    pub crafts_lost: u32,
+}
+
+/// The fuel a craft had when last tallied.
+#[derive(Component)]
+struct TalliedFuel(f32);
+
+fn reset_game(mut stats: ResMut<GameStats>, mut outcome: ResMut<GameOutcome>) {
+    *stats = GameStats::default();
+    *outcome = GameOutcome::default();
+}
+
+fn tally_time(time: Res<Time>, mut stats: ResMut<GameStats>) {
+    stats.elapsed += time.delta_secs_f64();
+}
+
+/// Fuel only ever goes down, so whatever a craft no longer has, it spent.
+fn tally_delta_v(
+    mut commands: Commands,
+    crafts: Query<(Entity, &Craft, &Fuel, &Owner, Option<&TalliedFuel>)>,
+    balance: Balance,
+    players: Res<Players>,
+    mut stats: ResMut<GameStats>,
+) {
+    for (entity, craft, fuel, owner, tallied) in &crafts {
+        if owner.0 != players.perspective() {
+            continue;
+        }
+        // Launches are paid for before the craft is first seen
+        let before = match tallied {
+            Some(tallied) => tallied.0,
+            None => balance
+                .class(&craft.class)
+                .map_or(fuel.0, |class| class.fuel),
+        };
+        if tallied.is_none() || before != fuel.0 {
+            stats.delta_v += before - fuel.0;
+            commands.entity(entity).insert(TalliedFuel(fuel.0));
+        }
+    }
+}
+
+fn tally_assists(
+    mut assists: EventReader<GravityAssist>,
+    owners: Query<&Owner>,
+    players: Res<Players>,
+    mut stats: ResMut<GameStats>,
+) {
+    for assist in assists.read() {
+        if owners
+            .get(assist.craft)
+            .is_ok_and(|owner| owner.0 == players.perspective())
+        {
+            stats.gravity_assists += 1;
+        }
+    }
+}
+
+fn tally_losses(
+    mut losses: EventReader<CraftsLost>,
+    players: Res<Players>,
+    mut stats: ResMut<GameStats>,
+) {
+    for loss in losses.read() {
+        if loss.faction == players.perspective() {
+            stats.crafts_lost += loss.count;
+        }
+    }
+}
+
+/// Wins once any of the [`WinConditions`] holds, and loses once this side has
+/// no bodies and no crafts left in a scenario with an opponent. After a
+/// win, playing on can only end in defeat.
+fn win_conditions(
+    bodies: Query<(&Crafts, Option<&Owner>), With<Body>>,
+    crafts: Query<&Owner, With<Craft>>,
+    conditions: Res<WinConditions>,
+    scenario: Res<Scenario>,
+    players: Res<Players>,
+    mut outcome: ResMut<GameOutcome>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    // Nothing is owned before a scenario finishes loading
+    if bodies.is_empty() {
+        return;
+    }
+    let owned = |faction: Faction| {
+        bodies
+            .iter()
+            .filter(move |(_, owner)| owner.is_some_and(|owner| owner.0 == faction))
+    };
+    let flying = |faction: Faction| crafts.iter().filter(|owner| owner.0 == faction).count();
+    let holdings = |faction: Faction| owned(faction).count() + flying(faction);
+    let contested = scenario
+        .bodies
+        .iter()
+        .any(|body| body.owner == Some(Faction::Opponent));
+    let us = players.perspective();
+    let our_crafts = owned(us).map(|(crafts, _)| crafts.0).sum::<u32>() + flying(us) as u32;
+
+    let (won, title, details) = if contested && holdings(us) == 0 {
+        (false, "defeat", "defeat-every-body")
+    } else if outcome.won {
+        return;
+    } else if conditions.eliminate_opponent && contested && holdings(us.other()) == 0 {
+        (true, "victory", "victory-eliminated")
+    } else if conditions.own_all_bodies && owned(us).count() == bodies.iter().len() {
+        (true, "victory", "victory-every-body")
+    } else if conditions
+        .craft_count
+        .is_some_and(|count| our_crafts >= count)
+    {
+        (true, "victory", "victory-fleet")
+    } else {
+        return;
+    };
+    *outcome = GameOutcome {
+        won,
+        title: tr(title),
+        details: vec![tr(details)],
+    };
+    next_state.set(AppState::GameOver);
+}
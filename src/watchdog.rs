@@ -0,0 +1,193 @@
+use crate::app_state::AppState;
+use crate::bodies::ScenarioSystems;
+use crate::events::Toasts;
+use crate::i18n::{format_number, tr, tr_args};
+use crate::physics::{
+    Mass, Motion, PhysicsConstants, Propagation, SimPosition, SimVelocity, Substeps,
+};
+use bevy::ecs::system::SystemParam;
+use bevy::log::error;
+use bevy::math::DVec3;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+
+/// Catches bodies and crafts whose position or velocity goes non-finite or
+/// absurdly large after integration, logs what led to it, and puts the
+/// simulation back to the last good checkpoint rather than letting the bad
+/// values spread through gravity to everything else.
+pub struct WatchdogPlugin;
+
+impl Plugin for WatchdogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Watchdog>()
+            .add_systems(
+                FixedUpdate,
+                (recover, take_checkpoint)
+                    .chain()
+                    .after(Motion)
+                    .before(Propagation)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(Update, reset.in_set(ScenarioSystems::Initialize));
+    }
+}
+
+/// Farther from the origin than any scenario puts anything.
+const MAX_DISTANCE: f64 = 1e9;
+/// Faster than anything gets by gravity alone in any scenario.
+const MAX_SPEED: f64 = 1e7;
+/// Simulated seconds between checkpoints.
+const CHECKPOINT_INTERVAL: f64 = 5.;
+
+/// The last state known to be good, and who has already been rolled back for.
+#[derive(Resource, Default)]
+pub struct Watchdog {
+    checkpoint: Option<Checkpoint>,
+    /// Entities that caused a rollback since the last checkpoint. If they
+    /// blow up again they're removed instead, so a blowup that always
+    /// recurs doesn't loop.
+    rolled_back: HashSet<Entity>,
+}
+
+struct Checkpoint {
+    /// Simulated seconds.
+    time: f64,
+    states: HashMap<Entity, (DVec3, DVec3)>,
+}
+
+fn sane(position: DVec3, velocity: DVec3) -> bool {
+    position.is_finite()
+        && velocity.is_finite()
+        && position.length() < MAX_DISTANCE
+        && velocity.length() < MAX_SPEED
+}
+
+fn reset(mut watchdog: ResMut<Watchdog>) {
+    *watchdog = Watchdog::default();
+}
+
+fn take_checkpoint(
+    moving: Query<(Entity, &SimPosition, &SimVelocity)>,
+    mut watchdog: ResMut<Watchdog>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs_f64();
+    if watchdog
+        .checkpoint
+        .as_ref()
+        .is_some_and(|checkpoint| now - checkpoint.time < CHECKPOINT_INTERVAL)
+    {
+        return;
+    }
+    // A whole interval without blowing up again, so any later blowup is a
+    // new one
+    watchdog.rolled_back.clear();
+    watchdog.checkpoint = Some(Checkpoint {
+        time: now,
+        states: moving
+            .iter()
+            .map(|(entity, position, velocity)| (entity, (position.0, velocity.0)))
+            .collect(),
+    });
+}
+
+type MovingState = (
+    Entity,
+    Option<&'static Name>,
+    Option<&'static Mass>,
+    &'static mut SimPosition,
+    &'static mut SimVelocity,
+);
+
+/// What the integration ran with, logged when it blows up.
+#[derive(SystemParam)]
+struct Integration<'w> {
+    physics: Res<'w, PhysicsConstants>,
+    substeps: Res<'w, Substeps>,
+}
+
+/// Rolls everything back to the checkpoint, or removes what blew up if
+/// there's no checkpoint to go back to or it blew up from there before.
+fn recover(
+    mut commands: Commands,
+    mut moving: Query<MovingState>,
+    mut watchdog: ResMut<Watchdog>,
+    mut toasts: Option<ResMut<Toasts>>,
+    integration: Integration,
+    time: Res<Time>,
+    real_time: Res<Time<Real>>,
+) {
+    let offenders: Vec<_> = moving
+        .iter()
+        .filter(|(.., position, velocity)| !sane(position.0, velocity.0))
+        .map(|(entity, name, mass, position, velocity)| {
+            let last_good = watchdog
+                .checkpoint
+                .as_ref()
+                .and_then(|checkpoint| checkpoint.states.get(&entity));
+            error!(
+                ?entity,
+                name = name.map(Name::as_str),
+                mass = mass.map(|mass| mass.0),
+                position = ?position.0,
+                velocity = ?velocity.0,
+                ?last_good,
+                g = integration.physics.g,
+                softening = integration.physics.softening,
+                dt = time.delta_secs_f64(),
+                substeps = integration.substeps.0,
+                "simulation blew up"
+            );
+            (
+                entity,
+                name.map_or_else(|| tr("watchdog-unnamed"), Name::to_string),
+            )
+        })
+        .collect();
+    let Some((_, first)) = offenders.first() else {
+        return;
+    };
+
+    let repeat = offenders
+        .iter()
+        .any(|(entity, _)| watchdog.rolled_back.contains(entity));
+    let message = match watchdog.checkpoint.take() {
+        Some(checkpoint) if !repeat => {
+            for (entity, _, _, mut position, mut velocity) in &mut moving {
+                if let Some(&(last_position, last_velocity)) = checkpoint.states.get(&entity) {
+                    position.0 = last_position;
+                    velocity.0 = last_velocity;
+                } else if !sane(position.0, velocity.0) {
+                    commands.entity(entity).despawn();
+                }
+            }
+            watchdog
+                .rolled_back
+                .extend(offenders.iter().map(|(entity, _)| *entity));
+            let now = time.elapsed_secs_f64();
+            let rewound = now - checkpoint.time;
+            // Counted from now, so the next checkpoint waits out a whole
+            // interval from the rewound state
+            watchdog.checkpoint = Some(Checkpoint {
+                time: now,
+                ..checkpoint
+            });
+            tr_args(
+                "watchdog-rewound",
+                &[
+                    ("name", first.as_str().into()),
+                    ("seconds", format_number(rewound, 0).into()),
+                ],
+            )
+        }
+        _ => {
+            for (entity, _) in &offenders {
+                commands.entity(*entity).despawn();
+            }
+            tr_args("watchdog-removed", &[("name", first.as_str().into())])
+        }
+    };
+    if let Some(toasts) = &mut toasts {
+        toasts.push(message, real_time.elapsed_secs_f64());
+    }
+}
@@ -0,0 +1,198 @@
+use std::io;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SocketError {
+    #[error("the other side hung up")]
+    Closed,
+    #[error("network error: {0}")]
+    Io(#[from] io::Error),
+    #[error("websocket error: {0}")]
+    WebSocket(String),
+}
+
+// Online matches run over WebSockets so browser builds can join the same
+// matches as native ones
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::WebSocket;
+
+#[cfg(target_arch = "wasm32")]
+pub use browser::WebSocket;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use super::SocketError;
+    use std::io;
+    use std::net::{SocketAddr, TcpStream};
+    use std::time::Duration;
+    use tungstenite::{Error, Message};
+
+    /// Longest to wait on a connection or handshake.
+    const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(3);
+
+    /// A non-blocking WebSocket over TCP, for hosting or joining.
+    pub struct WebSocket(tungstenite::WebSocket<TcpStream>);
+
+    impl From<Error> for SocketError {
+        fn from(err: Error) -> Self {
+            match err {
+                Error::ConnectionClosed | Error::AlreadyClosed => Self::Closed,
+                Error::Io(err) => Self::Io(err),
+                err => Self::WebSocket(err.to_string()),
+            }
+        }
+    }
+
+    impl WebSocket {
+        /// Completes the server side of the handshake on a stream from a
+        /// listener.
+        pub fn accept(stream: TcpStream) -> Result<Self, SocketError> {
+            // Handshake while blocking, then never block a frame again
+            stream.set_nonblocking(false)?;
+            stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+            let socket = tungstenite::accept(stream)
+                .map_err(|err| SocketError::WebSocket(err.to_string()))?;
+            Self::ready(socket)
+        }
+
+        pub fn connect(address: &str) -> Result<Self, SocketError> {
+            let address: SocketAddr = address
+                .parse()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+            let stream = TcpStream::connect_timeout(&address, HANDSHAKE_TIMEOUT)?;
+            stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+            let (socket, _) = tungstenite::client(format!("ws://{address}/"), stream)
+                .map_err(|err| SocketError::WebSocket(err.to_string()))?;
+            Self::ready(socket)
+        }
+
+        fn ready(socket: tungstenite::WebSocket<TcpStream>) -> Result<Self, SocketError> {
+            let stream = socket.get_ref();
+            stream.set_read_timeout(None)?;
+            stream.set_nonblocking(true)?;
+            stream.set_nodelay(true)?;
+            Ok(Self(socket))
+        }
+
+        /// Queues a text message for the next [`WebSocket::receive`].
+        pub fn send(&mut self, text: String) -> Result<(), SocketError> {
+            match self.0.write(Message::text(text)) {
+                Err(Error::Io(err)) if err.kind() == io::ErrorKind::WouldBlock => Ok(()),
+                result => Ok(result?),
+            }
+        }
+
+        /// Sends whatever the socket will take, and returns every text
+        /// message that has arrived.
+        pub fn receive(&mut self) -> Result<Vec<String>, SocketError> {
+            match self.0.flush() {
+                Err(Error::Io(err)) if err.kind() == io::ErrorKind::WouldBlock => {}
+                result => result?,
+            }
+            let mut texts = Vec::new();
+            loop {
+                match self.0.read() {
+                    Ok(Message::Text(text)) => texts.push(text.to_string()),
+                    Ok(Message::Close(_)) => return Err(SocketError::Closed),
+                    // Pings are answered on the next flush
+                    Ok(_) => {}
+                    Err(Error::Io(err)) if err.kind() == io::ErrorKind::WouldBlock => break,
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            Ok(texts)
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use super::SocketError;
+    use send_wrapper::SendWrapper;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen::closure::Closure;
+    use web_sys::{Event, MessageEvent};
+
+    /// The browser's WebSocket, for joining over plain `ws://`. Browsers only
+    /// ever run one thread, so it can live in a resource.
+    pub struct WebSocket(SendWrapper<Inner>);
+
+    struct Inner {
+        socket: web_sys::WebSocket,
+        inbox: Rc<RefCell<Inbox>>,
+        /// Held until the socket opens.
+        outgoing: Vec<String>,
+        _on_message: Closure<dyn FnMut(MessageEvent)>,
+        _on_close: Closure<dyn FnMut(Event)>,
+    }
+
+    #[derive(Default)]
+    struct Inbox {
+        texts: Vec<String>,
+        closed: bool,
+    }
+
+    impl WebSocket {
+        pub fn connect(address: &str) -> Result<Self, SocketError> {
+            let socket = web_sys::WebSocket::new(&format!("ws://{address}/"))
+                .map_err(|err| SocketError::WebSocket(format!("{err:?}")))?;
+            let inbox = Rc::new(RefCell::new(Inbox::default()));
+
+            let on_message = Closure::<dyn FnMut(MessageEvent)>::new({
+                let inbox = inbox.clone();
+                move |event: MessageEvent| {
+                    if let Some(text) = event.data().as_string() {
+                        inbox.borrow_mut().texts.push(text);
+                    }
+                }
+            });
+            let on_close = Closure::<dyn FnMut(Event)>::new({
+                let inbox = inbox.clone();
+                move |_| inbox.borrow_mut().closed = true
+            });
+            socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+            socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+            socket.set_onerror(Some(on_close.as_ref().unchecked_ref()));
+
+            Ok(Self(SendWrapper::new(Inner {
+                socket,
+                inbox,
+                outgoing: Vec::new(),
+                _on_message: on_message,
+                _on_close: on_close,
+            })))
+        }
+
+        /// Queues a text message for the next [`WebSocket::receive`].
+        pub fn send(&mut self, text: String) -> Result<(), SocketError> {
+            self.0.outgoing.push(text);
+            Ok(())
+        }
+
+        /// Sends anything queued once the socket is open, and returns every
+        /// text message that has arrived.
+        pub fn receive(&mut self) -> Result<Vec<String>, SocketError> {
+            let inner = &mut *self.0;
+            if inner.socket.ready_state() == web_sys::WebSocket::OPEN {
+                for text in inner.outgoing.drain(..) {
+                    inner
+                        .socket
+                        .send_with_str(&text)
+                        .map_err(|err| SocketError::WebSocket(format!("{err:?}")))?;
+                }
+            }
+            let mut inbox = inner.inbox.borrow_mut();
+            if inbox.closed {
+                return Err(SocketError::Closed);
+            }
+            Ok(std::mem::take(&mut inbox.texts))
+        }
+    }
+
+    impl Drop for Inner {
+        fn drop(&mut self) {
+            let _ = self.socket.close();
+        }
+    }
+}
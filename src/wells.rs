@@ -0,0 +1,145 @@
+use crate::camera::PlotCamera;
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use bevy_egui::egui::{self, Color32};
+
+/// Shades the space plot by gravitational potential, in bands like contour
+/// lines, so players can see the wells they sling through.
+///
+/// The potential is sampled on a grid of screen cells, and only sampled
+/// again once the view or the bodies have moved or changed enough to show.
+/// Painted by egui under the plot, like the starfield.
+pub struct GravityWellsPlugin;
+
+impl Plugin for GravityWellsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GravityWells>();
+    }
+}
+
+/// Screen points across each sample.
+const CELL: f32 = 10.;
+/// Bands of equal potential, from the shallowest visible to the deepest.
+const BANDS: f32 = 10.;
+const DEEP: Color32 = Color32::from_rgb(70, 110, 255);
+/// Of the deepest band.
+const MAX_OPACITY: f32 = 0.45;
+/// Relative change in a body's mass that's worth sampling again for.
+const MASS_CHANGE: f64 = 0.01;
+
+/// What the samples were taken for.
+#[derive(PartialEq)]
+struct View {
+    center: DVec2,
+    scale: f64,
+    size: egui::Vec2,
+}
+
+#[derive(Resource, Default)]
+pub struct GravityWells {
+    view: Option<View>,
+    /// Positions and masses when sampled.
+    bodies: Vec<(DVec2, f64)>,
+    columns: usize,
+    rows: usize,
+    /// Band colors, row by row from the top.
+    colors: Vec<Color32>,
+}
+
+impl GravityWells {
+    /// Shades `rect` by the potential of `bodies`, given as positions and
+    /// masses, for where the camera looks.
+    pub fn paint(
+        &mut self,
+        painter: &egui::Painter,
+        rect: egui::Rect,
+        camera: &PlotCamera,
+        bodies: Vec<(DVec2, f64)>,
+        g: f64,
+        softening: f64,
+    ) {
+        if !camera.is_placed() || bodies.is_empty() {
+            return;
+        }
+        let view = View {
+            center: camera.center,
+            scale: camera.scale,
+            size: rect.size(),
+        };
+        if self.stale(&view, &bodies) {
+            self.sample(&view, &bodies, g, softening);
+            self.view = Some(view);
+            self.bodies = bodies;
+        }
+
+        let mut mesh = egui::Mesh::default();
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let color = self.colors[row * self.columns + column];
+                if color == Color32::TRANSPARENT {
+                    continue;
+                }
+                let min = rect.min + egui::vec2(column as f32, row as f32) * CELL;
+                let cell = egui::Rect::from_min_size(min, egui::Vec2::splat(CELL)).intersect(rect);
+                mesh.add_colored_rect(cell, color);
+            }
+        }
+        painter.add(mesh);
+    }
+
+    /// Whether the view has changed at all, or any body has moved half a
+    /// cell or changed mass noticeably.
+    fn stale(&self, view: &View, bodies: &[(DVec2, f64)]) -> bool {
+        let Some(sampled) = &self.view else {
+            return true;
+        };
+        let tolerance = view.scale * CELL as f64 / 2.;
+        sampled != view
+            || bodies.len() != self.bodies.len()
+            || bodies.iter().zip(&self.bodies).any(|(now, then)| {
+                now.0.distance(then.0) > tolerance
+                    || (now.1 - then.1).abs() > then.1.abs() * MASS_CHANGE
+            })
+    }
+
+    fn sample(&mut self, view: &View, bodies: &[(DVec2, f64)], g: f64, softening: f64) {
+        self.columns = (view.size.x / CELL).ceil() as usize;
+        self.rows = (view.size.y / CELL).ceil() as usize;
+        let half = DVec2::new(view.size.x as f64, view.size.y as f64) / 2.;
+        let cell = CELL as f64;
+        // Depth of the well at each cell's middle, with screen y pointing down
+        let depths: Vec<f64> = (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| (row, column)))
+            .map(|(row, column)| {
+                let screen = DVec2::new(column as f64 + 0.5, row as f64 + 0.5) * cell - half;
+                let world = view.center + DVec2::new(screen.x, -screen.y) * view.scale;
+                bodies
+                    .iter()
+                    .map(|&(position, mass)| {
+                        g * mass / (world.distance_squared(position) + softening * softening).sqrt()
+                    })
+                    .sum()
+            })
+            .collect();
+
+        // Logarithmic, so both a star's well and a moon's show
+        let (shallowest, deepest) = depths
+            .iter()
+            .filter(|depth| **depth > 0.)
+            .fold((f64::INFINITY, 0_f64), |(low, high), depth| {
+                (low.min(*depth), high.max(*depth))
+            });
+        let range = (deepest / shallowest).ln();
+        self.colors = depths
+            .into_iter()
+            .map(|depth| {
+                if depth <= 0. || !range.is_finite() || range <= 0. {
+                    return Color32::TRANSPARENT;
+                }
+                let level = ((depth / shallowest).ln() / range) as f32;
+                let band = (level * BANDS).floor().min(BANDS - 1.) / (BANDS - 1.);
+                DEEP.gamma_multiply(band * MAX_OPACITY)
+            })
+            .collect();
+    }
+}
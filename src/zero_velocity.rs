@@ -0,0 +1,226 @@
+use crate::orbital_mechanics::{dominant_attractor, strongest_attractor};
+use crate::physics::ScratchBody;
+use bevy::math::{DVec2, DVec3};
+use std::collections::{HashMap, VecDeque};
+
+/// The frame turning with a pair of bodies about their barycenter, in which
+/// a craft's Jacobi energy stays nearly constant while the pair's orbit is
+/// nearly circular, as in the restricted three-body problem.
+#[derive(Clone, Copy, Debug)]
+pub struct RotatingFrame {
+    /// Indices into the bodies the frame was made from.
+    pub primary: usize,
+    pub secondary: usize,
+    bodies: [ScratchBody; 2],
+    barycenter: DVec3,
+    velocity: DVec3,
+    /// Counterclockwise radians per second.
+    angular_velocity: f64,
+    g: f64,
+    softening: f64,
+}
+
+impl RotatingFrame {
+    /// The frame of the pair a craft at `position` moves among: the body
+    /// pulling on it hardest and what that body orbits, or near the heaviest
+    /// body, that and whichever other body pulls hardest.
+    pub fn around(position: DVec3, bodies: &[ScratchBody], g: f64, softening: f64) -> Option<Self> {
+        let nearest = strongest_attractor(position, bodies)?;
+        let (primary, secondary) = match dominant_attractor(nearest, bodies) {
+            Some(primary) => (primary, nearest),
+            None => {
+                let others: Vec<_> = bodies
+                    .iter()
+                    .enumerate()
+                    .filter(|&(index, _)| index != nearest)
+                    .collect();
+                let scratch: Vec<_> = others.iter().map(|(_, body)| **body).collect();
+                (nearest, others[strongest_attractor(position, &scratch)?].0)
+            }
+        };
+        Self::new(primary, secondary, bodies, g, softening)
+    }
+
+    /// `None` if the two bodies sit together or don't turn about each other.
+    pub fn new(
+        primary: usize,
+        secondary: usize,
+        bodies: &[ScratchBody],
+        g: f64,
+        softening: f64,
+    ) -> Option<Self> {
+        let pair = [bodies[primary], bodies[secondary]];
+        let total = pair[0].mass + pair[1].mass;
+        let offset = pair[1].position - pair[0].position;
+        let distance_sq = offset.truncate().length_squared();
+        if total <= 0. || distance_sq <= 0. {
+            return None;
+        }
+        let angular_velocity = offset.cross(pair[1].velocity - pair[0].velocity).z / distance_sq;
+        if angular_velocity == 0. {
+            return None;
+        }
+        Some(Self {
+            primary,
+            secondary,
+            bodies: pair,
+            barycenter: (pair[0].position * pair[0].mass + pair[1].position * pair[1].mass) / total,
+            velocity: (pair[0].velocity * pair[0].mass + pair[1].velocity * pair[1].mass) / total,
+            angular_velocity,
+            g,
+            softening,
+        })
+    }
+
+    /// Gravitational plus centrifugal potential per unit mass at `point`.
+    pub fn effective_potential(&self, point: DVec2) -> f64 {
+        let centrifugal = -0.5
+            * self.angular_velocity.powi(2)
+            * point.distance_squared(self.barycenter.truncate());
+        let gravity: f64 = self
+            .bodies
+            .iter()
+            .map(|body| {
+                let distance_sq = point.distance_squared(body.position.truncate());
+                -self.g * body.mass / (distance_sq + self.softening * self.softening).sqrt()
+            })
+            .sum();
+        centrifugal + gravity
+    }
+
+    /// Kinetic energy in the frame plus effective potential, per unit mass.
+    /// Places where the effective potential is higher are out of reach.
+    pub fn jacobi_energy(&self, position: DVec3, velocity: DVec3) -> f64 {
+        let offset = (position - self.barycenter).truncate();
+        let turning = DVec2::new(-offset.y, offset.x) * self.angular_velocity;
+        let relative = (velocity - self.velocity).truncate() - turning;
+        relative.length_squared() / 2. + self.effective_potential(position.truncate())
+    }
+
+    /// Where the effective potential equals `energy`, between `min` and `max`,
+    /// as polylines. A craft with that Jacobi energy can't cross them into
+    /// where the potential is higher.
+    pub fn zero_velocity_curves(&self, energy: f64, min: DVec2, max: DVec2) -> Vec<Vec<DVec2>> {
+        let size = max - min;
+        let step = size.max_element() / CURVE_SAMPLES as f64;
+        if !step.is_finite() || step <= 0. {
+            return Vec::new();
+        }
+        let columns = (size.x / step).ceil() as usize + 1;
+        let rows = (size.y / step).ceil() as usize + 1;
+        let point = |column: usize, row: usize| min + DVec2::new(column as f64, row as f64) * step;
+        let heights: Vec<f64> = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| self.effective_potential(point(column, row)) - energy)
+            .collect();
+        contours(&heights, columns, rows)
+            .into_iter()
+            .map(|line| line.into_iter().map(|grid| min + grid * step).collect())
+            .collect()
+    }
+}
+
+/// Samples across the wider side of the area curves are traced in.
+const CURVE_SAMPLES: usize = 120;
+
+/// A side of a grid cell: horizontal or not, and its lower left corner.
+type Edge = (bool, usize, usize);
+
+/// Lines along which `heights`, sampled row by row on a grid, cross zero, in
+/// grid coordinates. Marching squares, with the pieces joined up.
+fn contours(heights: &[f64], columns: usize, rows: usize) -> Vec<Vec<DVec2>> {
+    let height = |column: usize, row: usize| heights[row * columns + column];
+    let mut crossings = HashMap::<Edge, DVec2>::new();
+    let mut segments = Vec::<(Edge, Edge)>::new();
+    let mut cross = |edge: Edge| {
+        let (horizontal, column, row) = edge;
+        let (end_column, end_row) = if horizontal {
+            (column + 1, row)
+        } else {
+            (column, row + 1)
+        };
+        let (a, b) = (height(column, row), height(end_column, end_row));
+        let along = a / (a - b);
+        let start = DVec2::new(column as f64, row as f64);
+        let end = DVec2::new(end_column as f64, end_row as f64);
+        crossings.insert(edge, start.lerp(end, along));
+        edge
+    };
+
+    for row in 0..rows.saturating_sub(1) {
+        for column in 0..columns.saturating_sub(1) {
+            let corners = [
+                height(column, row),
+                height(column + 1, row),
+                height(column + 1, row + 1),
+                height(column, row + 1),
+            ];
+            // Bottom, right, top and left, each between two corners
+            let sides = [
+                (true, column, row),
+                (false, column + 1, row),
+                (true, column, row + 1),
+                (false, column, row),
+            ];
+            let crossed: Vec<Edge> = [(0, 1), (1, 2), (3, 2), (0, 3)]
+                .into_iter()
+                .zip(sides)
+                .filter(|&((a, b), _)| (corners[a] > 0.) != (corners[b] > 0.))
+                .map(|(_, side)| cross(side))
+                .collect();
+            match crossed[..] {
+                [a, b] => segments.push((a, b)),
+                [bottom, right, top, left] => {
+                    // A saddle, split by what the middle of the cell is like
+                    let middle = corners.iter().sum::<f64>() / 4.;
+                    if (middle > 0.) == (corners[0] > 0.) {
+                        segments.extend([(bottom, right), (top, left)]);
+                    } else {
+                        segments.extend([(bottom, left), (right, top)]);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut by_edge = HashMap::<Edge, Vec<usize>>::new();
+    for (index, &(a, b)) in segments.iter().enumerate() {
+        by_edge.entry(a).or_default().push(index);
+        by_edge.entry(b).or_default().push(index);
+    }
+    let mut used = vec![false; segments.len()];
+    let mut lines = Vec::new();
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let (first, last) = segments[start];
+        let mut line = VecDeque::from([first, last]);
+        // Follow the curve one way, then the other
+        for forward in [true, false] {
+            loop {
+                let end = if forward {
+                    line[line.len() - 1]
+                } else {
+                    line[0]
+                };
+                let next = by_edge[&end].iter().copied().find(|&index| !used[index]);
+                let Some(next) = next else {
+                    break;
+                };
+                used[next] = true;
+                let (a, b) = segments[next];
+                let other = if a == end { b } else { a };
+                if forward {
+                    line.push_back(other);
+                } else {
+                    line.push_front(other);
+                }
+            }
+        }
+        lines.push(line.into_iter().map(|edge| crossings[&edge]).collect());
+    }
+    lines
+}
@@ -0,0 +1,20 @@
+//! Durations on the command line must be something the simulation can step.
+
+use clap::Parser;
+use slingcraft::cli::Cli;
+
+fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+    Cli::try_parse_from(["slingcraft"].iter().chain(args))
+}
+
+#[test]
+fn durations_must_be_positive_and_finite() {
+    for flag in ["--timestep", "--telemetry-interval"] {
+        for bad in ["0", "-0.01", "nan", "inf", "soon"] {
+            assert!(parse(&[flag, bad]).is_err(), "{flag} {bad} was accepted");
+        }
+    }
+    let cli = parse(&["--timestep", "0.01", "--telemetry-interval", "2"]).unwrap();
+    assert_eq!(cli.timestep, 0.01);
+    assert_eq!(cli.telemetry_interval, 2.);
+}
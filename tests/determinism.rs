@@ -0,0 +1,45 @@
+//! Same scenario, same seed, same result, down to the last bit.
+
+use bevy::math::DVec3;
+use bevy::tasks::{ComputeTaskPool, TaskPool};
+use slingcraft::determinism::assert_deterministic;
+use slingcraft::physics::{PhysicsConstants, pairwise_gravity};
+use slingcraft::scenario::Scenario;
+
+const TIMESTEP: f64 = 1. / 64.;
+
+#[test]
+fn default_scenario_replays_bit_identically() {
+    // Long enough for the opponent to launch crafts
+    let state = assert_deterministic(&Scenario::default(), 3_000, TIMESTEP, 7, 0.);
+    assert_eq!(state.bodies.len(), Scenario::default().bodies.len());
+    assert!(!state.crafts.is_empty(), "no crafts in flight to compare");
+}
+
+#[test]
+fn parallel_gravity_sums_like_sequential() {
+    let constants = PhysicsConstants::default();
+    let softening = constants.softening as f64;
+    let g = constants.g as f64;
+    // Enough bodies to be split across several tasks
+    let bodies: Vec<_> = (0..200)
+        .map(|i| {
+            let angle = i as f64 * 0.7;
+            let distance = 10. + i as f64;
+            (
+                DVec3::new(angle.cos(), angle.sin(), 0.) * distance,
+                1. + (i % 7) as f64,
+            )
+        })
+        .collect();
+    let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+    let (sequential, sequential_energy) = pairwise_gravity(&bodies, softening, g, None);
+    let (parallel, parallel_energy) = pairwise_gravity(&bodies, softening, g, Some(pool));
+    assert_eq!(sequential_energy.to_bits(), parallel_energy.to_bits());
+    for (a, b) in sequential.iter().zip(&parallel) {
+        assert_eq!(
+            a.to_array().map(f64::to_bits),
+            b.to_array().map(f64::to_bits)
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Runs small systems headless for many orbits and checks that the physics
+//! still conserves what it should.
+
+use bevy::prelude::*;
+use slingcraft::bench::headless_app;
+use slingcraft::bodies::Body;
+use slingcraft::physics::{
+    GravitySolver, Mass, PhysicsConstants, SimPosition, SimVelocity, TotalEnergy,
+};
+use slingcraft::scenario::{BodySpec, Scenario};
+
+const TIMESTEP: f64 = 1. / 64.;
+/// Around ten orbits of the inner planet.
+const TICKS: u32 = 20_000;
+
+/// Largest relative change in total energy allowed at any tick. Symplectic
+/// Euler lets energy wobble about its true value each orbit but not drift.
+const ENERGY_TOLERANCE: f64 = 1e-2;
+/// Largest relative change in angular momentum. Pair forces are central, so
+/// it is only lost to rounding.
+const ANGULAR_MOMENTUM_TOLERANCE: f64 = 1e-6;
+/// Fixed steps, then adaptive substeps.
+const STEP_TOLERANCES: [f32; 2] = [0., 1e-4];
+
+fn body(name: &str, radius: f32, position: [f32; 2]) -> BodySpec {
+    BodySpec {
+        name: name.into(),
+        radius,
+        color: [255, 255, 255],
+        position,
+        density: None,
+        owner: None,
+        velocity: None,
+        crafts: Some(0),
+        appearance: None,
+        spin: None,
+        atmosphere: None,
+        on_rails: false,
+        comet: None,
+        orbit: None,
+    }
+}
+
+fn scenario(bodies: Vec<BodySpec>) -> Scenario {
+    Scenario {
+        bodies,
+        ..default()
+    }
+}
+
+/// A star and one planet on a circular orbit.
+fn two_body() -> Scenario {
+    scenario(vec![
+        body("Star", 5., [0., 0.]),
+        body("Planet", 1., [25., 0.]),
+    ])
+}
+
+/// A star and two planets tugging on each other as they go around.
+fn three_body() -> Scenario {
+    scenario(vec![
+        body("Star", 5., [0., 0.]),
+        body("Inner", 1., [25., 0.]),
+        body("Outer", 1.5, [0., -50.]),
+    ])
+}
+
+fn angular_momentum(world: &mut World) -> f64 {
+    world
+        .query_filtered::<(&SimPosition, &SimVelocity, &Mass), With<Body>>()
+        .iter(world)
+        .map(|(position, velocity, mass)| mass.0 as f64 * position.0.cross(velocity.0).z)
+        .sum()
+}
+
+fn relative_change(initial: f64, value: f64) -> f64 {
+    ((value - initial) / initial).abs()
+}
+
+fn assert_conserved(name: &str, scenario: Scenario) {
+    let integrators = GravitySolver::ALL
+        .iter()
+        .flat_map(|&solver| STEP_TOLERANCES.map(|tolerance| (solver, tolerance)));
+    for (solver, step_tolerance) in integrators {
+        let mut app = headless_app(scenario.clone(), TIMESTEP, 0);
+        app.insert_resource(solver);
+        app.world_mut()
+            .resource_mut::<PhysicsConstants>()
+            .step_tolerance = step_tolerance;
+        let body_count = app
+            .world_mut()
+            .query_filtered::<(), With<Body>>()
+            .iter(app.world())
+            .count();
+        assert_eq!(body_count, scenario.bodies.len(), "{name}: bodies missing");
+        let initial_energy = app.world().resource::<TotalEnergy>().0;
+        let initial_angular_momentum = angular_momentum(app.world_mut());
+
+        let mut worst_energy: f64 = 0.;
+        let mut worst_angular_momentum: f64 = 0.;
+        for _ in 0..TICKS {
+            app.update();
+            let energy = app.world().resource::<TotalEnergy>().0;
+            worst_energy = worst_energy.max(relative_change(initial_energy, energy));
+            let momentum = angular_momentum(app.world_mut());
+            worst_angular_momentum =
+                worst_angular_momentum.max(relative_change(initial_angular_momentum, momentum));
+        }
+
+        let solver = format!("{}, step tolerance {step_tolerance}", solver.label());
+        assert!(
+            worst_energy < ENERGY_TOLERANCE,
+            "{name} with {solver}: energy changed by {worst_energy:e}"
+        );
+        assert!(
+            worst_angular_momentum < ANGULAR_MOMENTUM_TOLERANCE,
+            "{name} with {solver}: angular momentum changed by {worst_angular_momentum:e}"
+        );
+    }
+}
+
+#[test]
+fn two_body_conserves_energy_and_angular_momentum() {
+    assert_conserved("two-body", two_body());
+}
+
+#[test]
+fn three_body_conserves_energy_and_angular_momentum() {
+    assert_conserved("three-body", three_body());
+}
@@ -0,0 +1,31 @@
+//! Numbers are written the way the current language writes them.
+
+use slingcraft::i18n::{Language, format_number, set_language, tr_args};
+
+// The language is global, so both are checked in one test rather than racing
+#[test]
+fn numbers_follow_the_current_language() {
+    set_language(Language::English);
+    assert_eq!(format_number(1234567.891, 2), "1,234,567.89");
+    assert_eq!(format_number(-1234.5, 1), "-1,234.5");
+    assert_eq!(format_number(999., 0), "999");
+    assert_eq!(format_number(-0.001, 2), "0.00");
+    assert_eq!(
+        tr_args("samples", &[("count", 12000.into())]),
+        "12,000 samples"
+    );
+    assert_eq!(tr_args("samples", &[("count", 1.into())]), "1 sample");
+
+    set_language(Language::German);
+    assert_eq!(format_number(1234567.891, 2), "1.234.567,89");
+    assert_eq!(format_number(-1234.5, 1), "-1.234,5");
+    assert_eq!(format_number(999., 0), "999");
+    assert_eq!(format_number(-0.001, 2), "0,00");
+    assert_eq!(
+        tr_args("samples", &[("count", 12000.into())]),
+        "12.000 Messwerte"
+    );
+    assert_eq!(tr_args("samples", &[("count", 1.into())]), "1 Messwert");
+
+    set_language(Language::English);
+}
@@ -0,0 +1,39 @@
+//! Analytic orbits land where the conic says they should.
+
+use bevy::math::DVec3;
+use slingcraft::orbital_mechanics::{OrbitalElements, propagate_kepler};
+
+const MU: f64 = 500.;
+
+fn specific_energy(position: DVec3, velocity: DVec3) -> f64 {
+    velocity.length_squared() / 2. - MU / position.length()
+}
+
+#[test]
+fn elliptic_orbit_returns_after_one_period() {
+    let position = DVec3::new(30., 0., 0.);
+    let velocity = DVec3::new(0., 3., 0.);
+    let period = OrbitalElements::new(position, velocity, MU)
+        .and_then(|elements| elements.period)
+        .unwrap();
+    let (end_position, end_velocity) = propagate_kepler(position, velocity, MU, period).unwrap();
+    assert!(end_position.distance(position) < 1e-6, "{end_position}");
+    assert!(end_velocity.distance(velocity) < 1e-6, "{end_velocity}");
+}
+
+#[test]
+fn hyperbolic_orbit_keeps_its_energy_and_momentum() {
+    let position = DVec3::new(-200., 20., 0.);
+    let velocity = DVec3::new(5., 0., 0.);
+    let (end_position, end_velocity) = propagate_kepler(position, velocity, MU, 60.).unwrap();
+    // Where RK4 with a millionth-of-a-minute step puts it
+    let reference = DVec3::new(5.414, -145.845, 0.);
+    assert!(end_position.distance(reference) < 0.01, "{end_position}");
+    let start = specific_energy(position, velocity);
+    let end = specific_energy(end_position, end_velocity);
+    assert!(((end - start) / start).abs() < 1e-9, "{start} -> {end}");
+    let momentum = |position: DVec3, velocity: DVec3| position.cross(velocity).z;
+    let start = momentum(position, velocity);
+    let end = momentum(end_position, end_velocity);
+    assert!(((end - start) / start).abs() < 1e-9, "{start} -> {end}");
+}
@@ -0,0 +1,36 @@
+//! Scenarios shared in a link come back as they went in, and a link can't
+//! unpack to more than the size limit.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use flate2::Compression;
+use flate2::write::DeflateEncoder;
+use slingcraft::scenario::{MAX_LINK_SCENARIO_BYTES, Scenario, ScenarioError};
+use std::io::Write;
+
+#[test]
+fn default_scenario_round_trips() {
+    let scenario = Scenario::default();
+    let fragment = scenario.to_link_fragment().unwrap();
+    let loaded = Scenario::from_link_fragment(&fragment).unwrap();
+    assert_eq!(
+        toml::to_string(&loaded).unwrap(),
+        toml::to_string(&scenario).unwrap()
+    );
+}
+
+#[test]
+fn oversized_link_is_rejected() {
+    // A comment compresses to almost nothing, and stays valid TOML however
+    // much of it is read
+    let mut toml = "#".to_string();
+    toml.push_str(&" ".repeat(MAX_LINK_SCENARIO_BYTES as usize * 2));
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(toml.as_bytes()).unwrap();
+    let fragment = URL_SAFE_NO_PAD.encode(encoder.finish().unwrap());
+
+    assert!(matches!(
+        Scenario::from_link_fragment(&fragment),
+        Err(ScenarioError::LinkTooLarge)
+    ));
+}
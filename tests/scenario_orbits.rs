@@ -0,0 +1,122 @@
+//! Bodies given an `orbit` start where, and moving how, that orbit says.
+
+use bevy::math::{DVec2, DVec3};
+use slingcraft::bodies::body_mass;
+use slingcraft::orbital_mechanics::state_on_orbit;
+use slingcraft::scenario::Scenario;
+use std::f64::consts::FRAC_PI_2;
+
+const SCENARIO: &str = r#"
+[[bodies]]
+name = "Star"
+radius = 5.0
+color = [255, 220, 100]
+position = [10.0, -20.0]
+
+[[bodies]]
+name = "Moon"
+radius = 0.5
+color = [180, 180, 180]
+orbit = { around = "Planet", distance = 6.0, eccentricity = 0.2, periapsis = 90.0 }
+
+[[bodies]]
+name = "Planet"
+radius = 1.5
+color = [80, 140, 255]
+orbit = { around = "Star", distance = 120.0, phase = 45.0 }
+"#;
+
+/// Speed at periapsis, by vis-viva.
+fn periapsis_speed(semi_major_axis: f64, eccentricity: f64, mu: f64) -> f64 {
+    (mu * (1. + eccentricity) / (semi_major_axis * (1. - eccentricity))).sqrt()
+}
+
+#[test]
+fn state_on_orbit_starts_at_periapsis_facing_the_right_way() {
+    let (position, velocity) = state_on_orbit(10., 0.5, FRAC_PI_2, 0., 100.);
+    assert!(
+        position.distance(DVec3::new(0., 5., 0.)) < 1e-9,
+        "{position}"
+    );
+    let speed = periapsis_speed(10., 0.5, 100.);
+    assert!(
+        velocity.distance(DVec3::new(-speed, 0., 0.)) < 1e-9,
+        "{velocity}"
+    );
+
+    let (position, velocity) = state_on_orbit(10., 0., 0., FRAC_PI_2, 100.);
+    assert!(
+        position.distance(DVec3::new(0., 10., 0.)) < 1e-9,
+        "{position}"
+    );
+    assert!(
+        velocity.distance(DVec3::new(-10f64.sqrt(), 0., 0.)) < 1e-9,
+        "{velocity}"
+    );
+}
+
+#[test]
+fn state_on_orbit_at_periapsis_matches_vis_viva_whichever_way_it_points() {
+    for eccentricity in [0., 0.3, 0.9] {
+        for argument_of_periapsis in [0., 1., -2.5] {
+            let (position, velocity) =
+                state_on_orbit(8., eccentricity, argument_of_periapsis, 0., 50.);
+            let distance = 8. * (1. - eccentricity);
+            assert!(
+                (position.length() - distance).abs() < 1e-9,
+                "{position} at e = {eccentricity}, ω = {argument_of_periapsis}"
+            );
+            let speed = periapsis_speed(8., eccentricity, 50.);
+            assert!(
+                (velocity.length() - speed).abs() < 1e-9,
+                "{velocity} at e = {eccentricity}, ω = {argument_of_periapsis}"
+            );
+            // Periapsis is where the craft moves neither in nor out
+            assert!(position.dot(velocity).abs() < 1e-9);
+        }
+    }
+}
+
+#[test]
+fn orbits_are_placed_relative_to_what_they_go_around() {
+    let scenario = Scenario::from_bytes(SCENARIO.as_bytes()).unwrap();
+    let body = |name: &str| {
+        let spec = scenario
+            .bodies
+            .iter()
+            .find(|spec| spec.name == name)
+            .unwrap();
+        let [x, y] = spec.position;
+        let [vx, vy] = spec.velocity.unwrap_or_default();
+        let mass = body_mass(spec.radius, spec.density(&scenario.physics)) as f64;
+        (
+            DVec2::new(x as f64, y as f64),
+            DVec2::new(vx as f64, vy as f64),
+            mass,
+        )
+    };
+    let g = scenario.physics.g as f64;
+    let (star, _, star_mass) = body("Star");
+    let (planet, planet_velocity, planet_mass) = body("Planet");
+    let (moon, moon_velocity, moon_mass) = body("Moon");
+
+    let along = DVec2::from_angle(45f64.to_radians());
+    assert!((planet - star).distance(along * 120.) < 1e-3, "{planet}");
+    let speed = (g * (star_mass + planet_mass) / 120.).sqrt();
+    assert!(
+        planet_velocity.distance(along.perp() * speed) < 1e-3,
+        "{planet_velocity}"
+    );
+
+    // Periapsis straight up from the planet, a fifth of the way in
+    assert!(
+        (moon - planet).distance(DVec2::new(0., 4.8)) < 1e-3,
+        "{moon}"
+    );
+    let speed = periapsis_speed(6., 0.2, g * (planet_mass + moon_mass));
+    let relative = moon_velocity - planet_velocity;
+    assert!(
+        relative.distance(DVec2::new(-speed, 0.)) < 1e-3,
+        "{relative} against {speed}"
+    );
+}
@@ -0,0 +1,47 @@
+//! Bodies that blow up are rewound, unless they blow up again straight away.
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use slingcraft::bench::headless_app;
+use slingcraft::bodies::Body;
+use slingcraft::physics::SimVelocity;
+use slingcraft::scenario::Scenario;
+
+const TIMESTEP: f64 = 1. / 64.;
+/// Comfortably more than the watchdog waits between checkpoints.
+const HEALTHY_TICKS: usize = 64 * 8;
+
+fn run(app: &mut App, ticks: usize) {
+    for _ in 0..ticks {
+        app.update();
+    }
+}
+
+fn blow_up(app: &mut App, body: Entity) {
+    app.world_mut().get_mut::<SimVelocity>(body).unwrap().0 = DVec3::NAN;
+    app.update();
+}
+
+fn rewound(app: &App, body: Entity) -> bool {
+    app.world()
+        .get::<SimVelocity>(body)
+        .is_some_and(|velocity| velocity.0.is_finite())
+}
+
+#[test]
+fn separate_blowups_are_each_rewound() {
+    let mut app = headless_app(Scenario::default(), TIMESTEP, 0);
+    let mut bodies = app.world_mut().query_filtered::<Entity, With<Body>>();
+    let body = bodies.iter(app.world()).next().unwrap();
+    run(&mut app, HEALTHY_TICKS);
+
+    blow_up(&mut app, body);
+    assert!(rewound(&app, body), "first blowup wasn't rewound");
+    run(&mut app, HEALTHY_TICKS);
+    blow_up(&mut app, body);
+    assert!(rewound(&app, body), "second blowup wasn't rewound");
+
+    // Without a healthy checkpoint since, it's removed instead
+    blow_up(&mut app, body);
+    assert!(app.world().get_entity(body).is_err());
+}